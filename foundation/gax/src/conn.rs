@@ -95,7 +95,7 @@ impl ConnectionManager {
             Environment::GoogleCloud(project) => {
                 Self::create_connections(pool_size, domain_name, audience, scopes, project).await?
             }
-            Environment::Emulator(host) => Self::create_emulator_connections(host).await?,
+            Environment::Emulator(host) => Self::create_emulator_connections(pool_size, host).await?,
         };
         Ok(Self {
             index: AtomicI64::new(0),
@@ -134,16 +134,20 @@ impl ConnectionManager {
         Ok(conns)
     }
 
-    async fn create_emulator_connections(host: &str) -> Result<Vec<Channel>, Error> {
-        let mut conns = Vec::with_capacity(1);
+    async fn create_emulator_connections(pool_size: usize, host: &str) -> Result<Vec<Channel>, Error> {
+        let pool_size = std::cmp::max(pool_size, 1);
+        let mut conns = Vec::with_capacity(pool_size);
         let endpoint = TonicChannel::from_shared(format!("http://{}", host).into_bytes())
             .map_err(|_| Error::InvalidEmulatorHOST(host.to_string()))?;
-        let con = Self::connect(endpoint).await?;
-        conns.push(
-            ServiceBuilder::new()
-                .option_layer::<AsyncFilterLayer<AsyncAuthInterceptor>>(None)
-                .service(con),
-        );
+        for _i_ in 0..pool_size {
+            // each connect opens the separate http/2 connection.
+            let con = Self::connect(endpoint.clone()).await?;
+            conns.push(
+                ServiceBuilder::new()
+                    .option_layer::<AsyncFilterLayer<AsyncAuthInterceptor>>(None)
+                    .service(con),
+            );
+        }
         Ok(conns)
     }
 
@@ -157,8 +161,17 @@ impl ConnectionManager {
     }
 
     pub fn conn(&self) -> Channel {
-        let current = self.index.fetch_add(1, Ordering::SeqCst) as usize;
+        self.conn_at(self.next_index())
+    }
+
+    /// next_index returns the index of the channel to use next in the round-robin order.
+    pub fn next_index(&self) -> usize {
+        self.index.fetch_add(1, Ordering::SeqCst) as usize % self.conns.len()
+    }
+
+    /// conn_at returns the channel at the index, which wraps around the number of the channels.
+    pub fn conn_at(&self, index: usize) -> Channel {
         //clone() reuses http/2 connection
-        self.conns[current % self.conns.len()].clone()
+        self.conns[index % self.conns.len()].clone()
     }
 }
@@ -1,4 +1,4 @@
-pub use tokio_retry::strategy::ExponentialBackoff;
+pub use tokio_retry::strategy::{jitter, ExponentialBackoff};
 pub use tokio_retry::Condition;
 
 use crate::cancel::CancellationToken;
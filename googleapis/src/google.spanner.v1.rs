@@ -724,6 +724,22 @@ pub struct QueryPlan {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TransactionOptions {
+    /// When `exclude_txn_from_change_streams` is set to `true`:
+    ///   * Mutations from this transaction will not be recorded in change streams
+    ///   with DDL option `allow_txn_exclusion=true` that are tracking columns
+    ///   modified by these transactions.
+    ///   * Mutations from this transaction will be recorded in change streams with
+    ///   DDL option `allow_txn_exclusion=false or not set` that are tracking
+    ///   columns modified by these transactions.
+    ///
+    /// When `exclude_txn_from_change_streams` is set to `false` or not set,
+    /// mutations from this transaction will be recorded in all change streams that
+    /// are tracking columns modified by these transactions.
+    /// `exclude_txn_from_change_streams` may only be specified for read-write or
+    /// partitioned-dml transactions, otherwise the API will return an
+    /// `INVALID_ARGUMENT` error.
+    #[prost(bool, tag = "5")]
+    pub exclude_txn_from_change_streams: bool,
     /// Required. The type of transaction.
     #[prost(oneof = "transaction_options::Mode", tags = "1, 3, 2")]
     pub mode: ::core::option::Option<transaction_options::Mode>,
@@ -969,6 +985,13 @@ pub struct Type {
     /// affect serialization) and clients can ignore it on the read path.
     #[prost(enumeration = "TypeAnnotationCode", tag = "4")]
     pub type_annotation: i32,
+    /// If \[code][google.spanner.v1.Type.code\] ==
+    /// \[PROTO][google.spanner.v1.TypeCode.PROTO\] or
+    /// \[code][google.spanner.v1.Type.code\] ==
+    /// \[ENUM][google.spanner.v1.TypeCode.ENUM\], then `proto_type_fqn` is the fully
+    /// qualified name of the proto type representing the proto/enum definition.
+    #[prost(string, tag = "5")]
+    pub proto_type_fqn: ::prost::alloc::string::String,
 }
 /// `StructType` defines the fields of a \[STRUCT][google.spanner.v1.TypeCode.STRUCT\] type.
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -1022,6 +1045,9 @@ pub enum TypeCode {
     /// Encoded as `number`, or the strings `"NaN"`, `"Infinity"`, or
     /// `"-Infinity"`.
     Float64 = 3,
+    /// Encoded as `number`, or the strings `"NaN"`, `"Infinity"`, or
+    /// `"-Infinity"`.
+    Float32 = 15,
     /// Encoded as `string` in RFC 3339 timestamp format. The time zone
     /// must be present, and must be `"Z"`.
     ///
@@ -1064,6 +1090,17 @@ pub enum TypeCode {
     ///    preserved.
     /// - JSON array elements will have their order preserved.
     Json = 11,
+    /// Encoded as a base64-encoded `string`, as described in RFC 4648,
+    /// section 4.
+    Proto = 13,
+    /// Encoded as `string`, in decimal format.
+    Enum = 14,
+    /// Encoded as `string`, in `ISO8601` duration format -
+    /// `P\[n\]Y\[n\]M\[n\]DT\[n\]H\[n\]M\[n[.fraction]\]S`
+    /// where `n` is an integer.
+    /// For example, `P1Y2M3DT4H5M6.5S` represents time duration of 1 year, 2
+    /// months, 3 days, 4 hours, 5 minutes, and 6.5 seconds.
+    Interval = 16,
 }
 impl TypeCode {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -1076,6 +1113,7 @@ impl TypeCode {
             TypeCode::Bool => "BOOL",
             TypeCode::Int64 => "INT64",
             TypeCode::Float64 => "FLOAT64",
+            TypeCode::Float32 => "FLOAT32",
             TypeCode::Timestamp => "TIMESTAMP",
             TypeCode::Date => "DATE",
             TypeCode::String => "STRING",
@@ -1084,6 +1122,9 @@ impl TypeCode {
             TypeCode::Struct => "STRUCT",
             TypeCode::Numeric => "NUMERIC",
             TypeCode::Json => "JSON",
+            TypeCode::Proto => "PROTO",
+            TypeCode::Enum => "ENUM",
+            TypeCode::Interval => "INTERVAL",
         }
     }
 }
@@ -1391,6 +1432,15 @@ pub struct Session {
     /// The database role which created this session.
     #[prost(string, tag = "5")]
     pub creator_role: ::prost::alloc::string::String,
+    /// Optional. If true, specifies a multiplexed session. A multiplexed session
+    /// may be used for multiple, concurrent read-only operations but can not be
+    /// used for read-write transactions, partitioned reads, or partitioned
+    /// queries. Multiplexed sessions can be created via
+    /// \[CreateSession][google.spanner.v1.Spanner.CreateSession\] but not via
+    /// \[BatchCreateSessions][google.spanner.v1.Spanner.BatchCreateSessions\].
+    /// Multiplexed sessions may not be deleted nor listed.
+    #[prost(bool, tag = "6")]
+    pub multiplexed: bool,
 }
 /// The request for \[GetSession][google.spanner.v1.Spanner.GetSession\].
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -1528,6 +1578,118 @@ pub mod request_options {
         }
     }
 }
+/// The DirectedReadOptions can be used to indicate which replicas or regions
+/// should be used for non-transactional reads or queries.
+///
+/// DirectedReadOptions may only be specified for a read-only transaction,
+/// otherwise the API will return an `INVALID_ARGUMENT` error.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DirectedReadOptions {
+    /// Required. At most one of either include_replicas or exclude_replicas
+    /// should be present in the message.
+    #[prost(oneof = "directed_read_options::Replicas", tags = "1, 2")]
+    pub replicas: ::core::option::Option<directed_read_options::Replicas>,
+}
+/// Nested message and enum types in `DirectedReadOptions`.
+pub mod directed_read_options {
+    /// The directed read replica selector.
+    /// Callers must provide one or more of the following fields for replica
+    /// selection:
+    ///
+    /// * `location` - The location must be one of the regions within the
+    ///    multi-region configuration of your database.
+    /// * `type` - The type of the replica.
+    ///
+    /// Some examples of using replica_selectors are:
+    ///
+    /// * `location:us-east1` --> The "us-east1" replica(s) of any available type
+    ///                            will be used to process the request.
+    /// * `type:READ_ONLY`    --> The "READ_ONLY" type replica(s) in nearest
+    ///                            available location will be used to process the
+    ///                            request.
+    /// * `location:us-east1 type:READ_ONLY` --> The "READ_ONLY" type replica(s)
+    ///                            in location "us-east1" will be used to process
+    ///                            the request.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ReplicaSelection {
+        /// The location or region of the serving requests, e.g. "us-east1".
+        #[prost(string, tag = "1")]
+        pub location: ::prost::alloc::string::String,
+        /// The type of replica.
+        #[prost(enumeration = "replica_selection::Type", tag = "2")]
+        pub r#type: i32,
+    }
+    /// Nested message and enum types in `ReplicaSelection`.
+    pub mod replica_selection {
+        /// Indicates the type of replica.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+        #[repr(i32)]
+        pub enum Type {
+            /// Not specified.
+            Unspecified = 0,
+            /// Read-write replicas support both reads and writes.
+            ReadWrite = 1,
+            /// Read-only replicas only support reads (not writes).
+            ReadOnly = 2,
+        }
+        impl Type {
+            /// String value of the enum field names used in the ProtoBuf definition.
+            ///
+            /// The values are not transformed in any way and thus are considered stable
+            /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+            pub fn as_str_name(&self) -> &'static str {
+                match self {
+                    Type::Unspecified => "TYPE_UNSPECIFIED",
+                    Type::ReadWrite => "READ_WRITE",
+                    Type::ReadOnly => "READ_ONLY",
+                }
+            }
+        }
+    }
+    /// An IncludeReplicas contains a repeated set of ReplicaSelection which
+    /// indicates the order in which replicas should be considered.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct IncludeReplicas {
+        /// The directed read replica selector.
+        #[prost(message, repeated, tag = "1")]
+        pub replica_selections: ::prost::alloc::vec::Vec<ReplicaSelection>,
+        /// If true, Spanner will not route requests to a replica outside the
+        /// include_replicas list when all of the specified replicas are unavailable
+        /// or unhealthy. Default value is `false`.
+        #[prost(bool, tag = "2")]
+        pub auto_failover_disabled: bool,
+    }
+    /// An ExcludeReplicas contains a repeated set of ReplicaSelection that should
+    /// be excluded from serving requests.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ExcludeReplicas {
+        /// The directed read replica selector.
+        #[prost(message, repeated, tag = "1")]
+        pub replica_selections: ::prost::alloc::vec::Vec<ReplicaSelection>,
+    }
+    /// Required. At most one of either include_replicas or exclude_replicas
+    /// should be present in the message.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Replicas {
+        /// Include_replicas indicates the order of replicas (as they appear in
+        /// this list) to process the request. If auto_failover_disabled is set to
+        /// true and all replicas are exhausted without finding a healthy replica,
+        /// Spanner will wait for a replica in the list to become available, requests
+        /// may fail due to `DEADLINE_EXCEEDED` errors.
+        #[prost(message, tag = "1")]
+        IncludeReplicas(IncludeReplicas),
+        /// Exclude_replicas indicates that specified replicas should be excluded
+        /// from serving requests. Spanner will not route requests to the replicas
+        /// in this list.
+        #[prost(message, tag = "2")]
+        ExcludeReplicas(ExcludeReplicas),
+    }
+}
 /// The request for \[ExecuteSql][google.spanner.v1.Spanner.ExecuteSql\] and
 /// \[ExecuteStreamingSql][google.spanner.v1.Spanner.ExecuteStreamingSql\].
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -1613,6 +1775,16 @@ pub struct ExecuteSqlRequest {
     /// Common options for this request.
     #[prost(message, optional, tag = "11")]
     pub request_options: ::core::option::Option<RequestOptions>,
+    /// Directed read options for this request.
+    #[prost(message, optional, tag = "15")]
+    pub directed_read_options: ::core::option::Option<DirectedReadOptions>,
+    /// If this is for a partitioned query and this field is set to `true`, the
+    /// request will be executed via Spanner independent compute resources.
+    ///
+    /// If the field is set to `true` but the request does not set
+    /// `partition_token`, the API will return an `INVALID_ARGUMENT` error.
+    #[prost(bool, tag = "16")]
+    pub data_boost_enabled: bool,
 }
 /// Nested message and enum types in `ExecuteSqlRequest`.
 pub mod execute_sql_request {
@@ -2004,6 +2176,16 @@ pub struct ReadRequest {
     /// Common options for this request.
     #[prost(message, optional, tag = "11")]
     pub request_options: ::core::option::Option<RequestOptions>,
+    /// Directed read options for this request.
+    #[prost(message, optional, tag = "14")]
+    pub directed_read_options: ::core::option::Option<DirectedReadOptions>,
+    /// If this is for a partitioned read and this field is set to `true`, the
+    /// request will be executed via Spanner independent compute resources.
+    ///
+    /// If the field is set to `true` but the request does not set
+    /// `partition_token`, the API will return an `INVALID_ARGUMENT` error.
+    #[prost(bool, tag = "15")]
+    pub data_boost_enabled: bool,
 }
 /// The request for \[BeginTransaction][google.spanner.v1.Spanner.BeginTransaction\].
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2040,6 +2222,13 @@ pub struct CommitRequest {
     /// `false`.
     #[prost(bool, tag = "5")]
     pub return_commit_stats: bool,
+    /// Optional. The amount of latency this request is willing to incur in order
+    /// to improve throughput. If this field is not set, Spanner assumes requests
+    /// are relatively latency sensitive and automatically determines an
+    /// appropriate delay time. You can specify a batching delay value between 0
+    /// and 500 ms.
+    #[prost(message, optional, tag = "8")]
+    pub max_commit_delay: ::core::option::Option<::prost_types::Duration>,
     /// Common options for this request.
     #[prost(message, optional, tag = "6")]
     pub request_options: ::core::option::Option<RequestOptions>,
@@ -2080,6 +2269,63 @@ pub struct RollbackRequest {
     #[prost(bytes = "vec", tag = "2")]
     pub transaction_id: ::prost::alloc::vec::Vec<u8>,
 }
+/// The request for \[BatchWrite][google.spanner.v1.Spanner.BatchWrite\].
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchWriteRequest {
+    /// Required. The session in which the batch request is to be run.
+    #[prost(string, tag = "1")]
+    pub session: ::prost::alloc::string::String,
+    /// Common options for this request.
+    #[prost(message, optional, tag = "3")]
+    pub request_options: ::core::option::Option<RequestOptions>,
+    /// Required. The groups of mutations to be applied.
+    #[prost(message, repeated, tag = "4")]
+    pub mutation_groups: ::prost::alloc::vec::Vec<batch_write_request::MutationGroup>,
+    /// Optional. When `exclude_txn_from_change_streams` is set to `true`:
+    ///   * Mutations from all transactions in this batch write operation will not
+    ///   be recorded in change streams with DDL option `allow_txn_exclusion=true`
+    ///   that are tracking columns modified by these transactions.
+    ///   * Mutations from all transactions in this batch write operation will be
+    ///   recorded in change streams with DDL option `allow_txn_exclusion=false or
+    ///   not set` that are tracking columns modified by these transactions.
+    ///
+    /// When `exclude_txn_from_change_streams` is set to `false` or not set,
+    /// mutations from all transactions in this batch write operation will be
+    /// recorded in all change streams that are tracking columns modified by these
+    /// transactions.
+    #[prost(bool, tag = "5")]
+    pub exclude_txn_from_change_streams: bool,
+}
+/// Nested message and enum types in `BatchWriteRequest`.
+pub mod batch_write_request {
+    /// A group of mutations to be committed together. Related mutations should be
+    /// placed in a group. For example, two mutations inserting rows with the same
+    /// primary key prefix in both parent and child tables are related.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct MutationGroup {
+        /// Required. The mutations in this group.
+        #[prost(message, repeated, tag = "1")]
+        pub mutations: ::prost::alloc::vec::Vec<super::Mutation>,
+    }
+}
+/// The result of applying a batch of mutations.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchWriteResponse {
+    /// The mutation groups applied in this batch. The values index into the
+    /// `mutation_groups` field in the corresponding `BatchWriteRequest`.
+    #[prost(int32, repeated, tag = "1")]
+    pub indexes: ::prost::alloc::vec::Vec<i32>,
+    /// An `OK` status indicates success. Any other status indicates a failure.
+    #[prost(message, optional, tag = "2")]
+    pub status: ::core::option::Option<super::super::rpc::Status>,
+    /// The commit timestamp of the transaction that applied this batch.
+    /// Present if `status` is `OK`, absent otherwise.
+    #[prost(message, optional, tag = "3")]
+    pub commit_timestamp: ::core::option::Option<::prost_types::Timestamp>,
+}
 /// Generated client implementations.
 pub mod spanner_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -2390,6 +2636,32 @@ pub mod spanner_client {
             let path = http::uri::PathAndQuery::from_static("/google.spanner.v1.Spanner/Rollback");
             self.inner.unary(request.into_request(), path, codec).await
         }
+        /// Batches the supplied mutation groups in a collection of efficient
+        /// transactions. All mutations in a group are committed atomically. However,
+        /// mutations across groups can be committed non-atomically in an unspecified
+        /// order and thus, they must be independent of each other. Partial failure is
+        /// possible, i.e., some groups may have been committed successfully, while
+        /// some may have failed. The results of individual batches are streamed into
+        /// the response as the batches are applied.
+        ///
+        /// BatchWrite requests are not replay protected, meaning that each mutation
+        /// group may be applied more than once. Replays of non-idempotent mutations
+        /// may have undesirable effects. For example, replays of an insert mutation
+        /// may produce an already exists error or if you use generated or commit
+        /// timestamp-based keys, it may result in additional rows being added to the
+        /// mutation's table. We recommend structuring your mutation groups to be
+        /// idempotent to avoid this issue.
+        pub async fn batch_write(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BatchWriteRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::BatchWriteResponse>>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(tonic::Code::Unknown, format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/google.spanner.v1.Spanner/BatchWrite");
+            self.inner.server_streaming(request.into_request(), path, codec).await
+        }
         /// Creates a set of partition tokens that can be used to execute a query
         /// operation in parallel.  Each of the returned partition tokens can be used
         /// by [ExecuteStreamingSql][google.spanner.v1.Spanner.ExecuteStreamingSql] to specify a subset
@@ -1,4 +1,4 @@
-use crate::symbol::{COLUMN, COLUMN_NAME, COMMIT_TIMESTAMP};
+use crate::symbol::{COLUMN, COLUMN_NAME, COMMIT_TIMESTAMP, DEFAULT, SKIP};
 use convert_case::{Case, Casing};
 use syn::ext::IdentExt;
 use syn::spanned::Spanned;
@@ -11,6 +11,8 @@ pub(crate) struct Column<'a> {
     field: &'a Field,
     pub column_name: Option<String>,
     pub commit_timestamp: bool,
+    pub skip: bool,
+    pub default: bool,
 }
 
 impl<'a> Column<'a> {
@@ -29,6 +31,8 @@ impl<'a> From<&'a Field> for Column<'a> {
     /// Extract out the `#[column(...)]` attributes from a struct field.
     fn from(field: &'a Field) -> Self {
         let mut commit_timestamp = false;
+        let mut skip = false;
+        let mut default = false;
         let mut column_name = None;
         for meta_item in field.attrs.iter().flat_map(|attr| get_meta_items(attr).unwrap()) {
             match &meta_item {
@@ -42,6 +46,14 @@ impl<'a> From<&'a Field> for Column<'a> {
                 Meta(Path(word)) if word == COMMIT_TIMESTAMP => {
                     commit_timestamp = true;
                 }
+                // Parse `#[column(skip)]`
+                Meta(Path(word)) if word == SKIP => {
+                    skip = true;
+                }
+                // Parse `#[column(default)]`
+                Meta(Path(word)) if word == DEFAULT => {
+                    default = true;
+                }
                 _ => {}
             }
         }
@@ -49,6 +61,8 @@ impl<'a> From<&'a Field> for Column<'a> {
         Self {
             field,
             commit_timestamp,
+            skip,
+            default,
             column_name,
         }
     }
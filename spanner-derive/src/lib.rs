@@ -105,6 +105,32 @@
 //! }
 //!```
 //!
+//! ### ToStruct derive
+//!
+//! `#[derive(ToStruct)]` generates only the implementation of `ToStruct`.
+//! It is used for the structs that are only written, such as the parameters of the statement.
+//! `#[spanner(skip)]` excludes the field that is not a column.
+//!
+//! ```ignore
+//! use google_cloud_spanner::statement::Statement;
+//! use google_cloud_spanner_derive::ToStruct;
+//!
+//! #[derive(ToStruct)]
+//! pub struct UserLevel {
+//!     pub user_id: String,
+//!     #[spanner(name = "LevelX")]
+//!     pub level: i64,
+//!     #[spanner(skip)]
+//!     pub cached: bool,
+//! }
+//!
+//! fn statement(user_level: &UserLevel) -> Statement {
+//!     Statement::from_struct("UPDATE UserCharacter SET LevelX = @LevelX WHERE UserId = @UserId", user_level).unwrap()
+//! }
+//! ```
+//!
+//! The skipped fields are set to `Default::default()` when `Table` or `Query` is derived.
+//!
 //! ### Query derive
 //!
 //! `#[derive(Query)]` generates the implementation for following traits.
@@ -152,20 +178,103 @@
 //!    }
 //! }
 //! ```
+//!
+//! `#[spanner(default)]` sets `Default::default()` to the field if the column is absent from the result set.
+//! The error of the field is returned as `google_cloud_spanner::row::Error::FieldError` with the names of the field and the column.
+//!
+//! ```ignore
+//! use google_cloud_spanner_derive::Query;
+//!
+//! #[derive(Query)]
+//! pub struct UserSummary {
+//!     pub user_id: String,
+//!     // NULL is read as None
+//!     pub nickname: Option<String>,
+//!     // not selected by some queries
+//!     #[spanner(default)]
+//!     pub item_count: i64,
+//! }
+//! ```
+//!
+//! ### ToSpannerValue derive
+//!
+//! `#[derive(ToSpannerValue)]` generates the implementation for following traits for enums with unit variants.
+//! * `ToKind`
+//! * `TryFromValue`
+//!
+//! The variants are mapped to STRING by default. Use `#[spanner(repr = "int64")]` to map them to their discriminants.
+//!
+//! ```ignore
+//! use google_cloud_spanner_derive::{Table, ToSpannerValue};
+//!
+//! #[derive(ToSpannerValue)]
+//! #[spanner(repr = "int64")]
+//! pub enum Priority {
+//!     Low = 1,
+//!     High = 10,
+//! }
+//!
+//! #[derive(ToSpannerValue)]
+//! #[spanner(rename_all = "SCREAMING_SNAKE_CASE")]
+//! pub enum Status {
+//!     // IN_PROGRESS
+//!     InProgress,
+//!     // #[spanner(name=...) is used when the value differs from the variant name
+//!     #[spanner(name = "DONE")]
+//!     Completed,
+//! }
+//!
+//! #[derive(Table)]
+//! pub struct Task {
+//!     pub task_id: String,
+//!     pub priority: Priority,
+//!     pub status: Option<Status>,
+//! }
+//! ```
+//!
+//! An unknown value is returned as `google_cloud_spanner::row::Error::UnknownEnumValue` when reading.
+//!
+//! ### statement! macro
+//!
+//! `statement!` creates a `Statement` and binds the parameters.
+//! The '@' parameters in the SQL are checked at compile time, so that
+//! a parameter used but not bound, or bound but not used, is a compile error.
+//! The values are bound by reference, like `Statement::add_param`.
+//! Add `allow_unused` to bind the parameters that are not used in the SQL.
+//!
+//! ```ignore
+//! use google_cloud_spanner::statement::Statement;
+//! use google_cloud_spanner_derive::statement;
+//!
+//! fn find_user(user_id: &str, name: &String) -> Statement {
+//!     statement!("SELECT * FROM User WHERE UserId = @user_id AND Name = @name", user_id = user_id, name = name)
+//! }
+//!
+//! fn find_user_with_unused(user_id: &str) -> Statement {
+//!     statement!("SELECT * FROM User WHERE UserId = @user_id", user_id = user_id, premium = true, allow_unused)
+//! }
+//! ```
 
 mod column;
 mod query;
+mod statement;
 mod symbol;
 mod table;
+mod value;
 
 use proc_macro::TokenStream;
 use quote::{quote, ToTokens};
-use syn::{parse_macro_input, ItemStruct};
+use syn::{parse_macro_input, ItemEnum, ItemStruct};
+
+use crate::statement::StatementInput;
 
 #[proc_macro_derive(Table, attributes(spanner))]
 pub fn table(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as ItemStruct);
-    let table = table::generate_table_methods(item.clone());
+    let table = match table::generate_table_methods(item.clone()) {
+        Ok(table) => table,
+        Err(e) => return e.into_compile_error().into(),
+    };
     let query = query::generate_query_methods(item);
     wrap_in_dummy_mod(quote! {
         #table
@@ -173,6 +282,15 @@ pub fn table(input: TokenStream) -> TokenStream {
     })
 }
 
+#[proc_macro_derive(ToStruct, attributes(spanner))]
+pub fn to_struct(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemStruct);
+    match table::generate_table_methods(item) {
+        Ok(table) => wrap_in_dummy_mod(table),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
 #[proc_macro_derive(Query, attributes(spanner))]
 pub fn query(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as ItemStruct);
@@ -180,13 +298,35 @@ pub fn query(input: TokenStream) -> TokenStream {
     wrap_in_dummy_mod(query)
 }
 
+#[proc_macro_derive(ToSpannerValue, attributes(spanner))]
+pub fn to_spanner_value(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemEnum);
+    match value::generate_value_methods(item) {
+        Ok(value) => wrap_in_dummy_mod(value),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+pub fn statement(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as StatementInput);
+    match statement::generate_statement(input) {
+        Ok(stmt) => stmt.into_token_stream().into(),
+        Err(e) => {
+            // wrapped in a block to report more than one error in the expression position
+            let errors = e.into_compile_error();
+            quote! {{ #errors }}.into()
+        }
+    }
+}
+
 fn wrap_in_dummy_mod(item: impl ToTokens) -> TokenStream {
     //reference https://github.com/diesel-rs/diesel/blob/94599bdc86692900c888974bb4a03568799978d3/diesel_derives/src/util.rs
     let wrapped = quote! {
         #[allow(unused_imports)]
         const _: () = {
-            use google_cloud_spanner::statement::{ToStruct, ToKind, Kinds, Types};
-            use google_cloud_spanner::row::{Struct, TryFromValue, TryFromStruct, Row, Error as RowError};
+            use google_cloud_spanner::statement::{ToStruct, ToKind, Kinds, Types, Kind, Type};
+            use google_cloud_spanner::row::{Struct, TryFromValue, TryFromStruct, Row, Error as RowError, Field, Value};
             use google_cloud_spanner::value::CommitTimestamp;
             use std::convert::TryFrom;
 
@@ -1,3 +1,4 @@
+use syn::ext::IdentExt;
 use syn::ItemStruct;
 
 use quote::{quote, ToTokens};
@@ -11,10 +12,31 @@ pub(crate) fn generate_query_methods(item: ItemStruct) -> impl ToTokens {
     for field in &item.fields {
         let field_var = field.ident.as_ref().unwrap();
         let column = Column::from(field);
+        if column.skip {
+            try_from_struct_fields.push(quote! {
+                #field_var: Default::default()
+            });
+            continue;
+        }
         let column_name = column.name();
-        try_from_struct_fields.push(quote! {
-            #field_var: s.column_by_name(#column_name)?
-        });
+        let field_name = format!("{struct_name}.{}", field_var.unraw());
+        let ty = &field.ty;
+        let to_field_error =
+            quote! { |e| RowError::FieldError(#field_name.to_string(), #column_name.to_string(), Box::new(e)) };
+        let value = quote! { s.column_by_name(#column_name).map_err(#to_field_error) };
+        if column.default {
+            // the column absent from the result set
+            try_from_struct_fields.push(quote! {
+                #field_var: match s.column_by_name::<#ty>(#column_name) {
+                    Err(RowError::NoColumnFound(_)) => Default::default(),
+                    r => r.map_err(#to_field_error)?,
+                }
+            });
+        } else {
+            try_from_struct_fields.push(quote! {
+                #field_var: #value?
+            });
+        }
     }
 
     quote! {
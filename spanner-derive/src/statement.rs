@@ -0,0 +1,98 @@
+use std::collections::BTreeSet;
+
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Error, Expr, Ident, LitStr, Token};
+
+const ALLOW_UNUSED: &str = "allow_unused";
+
+pub(crate) struct StatementInput {
+    sql: LitStr,
+    bindings: Vec<(Ident, Expr)>,
+    allow_unused: bool,
+}
+
+enum Argument {
+    Binding(Ident, Box<Expr>),
+    AllowUnused,
+}
+
+impl Parse for Argument {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            return Ok(Argument::Binding(name, Box::new(input.parse()?)));
+        }
+        if name == ALLOW_UNUSED {
+            Ok(Argument::AllowUnused)
+        } else {
+            Err(Error::new(name.span(), "expected `name = value` or `allow_unused`"))
+        }
+    }
+}
+
+impl Parse for StatementInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let sql: LitStr = input.parse()?;
+        let mut bindings = vec![];
+        let mut allow_unused = false;
+        if input.parse::<Option<Token![,]>>()?.is_some() {
+            for argument in Punctuated::<Argument, Token![,]>::parse_terminated(input)? {
+                match argument {
+                    Argument::Binding(name, value) => bindings.push((name, *value)),
+                    Argument::AllowUnused => allow_unused = true,
+                }
+            }
+        }
+        Ok(StatementInput {
+            sql,
+            bindings,
+            allow_unused,
+        })
+    }
+}
+
+pub(crate) fn generate_statement(input: StatementInput) -> Result<impl ToTokens, Error> {
+    let sql = input.sql.value();
+    let placeholders = google_cloud_spanner_sql::param_names(&sql);
+
+    let mut errors: Vec<Error> = vec![];
+    let mut bound = BTreeSet::new();
+    for (name, _) in &input.bindings {
+        if !bound.insert(name.to_string()) {
+            errors.push(Error::new(name.span(), format!("parameter `{name}` is bound more than once")));
+        } else if !input.allow_unused && !placeholders.contains(&name.to_string()) {
+            errors.push(Error::new(
+                name.span(),
+                format!("parameter `{name}` is not used in the SQL, add `allow_unused` to bind it anyway"),
+            ));
+        }
+    }
+    let unbound: Vec<String> = placeholders.difference(&bound).map(|v| format!("@{v}")).collect();
+    if !unbound.is_empty() {
+        errors.insert(
+            0,
+            Error::new(input.sql.span(), format!("parameters are not bound: {}", unbound.join(", "))),
+        );
+    }
+    // report all the errors at once
+    if let Some(mut error) = errors.first().cloned() {
+        errors.into_iter().skip(1).for_each(|e| error.combine(e));
+        return Err(error);
+    }
+
+    let sql = &input.sql;
+    let names = input.bindings.iter().map(|(name, _)| name.to_string());
+    let values = input.bindings.iter().map(|(_, value)| value);
+    Ok(quote! {
+        {
+            let mut stmt = google_cloud_spanner::statement::Statement::new(#sql);
+            #(
+                stmt.add_param(#names, &(#values));
+            )*
+            stmt
+        }
+    })
+}
@@ -8,6 +8,10 @@ pub(crate) struct Symbol(&'static str);
 pub(crate) const COMMIT_TIMESTAMP: Symbol = Symbol("commitTimestamp");
 pub(crate) const COLUMN_NAME: Symbol = Symbol("name");
 pub(crate) const COLUMN: Symbol = Symbol("spanner");
+pub(crate) const DEFAULT: Symbol = Symbol("default");
+pub(crate) const SKIP: Symbol = Symbol("skip");
+pub(crate) const REPR: Symbol = Symbol("repr");
+pub(crate) const RENAME_ALL: Symbol = Symbol("rename_all");
 
 impl PartialEq<Symbol> for Ident {
     fn eq(&self, word: &Symbol) -> bool {
@@ -1,34 +1,45 @@
-use syn::ItemStruct;
+use syn::spanned::Spanned;
+use syn::{Error, Fields, ItemStruct};
 
-use quote::{quote, ToTokens};
+use quote::{quote, quote_spanned, ToTokens};
 
 use crate::column::Column;
 
-pub(crate) fn generate_table_methods(item: ItemStruct) -> impl ToTokens {
+pub(crate) fn generate_table_methods(item: ItemStruct) -> Result<impl ToTokens, Error> {
     let struct_name = item.ident;
+    if !matches!(item.fields, Fields::Named(_)) {
+        return Err(Error::new(
+            item.fields.span(),
+            "ToStruct can only be derived for structs with named fields",
+        ));
+    }
 
     let mut to_kinds_fields = Vec::with_capacity(item.fields.len());
     let mut get_types_fields = Vec::with_capacity(item.fields.len());
     for field in &item.fields {
         let field_var = field.ident.as_ref().unwrap();
         let column = Column::from(field);
+        if column.skip {
+            continue;
+        }
         let column_name = column.name();
         let ty = &field.ty;
-        let mut get_field_type = quote! { <#ty> };
+        // spanned to report the field type that does not implement ToKind
+        let mut get_field_type = quote_spanned! { ty.span()=> <#ty> };
         let mut to_kind_field_type = quote! { self.#field_var };
         if column.commit_timestamp {
             get_field_type = quote! { CommitTimestamp };
             to_kind_field_type = quote! { CommitTimestamp::new() };
         }
-        to_kinds_fields.push(quote! {
+        to_kinds_fields.push(quote_spanned! { ty.span()=>
             (#column_name, #to_kind_field_type.to_kind())
         });
-        get_types_fields.push(quote! {
+        get_types_fields.push(quote_spanned! { ty.span()=>
             (#column_name, #get_field_type::get_type())
         });
     }
 
-    quote! {
+    Ok(quote! {
 
         impl ToStruct for #struct_name  {
 
@@ -47,5 +58,5 @@ pub(crate) fn generate_table_methods(item: ItemStruct) -> impl ToTokens {
                 ]
             }
         }
-    }
+    })
 }
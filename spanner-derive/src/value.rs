@@ -0,0 +1,160 @@
+use crate::symbol::{COLUMN, COLUMN_NAME, RENAME_ALL, REPR};
+use convert_case::{Case, Casing};
+use quote::{quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::Lit::Str;
+use syn::Meta::{List, NameValue};
+use syn::NestedMeta::Meta;
+use syn::{Attribute, Error, Fields, ItemEnum};
+
+enum Repr {
+    Int64,
+    String,
+}
+
+struct Container {
+    repr: Repr,
+    rename_all: Option<Case>,
+}
+
+pub(crate) fn generate_value_methods(item: ItemEnum) -> Result<impl ToTokens, Error> {
+    let enum_name = &item.ident;
+    let container = parse_container(&item.attrs)?;
+
+    let mut variants = Vec::with_capacity(item.variants.len());
+    let mut values = Vec::with_capacity(item.variants.len());
+    for variant in &item.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(variant.span(), "only unit variants are supported"));
+        }
+        let variant_var = &variant.ident;
+        variants.push(variant_var);
+        match container.repr {
+            Repr::Int64 => values.push(quote! { #enum_name::#variant_var as i64 }),
+            Repr::String => {
+                let name = match variant_name(&variant.attrs)? {
+                    Some(name) => name,
+                    None => match container.rename_all {
+                        Some(case) => variant_var.unraw().to_string().to_case(case),
+                        None => variant_var.unraw().to_string(),
+                    },
+                };
+                values.push(quote! { #name });
+            }
+        }
+    }
+
+    let (value_type, to_value, format_value) = match container.repr {
+        Repr::Int64 => (quote! { i64 }, quote! { v }, quote! { v.to_string() }),
+        Repr::String => (quote! { String }, quote! { v.as_str() }, quote! { v }),
+    };
+
+    Ok(quote! {
+        impl ToKind for #enum_name {
+            fn to_kind(&self) -> Kind {
+                let v: #value_type = match self {
+                    #(
+                        #enum_name::#variants => (#values).into(),
+                    )*
+                };
+                v.to_kind()
+            }
+            fn get_type() -> Type {
+                <#value_type>::get_type()
+            }
+        }
+
+        impl TryFromValue for #enum_name {
+            fn try_from(item: &Value, field: &Field) -> Result<Self, RowError> {
+                let v = <#value_type as TryFromValue>::try_from(item, field)?;
+                #(
+                    if #to_value == (#values) {
+                        return Ok(#enum_name::#variants);
+                    }
+                )*
+                Err(RowError::UnknownEnumValue(field.name.to_string(), #format_value))
+            }
+        }
+    })
+}
+
+fn parse_container(attrs: &[Attribute]) -> Result<Container, Error> {
+    let mut container = Container {
+        repr: Repr::String,
+        rename_all: None,
+    };
+    for meta_item in attrs
+        .iter()
+        .map(get_meta_items)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+    {
+        match &meta_item {
+            // Parse `#[spanner(repr = "int64")]`
+            Meta(NameValue(m)) if m.path == REPR => {
+                container.repr = match &m.lit {
+                    Str(s) if s.value() == "int64" => Repr::Int64,
+                    Str(s) if s.value() == "string" => Repr::String,
+                    _ => return Err(Error::new(m.lit.span(), "expected \"int64\" or \"string\"")),
+                }
+            }
+            // Parse `#[spanner(rename_all = "SCREAMING_SNAKE_CASE")]`
+            Meta(NameValue(m)) if m.path == RENAME_ALL => {
+                container.rename_all = match &m.lit {
+                    Str(s) => Some(to_case(&s.value()).ok_or_else(|| Error::new(s.span(), "unknown case"))?),
+                    _ => return Err(Error::new(m.lit.span(), "expected string")),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(container)
+}
+
+fn variant_name(attrs: &[Attribute]) -> Result<Option<String>, Error> {
+    for meta_item in attrs
+        .iter()
+        .map(get_meta_items)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+    {
+        // Parse `#[spanner(name = "foo")]`
+        if let Meta(NameValue(m)) = &meta_item {
+            if m.path == COLUMN_NAME {
+                if let Str(s) = &m.lit {
+                    return Ok(Some(s.value()));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn to_case(value: &str) -> Option<Case> {
+    let case = match value {
+        "lowercase" => Case::Flat,
+        "UPPERCASE" => Case::UpperFlat,
+        "PascalCase" => Case::Pascal,
+        "camelCase" => Case::Camel,
+        "snake_case" => Case::Snake,
+        "SCREAMING_SNAKE_CASE" => Case::UpperSnake,
+        "kebab-case" => Case::Kebab,
+        "SCREAMING-KEBAB-CASE" => Case::UpperKebab,
+        _ => return None,
+    };
+    Some(case)
+}
+
+fn get_meta_items(attr: &Attribute) -> Result<Vec<syn::NestedMeta>, Error> {
+    if attr.path != COLUMN {
+        return Ok(Vec::new());
+    }
+
+    match attr.parse_meta()? {
+        List(meta) => Ok(meta.nested.into_iter().collect()),
+        _ => Err(Error::new(attr.span(), "expected [spanner(...)]")),
+    }
+}
@@ -0,0 +1,7 @@
+#[test]
+fn test_compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/statement_*.rs");
+    t.compile_fail("tests/ui/to_struct_tuple.rs");
+    t.pass("tests/ui/to_struct_skip.rs");
+}
@@ -1,11 +1,15 @@
 use google_cloud_spanner::client::Client;
 use google_cloud_spanner::mutation::insert_struct;
 use google_cloud_spanner::reader::AsyncIterator;
+use google_cloud_spanner::row::{Error as RowError, Field, Row, Value};
 use google_cloud_spanner::statement::Statement;
+use google_cloud_spanner::statement::{Kind, ToKind, ToStruct};
 use google_cloud_spanner::value::SpannerNumeric;
-use google_cloud_spanner_derive::{Query, Table};
+use google_cloud_spanner_derive::{statement, Query, Table, ToSpannerValue, ToStruct};
 use serde::{Deserialize, Serialize};
 use serial_test::serial;
+use std::collections::HashMap;
+use std::sync::Arc;
 use time::{Date, OffsetDateTime};
 
 #[derive(Table, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -189,3 +193,253 @@ async fn test_query_derive() -> Result<(), anyhow::Error> {
     }
     Ok(())
 }
+
+#[derive(ToSpannerValue, Clone, Copy, Debug, PartialEq, Eq)]
+#[spanner(repr = "int64")]
+pub enum Priority {
+    Low = 1,
+    High = 10,
+}
+
+#[derive(ToSpannerValue, Clone, Copy, Debug, PartialEq, Eq)]
+#[spanner(repr = "string", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Status {
+    InProgress,
+    #[spanner(name = "DONE")]
+    Completed,
+}
+
+#[derive(Table, Debug, PartialEq)]
+pub struct Task {
+    pub priority: Priority,
+    pub status: Option<Status>,
+    pub history: Vec<Status>,
+}
+
+fn single_row(kinds: Vec<(&'static str, Kind)>) -> Row {
+    let mut index = HashMap::new();
+    let mut fields = vec![];
+    let mut values = vec![];
+    for (i, (name, kind)) in kinds.into_iter().enumerate() {
+        index.insert(name.to_string(), i);
+        fields.push(Field {
+            name: name.to_string(),
+            r#type: None,
+        });
+        values.push(Value { kind: Some(kind) });
+    }
+    Row::new(Arc::new(index), Arc::new(fields), values)
+}
+
+#[test]
+fn test_to_spanner_value_derive() {
+    assert_eq!(Priority::High.to_kind(), Kind::StringValue("10".to_string()));
+    assert_eq!(Priority::get_type(), i64::get_type());
+    assert_eq!(Status::InProgress.to_kind(), Kind::StringValue("IN_PROGRESS".to_string()));
+    assert_eq!(Status::Completed.to_kind(), Kind::StringValue("DONE".to_string()));
+    assert_eq!(Status::get_type(), String::get_type());
+
+    let task = Task {
+        priority: Priority::Low,
+        status: None,
+        history: vec![Status::InProgress, Status::Completed],
+    };
+    let decoded: Task = single_row(task.to_kinds()).try_into().unwrap();
+    assert_eq!(decoded, task);
+
+    let row = single_row(vec![("Status", "UNKNOWN".to_kind()), ("Priority", 2_i64.to_kind())]);
+    match row.column_by_name::<Status>("Status") {
+        Err(RowError::UnknownEnumValue(field, value)) => {
+            assert_eq!((field.as_str(), value.as_str()), ("Status", "UNKNOWN"))
+        }
+        _ => panic!("must be unknown enum value"),
+    }
+    match row.column_by_name::<Priority>("Priority") {
+        Err(RowError::UnknownEnumValue(field, value)) => {
+            assert_eq!((field.as_str(), value.as_str()), ("Priority", "2"))
+        }
+        _ => panic!("must be unknown enum value"),
+    }
+}
+
+#[derive(ToStruct)]
+pub struct UserLevel {
+    pub user_id: String,
+    #[spanner(name = "LevelX")]
+    pub level: Option<i64>,
+    pub tags: Vec<String>,
+    pub item: Option<UserItem>,
+    #[spanner(commitTimestamp)]
+    pub updated_at: OffsetDateTime,
+    #[spanner(skip)]
+    #[allow(dead_code)]
+    pub cached: HashMap<String, String>,
+}
+
+#[derive(Table, Debug, PartialEq)]
+pub struct SkippedTask {
+    pub priority: Priority,
+    #[spanner(skip)]
+    pub memo: String,
+}
+
+#[test]
+fn test_to_struct_derive() {
+    let user_level = UserLevel {
+        user_id: "user1".to_string(),
+        level: None,
+        tags: vec!["a".to_string()],
+        item: Some(UserItem::default()),
+        updated_at: OffsetDateTime::UNIX_EPOCH,
+        cached: HashMap::from([("k".to_string(), "v".to_string())]),
+    };
+    let kinds = user_level.to_kinds();
+    let types = UserLevel::get_types();
+    assert_eq!(
+        kinds.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+        vec!["UserId", "LevelX", "Tags", "Item", "UpdatedAt"]
+    );
+    assert_eq!(
+        types.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+        kinds.iter().map(|(name, _)| *name).collect::<Vec<_>>()
+    );
+    assert_eq!(kinds[1].1, Option::<i64>::None.to_kind());
+    assert_eq!(kinds[3].1, UserItem::default().to_kind());
+    assert_eq!(types[3].1, UserItem::get_type());
+    assert_eq!(kinds[4].1, "spanner.commit_timestamp()".to_kind());
+
+    Statement::from_struct("UPDATE UserCharacter SET LevelX = @LevelX WHERE UserId = @UserId", &user_level).unwrap();
+
+    let task = SkippedTask {
+        priority: Priority::High,
+        memo: "not a column".to_string(),
+    };
+    assert_eq!(SkippedTask::get_types().len(), 1);
+    let decoded: SkippedTask = single_row(task.to_kinds()).try_into().unwrap();
+    assert_eq!(decoded.priority, Priority::High);
+    assert_eq!(decoded.memo, "");
+}
+
+#[test]
+fn test_statement_macro() {
+    let user_id = "user1";
+    let name = "name".to_string();
+    let stmt = statement!(
+        "SELECT * FROM User WHERE UserId = @user_id AND Name = @name OR UserId = @user_id",
+        user_id = user_id,
+        name = &name
+    );
+    let mut expected =
+        Statement::new("SELECT * FROM User WHERE UserId = @user_id AND Name = @name OR UserId = @user_id");
+    expected.add_param("user_id", &user_id);
+    expected.add_param("name", &name);
+    assert_eq!(stmt, expected);
+
+    // '@' in the string literals, comments, hints and system variables are not parameters
+    let stmt = statement!(
+        "SELECT '@a', \"@b\", `@c`, @@d FROM User@{FORCE_INDEX=UserIndex} -- @e
+        # @f
+        /* @g */ WHERE Level = @level",
+        level = 1_i64
+    );
+    let expected = Statement::new(
+        "SELECT '@a', \"@b\", `@c`, @@d FROM User@{FORCE_INDEX=UserIndex} -- @e
+        # @f
+        /* @g */ WHERE Level = @level",
+    );
+    assert_eq!(stmt, expected.with_param("level", &1_i64));
+
+    let stmt = statement!("SELECT 1", premium = true, allow_unused);
+    assert_eq!(stmt, Statement::new("SELECT 1").with_param("premium", &true));
+    assert_eq!(statement!("SELECT 1"), Statement::new("SELECT 1"));
+}
+
+#[derive(Query, Debug, PartialEq)]
+pub struct UserSummary {
+    pub user_id: String,
+    pub nickname: Option<String>,
+    #[spanner(name = "Levels")]
+    pub level_history: Vec<i64>,
+    #[spanner(default)]
+    pub item_count: i64,
+}
+
+#[test]
+fn test_query_derive_mapping() {
+    let row = single_row(vec![
+        ("UserId", "user1".to_kind()),
+        ("Nickname", Option::<String>::None.to_kind()),
+        ("Levels", vec![1_i64, 2].to_kind()),
+    ]);
+    let summary: UserSummary = row.try_into().unwrap();
+    assert_eq!(
+        summary,
+        UserSummary {
+            user_id: "user1".to_string(),
+            nickname: None,
+            level_history: vec![1, 2],
+            item_count: 0,
+        }
+    );
+
+    let row = single_row(vec![
+        ("UserId", "user1".to_kind()),
+        ("Nickname", "nick".to_kind()),
+        ("Levels", vec![1_i64].to_kind()),
+        ("ItemCount", 3_i64.to_kind()),
+    ]);
+    let summary: UserSummary = row.try_into().unwrap();
+    assert_eq!((summary.nickname.as_deref(), summary.item_count), (Some("nick"), 3));
+
+    // the required column is missing
+    let row = single_row(vec![("UserId", "user1".to_kind()), ("Nickname", "nick".to_kind())]);
+    match UserSummary::try_from(row) {
+        Err(RowError::FieldError(field, column, e)) => {
+            assert_eq!((field.as_str(), column.as_str()), ("UserSummary.level_history", "Levels"));
+            assert!(matches!(*e, RowError::NoColumnFound(_)));
+        }
+        r => panic!("must be field error {:?}", r),
+    }
+
+    // the default column is present but invalid
+    let row = single_row(vec![
+        ("UserId", "user1".to_kind()),
+        ("Nickname", "nick".to_kind()),
+        ("Levels", vec![1_i64].to_kind()),
+        ("ItemCount", "x".to_kind()),
+    ]);
+    match UserSummary::try_from(row) {
+        Err(RowError::FieldError(field, column, _)) => {
+            assert_eq!((field.as_str(), column.as_str()), ("UserSummary.item_count", "ItemCount"))
+        }
+        r => panic!("must be field error {:?}", r),
+    }
+}
+
+#[derive(Query, Debug, PartialEq)]
+pub struct Character {
+    pub level: i32,
+    pub hit_points: u16,
+}
+
+#[test]
+fn test_query_derive_narrow_int() {
+    let row = single_row(vec![("Level", 99_i64.to_kind()), ("HitPoints", 500_i64.to_kind())]);
+    let character: Character = row.try_into().unwrap();
+    assert_eq!(
+        character,
+        Character {
+            level: 99,
+            hit_points: 500
+        }
+    );
+
+    let row = single_row(vec![("Level", i64::MAX.to_kind()), ("HitPoints", 500_i64.to_kind())]);
+    match Character::try_from(row) {
+        Err(RowError::FieldError(field, _, e)) => {
+            assert_eq!(field, "Character.level");
+            assert!(matches!(*e, RowError::OutOfRange(_, _, "i32")), "{e}");
+        }
+        r => panic!("must be out of range {:?}", r),
+    }
+}
@@ -0,0 +1,5 @@
+use google_cloud_spanner_derive::statement;
+
+fn main() {
+    let _ = statement!("SELECT * FROM User WHERE UserId = @UserId", UserId = "a", UserId = "b");
+}
@@ -0,0 +1,10 @@
+use google_cloud_spanner_derive::statement;
+
+fn main() {
+    // @Premium is in the comment, so it is not a placeholder.
+    let _ = statement!(
+        "SELECT * FROM User WHERE UserId = @UserId -- AND Premium = @Premium",
+        UserId = "user",
+        Premium = true
+    );
+}
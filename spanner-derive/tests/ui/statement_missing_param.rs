@@ -0,0 +1,9 @@
+use google_cloud_spanner_derive::statement;
+
+fn main() {
+    // @UserId is in the string literal, so it is not a placeholder.
+    let _ = statement!(
+        "SELECT * FROM User WHERE UserId = @UserId AND Premium = @Premium AND Name = '@UserId'",
+        UserId = "user"
+    );
+}
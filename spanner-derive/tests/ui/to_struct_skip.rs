@@ -0,0 +1,22 @@
+use google_cloud_spanner::statement::Statement;
+use google_cloud_spanner_derive::ToStruct;
+
+// Cache does not implement ToKind, which is fine because the field is skipped.
+pub struct Cache;
+
+#[derive(ToStruct)]
+pub struct UserLevel {
+    pub user_id: String,
+    pub level: i64,
+    #[spanner(skip)]
+    pub cache: Cache,
+}
+
+fn main() {
+    let user_level = UserLevel {
+        user_id: "user".to_string(),
+        level: 1,
+        cache: Cache,
+    };
+    let _ = Statement::from_struct("UPDATE User SET Level = @level WHERE UserId = @user_id", &user_level);
+}
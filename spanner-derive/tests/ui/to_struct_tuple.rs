@@ -0,0 +1,6 @@
+use google_cloud_spanner_derive::ToStruct;
+
+#[derive(ToStruct)]
+pub struct UserLevel(String, i64);
+
+fn main() {}
@@ -0,0 +1,120 @@
+//! # google-cloud-spanner-sql
+//!
+//! The SQL lexer shared by google-cloud-spanner and google-cloud-spanner-derive.
+//! https://cloud.google.com/spanner/docs/reference/standard-sql/lexical
+
+use std::collections::BTreeSet;
+
+/// skip_literal_or_comment returns the end of the string literal, the quoted identifier or the comment
+/// starting at i, or None if none of them starts at i.
+pub fn skip_literal_or_comment(chars: &[char], i: usize) -> Option<usize> {
+    let c = chars[i];
+    match c {
+        '\'' | '"' | '`' => {
+            // r'...' and R"..." are raw strings, in which the backslash is not an escape character.
+            let raw = c != '`' && i > 0 && matches!(chars[i - 1], 'r' | 'R') && {
+                i < 2 || !is_identifier_char(chars[i - 2]) || matches!(chars[i - 2], 'b' | 'B')
+            };
+            let triple = c != '`' && chars.get(i + 1) == Some(&c) && chars.get(i + 2) == Some(&c);
+            let quote_len = if triple { 3 } else { 1 };
+            let mut end = i + quote_len;
+            while end < chars.len() {
+                if chars[end] == '\\' && !raw {
+                    end += 2;
+                } else if chars[end] == c
+                    && (!triple || (chars.get(end + 1) == Some(&c) && chars.get(end + 2) == Some(&c)))
+                {
+                    end += quote_len;
+                    break;
+                } else {
+                    end += 1;
+                }
+            }
+            Some(end.min(chars.len()))
+        }
+        // line comment
+        '#' => Some(skip_line(chars, i)),
+        '-' if chars.get(i + 1) == Some(&'-') => Some(skip_line(chars, i)),
+        '/' if chars.get(i + 1) == Some(&'*') => Some(
+            (i + 2..chars.len().saturating_sub(1))
+                .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+                .map(|j| j + 2)
+                .unwrap_or(chars.len()),
+        ),
+        _ => None,
+    }
+}
+
+/// param_names returns the names of the '@' parameters outside of the string literals,
+/// quoted identifiers and comments. '@@' system variables and '@{...}' hints are ignored.
+pub fn param_names(sql: &str) -> BTreeSet<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut result = BTreeSet::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some(end) = skip_literal_or_comment(&chars, i) {
+            i = end;
+        } else if chars[i] == '@' && chars.get(i + 1) == Some(&'@') {
+            i = (i + 2..chars.len())
+                .find(|&j| !is_identifier_char(chars[j]))
+                .unwrap_or(chars.len());
+        } else if chars[i] == '@' {
+            let start = i + 1;
+            let end = (start..chars.len())
+                .find(|&j| !is_identifier_char(chars[j]))
+                .unwrap_or(chars.len());
+            if start < end && !chars[start].is_ascii_digit() {
+                result.insert(chars[start..end].iter().collect());
+            }
+            i = end.max(start);
+        } else {
+            i += 1;
+        }
+    }
+    result
+}
+
+/// is_identifier_char returns true if the character can be a part of the unquoted identifier.
+pub fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn skip_line(chars: &[char], i: usize) -> usize {
+    (i..chars.len()).find(|&j| chars[j] == '\n').unwrap_or(chars.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{param_names, skip_literal_or_comment};
+
+    fn skip(sql: &str, i: usize) -> Option<usize> {
+        let chars: Vec<char> = sql.chars().collect();
+        skip_literal_or_comment(&chars, i)
+    }
+
+    #[test]
+    fn test_skip_literal_or_comment() {
+        assert_eq!(skip("'a\\'b' x", 0), Some(6));
+        assert_eq!(skip("r'a\\' x", 1), Some(5));
+        assert_eq!(skip("br'a\\' x", 2), Some(6));
+        // 'r' is the end of the identifier, so the string is not raw.
+        assert_eq!(skip("xr'a\\'b' x", 2), Some(8));
+        assert_eq!(skip("'''a'b''' x", 0), Some(9));
+        assert_eq!(skip("`a\\`b` x", 0), Some(6));
+        assert_eq!(skip("-- a\nb", 0), Some(4));
+        assert_eq!(skip("# a\nb", 0), Some(3));
+        assert_eq!(skip("/* a */b", 0), Some(7));
+        assert_eq!(skip("/* a", 0), Some(4));
+        assert_eq!(skip("'a", 0), Some(2));
+        assert_eq!(skip("- a", 0), None);
+        assert_eq!(skip("a", 0), None);
+    }
+
+    #[test]
+    fn test_param_names() {
+        let names = param_names(
+            "@{FORCE_INDEX=Idx} SELECT @a, @b_1, @@version, '@c', r'\\' @d, \"@e\", `@f`, @1 -- @g\n/* @h */ #@i\n@a",
+        );
+        assert_eq!(names.into_iter().collect::<Vec<_>>(), vec!["a", "b_1", "d"]);
+    }
+}
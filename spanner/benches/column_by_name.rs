@@ -0,0 +1,71 @@
+//! Measures reading the columns of the rows sharing the index of the result set,
+//! by the first column with the name and by all the columns with the duplicated name.
+//!
+//! cargo bench -p google-cloud-spanner --bench column_by_name
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use google_cloud_spanner::row::{Field, Row, Value};
+use prost_types::value::Kind;
+
+const COLUMNS: usize = 50;
+const ROWS: usize = 100_000;
+
+fn run(name: &str, f: impl Fn() -> i64) -> Duration {
+    let start = Instant::now();
+    black_box(f());
+    let elapsed = start.elapsed();
+    println!("{name}: {elapsed:?} ({:?}/row)", elapsed / ROWS as u32);
+    elapsed
+}
+
+fn main() {
+    // the last half of the columns duplicates the names of the first half, such as `SELECT a.*, b.*`.
+    let fields: Arc<Vec<Field>> = Arc::new(
+        (0..COLUMNS)
+            .map(|i| Field {
+                name: format!("Column{}", i % (COLUMNS / 2)),
+                r#type: None,
+            })
+            .collect(),
+    );
+    let values: Vec<Value> = (0..COLUMNS)
+        .map(|i| Value {
+            kind: Some(Kind::StringValue(i.to_string())),
+        })
+        .collect();
+    let names: Vec<String> = fields.iter().take(COLUMNS / 2).map(|f| f.name.to_string()).collect();
+
+    // built once for the result set in the same way as the reader, where the first column wins.
+    let mut index = HashMap::with_capacity(fields.len());
+    for (i, f) in fields.iter().enumerate() {
+        index.entry(f.name.to_string()).or_insert(i);
+    }
+    let index = Arc::new(index);
+    let rows = || (0..ROWS).map(|_| Row::new(Arc::clone(&index), Arc::clone(&fields), values.clone()));
+
+    run("column_by_name", || {
+        let mut sum = 0;
+        for row in rows() {
+            for name in &names {
+                sum += row.column_by_name::<i64>(name).unwrap();
+            }
+        }
+        sum
+    });
+
+    run("column_indices", || {
+        let mut sum = 0;
+        for row in rows() {
+            for name in &names {
+                for i in row.column_indices(name) {
+                    sum += row.column::<i64>(i).unwrap();
+                }
+            }
+        }
+        sum
+    });
+}
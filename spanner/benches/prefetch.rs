@@ -0,0 +1,96 @@
+//! Compares reading a large synthetic result set with and without the prefetch.
+//! Every message takes NETWORK_DELAY to be received and every row takes PROCESSING_TIME to be processed,
+//! so the prefetch overlaps them.
+//!
+//! cargo bench -p google-cloud-spanner --bench prefetch
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use futures_util::{Stream, StreamExt};
+
+use google_cloud_gax::grpc::Status;
+use google_cloud_googleapis::spanner::v1::PartialResultSet;
+use google_cloud_spanner::reader::Prefetch;
+use prost_types::value::Kind;
+use prost_types::Value;
+
+const MESSAGES: usize = 500;
+const ROWS_PER_MESSAGE: usize = 10;
+const NETWORK_DELAY: Duration = Duration::from_millis(1);
+const PROCESSING_TIME: Duration = Duration::from_micros(100);
+
+fn stream() -> impl Stream<Item = Result<PartialResultSet, Status>> + Send + Unpin + 'static {
+    Box::pin(futures_util::stream::unfold(0, |i| async move {
+        if i == MESSAGES {
+            return None;
+        }
+        tokio::time::sleep(NETWORK_DELAY).await;
+        let message = PartialResultSet {
+            metadata: None,
+            values: (0..ROWS_PER_MESSAGE)
+                .map(|j| Value {
+                    kind: Some(Kind::StringValue(format!("row{}", i * ROWS_PER_MESSAGE + j))),
+                })
+                .collect(),
+            chunked_value: false,
+            resume_token: vec![],
+            stats: None,
+        };
+        Some((Ok(message), i + 1))
+    }))
+}
+
+fn process(message: PartialResultSet) -> usize {
+    let mut size = 0;
+    for value in message.values {
+        let start = Instant::now();
+        while start.elapsed() < PROCESSING_TIME {
+            std::hint::spin_loop();
+        }
+        if let Some(Kind::StringValue(v)) = value.kind {
+            size += v.len();
+        }
+    }
+    size
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let rows = (MESSAGES * ROWS_PER_MESSAGE) as f64;
+    println!("{name}: {elapsed:?} ({:.0} rows/s)", rows / elapsed.as_secs_f64());
+}
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let start = Instant::now();
+        let mut s = stream();
+        let mut size = 0;
+        while let Some(message) = s.next().await {
+            size += process(message.unwrap());
+        }
+        black_box(size);
+        let without = start.elapsed();
+        report("prefetch=0", without);
+
+        let mut with = without;
+        for prefetch_messages in [1, 4, 16] {
+            let start = Instant::now();
+            let mut prefetch = Prefetch::new(stream(), prefetch_messages, 0);
+            let mut size = 0;
+            while let Some(message) = prefetch.message().await.unwrap() {
+                size += process(message);
+            }
+            black_box(size);
+            let elapsed = start.elapsed();
+            report(&format!("prefetch={prefetch_messages}"), elapsed);
+            if prefetch_messages == 4 {
+                with = elapsed;
+            }
+        }
+        println!("speedup of prefetch=4: {:.2}x", without.as_secs_f64() / with.as_secs_f64());
+    });
+}
@@ -25,8 +25,14 @@ impl ConnectionManager {
         self.inner.num()
     }
 
+    /// conn returns the client of the next channel in the round-robin order.
     pub fn conn(&self) -> Client {
-        let conn = self.inner.conn();
-        Client::new(SpannerClient::new(conn))
+        self.conn_at(self.inner.next_index())
+    }
+
+    /// conn_at returns the client of the channel at the index, which wraps around the number of the channels.
+    pub fn conn_at(&self, index: usize) -> Client {
+        let channel = index % self.num();
+        Client::new(SpannerClient::new(self.inner.conn_at(channel))).on_channel(channel)
     }
 }
@@ -0,0 +1,334 @@
+//! fake_spanner is the in-process Spanner server for the tests which can not depend on the emulator,
+//! such as the ones counting the connections or scripting the responses.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use futures_util::stream::{self, Iter};
+use parking_lot::Mutex;
+
+use crate::client::{Client, ClientConfig};
+use crate::session::SessionConfig;
+use google_cloud_gax::grpc::body::BoxBody;
+use google_cloud_gax::grpc::codec::ProstCodec;
+use google_cloud_gax::grpc::codegen::http;
+use google_cloud_gax::grpc::codegen::{empty_body, BoxFuture, Context, Poll, Service};
+use google_cloud_gax::grpc::server::{Grpc, ServerStreamingService, UnaryService};
+use google_cloud_gax::grpc::transport::server::TcpConnectInfo;
+use google_cloud_gax::grpc::transport::{Body, NamedService, Server};
+use google_cloud_gax::grpc::{Code, Request, Response, Status};
+use google_cloud_gax::project::ProjectOptions;
+use google_cloud_googleapis::spanner::v1::result_set_stats::RowCount;
+use google_cloud_googleapis::spanner::v1::transaction_selector::Selector;
+use google_cloud_googleapis::spanner::v1::{
+    BatchCreateSessionsRequest, BatchCreateSessionsResponse, BatchWriteRequest, BatchWriteResponse, CommitRequest,
+    CommitResponse, DeleteSessionRequest, ExecuteBatchDmlRequest, ExecuteBatchDmlResponse, ExecuteSqlRequest,
+    PartialResultSet, ReadRequest, ResultSet, ResultSetMetadata, ResultSetStats, RollbackRequest, Session, Transaction,
+    TransactionSelector,
+};
+
+/// The id of the transaction begun by the first statement of the ExecuteBatchDml.
+pub(crate) const BEGUN_TRANSACTION_ID: &[u8] = b"fake-transaction";
+
+/// FakeSpanner records the requests by the connection and answers them with the fake sessions,
+/// the empty result sets, the scripted BatchWrite and ExecuteBatchDml responses and the errors set to the methods.
+#[derive(Clone, Default)]
+pub(crate) struct FakeSpanner {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    requests: Mutex<HashMap<(SocketAddr, String), usize>>,
+    sessions: AtomicUsize,
+    deleted: AtomicUsize,
+    batch_write: Mutex<Vec<Result<BatchWriteResponse, Status>>>,
+    batch_dml: Mutex<Vec<Result<i64, Status>>>,
+    rollbacks: Mutex<Vec<RollbackRequest>>,
+    commits: Mutex<Vec<CommitRequest>>,
+    partial_result_sets: Mutex<Vec<PartialResultSet>>,
+    selectors: Mutex<Vec<Option<TransactionSelector>>>,
+    queries: Mutex<Vec<ExecuteSqlRequest>>,
+    errors: Mutex<HashMap<String, Status>>,
+}
+
+impl FakeSpanner {
+    /// start serves the fake server on the random local port and returns the host to connect.
+    pub(crate) async fn start(&self) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+        let incoming = stream::unfold(listener, |listener| async move {
+            let stream = listener.accept().await.map(|(stream, _)| stream);
+            Some((stream, listener))
+        });
+        tokio::spawn(
+            Server::builder()
+                .add_service(self.clone())
+                .serve_with_incoming(incoming),
+        );
+        host
+    }
+
+    /// requests_per_connection returns the number of the requests of the method received on each of the connections.
+    pub(crate) fn requests_per_connection(&self, method: &str) -> Vec<usize> {
+        self.inner
+            .requests
+            .lock()
+            .iter()
+            .filter(|((_, m), _)| m == method)
+            .map(|(_, count)| *count)
+            .collect()
+    }
+
+    /// requests returns the number of the requests of the method received.
+    pub(crate) fn requests(&self, method: &str) -> usize {
+        self.requests_per_connection(method).iter().sum()
+    }
+
+    /// client starts the fake server and returns the client with one session connected to it.
+    pub(crate) async fn client(&self) -> Client {
+        let host = self.start().await;
+        let mut session_config = SessionConfig::default();
+        session_config.min_opened = 1;
+        session_config.max_opened = 1;
+        let config = ClientConfig {
+            session_config,
+            project: ProjectOptions::Emulated(host),
+            ..Default::default()
+        };
+        Client::new_with_config("projects/p/instances/i/databases/d", config)
+            .await
+            .unwrap()
+    }
+
+    pub(crate) fn deleted_sessions(&self) -> usize {
+        self.inner.deleted.load(Ordering::SeqCst)
+    }
+
+    /// rollbacks returns the Rollback requests received.
+    pub(crate) fn rollbacks(&self) -> Vec<RollbackRequest> {
+        self.inner.rollbacks.lock().clone()
+    }
+
+    /// commits returns the Commit requests received.
+    pub(crate) fn commits(&self) -> Vec<CommitRequest> {
+        self.inner.commits.lock().clone()
+    }
+
+    /// selectors returns the transaction selectors of the streaming reads and queries received.
+    pub(crate) fn selectors(&self) -> Vec<Option<TransactionSelector>> {
+        self.inner.selectors.lock().clone()
+    }
+
+    /// queries returns the ExecuteStreamingSql requests received.
+    pub(crate) fn queries(&self) -> Vec<ExecuteSqlRequest> {
+        self.inner.queries.lock().clone()
+    }
+
+    /// set_partial_result_sets sets the results of the streaming reads and queries.
+    pub(crate) fn set_partial_result_sets(&self, results: Vec<PartialResultSet>) {
+        *self.inner.partial_result_sets.lock() = results;
+    }
+
+    fn stream_results(&self, selector: Option<TransactionSelector>) -> Vec<Result<PartialResultSet, Status>> {
+        self.inner.selectors.lock().push(selector);
+        self.inner.partial_result_sets.lock().iter().cloned().map(Ok).collect()
+    }
+
+    /// set_error sets the error returned by the next request of the method.
+    pub(crate) fn set_error(&self, method: &str, error: Status) {
+        self.inner.errors.lock().insert(method.to_string(), error);
+    }
+
+    /// set_batch_write sets the responses of the next BatchWrite.
+    pub(crate) fn set_batch_write(&self, responses: Vec<Result<BatchWriteResponse, Status>>) {
+        *self.inner.batch_write.lock() = responses;
+    }
+
+    /// set_batch_dml sets the results of the statements of the next ExecuteBatchDml.
+    /// The statements after the first error are not executed, as Spanner does.
+    pub(crate) fn set_batch_dml(&self, results: Vec<Result<i64, Status>>) {
+        *self.inner.batch_dml.lock() = results;
+    }
+
+    fn execute_batch_dml(&self, request: ExecuteBatchDmlRequest) -> ExecuteBatchDmlResponse {
+        let begin = matches!(request.transaction.and_then(|tx| tx.selector), Some(Selector::Begin(_)));
+        let mut response = ExecuteBatchDmlResponse::default();
+        for result in std::mem::take(&mut *self.inner.batch_dml.lock()) {
+            match result {
+                Ok(count) => {
+                    let metadata = (begin && response.result_sets.is_empty()).then(|| ResultSetMetadata {
+                        transaction: Some(Transaction {
+                            id: BEGUN_TRANSACTION_ID.to_vec(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    });
+                    response.result_sets.push(ResultSet {
+                        metadata,
+                        rows: vec![],
+                        stats: Some(ResultSetStats {
+                            row_count: Some(RowCount::RowCountExact(count)),
+                            ..Default::default()
+                        }),
+                    });
+                }
+                Err(status) => {
+                    response.status = Some(google_cloud_googleapis::rpc::Status {
+                        code: status.code() as i32,
+                        message: status.message().to_string(),
+                        details: vec![],
+                    });
+                    break;
+                }
+            }
+        }
+        response
+    }
+
+    fn batch_create_sessions(&self, request: BatchCreateSessionsRequest) -> BatchCreateSessionsResponse {
+        let session = (0..request.session_count)
+            .map(|_| {
+                let id = self.inner.sessions.fetch_add(1, Ordering::SeqCst);
+                Session {
+                    name: format!("{}/sessions/{}", request.database, id),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        BatchCreateSessionsResponse { session }
+    }
+}
+
+impl NamedService for FakeSpanner {
+    const NAME: &'static str = "google.spanner.v1.Spanner";
+}
+
+/// Unary is the unary method answered by the function.
+struct Unary<F>(F);
+
+impl<Req, Res, F> UnaryService<Req> for Unary<F>
+where
+    F: FnMut(Req) -> Res,
+    Res: Send + 'static,
+{
+    type Response = Res;
+    type Future = BoxFuture<Response<Res>, Status>;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        let response = Response::new((self.0)(request.into_inner()));
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// Streaming is the server streaming method answered by the function.
+struct Streaming<F>(F);
+
+impl<Req, Res, F> ServerStreamingService<Req> for Streaming<F>
+where
+    F: FnMut(Req) -> Vec<Result<Res, Status>>,
+    Res: Send + 'static,
+{
+    type Response = Res;
+    type ResponseStream = Iter<std::vec::IntoIter<Result<Res, Status>>>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        let responses = (self.0)(request.into_inner());
+        Box::pin(async move { Ok(Response::new(stream::iter(responses))) })
+    }
+}
+
+impl Service<http::Request<Body>> for FakeSpanner {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+        let method = request.uri().path().rsplit('/').next().unwrap_or_default().to_string();
+        if let Some(addr) = request
+            .extensions()
+            .get::<TcpConnectInfo>()
+            .and_then(TcpConnectInfo::remote_addr)
+        {
+            *self.inner.requests.lock().entry((addr, method.clone())).or_default() += 1;
+        }
+        let fake = self.clone();
+        Box::pin(async move {
+            if let Some(error) = fake.inner.errors.lock().remove(&method) {
+                return Ok(error.to_http());
+            }
+            let response = match request.uri().path() {
+                "/google.spanner.v1.Spanner/BatchCreateSessions" => {
+                    let codec = ProstCodec::<BatchCreateSessionsResponse, BatchCreateSessionsRequest>::default();
+                    let service = Unary(|r: BatchCreateSessionsRequest| fake.batch_create_sessions(r));
+                    Grpc::new(codec).unary(service, request).await
+                }
+                "/google.spanner.v1.Spanner/DeleteSession" => {
+                    let codec = ProstCodec::<(), DeleteSessionRequest>::default();
+                    let service = Unary(|_: DeleteSessionRequest| {
+                        fake.inner.deleted.fetch_add(1, Ordering::SeqCst);
+                    });
+                    Grpc::new(codec).unary(service, request).await
+                }
+                "/google.spanner.v1.Spanner/Rollback" => {
+                    let codec = ProstCodec::<(), RollbackRequest>::default();
+                    let service = Unary(|r: RollbackRequest| fake.inner.rollbacks.lock().push(r));
+                    Grpc::new(codec).unary(service, request).await
+                }
+                "/google.spanner.v1.Spanner/Commit" => {
+                    let codec = ProstCodec::<CommitResponse, CommitRequest>::default();
+                    let service = Unary(|r: CommitRequest| {
+                        fake.inner.commits.lock().push(r);
+                        CommitResponse {
+                            commit_timestamp: Some(prost_types::Timestamp::default()),
+                            commit_stats: None,
+                        }
+                    });
+                    Grpc::new(codec).unary(service, request).await
+                }
+                "/google.spanner.v1.Spanner/ExecuteSql" => {
+                    let codec = ProstCodec::<ResultSet, ExecuteSqlRequest>::default();
+                    let service = Unary(|_: ExecuteSqlRequest| ResultSet::default());
+                    Grpc::new(codec).unary(service, request).await
+                }
+                "/google.spanner.v1.Spanner/ExecuteBatchDml" => {
+                    let codec = ProstCodec::<ExecuteBatchDmlResponse, ExecuteBatchDmlRequest>::default();
+                    let service = Unary(|r: ExecuteBatchDmlRequest| fake.execute_batch_dml(r));
+                    Grpc::new(codec).unary(service, request).await
+                }
+                "/google.spanner.v1.Spanner/ExecuteStreamingSql" => {
+                    let codec = ProstCodec::<PartialResultSet, ExecuteSqlRequest>::default();
+                    let service = Streaming(|r: ExecuteSqlRequest| {
+                        fake.inner.queries.lock().push(r.clone());
+                        fake.stream_results(r.transaction)
+                    });
+                    Grpc::new(codec).server_streaming(service, request).await
+                }
+                "/google.spanner.v1.Spanner/StreamingRead" => {
+                    let codec = ProstCodec::<PartialResultSet, ReadRequest>::default();
+                    let service = Streaming(|r: ReadRequest| fake.stream_results(r.transaction));
+                    Grpc::new(codec).server_streaming(service, request).await
+                }
+                "/google.spanner.v1.Spanner/BatchWrite" => {
+                    let codec = ProstCodec::<BatchWriteResponse, BatchWriteRequest>::default();
+                    let service = Streaming(|_: BatchWriteRequest| std::mem::take(&mut *fake.inner.batch_write.lock()));
+                    Grpc::new(codec).server_streaming(service, request).await
+                }
+                _ => http::Response::builder()
+                    .header("grpc-status", (Code::Unimplemented as i32).to_string())
+                    .header("content-type", "application/grpc")
+                    .body(empty_body())
+                    .unwrap(),
+            };
+            Ok(response)
+        })
+    }
+}
@@ -1,4 +1,6 @@
 pub mod conn_pool;
+#[cfg(test)]
+pub(crate) mod fake_spanner;
 pub mod spanner_client;
 
 #[cfg(test)]
@@ -41,6 +43,7 @@ mod tests {
         let request = BeginTransactionRequest {
             session: session.name.to_string(),
             options: Option::from(TransactionOptions {
+                exclude_txn_from_change_streams: false,
                 mode: Option::from(transaction_options::Mode::ReadOnly(transaction_options::ReadOnly {
                     return_read_timestamp: false,
                     timestamp_bound: None,
@@ -59,6 +62,7 @@ mod tests {
         let request = BeginTransactionRequest {
             session: session.name.to_string(),
             options: Some(TransactionOptions {
+                exclude_txn_from_change_streams: false,
                 mode: Some(transaction_options::Mode::ReadWrite(transaction_options::ReadWrite::default())),
             }),
             request_options: None,
@@ -191,6 +195,8 @@ mod tests {
             seqno: 0,
             query_options: None,
             request_options: None,
+            directed_read_options: None,
+            data_boost_enabled: false,
         };
         match client.execute_sql(request, None, None).await {
             Ok(res) => {
@@ -217,6 +223,8 @@ mod tests {
             seqno: 0,
             query_options: None,
             request_options: None,
+            directed_read_options: None,
+            data_boost_enabled: false,
         };
 
         let resume_token = match client.execute_streaming_sql(request.clone(), None, None).await {
@@ -251,6 +259,7 @@ mod tests {
         let request = BeginTransactionRequest {
             session: session.name.to_string(),
             options: Option::from(TransactionOptions {
+                exclude_txn_from_change_streams: false,
                 mode: Option::from(transaction_options::Mode::ReadOnly(transaction_options::ReadOnly {
                     return_read_timestamp: false,
                     timestamp_bound: None,
@@ -321,7 +330,7 @@ mod tests {
 
     #[tokio::test]
     #[serial]
-    async fn test_execute_batch_dml_error_as_tonic_check() {
+    async fn test_execute_batch_dml_error_in_status() {
         let mut client = create_spanner_client().await;
         let session = create_session(&mut client).await;
         let tx = begin_read_write_transaction(&mut client, &session).await;
@@ -353,15 +362,13 @@ mod tests {
             .await
             .unwrap();
         match result {
-            Ok(res) => panic!("must be error code = {:?}", res.into_inner().status.unwrap().code),
-            Err(status) => {
-                assert_eq!(
-                    Code::InvalidArgument,
-                    status.code(),
-                    "gRPC success but error found : {:?}",
-                    status
-                );
+            Ok(res) => {
+                let res = res.into_inner();
+                let status = res.status.unwrap();
+                assert_eq!(Code::InvalidArgument, Code::from(status.code), "{:?}", status);
+                assert!(res.result_sets.is_empty());
             }
+            Err(err) => panic!("the failed statement must be returned in the status: {:?}", err),
         };
     }
 
@@ -384,6 +391,8 @@ mod tests {
             resume_token: vec![],
             partition_token: vec![],
             request_options: None,
+            directed_read_options: None,
+            data_boost_enabled: false,
             limit: 0,
         };
 
@@ -414,6 +423,8 @@ mod tests {
             resume_token: vec![],
             partition_token: vec![],
             request_options: None,
+            directed_read_options: None,
+            data_boost_enabled: false,
             limit: 0,
         };
 
@@ -464,6 +475,7 @@ mod tests {
                 transaction_tag: "".to_string(),
             }),
             return_commit_stats: false,
+            max_commit_delay: None,
         };
 
         match client.commit(request, None, None).await {
@@ -1,4 +1,6 @@
 use google_cloud_gax::cancel::CancellationToken;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use google_cloud_gax::conn::Channel;
@@ -8,10 +10,11 @@ use google_cloud_gax::retry::{invoke_fn, RetrySetting};
 use google_cloud_googleapis::spanner::v1 as internal;
 use google_cloud_googleapis::spanner::v1::spanner_client::SpannerClient;
 use google_cloud_googleapis::spanner::v1::{
-    BatchCreateSessionsRequest, BatchCreateSessionsResponse, BeginTransactionRequest, CommitRequest, CommitResponse,
-    CreateSessionRequest, DeleteSessionRequest, ExecuteBatchDmlRequest, ExecuteBatchDmlResponse, ExecuteSqlRequest,
-    GetSessionRequest, ListSessionsRequest, ListSessionsResponse, PartialResultSet, PartitionQueryRequest,
-    PartitionReadRequest, PartitionResponse, ReadRequest, ResultSet, RollbackRequest, Session, Transaction,
+    BatchCreateSessionsRequest, BatchCreateSessionsResponse, BatchWriteRequest, BatchWriteResponse,
+    BeginTransactionRequest, CommitRequest, CommitResponse, CreateSessionRequest, DeleteSessionRequest,
+    ExecuteBatchDmlRequest, ExecuteBatchDmlResponse, ExecuteSqlRequest, GetSessionRequest, ListSessionsRequest,
+    ListSessionsResponse, PartialResultSet, PartitionQueryRequest, PartitionReadRequest, PartitionResponse,
+    ReadRequest, ResultSet, RollbackRequest, Session, Transaction,
 };
 
 pub(crate) fn ping_query_request(session_name: impl Into<String>) -> internal::ExecuteSqlRequest {
@@ -27,6 +30,8 @@ pub(crate) fn ping_query_request(session_name: impl Into<String>) -> internal::E
         seqno: 0,
         query_options: None,
         request_options: None,
+        directed_read_options: None,
+        data_boost_enabled: false,
     }
 }
 
@@ -40,15 +45,54 @@ fn default_setting() -> RetrySetting {
     }
 }
 
+const REVOKED_MESSAGE: &str = "the session was evicted from the pool as leaked";
+
+/// batch_write_setting does not retry, because the BatchWrite is not replay protected
+/// and the groups may be applied before the error is returned.
+fn batch_write_setting() -> RetrySetting {
+    RetrySetting {
+        codes: vec![],
+        ..default_setting()
+    }
+}
+
 #[derive(Clone)]
 pub struct Client {
     inner: SpannerClient<Channel>,
+    channel: usize,
+    /// Whether the session using the client was taken back from its holder, after which the requests fail.
+    revoked: Option<Arc<AtomicBool>>,
 }
 
 impl Client {
     /// create new spanner client
     pub fn new(inner: SpannerClient<Channel>) -> Client {
-        Client { inner }
+        Client {
+            inner,
+            channel: 0,
+            revoked: None,
+        }
+    }
+
+    /// revocable returns the client whose requests fail after the flag is set.
+    pub(crate) fn revocable(mut self, revoked: Arc<AtomicBool>) -> Client {
+        self.revoked = Some(revoked);
+        self
+    }
+
+    fn is_revoked(&self) -> bool {
+        self.revoked.as_ref().is_some_and(|r| r.load(Ordering::Acquire))
+    }
+
+    /// on_channel records the index of the channel of the connection pool the client uses.
+    pub(crate) fn on_channel(mut self, channel: usize) -> Client {
+        self.channel = channel;
+        self
+    }
+
+    /// channel returns the index of the channel of the connection pool the client uses.
+    pub fn channel(&self) -> usize {
+        self.channel
     }
 
     /// create_session creates a new session. A session can be used to perform
@@ -98,6 +142,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<Session>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let database = &req.database;
         invoke_fn(
@@ -147,6 +194,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<BatchCreateSessionsResponse>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let database = &req.database;
         invoke_fn(
@@ -194,6 +244,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<Session>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let name = &req.name;
         invoke_fn(
@@ -240,6 +293,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<ListSessionsResponse>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let database = &req.database;
         invoke_fn(
@@ -288,6 +344,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<()>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let name = &req.name;
         invoke_fn(
@@ -344,6 +403,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<ResultSet>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -394,6 +456,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<Streaming<PartialResultSet>>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -450,6 +515,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<ExecuteBatchDmlResponse>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -457,21 +525,12 @@ impl Client {
             Some(setting),
             |spanner_client| async {
                 let request = create_request(format!("session={}", session), req.clone());
-                let result = spanner_client.execute_batch_dml(request).await;
-                match result {
-                    Ok(response) => match response.get_ref().status.as_ref() {
-                        Some(s) => {
-                            let code = Code::from(s.code);
-                            if code == Code::Ok {
-                                Ok(response)
-                            } else {
-                                Err((Status::new(code, s.message.to_string()), spanner_client))
-                            }
-                        }
-                        None => Ok(response),
-                    },
-                    Err(err) => Err((err, spanner_client)),
-                }
+                // the status of the failed statement is returned in the response with the row counts
+                // of the statements before it, so that it is not the error of the RPC.
+                spanner_client
+                    .execute_batch_dml(request)
+                    .await
+                    .map_err(|e| (e, spanner_client))
             },
             &mut self.inner,
         )
@@ -519,6 +578,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<ResultSet>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -566,6 +628,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<Streaming<PartialResultSet>>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -615,6 +680,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<Transaction>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -674,6 +742,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<CommitResponse>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -724,6 +795,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<()>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -738,6 +812,64 @@ impl Client {
         .await
     }
 
+    /// BatchWrite batches the supplied mutation groups in a collection of efficient
+    /// transactions. All mutations in a group are committed atomically. However,
+    /// mutations across groups can be committed non-atomically in an unspecified
+    /// order and thus, they must be independent of each other. Partial failure is
+    /// possible, i.e., some groups may have been committed successfully, while
+    /// some may have failed. The results of individual batches are streamed into
+    /// the response as the batches are applied.
+    ///
+    /// BatchWrite requests are not replay protected, meaning that each mutation
+    /// group may be applied more than once. The request is not retried unless retry is set.
+    #[cfg(not(feature = "trace"))]
+    pub async fn batch_write(
+        &mut self,
+        req: BatchWriteRequest,
+        cancel: Option<CancellationToken>,
+        retry: Option<RetrySetting>,
+    ) -> Result<Response<Streaming<BatchWriteResponse>>, Status> {
+        self._batch_write(req, cancel, retry).await
+    }
+
+    #[cfg(feature = "trace")]
+    #[tracing::instrument(skip_all)]
+    pub async fn batch_write(
+        &mut self,
+        req: BatchWriteRequest,
+        cancel: Option<CancellationToken>,
+        retry: Option<RetrySetting>,
+    ) -> Result<Response<Streaming<BatchWriteResponse>>, Status> {
+        self._batch_write(req, cancel, retry).await
+    }
+
+    #[inline(always)]
+    async fn _batch_write(
+        &mut self,
+        req: BatchWriteRequest,
+        cancel: Option<CancellationToken>,
+        retry: Option<RetrySetting>,
+    ) -> Result<Response<Streaming<BatchWriteResponse>>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
+        let setting = retry.unwrap_or_else(batch_write_setting);
+        let session = &req.session;
+        invoke_fn(
+            cancel,
+            Some(setting),
+            |spanner_client| async {
+                let request = create_request(format!("session={}", session), req.clone());
+                spanner_client
+                    .batch_write(request)
+                    .await
+                    .map_err(|e| (e, spanner_client))
+            },
+            &mut self.inner,
+        )
+        .await
+    }
+
     /// PartitionQuery creates a set of partition tokens that can be used to execute a query
     /// operation in parallel.  Each of the returned partition tokens can be used
     /// by ExecuteStreamingSql to specify a subset
@@ -777,6 +909,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<PartitionResponse>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -835,6 +970,9 @@ impl Client {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<Response<PartitionResponse>, Status> {
+        if self.is_revoked() {
+            return Err(Status::new(Code::FailedPrecondition, REVOKED_MESSAGE));
+        }
         let setting = retry.unwrap_or_else(default_setting);
         let session = &req.session;
         invoke_fn(
@@ -1,29 +1,54 @@
 use google_cloud_gax::retry::{invoke_fn, TryAs};
-use google_cloud_googleapis::spanner::v1::{commit_request, transaction_options, Mutation, TransactionOptions};
+use google_cloud_googleapis::spanner::v1::{
+    commit_request, BatchWriteRequest, BatchWriteResponse, Mutation, TransactionOptions,
+};
 
 use crate::apiv1::conn_pool::{ConnectionManager, SPANNER};
-use crate::session::{ManagedSession, SessionConfig, SessionError, SessionManager};
-use crate::statement::Statement;
-use crate::transaction::{CallOptions, QueryOptions};
-use crate::transaction_ro::{BatchReadOnlyTransaction, ReadOnlyTransaction};
-use crate::transaction_rw::{commit, CommitOptions, ReadWriteTransaction};
+use crate::key::ToKey;
+use crate::mutation::{check_delete_all_keys, CommitLimitError, CommitLimits, CommitSplitter, MutationGroup};
+use crate::reader::AsyncIterator;
+use crate::row::{Error as RowError, Row};
+use crate::session::{
+    CheckedOutSession, ManagedSession, SessionConfig, SessionError, SessionManager, SessionPoolStats,
+};
+use crate::statement::{has_then_return, sql_keywords, Statement};
+use crate::stats::QueryPlan;
+use crate::transaction::{CallOptions, DirectedReadOptions, Priority, QueryError, QueryOptions, Transaction};
+use crate::transaction_ro::{BatchReadOnlyTransaction, BatchReadOnlyTransactionId, ReadOnlyTransaction};
+use crate::transaction_rw::{
+    commit, partitioned_dml_options, read_write_options, BatchUpdateError, CommitOptions, CommitResult, ReadLockMode,
+    ReadWriteTransaction, MAX_COMMIT_DELAY,
+};
 use crate::value::{Timestamp, TimestampBound};
 
-use crate::retry::TransactionRetrySetting;
+use crate::retry::{invoke_transaction_fn, TransactionRetrySetting};
 
 use google_cloud_auth::Project;
 use google_cloud_gax::cancel::CancellationToken;
 use google_cloud_gax::conn::Environment;
-use google_cloud_gax::grpc::{Code, Status};
+use google_cloud_gax::grpc::{Code, Status, Streaming};
 use google_cloud_gax::project::ProjectOptions;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone, Default)]
 pub struct PartitionedUpdateOption {
     pub begin_options: CallOptions,
     pub query_options: Option<QueryOptions>,
+    /// The overall time to execute the statement including the retries. None means no limit.
+    pub timeout: Option<Duration>,
+    /// Whether to exclude the mutations of the statement from the change streams with the allow_txn_exclusion option.
+    pub exclude_txn_from_change_streams: bool,
+}
+
+impl PartitionedUpdateOption {
+    /// with_exclude_txn_from_change_streams returns the option which excludes the mutations from the change streams.
+    pub fn with_exclude_txn_from_change_streams(mut self, exclude: bool) -> Self {
+        self.exclude_txn_from_change_streams = exclude;
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -45,11 +70,96 @@ impl Default for ReadOnlyTransactionOption {
 pub struct ReadWriteTransactionOption {
     pub begin_options: CallOptions,
     pub commit_options: CommitOptions,
+    /// The attempts and the timeout to retry the transaction aborted by the server.
+    pub retry_setting: TransactionRetrySetting,
+    /// The tag of the transaction, which is shown in the transaction statistics and lock statistics tables.
+    /// It is truncated to 50 characters.
+    pub transaction_tag: Option<String>,
+    /// Whether to exclude the mutations of the transaction from the change streams with the allow_txn_exclusion option.
+    /// The error of the server which rejects it, such as INVALID_ARGUMENT, is returned as it is.
+    pub exclude_txn_from_change_streams: bool,
+    /// The read lock mode of the transaction. None leaves it to the server default, which is pessimistic.
+    pub read_lock_mode: Option<ReadLockMode>,
+}
+
+impl ReadWriteTransactionOption {
+    /// with_transaction_tag returns the option with the transaction tag.
+    pub fn with_transaction_tag<T: Into<String>>(mut self, tag: T) -> Self {
+        self.transaction_tag = Some(tag.into());
+        self
+    }
+
+    /// with_exclude_txn_from_change_streams returns the option which excludes the mutations from the change streams.
+    pub fn with_exclude_txn_from_change_streams(mut self, exclude: bool) -> Self {
+        self.exclude_txn_from_change_streams = exclude;
+        self
+    }
+
+    /// with_read_lock_mode returns the option with the read lock mode.
+    /// The optimistic transaction acquires no read lock on read, and the reads are validated at the commit,
+    /// which aborts if the data read is changed. The retry setting is not changed by the read lock mode,
+    /// so use with_retry_setting(TransactionRetrySetting::optimistic()) to retry the optimistic transaction,
+    /// which aborts more often, more times.
+    pub fn with_read_lock_mode(mut self, read_lock_mode: ReadLockMode) -> Self {
+        self.read_lock_mode = Some(read_lock_mode);
+        self
+    }
+
+    /// with_retry_setting returns the option with the setting to retry the aborted transaction.
+    pub fn with_retry_setting(mut self, retry_setting: TransactionRetrySetting) -> Self {
+        self.retry_setting = retry_setting;
+        self
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ApplyAtLeastOnceOption {
+    pub commit_options: CommitOptions,
+    /// The tag of the transaction, which is shown in the transaction statistics and lock statistics tables.
+    /// It is truncated to 50 characters.
+    pub transaction_tag: Option<String>,
+    /// Whether to exclude the mutations from the change streams with the allow_txn_exclusion option.
+    pub exclude_txn_from_change_streams: bool,
+}
+
+impl From<CommitOptions> for ApplyAtLeastOnceOption {
+    fn from(commit_options: CommitOptions) -> Self {
+        ApplyAtLeastOnceOption {
+            commit_options,
+            ..Default::default()
+        }
+    }
+}
+
+impl ApplyAtLeastOnceOption {
+    /// with_transaction_tag returns the option with the transaction tag.
+    pub fn with_transaction_tag<T: Into<String>>(mut self, tag: T) -> Self {
+        self.transaction_tag = Some(tag.into());
+        self
+    }
+
+    /// with_exclude_txn_from_change_streams returns the option which excludes the mutations from the change streams.
+    pub fn with_exclude_txn_from_change_streams(mut self, exclude: bool) -> Self {
+        self.exclude_txn_from_change_streams = exclude;
+        self
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct BatchWriteOption {
+    pub call_options: CallOptions,
+    /// Whether to exclude the mutations of the batch from the change streams with the allow_txn_exclusion option.
+    pub exclude_txn_from_change_streams: bool,
+    /// Whether to write the deletes of all the keys of a table, which are rejected with
+    /// ClientConfig::reject_delete_all_keys.
+    pub allow_delete_all_keys: bool,
 }
 
 #[derive(Clone, Debug)]
 pub struct ChannelConfig {
-    /// num_channels is the number of gRPC channels.
+    /// num_channels is the number of gRPC channels, each of which is the separate HTTP/2 connection.
+    /// Each session is bound to one of the channels and the new sessions are spread over them round-robin,
+    /// so that the concurrent requests are not limited by the streams of a single connection.
     pub num_channels: usize,
 }
 
@@ -70,6 +180,18 @@ pub struct ClientConfig {
     pub endpoint: String,
     /// Runtime project
     pub project: ProjectOptions,
+    /// The default priority of the requests whose options have no priority.
+    /// None leaves the priority to the server default.
+    pub priority: Option<Priority>,
+    /// The default max_commit_delay of the commits whose options have no max_commit_delay.
+    /// The longer delay improves the throughput of the commits by batching them, at the cost of the latency of each commit.
+    pub max_commit_delay: Option<Duration>,
+    /// The default DirectedReadOptions of the reads and the queries in the read-only transactions.
+    /// None leaves the replica selection to the server.
+    pub directed_read_options: Option<DirectedReadOptions>,
+    /// The guardrail that rejects the mutations deleting all the keys of a table, such as `delete("User", all_keys())`,
+    /// unless allow_delete_all_keys of the options is true.
+    pub reject_delete_all_keys: bool,
 }
 
 impl Default for ClientConfig {
@@ -79,6 +201,10 @@ impl Default for ClientConfig {
             session_config: Default::default(),
             endpoint: SPANNER.to_string(),
             project: ProjectOptions::new("SPANNER_EMULATOR_HOST"),
+            priority: None,
+            max_commit_delay: None,
+            directed_read_options: None,
+            reject_delete_all_keys: false,
         };
         config.session_config.min_opened = config.channel_config.num_channels * 4;
         config.session_config.max_opened = config.channel_config.num_channels * 100;
@@ -127,6 +253,40 @@ impl TryAs<Status> for TxError {
     }
 }
 
+/// BatchWriteResult is the result of the mutation groups applied in a transaction by the BatchWrite.
+#[derive(Clone, Debug)]
+pub struct BatchWriteResult {
+    /// The indexes of the mutation groups in the request.
+    pub indexes: Vec<usize>,
+    /// The commit timestamp of the groups, or the status of the failure.
+    pub result: Result<Option<Timestamp>, Status>,
+}
+
+impl From<BatchWriteResponse> for BatchWriteResult {
+    fn from(response: BatchWriteResponse) -> Self {
+        let indexes = response.indexes.into_iter().map(|i| i as usize).collect();
+        let result = match response.status {
+            Some(status) if status.code != Code::Ok as i32 => Err(Status::new(Code::from(status.code), status.message)),
+            _ => Ok(response.commit_timestamp.map(|ts| ts.into())),
+        };
+        BatchWriteResult { indexes, result }
+    }
+}
+
+/// BatchWriteIterator returns the results of the BatchWrite in the order the groups are applied.
+pub struct BatchWriteIterator {
+    streaming: Streaming<BatchWriteResponse>,
+    // the session is used until the all results are returned.
+    _session: ManagedSession,
+}
+
+impl BatchWriteIterator {
+    /// next returns the result of the next applied mutation groups, or None if all the groups are applied.
+    pub async fn next(&mut self) -> Result<Option<BatchWriteResult>, Status> {
+        Ok(self.streaming.message().await?.map(BatchWriteResult::from))
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum RunInTxError {
     #[error(transparent)]
@@ -151,6 +311,34 @@ impl From<TxError> for RunInTxError {
     }
 }
 
+impl From<QueryError> for RunInTxError {
+    fn from(err: QueryError) -> Self {
+        match err {
+            QueryError::GRPC(err) => RunInTxError::GRPC(err),
+            QueryError::InvalidSession(err) => RunInTxError::InvalidSession(err),
+            err => RunInTxError::Any(err.into()),
+        }
+    }
+}
+
+impl From<BatchUpdateError> for RunInTxError {
+    fn from(err: BatchUpdateError) -> Self {
+        match err {
+            BatchUpdateError::GRPC(err) => RunInTxError::GRPC(err),
+            BatchUpdateError::StatementFailed(_, err, _) => RunInTxError::GRPC(err),
+        }
+    }
+}
+
+impl From<TxError> for QueryError {
+    fn from(err: TxError) -> Self {
+        match err {
+            TxError::GRPC(err) => QueryError::GRPC(err),
+            TxError::InvalidSession(err) => QueryError::InvalidSession(err),
+        }
+    }
+}
+
 impl TryAs<Status> for RunInTxError {
     fn try_as(&self) -> Option<&Status> {
         match self {
@@ -160,16 +348,35 @@ impl TryAs<Status> for RunInTxError {
     }
 }
 
+/// CommitBuilderError is the error of CommitBuilder::commit with the commit timestamps of the commits
+/// that had already succeeded when it occurred.
+#[derive(thiserror::Error, Debug)]
+pub enum CommitBuilderError {
+    #[error("{1} after {} commits", .0.len())]
+    Limit(Vec<Option<Timestamp>>, #[source] CommitLimitError),
+
+    #[error("{1} after {} commits", .0.len())]
+    Tx(Vec<Option<Timestamp>>, #[source] TxError),
+}
+
 /// Client is a client for reading and writing data to a Cloud Spanner database.
 /// A client is safe to use concurrently, except for its Close method.
 pub struct Client {
     sessions: Arc<SessionManager>,
+    priority: Option<Priority>,
+    max_commit_delay: Option<Duration>,
+    directed_read_options: Option<DirectedReadOptions>,
+    reject_delete_all_keys: bool,
 }
 
 impl Clone for Client {
     fn clone(&self) -> Self {
         Client {
             sessions: Arc::clone(&self.sessions),
+            priority: self.priority,
+            max_commit_delay: self.max_commit_delay,
+            directed_read_options: self.directed_read_options.clone(),
+            reject_delete_all_keys: self.reject_delete_all_keys,
         }
     }
 }
@@ -193,6 +400,22 @@ impl Client {
                 config.channel_config.num_channels * 100
             )));
         }
+        if config.session_config.max_opened == 0 {
+            return Err(InitializationError::InvalidConfig(
+                "max_opened sessions must be greater than 0".to_string(),
+            ));
+        }
+        if config.session_config.min_opened > config.session_config.max_opened {
+            return Err(InitializationError::InvalidConfig(format!(
+                "min_opened sessions must be at most max_opened: min_opened={}, max_opened={}",
+                config.session_config.min_opened, config.session_config.max_opened
+            )));
+        }
+        if let Some(delay) = config.max_commit_delay.filter(|delay| *delay > MAX_COMMIT_DELAY) {
+            return Err(InitializationError::InvalidConfig(format!(
+                "max_commit_delay must be at most {MAX_COMMIT_DELAY:?}: {delay:?}"
+            )));
+        }
 
         let environment = Environment::from_project(config.project).await?;
         let pool_size = config.channel_config.num_channels;
@@ -201,10 +424,15 @@ impl Client {
 
         Ok(Client {
             sessions: Arc::new(session_manager),
+            priority: config.priority,
+            max_commit_delay: config.max_commit_delay,
+            directed_read_options: config.directed_read_options,
+            reject_delete_all_keys: config.reject_delete_all_keys,
         })
     }
 
-    /// Close closes the client.
+    /// Close closes the client. The checked-out sessions are waited for up to SessionConfig::close_timeout,
+    /// and all the sessions in the pool are deleted. The client fails with "client closed" after it is closed.
     pub async fn close(&self) {
         self.sessions.close().await;
     }
@@ -237,12 +465,122 @@ impl Client {
     /// single provides a read-only snapshot transaction optimized for the case
     /// where only a single read or query is needed.  This is more efficient than
     /// using read_only_transaction for a single read or query.
+    /// The timestamp chosen by the server is returned in RowIterator::metadata.
     pub async fn single_with_timestamp_bound(&self, tb: TimestampBound) -> Result<ReadOnlyTransaction, TxError> {
-        let session = self.get_session().await?;
-        let result = ReadOnlyTransaction::single(session, tb).await?;
+        let session = self.sessions.get_read_only().await?;
+        let mut result = ReadOnlyTransaction::single(session, tb).await?;
+        result.set_default_priority(self.priority);
+        result.set_default_directed_read_options(self.directed_read_options.clone());
         Ok(result)
     }
 
+    /// read_row reads a single row in the strong single-use read-only transaction.
+    /// No BeginTransaction is issued.
+    pub async fn read_row(&self, table: &str, columns: &[&str], key: impl ToKey) -> Result<Option<Row>, TxError> {
+        self.read_row_with_timestamp_bound(table, columns, key, TimestampBound::strong_read())
+            .await
+    }
+
+    /// read_row_with_timestamp_bound reads a single row in the single-use read-only transaction with the TimestampBound.
+    /// Use single_with_timestamp_bound to get the read timestamp chosen by the server from RowIterator::metadata.
+    pub async fn read_row_with_timestamp_bound(
+        &self,
+        table: &str,
+        columns: &[&str],
+        key: impl ToKey,
+        tb: TimestampBound,
+    ) -> Result<Option<Row>, TxError> {
+        let mut tx = self.single_with_timestamp_bound(tb).await?;
+        Ok(tx.read_row(table, columns, key).await?)
+    }
+
+    /// single_query executes a query in the strong single-use read-only transaction and returns all the rows.
+    /// No BeginTransaction is issued.
+    pub async fn single_query(&self, stmt: Statement) -> Result<Vec<Row>, TxError> {
+        self.single_query_with_timestamp_bound(stmt, TimestampBound::strong_read())
+            .await
+    }
+
+    /// single_query_with_timestamp_bound executes a query in the single-use read-only transaction with the TimestampBound
+    /// and returns all the rows.
+    /// Use single_with_timestamp_bound to get the read timestamp chosen by the server from RowIterator::metadata.
+    pub async fn single_query_with_timestamp_bound(
+        &self,
+        stmt: Statement,
+        tb: TimestampBound,
+    ) -> Result<Vec<Row>, TxError> {
+        let mut tx = self.single_with_timestamp_bound(tb).await?;
+        let mut iter = tx.query(stmt).await?;
+        let mut rows = vec![];
+        while let Some(row) = iter.next().await? {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// analyze_query returns the query plan of the statement without executing it,
+    /// in the strong single-use read-only transaction.
+    /// No BeginTransaction is issued.
+    /// ```
+    /// use google_cloud_spanner::client::Client;
+    /// use google_cloud_spanner::statement::Statement;
+    ///
+    /// async fn run(client: Client) -> Result<(), anyhow::Error> {
+    ///     let plan = client.analyze_query(Statement::new("SELECT * FROM Guild WHERE OwnerUserID = 'u1'")).await?;
+    ///     println!("{plan}");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn analyze_query(&self, stmt: Statement) -> Result<QueryPlan, TxError> {
+        let mut tx = self.single().await?;
+        Ok(tx.analyze_query(stmt).await?)
+    }
+
+    /// query_as executes a query in the single read-only transaction and converts all the rows into T.
+    /// It returns TooManyRows without reading the rest of the rows if the rows are more than max_rows.
+    /// ```
+    /// use google_cloud_spanner::client::Client;
+    /// use google_cloud_spanner::row::{Error as RowError, Row};
+    /// use google_cloud_spanner::statement::Statement;
+    ///
+    /// struct Guild {
+    ///     guild_id: String,
+    /// }
+    ///
+    /// impl TryFrom<Row> for Guild {
+    ///     type Error = RowError;
+    ///     fn try_from(row: Row) -> Result<Self, RowError> {
+    ///         Ok(Guild { guild_id: row.column_by_name("GuildID")? })
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), anyhow::Error> {
+    ///     const DATABASE: &str = "projects/local-project/instances/test-instance/databases/local-database";
+    ///     let client = Client::new(DATABASE).await?;
+    ///     let guilds: Vec<Guild> = client.query_as(Statement::new("SELECT GuildID FROM Guild"), Some(1000)).await?;
+    ///     let guild: Option<Guild> = client.query_one_as(Statement::new("SELECT GuildID FROM Guild LIMIT 1")).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn query_as<T>(&self, stmt: Statement, max_rows: Option<usize>) -> Result<Vec<T>, QueryError>
+    where
+        T: TryFrom<Row, Error = RowError>,
+    {
+        let mut tx = self.single().await?;
+        tx.query_as(stmt, max_rows).await
+    }
+
+    /// query_one_as executes a query in the single read-only transaction and converts the row into T.
+    /// It returns None if no row is found, and TooManyRows if more than one row is found.
+    pub async fn query_one_as<T>(&self, stmt: Statement) -> Result<Option<T>, QueryError>
+    where
+        T: TryFrom<Row, Error = RowError>,
+    {
+        let mut tx = self.single().await?;
+        tx.query_one_as(stmt).await
+    }
+
     /// read_only_transaction returns a ReadOnlyTransaction that can be used for
     /// multiple reads from the database.
     ///
@@ -282,8 +620,11 @@ impl Client {
         &self,
         options: ReadOnlyTransactionOption,
     ) -> Result<ReadOnlyTransaction, TxError> {
-        let session = self.get_session().await?;
-        let result = ReadOnlyTransaction::begin(session, options.timestamp_bound, options.call_options).await?;
+        let session = self.sessions.get_read_only().await?;
+        let call_options = self.with_default_priority(options.call_options);
+        let mut result = ReadOnlyTransaction::begin(session, options.timestamp_bound, call_options).await?;
+        result.set_default_priority(self.priority);
+        result.set_default_directed_read_options(self.directed_read_options.clone());
         Ok(result)
     }
 
@@ -304,8 +645,27 @@ impl Client {
         &self,
         options: ReadOnlyTransactionOption,
     ) -> Result<BatchReadOnlyTransaction, TxError> {
+        // The partitioned reads and queries are not supported by the multiplexed session.
         let session = self.get_session().await?;
-        let result = BatchReadOnlyTransaction::begin(session, options.timestamp_bound, options.call_options).await?;
+        let call_options = self.with_default_priority(options.call_options);
+        let mut result = BatchReadOnlyTransaction::begin(session, options.timestamp_bound, call_options).await?;
+        result.set_default_priority(self.priority);
+        result.set_default_directed_read_options(self.directed_read_options.clone());
+        Ok(result)
+    }
+
+    /// batch_read_only_transaction_from_id returns the BatchReadOnlyTransaction begun by another client,
+    /// such as in another process, so that the partitions of it can be executed by this client.
+    /// The session of the transaction is still owned by the client that began it, which must keep the
+    /// transaction open until all the partitions are executed.
+    pub async fn batch_read_only_transaction_from_id(
+        &self,
+        id: BatchReadOnlyTransactionId,
+    ) -> Result<BatchReadOnlyTransaction, TxError> {
+        let session = self.sessions.get_detached(id.session.clone()).await?;
+        let mut result = BatchReadOnlyTransaction::from_id(session, id);
+        result.set_default_priority(self.priority);
+        result.set_default_directed_read_options(self.directed_read_options.clone());
         Ok(result)
     }
 
@@ -330,26 +690,38 @@ impl Client {
     ///
     /// PartitionedUpdate returns an estimated count of the number of rows affected.
     /// The actual number of affected rows may be greater than the estimate.
+    ///
+    /// The queries and the DML statements with THEN RETURN are rejected, and the
+    /// statement is retried on ABORTED and UNAVAILABLE errors until the timeout of the options.
     pub async fn partitioned_update_with_option(
         &self,
         stmt: Statement,
         options: PartitionedUpdateOption,
     ) -> Result<i64, TxError> {
-        let ro = TransactionRetrySetting::new(vec![Code::Aborted, Code::Internal]);
+        validate_partitioned_dml(&stmt.sql)?;
+        let begin_options = self.with_default_priority(options.begin_options.clone());
+        let tx_options = partitioned_dml_options(options.exclude_txn_from_change_streams);
+        let mut ro = TransactionRetrySetting::new(vec![Code::Aborted, Code::Internal, Code::Unavailable]);
+        ro.timeout = options.timeout;
         let session = Some(self.get_session().await?);
 
         // reuse session
-        invoke_fn(
-            options.begin_options.cancel.clone(),
-            Some(ro),
+        let run = invoke_transaction_fn(
+            begin_options.cancel.clone(),
+            ro,
             |session| async {
-                let mut tx =
-                    match ReadWriteTransaction::begin_partitioned_dml(session.unwrap(), options.begin_options.clone())
-                        .await
-                    {
-                        Ok(tx) => tx,
-                        Err(e) => return Err((TxError::GRPC(e.status), Some(e.session))),
-                    };
+                let mut tx = match ReadWriteTransaction::begin_internal(
+                    session.unwrap(),
+                    tx_options.clone(),
+                    begin_options.clone(),
+                    None,
+                )
+                .await
+                {
+                    Ok(tx) => tx,
+                    Err(e) => return Err((TxError::GRPC(e.status), Some(e.session))),
+                };
+                tx.set_default_priority(self.priority);
                 let qo = match options.query_options.clone() {
                     Some(o) => o,
                     None => QueryOptions::default(),
@@ -359,8 +731,13 @@ impl Client {
                     .map_err(|e| (TxError::GRPC(e), tx.take_session()))
             },
             session,
-        )
-        .await
+        );
+        match options.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, run)
+                .await
+                .unwrap_or_else(|_| Err(Status::deadline_exceeded("partitioned update timed out").into())),
+            None => run.await,
+        }
     }
 
     /// apply_at_least_once may attempt to apply mutations more than once; if
@@ -385,23 +762,45 @@ impl Client {
     /// apply's default replay protection may require an additional RPC.  So this
     /// method may be appropriate for latency sensitive and/or high throughput blind
     /// writing.
+    ///
+    /// The options are CommitOptions or ApplyAtLeastOnceOption, which has the transaction tag too.
     pub async fn apply_at_least_once_with_option(
         &self,
         ms: Vec<Mutation>,
-        options: CommitOptions,
+        options: impl Into<ApplyAtLeastOnceOption>,
     ) -> Result<Option<Timestamp>, TxError> {
+        let result = self.apply_at_least_once_with_commit_result(ms, options).await?;
+        Ok(result.timestamp)
+    }
+
+    /// apply_at_least_once_with_commit_result is the same as apply_at_least_once_with_option
+    /// but returns the CommitResult, which contains the commit stats if
+    /// CommitOptions::return_commit_stats is true.
+    pub async fn apply_at_least_once_with_commit_result(
+        &self,
+        ms: Vec<Mutation>,
+        options: impl Into<ApplyAtLeastOnceOption>,
+    ) -> Result<CommitResult, TxError> {
+        let options = options.into();
+        if self.reject_delete_all_keys && !options.commit_options.allow_delete_all_keys {
+            check_delete_all_keys(&ms)?;
+        }
         let ro = TransactionRetrySetting::default();
         let mut session = self.get_session().await?;
+        let commit_options = CommitOptions {
+            call_options: self.with_default_priority(options.commit_options.call_options.clone()),
+            max_commit_delay: options.commit_options.max_commit_delay.or(self.max_commit_delay),
+            ..options.commit_options.clone()
+        };
+        let transaction_tag = options.transaction_tag.as_deref();
 
         invoke_fn(
-            options.call_options.cancel.clone(),
+            commit_options.call_options.cancel.clone(),
             Some(ro),
             |session| async {
-                let tx = commit_request::Transaction::SingleUseTransaction(TransactionOptions {
-                    mode: Some(transaction_options::Mode::ReadWrite(transaction_options::ReadWrite::default())),
-                });
-                match commit(session, ms.clone(), tx, options.clone()).await {
-                    Ok(s) => Ok(s.commit_timestamp.map(|s| s.into())),
+                let tx = at_least_once_transaction(&options);
+                match commit(session, ms.clone(), tx, commit_options.clone(), transaction_tag).await {
+                    Ok(s) => Ok(s.into()),
                     Err(e) => Err((TxError::GRPC(e), session)),
                 }
             },
@@ -439,7 +838,18 @@ impl Client {
         ms: Vec<Mutation>,
         options: ReadWriteTransactionOption,
     ) -> Result<Option<Timestamp>, TxError> {
-        let result: Result<(Option<Timestamp>, ()), TxError> = self
+        let result = self.apply_with_commit_result(ms, options).await?;
+        Ok(result.timestamp)
+    }
+
+    /// apply_with_commit_result is the same as apply_with_option but returns the CommitResult,
+    /// which contains the commit stats if ReadWriteTransactionOption::commit_options.return_commit_stats is true.
+    pub async fn apply_with_commit_result(
+        &self,
+        ms: Vec<Mutation>,
+        options: ReadWriteTransactionOption,
+    ) -> Result<CommitResult, TxError> {
+        let result: Result<(CommitResult, ()), TxError> = self
             .read_write_transaction_sync_with_option(
                 |tx, _cancel| {
                     tx.buffer_write(ms.to_vec());
@@ -451,6 +861,17 @@ impl Client {
         Ok(result?.0)
     }
 
+    /// commit_builder returns the CommitBuilder which checks the mutations against the limits of a commit
+    /// before they are sent.
+    pub fn commit_builder(&self) -> CommitBuilder<'_> {
+        CommitBuilder {
+            client: self,
+            limits: CommitLimits::default(),
+            split: false,
+            options: ReadWriteTransactionOption::default(),
+        }
+    }
+
     /// ReadWriteTransaction executes a read-write transaction, with retries as
     /// necessary.
     ///
@@ -463,9 +884,12 @@ impl Client {
     /// returns an error other than ABORTED, ReadWriteTransaction will abort the
     /// transaction and return the error.
     ///
-    /// To limit the number of retries, set a deadline on the Context rather than
-    /// using a fixed limit on the number of attempts. ReadWriteTransaction will
-    /// retry as needed until that deadline is met.
+    /// Before calling f again, ReadWriteTransaction waits the delay in the RetryInfo
+    /// of the ABORTED error, or the exponential backoff with jitter without it.
+    /// To limit the retries, set the attempts and the timeout in
+    /// ReadWriteTransactionOption::retry_setting, or cancel the CancellationToken.
+    ///
+    /// The transaction borrowed by f can not be moved out of f.
     ///
     /// See <https://godoc.org/cloud.google.com/go/spanner#ReadWriteTransaction> for
     /// more details.
@@ -493,7 +917,7 @@ impl Client {
     ///                 let user_id = row.column_by_name::<String>("UserId")?;
     ///                 let item_id = row.column_by_name::<i64>("ItemId")?;
     ///                 let quantity = row.column_by_name::<i64>("Quantity")? + 1;
-    ///                 let m = update("UserItem", &["Quantity"], &[&user_id, &item_id, &quantity]);
+    ///                 let m = update("UserItem", &["UserId", "ItemId", "Quantity"], &[&user_id, &item_id, &quantity]);
     ///                 ms.push(m);
     ///             }
     ///             // The buffered mutation will be committed.  If the commit
@@ -528,9 +952,12 @@ impl Client {
     /// returns an error other than ABORTED, ReadWriteTransaction will abort the
     /// transaction and return the error.
     ///
-    /// To limit the number of retries, set a deadline on the Context rather than
-    /// using a fixed limit on the number of attempts. ReadWriteTransaction will
-    /// retry as needed until that deadline is met.
+    /// Before calling f again, ReadWriteTransaction waits the delay in the RetryInfo
+    /// of the ABORTED error, or the exponential backoff with jitter without it.
+    /// To limit the retries, set the attempts and the timeout in
+    /// ReadWriteTransactionOption::retry_setting, or cancel the CancellationToken.
+    ///
+    /// The transaction borrowed by f can not be moved out of f.
     ///
     /// See <https://godoc.org/cloud.google.com/go/spanner#ReadWriteTransaction> for
     /// more details.
@@ -546,18 +973,44 @@ impl Client {
             Option<CancellationToken>,
         ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'tx>>,
     {
+        let (commit_result, value) = self.read_write_transaction_with_commit_result(f, options).await?;
+        Ok((commit_result.timestamp, value))
+    }
+
+    /// read_write_transaction_with_commit_result is the same as read_write_transaction_with_option
+    /// but returns the CommitResult, which contains the commit stats if
+    /// ReadWriteTransactionOption::commit_options.return_commit_stats is true.
+    pub async fn read_write_transaction_with_commit_result<'a, T, E, F>(
+        &'a self,
+        f: F,
+        options: ReadWriteTransactionOption,
+    ) -> Result<(CommitResult, T), E>
+    where
+        E: TryAs<Status> + From<SessionError> + From<Status>,
+        F: for<'tx> Fn(
+            &'tx mut ReadWriteTransaction,
+            Option<CancellationToken>,
+        ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'tx>>,
+    {
+        let retry_setting = options.retry_setting.clone();
+        let transaction_tag = options.transaction_tag.clone();
+        let tx_options = read_write_options(options.exclude_txn_from_change_streams, options.read_lock_mode);
         let (bo, co) = Client::split_read_write_transaction_option(options);
 
-        let ro = TransactionRetrySetting::default();
         let session = Some(self.get_session().await?);
         let cancel = bo.cancel.clone();
         // must reuse session
-        invoke_fn(
+        invoke_transaction_fn(
             cancel.clone(),
-            Some(ro),
+            retry_setting,
             |session| async {
                 let cancel = cancel.clone().map(|v| v.child_token());
-                let mut tx = self.create_read_write_transaction::<E>(session, bo.clone()).await?;
+                let mut tx = self.create_read_write_transaction(
+                    session,
+                    tx_options.clone(),
+                    bo.clone(),
+                    transaction_tag.clone(),
+                );
                 let result = f(&mut tx, cancel).await;
                 tx.finish(result, Some(co.clone())).await
             },
@@ -566,7 +1019,7 @@ impl Client {
         .await
     }
 
-    /// begin_read_write_transaction creates new ReadWriteTransaction.
+    /// begin_read_write_transaction creates new ReadWriteTransaction, which is begun by its first statement.
     /// ```
     /// use google_cloud_spanner::mutation::update;
     /// use google_cloud_spanner::key::{Key, all_keys};
@@ -614,9 +1067,67 @@ impl Client {
     /// ```
     pub async fn begin_read_write_transaction(&self) -> Result<ReadWriteTransaction, TxError> {
         let session = self.get_session().await?;
-        ReadWriteTransaction::begin(session, ReadWriteTransactionOption::default().begin_options)
-            .await
-            .map_err(|e| e.status.into())
+        let options = ReadWriteTransactionOption::default();
+        Ok(self.create_read_write_transaction(
+            Some(session),
+            read_write_options(false, None),
+            options.begin_options,
+            None,
+        ))
+    }
+
+    /// batch_write applies the mutation groups without the atomicity across the groups.
+    /// Each result of the returned iterator tells which groups are committed or failed,
+    /// so that only the failed groups can be retried.
+    ///
+    /// The BatchWrite is not replay protected, so that the groups may be applied more than once.
+    /// ```
+    /// use google_cloud_spanner::client::Client;
+    /// use google_cloud_spanner::mutation::{group_mutations, insert, CommitLimits};
+    ///
+    /// async fn run(client: Client) -> Result<(), anyhow::Error> {
+    ///     let ms = vec![insert("Guild", &["GuildId", "OwnerUserId"], &[&"1", &"2"])];
+    ///     let mut results = client.batch_write(group_mutations(ms, &CommitLimits::default())).await?;
+    ///     while let Some(result) = results.next().await? {
+    ///         if let Err(status) = result.result {
+    ///             println!("failed groups={:?} status={:?}", result.indexes, status);
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn batch_write(&self, groups: Vec<MutationGroup>) -> Result<BatchWriteIterator, TxError> {
+        self.batch_write_with_option(groups, BatchWriteOption::default()).await
+    }
+
+    /// batch_write_with_option applies the mutation groups without the atomicity across the groups.
+    pub async fn batch_write_with_option(
+        &self,
+        groups: Vec<MutationGroup>,
+        options: BatchWriteOption,
+    ) -> Result<BatchWriteIterator, TxError> {
+        if self.reject_delete_all_keys && !options.allow_delete_all_keys {
+            for group in &groups {
+                check_delete_all_keys(&group.mutations)?;
+            }
+        }
+        let mut session = self.get_session().await?;
+        let call_options = self.with_default_priority(options.call_options);
+        let request = BatchWriteRequest {
+            session: session.session.name.to_string(),
+            request_options: Transaction::create_request_options(call_options.priority, None, None),
+            mutation_groups: groups.into_iter().map(|g| g.into()).collect(),
+            exclude_txn_from_change_streams: options.exclude_txn_from_change_streams,
+        };
+        let result = session
+            .spanner_client
+            .batch_write(request, call_options.cancel, call_options.retry)
+            .await;
+        let streaming = session.invalidate_if_needed(result).await?.into_inner();
+        Ok(BatchWriteIterator {
+            streaming,
+            _session: session,
+        })
     }
 
     /// Get open session count.
@@ -624,27 +1135,61 @@ impl Client {
         self.sessions.num_opened()
     }
 
+    /// warmup creates the sessions missing from SessionConfig::min_opened and returns the number of the sessions created.
+    /// The initial sessions are created when the client is created, and the ones failed to be created then
+    /// are retried in the background. Call warmup to wait for them, such as before reporting the readiness.
+    pub async fn warmup(&self) -> Result<usize, SessionError> {
+        self.sessions.warmup().await
+    }
+
+    /// Get the SessionConfig the session pool is running with.
+    pub fn session_config(&self) -> &SessionConfig {
+        self.sessions.config()
+    }
+
+    /// Get the current session counts of the session pool, such as for logging.
+    pub fn session_pool_stats(&self) -> SessionPoolStats {
+        self.sessions.stats()
+    }
+
+    /// Get the sessions checked out from the session pool, which are recorded with SessionConfig::leak_detection_threshold.
+    pub fn checked_out_sessions(&self) -> Vec<CheckedOutSession> {
+        self.sessions.checked_out_sessions()
+    }
+
+    /// Get the number of the requests which used the multiplexed session.
+    pub fn multiplexed_session_uses(&self) -> u64 {
+        self.sessions.multiplexed_session_uses()
+    }
+
     async fn read_write_transaction_sync_with_option<T, E>(
         &self,
         f: impl Fn(&mut ReadWriteTransaction, Option<CancellationToken>) -> Result<T, E>,
         options: ReadWriteTransactionOption,
-    ) -> Result<(Option<Timestamp>, T), E>
+    ) -> Result<(CommitResult, T), E>
     where
         E: TryAs<Status> + From<SessionError> + From<Status>,
     {
+        let retry_setting = options.retry_setting.clone();
+        let transaction_tag = options.transaction_tag.clone();
+        let tx_options = read_write_options(options.exclude_txn_from_change_streams, options.read_lock_mode);
         let (bo, co) = Client::split_read_write_transaction_option(options);
 
-        let ro = TransactionRetrySetting::default();
         let session = Some(self.get_session().await?);
 
         // reuse session
         let cancel = bo.cancel.clone();
-        invoke_fn(
+        invoke_transaction_fn(
             cancel.clone(),
-            Some(ro),
+            retry_setting,
             |session| async {
                 let cancel = cancel.clone().map(|v| v.child_token());
-                let mut tx = self.create_read_write_transaction::<E>(session, bo.clone()).await?;
+                let mut tx = self.create_read_write_transaction(
+                    session,
+                    tx_options.clone(),
+                    bo.clone(),
+                    transaction_tag.clone(),
+                );
                 let result = f(&mut tx, cancel);
                 tx.finish(result, Some(co.clone())).await
             },
@@ -653,17 +1198,30 @@ impl Client {
         .await
     }
 
-    async fn create_read_write_transaction<E>(
+    /// create_read_write_transaction returns the transaction begun by its first statement.
+    fn create_read_write_transaction(
         &self,
         session: Option<ManagedSession>,
+        tx_options: TransactionOptions,
         bo: CallOptions,
-    ) -> Result<ReadWriteTransaction, (E, Option<ManagedSession>)>
-    where
-        E: TryAs<Status> + From<SessionError> + From<Status>,
-    {
-        ReadWriteTransaction::begin(session.unwrap(), bo)
-            .await
-            .map_err(|e| (E::from(e.status), Some(e.session)))
+        transaction_tag: Option<String>,
+    ) -> ReadWriteTransaction {
+        let mut tx = ReadWriteTransaction::begin_inline(
+            session.unwrap(),
+            tx_options,
+            self.with_default_priority(bo),
+            transaction_tag,
+        );
+        tx.set_default_priority(self.priority);
+        tx.set_default_max_commit_delay(self.max_commit_delay);
+        tx.set_reject_delete_all_keys(self.reject_delete_all_keys);
+        tx
+    }
+
+    /// with_default_priority returns the options with the default priority of the client if they have no priority.
+    fn with_default_priority(&self, mut options: CallOptions) -> CallOptions {
+        options.priority = options.priority.or(self.priority);
+        options
     }
 
     async fn get_session(&self) -> Result<ManagedSession, SessionError> {
@@ -674,3 +1232,433 @@ impl Client {
         (options.begin_options, options.commit_options)
     }
 }
+
+/// CommitBuilder commits the mutations of an iterator within the limits of a commit,
+/// which are 80,000 mutations and 100 MB by default.
+///
+/// By default all the mutations are committed atomically in one commit, and the CommitLimitError is returned
+/// as soon as the mutations exceed the limits, before anything is sent.
+///
+/// With with_split(true), the mutations are committed in the multiple sequential commits each within the limits.
+/// The atomicity is lost across the commits: the mutations of the earlier commits stay committed
+/// when a later commit fails, and the other transactions can read the database between the commits.
+/// The error contains the commit timestamps of the commits that had already succeeded.
+pub struct CommitBuilder<'a> {
+    client: &'a Client,
+    limits: CommitLimits,
+    split: bool,
+    options: ReadWriteTransactionOption,
+}
+
+impl CommitBuilder<'_> {
+    /// with_limits sets the limits of a commit.
+    pub fn with_limits(mut self, limits: CommitLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// with_index_multiplier sets the multiplier of the mutation count for the secondary indexes.
+    /// See CommitLimits::index_multiplier.
+    pub fn with_index_multiplier(mut self, index_multiplier: usize) -> Self {
+        self.limits.index_multiplier = index_multiplier;
+        self
+    }
+
+    /// with_split sets whether to split the mutations into the multiple non-atomic commits.
+    pub fn with_split(mut self, split: bool) -> Self {
+        self.split = split;
+        self
+    }
+
+    /// with_options sets the options of each commit.
+    pub fn with_options(mut self, options: ReadWriteTransactionOption) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// commit commits the mutations and returns the commit timestamps of the commits in order.
+    /// Nothing is committed and the result is empty if there are no mutations.
+    pub async fn commit(
+        self,
+        mutations: impl IntoIterator<Item = Mutation>,
+    ) -> Result<Vec<Option<Timestamp>>, CommitBuilderError> {
+        let mut splitter = CommitSplitter::new(self.limits, self.split);
+        let mut timestamps = vec![];
+        for mutation in mutations {
+            match splitter.push(mutation) {
+                Ok(Some(ms)) => match self.client.apply_with_option(ms, self.options.clone()).await {
+                    Ok(timestamp) => timestamps.push(timestamp),
+                    Err(e) => return Err(CommitBuilderError::Tx(timestamps, e)),
+                },
+                Ok(None) => {}
+                Err(e) => return Err(CommitBuilderError::Limit(timestamps, e)),
+            }
+        }
+        if let Some(ms) = splitter.finish() {
+            match self.client.apply_with_option(ms, self.options).await {
+                Ok(timestamp) => timestamps.push(timestamp),
+                Err(e) => return Err(CommitBuilderError::Tx(timestamps, e)),
+            }
+        }
+        Ok(timestamps)
+    }
+}
+
+/// at_least_once_transaction returns the single use transaction, in which the mutations are committed
+/// by the one Commit without the BeginTransaction.
+fn at_least_once_transaction(options: &ApplyAtLeastOnceOption) -> commit_request::Transaction {
+    commit_request::Transaction::SingleUseTransaction(read_write_options(options.exclude_txn_from_change_streams, None))
+}
+
+/// validate_partitioned_dml rejects the statements which can not be executed as the partitioned DML.
+fn validate_partitioned_dml(sql: &str) -> Result<(), Status> {
+    let keywords = sql_keywords(sql);
+    if matches!(keywords.first().map(String::as_str), Some("SELECT") | Some("WITH")) {
+        return Err(Status::invalid_argument("the partitioned DML does not support the query"));
+    }
+    if has_then_return(sql) {
+        return Err(Status::invalid_argument("the partitioned DML does not support THEN RETURN"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use google_cloud_gax::grpc::{Code, Status as GrpcStatus};
+
+    use google_cloud_googleapis::rpc::Status;
+    use google_cloud_googleapis::spanner::v1::{commit_request, transaction_options, BatchWriteResponse};
+
+    use crate::apiv1::fake_spanner::FakeSpanner;
+    use crate::client::{
+        at_least_once_transaction, validate_partitioned_dml, ApplyAtLeastOnceOption, BatchWriteResult, Client,
+        ClientConfig, InitializationError, ReadWriteTransactionOption, TxError,
+    };
+    use crate::key::Key;
+    use crate::mutation::{insert, MutationGroup};
+    use crate::retry::TransactionRetrySetting;
+    use crate::session::SessionConfig;
+    use crate::statement::{single_type, Statement};
+    use crate::stats::QueryPlan;
+    use crate::transaction_rw::{CommitOptions, ReadLockMode};
+    use crate::value::TimestampBound;
+    use google_cloud_googleapis::spanner::v1::execute_sql_request::QueryMode;
+    use google_cloud_googleapis::spanner::v1::struct_type::Field;
+    use google_cloud_googleapis::spanner::v1::transaction_options::read_only::TimestampBound as TimestampBoundProto;
+    use google_cloud_googleapis::spanner::v1::transaction_options::Mode;
+    use google_cloud_googleapis::spanner::v1::transaction_selector::Selector;
+    use google_cloud_googleapis::spanner::v1::{
+        PartialResultSet, PlanNode, QueryPlan as ProtoQueryPlan, ResultSetMetadata, ResultSetStats, StructType,
+        TransactionOptions, TypeCode,
+    };
+    use prost_types::value::Kind;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_new_with_invalid_session_config() {
+        for (min_opened, max_opened) in [(0, 0), (11, 10)] {
+            let mut session_config = SessionConfig::default();
+            session_config.min_opened = min_opened;
+            session_config.max_opened = max_opened;
+            let config = ClientConfig {
+                session_config,
+                ..Default::default()
+            };
+            match Client::new_with_config("projects/p/instances/i/databases/d", config).await {
+                Err(InitializationError::InvalidConfig(_)) => {}
+                Err(e) => panic!("unexpected error {e:?}"),
+                Ok(_) => panic!("invalid config must be rejected"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_partitioned_dml() {
+        for sql in [
+            "UPDATE t SET flag = false WHERE flag IS NULL",
+            "DELETE FROM t WHERE note = 'THEN RETURN'",
+            "@{PDML_MAX_PARALLELISM=10} UPDATE t SET a = 1 WHERE true -- THEN RETURN",
+        ] {
+            validate_partitioned_dml(sql).unwrap();
+        }
+        for sql in [
+            "SELECT * FROM t",
+            "  (select 1)",
+            "WITH a AS (SELECT 1) SELECT * FROM a",
+            "UPDATE t SET a = 1 WHERE true THEN RETURN a",
+            "delete from t where true then\n return *",
+        ] {
+            assert_eq!(
+                validate_partitioned_dml(sql).unwrap_err().code(),
+                Code::InvalidArgument,
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_write_result() {
+        let responses = vec![
+            BatchWriteResponse {
+                indexes: vec![0, 2],
+                status: Some(Status {
+                    code: Code::Ok as i32,
+                    ..Default::default()
+                }),
+                commit_timestamp: Some(prost_types::Timestamp { seconds: 100, nanos: 0 }),
+            },
+            BatchWriteResponse {
+                indexes: vec![1],
+                status: Some(Status {
+                    code: Code::AlreadyExists as i32,
+                    message: "Row [1] in table Guild already exists".to_string(),
+                    details: vec![],
+                }),
+                commit_timestamp: None,
+            },
+        ];
+        let results: Vec<BatchWriteResult> = responses.into_iter().map(BatchWriteResult::from).collect();
+        assert_eq!(results[0].indexes, vec![0, 2]);
+        assert_eq!(results[0].result.as_ref().unwrap().as_ref().unwrap().seconds, 100);
+        assert_eq!(results[1].indexes, vec![1]);
+        let status = results[1].result.as_ref().unwrap_err();
+        assert_eq!(status.code(), Code::AlreadyExists);
+        assert_eq!(status.message(), "Row [1] in table Guild already exists");
+
+        let failed: Vec<usize> = results
+            .iter()
+            .filter(|r| r.result.is_err())
+            .flat_map(|r| r.indexes.clone())
+            .collect();
+        assert_eq!(failed, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_write_partial_failure() {
+        let fake = FakeSpanner::default();
+        let client = fake.client().await;
+        fake.set_batch_write(vec![
+            Ok(BatchWriteResponse {
+                indexes: vec![0, 2],
+                status: Some(Status::default()),
+                commit_timestamp: Some(prost_types::Timestamp { seconds: 100, nanos: 0 }),
+            }),
+            Ok(BatchWriteResponse {
+                indexes: vec![1],
+                status: Some(Status {
+                    code: Code::AlreadyExists as i32,
+                    message: "Row [1] in table Guild already exists".to_string(),
+                    details: vec![],
+                }),
+                commit_timestamp: None,
+            }),
+            Ok(BatchWriteResponse {
+                indexes: vec![3],
+                status: Some(Status::default()),
+                commit_timestamp: Some(prost_types::Timestamp { seconds: 101, nanos: 0 }),
+            }),
+        ]);
+        let groups = (0..4)
+            .map(|i| MutationGroup::new(vec![insert("Guild", &["GuildId"], &[&i.to_string()])]))
+            .collect();
+        let mut iter = client.batch_write(groups).await.unwrap();
+        let mut results = vec![];
+        while let Some(result) = iter.next().await.unwrap() {
+            results.push(result);
+        }
+        let summary: Vec<(Vec<usize>, Result<i64, Code>)> = results
+            .into_iter()
+            .map(|r| (r.indexes, r.result.map(|ts| ts.unwrap().seconds).map_err(|e| e.code())))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (vec![0, 2], Ok(100)),
+                (vec![1], Err(Code::AlreadyExists)),
+                (vec![3], Ok(101)),
+            ]
+        );
+        // the iterator holds the session until it is dropped.
+        drop(iter);
+
+        // the stream broken in the middle returns the results before the error.
+        fake.set_batch_write(vec![
+            Ok(BatchWriteResponse {
+                indexes: vec![0],
+                status: Some(Status::default()),
+                commit_timestamp: Some(prost_types::Timestamp { seconds: 102, nanos: 0 }),
+            }),
+            Err(GrpcStatus::new(Code::Internal, "internal")),
+        ]);
+        let groups = vec![MutationGroup::new(vec![insert("Guild", &["GuildId"], &[&"0"])])];
+        let mut iter = client.batch_write(groups).await.unwrap();
+        assert_eq!(iter.next().await.unwrap().unwrap().indexes, vec![0]);
+        assert_eq!(iter.next().await.unwrap_err().code(), Code::Internal);
+        drop(iter);
+
+        // the BatchWrite is not retried by default, because the groups may be applied before the error.
+        fake.set_error("BatchWrite", GrpcStatus::new(Code::Unavailable, "unavailable"));
+        let groups = vec![MutationGroup::new(vec![insert("Guild", &["GuildId"], &[&"0"])])];
+        match client.batch_write(groups).await {
+            Err(TxError::GRPC(status)) => assert_eq!(status.code(), Code::Unavailable),
+            _ => panic!("the BatchWrite must fail"),
+        }
+        assert_eq!(fake.requests("BatchWrite"), 3);
+        client.close().await;
+        assert_eq!(fake.deleted_sessions(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_single_use_reads_without_begin_transaction() {
+        let fake = FakeSpanner::default();
+        let client = fake.client().await;
+        fake.set_partial_result_sets(vec![PartialResultSet {
+            metadata: Some(ResultSetMetadata {
+                row_type: Some(StructType {
+                    fields: vec![Field {
+                        name: "GuildId".to_string(),
+                        r#type: Some(single_type(TypeCode::String)),
+                    }],
+                }),
+                ..Default::default()
+            }),
+            values: vec![prost_types::Value {
+                kind: Some(Kind::StringValue("guild1".to_string())),
+            }],
+            ..Default::default()
+        }]);
+
+        let row = client
+            .read_row("Guild", &["GuildId"], Key::new(&"guild1"))
+            .await
+            .unwrap();
+        assert_eq!(row.unwrap().column_by_name::<String>("GuildId").unwrap(), "guild1");
+        let rows = client
+            .single_query(Statement::new("SELECT GuildId FROM Guild"))
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        let stmt = Statement::new("SELECT GuildId FROM Guild");
+        let tb = TimestampBound::exact_staleness(Duration::from_secs(15));
+        let rows = client.single_query_with_timestamp_bound(stmt, tb).await.unwrap();
+        assert_eq!(rows[0].column_by_name::<String>("GuildId").unwrap(), "guild1");
+
+        // each read is one request in the single-use transaction.
+        assert_eq!(fake.requests("BeginTransaction"), 0);
+        assert_eq!(fake.requests("StreamingRead"), 1);
+        assert_eq!(fake.requests("ExecuteStreamingSql"), 2);
+        let bounds: Vec<TimestampBoundProto> = fake
+            .selectors()
+            .into_iter()
+            .map(|selector| match selector.and_then(|s| s.selector) {
+                Some(Selector::SingleUse(TransactionOptions {
+                    mode: Some(Mode::ReadOnly(ro)),
+                    ..
+                })) => ro.timestamp_bound.unwrap(),
+                other => panic!("unexpected selector {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            bounds,
+            vec![
+                TimestampBoundProto::Strong(true),
+                TimestampBoundProto::Strong(true),
+                TimestampBoundProto::ExactStaleness(prost_types::Duration { seconds: 15, nanos: 0 }),
+            ]
+        );
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_analyze_query() {
+        let fake = FakeSpanner::default();
+        let client = fake.client().await;
+        let plan = ProtoQueryPlan {
+            plan_nodes: vec![PlanNode {
+                display_name: "Distributed Union".to_string(),
+                ..Default::default()
+            }],
+        };
+        fake.set_partial_result_sets(vec![PartialResultSet {
+            metadata: Some(ResultSetMetadata {
+                row_type: Some(StructType {
+                    fields: vec![Field {
+                        name: "GuildId".to_string(),
+                        r#type: Some(single_type(TypeCode::String)),
+                    }],
+                }),
+                ..Default::default()
+            }),
+            stats: Some(ResultSetStats {
+                query_plan: Some(plan.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }]);
+
+        let stmt = Statement::new("SELECT GuildId FROM Guild");
+        assert_eq!(client.analyze_query(stmt).await.unwrap(), QueryPlan::from(plan));
+        assert_eq!(fake.requests("BeginTransaction"), 0);
+        let queries = fake.queries();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].query_mode, QueryMode::Plan as i32);
+        assert!(matches!(
+            queries[0].transaction.as_ref().and_then(|tx| tx.selector.as_ref()),
+            Some(Selector::SingleUse(_))
+        ));
+        client.close().await;
+    }
+
+    #[test]
+    fn test_at_least_once_transaction() {
+        let options = ApplyAtLeastOnceOption::from(CommitOptions {
+            return_commit_stats: true,
+            ..Default::default()
+        });
+        assert!(options.commit_options.return_commit_stats);
+        assert_eq!(options.transaction_tag, None);
+        match at_least_once_transaction(&options) {
+            commit_request::Transaction::SingleUseTransaction(tx) => {
+                assert!(!tx.exclude_txn_from_change_streams);
+                assert!(matches!(tx.mode, Some(transaction_options::Mode::ReadWrite(_))));
+            }
+            _ => panic!("must be the single use transaction"),
+        }
+
+        let options = options
+            .with_transaction_tag("blind-write")
+            .with_exclude_txn_from_change_streams(true);
+        assert_eq!(options.transaction_tag.as_deref(), Some("blind-write"));
+        match at_least_once_transaction(&options) {
+            commit_request::Transaction::SingleUseTransaction(tx) => assert!(tx.exclude_txn_from_change_streams),
+            _ => panic!("must be the single use transaction"),
+        }
+    }
+
+    #[test]
+    fn test_read_write_transaction_option_read_lock_mode() {
+        let default_take = TransactionRetrySetting::default().inner.take;
+        let options = ReadWriteTransactionOption::default();
+        assert_eq!(options.read_lock_mode, None);
+
+        let options = ReadWriteTransactionOption::default().with_read_lock_mode(ReadLockMode::Pessimistic);
+        assert_eq!(options.read_lock_mode, Some(ReadLockMode::Pessimistic));
+        assert_eq!(options.retry_setting.inner.take, default_take);
+
+        let options = ReadWriteTransactionOption::default().with_read_lock_mode(ReadLockMode::Optimistic);
+        assert_eq!(options.read_lock_mode, Some(ReadLockMode::Optimistic));
+        assert_eq!(options.retry_setting.inner.take, default_take);
+
+        // the retry setting of the caller is kept in either order.
+        let mut retry_setting = TransactionRetrySetting::default();
+        retry_setting.inner.take = 1;
+        let options = ReadWriteTransactionOption::default()
+            .with_retry_setting(retry_setting.clone())
+            .with_read_lock_mode(ReadLockMode::Optimistic);
+        assert_eq!(options.retry_setting.inner.take, 1);
+        let options = ReadWriteTransactionOption::default()
+            .with_read_lock_mode(ReadLockMode::Optimistic)
+            .with_retry_setting(TransactionRetrySetting::optimistic());
+        assert_eq!(options.retry_setting.inner.take, default_take * 2);
+    }
+}
@@ -0,0 +1,563 @@
+//! serde Deserializer for the Row.
+//!
+//! The columns are deserialized by their Spanner types.
+//! * BOOL, INT64, FLOAT32 and FLOAT64 are deserialized as the numbers, so the range of the narrower integers is checked by serde.
+//! * STRING, NUMERIC, TIMESTAMP, DATE and INTERVAL are deserialized as the strings. Use the types that deserialize themselves from the RFC3339 strings for TIMESTAMP.
+//! * BYTES is deserialized as the bytes. `Vec<u8>` and `serde_bytes` are supported.
+//! * JSON is deserialized as the nested structure with the `serde_json` feature.
+//! * ARRAY is deserialized as the sequence and STRUCT is deserialized as the map.
+//!
+//! ```
+//! use google_cloud_spanner::row::Row;
+//!
+//! #[derive(serde::Deserialize)]
+//! #[serde(rename_all = "PascalCase")]
+//! struct User {
+//!     user_id: String,
+//!     level: u32,
+//!     nickname: Option<String>,
+//! }
+//!
+//! fn read(row: &Row) -> Result<User, google_cloud_spanner::de::Error> {
+//!     row.deserialize()
+//! }
+//! ```
+
+use std::fmt::Display;
+
+use prost_types::value::Kind;
+use prost_types::Value;
+use serde::de::value::StrDeserializer;
+use serde::de::{DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+
+use google_cloud_googleapis::spanner::v1::struct_type::Field;
+use google_cloud_googleapis::spanner::v1::{Type, TypeCode};
+
+use crate::row::Row;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Message(String),
+    #[error("Unexpected NULL: column={0}")]
+    UnexpectedNull(String),
+    #[error("Failed to deserialize column: column={0}, {1}")]
+    Column(String, #[source] Box<Error>),
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+/// with_column adds the column name to the error unless it already has one.
+fn with_column(e: Error, column: &str) -> Error {
+    match e {
+        Error::Message(_) => Error::Column(column.to_string(), Box::new(e)),
+        e => e,
+    }
+}
+
+pub(crate) struct RowDeserializer<'a> {
+    fields: &'a [Field],
+    values: &'a [Value],
+}
+
+impl<'a> RowDeserializer<'a> {
+    pub(crate) fn new(row: &'a Row) -> Self {
+        RowDeserializer {
+            fields: row.fields(),
+            values: row.values(),
+        }
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for RowDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(FieldsAccess {
+            fields: self.fields,
+            values: self.values,
+            index: 0,
+        })
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(ValuesAccess {
+            fields: self.fields,
+            values: self.values,
+            element_type: None,
+            index: 0,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct map struct enum identifier ignored_any
+    }
+}
+
+/// FieldsAccess accesses the columns of the row or the fields of the struct as a map.
+struct FieldsAccess<'a> {
+    fields: &'a [Field],
+    values: &'a [Value],
+    index: usize,
+}
+
+impl<'de> MapAccess<'de> for FieldsAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        if self.index >= self.values.len() {
+            return Ok(None);
+        }
+        let name = self.fields.get(self.index).map(|f| f.name.as_str()).unwrap_or_default();
+        let key: StrDeserializer<'_, Error> = name.into_deserializer();
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let field = self.fields.get(self.index);
+        let value = &self.values[self.index];
+        self.index += 1;
+        let column = field.map(|f| f.name.as_str()).unwrap_or_default();
+        seed.deserialize(ValueDeserializer {
+            value,
+            value_type: field.and_then(|f| f.r#type.as_ref()),
+            column,
+        })
+        .map_err(|e| with_column(e, column))
+    }
+}
+
+/// ValuesAccess accesses the columns of the row or the elements of the array as a sequence.
+struct ValuesAccess<'a> {
+    fields: &'a [Field],
+    values: &'a [Value],
+    // the type of the array elements, or None for the columns of the row
+    element_type: Option<(&'a str, Option<&'a Type>)>,
+    index: usize,
+}
+
+impl<'de> SeqAccess<'de> for ValuesAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        let value = match self.values.get(self.index) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let (column, value_type) = match self.element_type {
+            Some(element_type) => element_type,
+            None => match self.fields.get(self.index) {
+                Some(field) => (field.name.as_str(), field.r#type.as_ref()),
+                None => ("", None),
+            },
+        };
+        self.index += 1;
+        seed.deserialize(ValueDeserializer {
+            value,
+            value_type,
+            column,
+        })
+        .map(Some)
+        .map_err(|e| with_column(e, column))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len() - self.index)
+    }
+}
+
+struct ValueDeserializer<'a> {
+    value: &'a Value,
+    value_type: Option<&'a Type>,
+    column: &'a str,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    fn code(&self) -> TypeCode {
+        self.value_type
+            .and_then(|tp| TypeCode::from_i32(tp.code))
+            .unwrap_or(TypeCode::Unspecified)
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self.value.kind, None | Some(Kind::NullValue(_)))
+    }
+
+    fn decode_bytes(&self, s: &str) -> Result<Vec<u8>, Error> {
+        crate::row::decode_base64(s).map_err(|e| Error::Message(format!("invalid base64: {e}")))
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let kind = match &self.value.kind {
+            None | Some(Kind::NullValue(_)) => return Err(Error::UnexpectedNull(self.column.to_string())),
+            Some(kind) => kind,
+        };
+        match (self.code(), kind) {
+            (TypeCode::Int64 | TypeCode::Enum, Kind::StringValue(s)) => match s.parse::<i64>() {
+                Ok(v) => visitor.visit_i64(v),
+                Err(e) => Err(Error::Message(format!("invalid INT64 {s}: {e}"))),
+            },
+            (TypeCode::Float64 | TypeCode::Float32, Kind::StringValue(s)) => match s.as_str() {
+                "NaN" => visitor.visit_f64(f64::NAN),
+                "Infinity" => visitor.visit_f64(f64::INFINITY),
+                "-Infinity" => visitor.visit_f64(f64::NEG_INFINITY),
+                _ => Err(Error::Message(format!("invalid FLOAT64 {s}"))),
+            },
+            (TypeCode::Bytes | TypeCode::Proto, Kind::StringValue(s)) => visitor.visit_byte_buf(self.decode_bytes(s)?),
+            #[cfg(feature = "serde_json")]
+            (TypeCode::Json, Kind::StringValue(s)) => {
+                let json: serde_json::Value =
+                    serde_json::from_str(s).map_err(|e| Error::Message(format!("invalid JSON: {e}")))?;
+                json.deserialize_any(visitor).map_err(|e| Error::Message(e.to_string()))
+            }
+            (TypeCode::Struct, Kind::ListValue(s)) => {
+                let fields = self
+                    .value_type
+                    .and_then(|tp| tp.struct_type.as_ref())
+                    .map(|tp| tp.fields.as_slice())
+                    .unwrap_or_default();
+                visitor.visit_map(FieldsAccess {
+                    fields,
+                    values: &s.values,
+                    index: 0,
+                })
+            }
+            (_, Kind::ListValue(s)) => {
+                let element_type = self.value_type.and_then(|tp| tp.array_element_type.as_deref());
+                visitor.visit_seq(ValuesAccess {
+                    fields: &[],
+                    values: &s.values,
+                    element_type: Some((self.column, element_type)),
+                    index: 0,
+                })
+            }
+            (_, Kind::StructValue(s)) => {
+                visitor.visit_map(serde::de::value::MapDeserializer::new(s.fields.iter().map(|(name, value)| {
+                    (
+                        name.as_str(),
+                        ValueDeserializer {
+                            value,
+                            value_type: None,
+                            column: name.as_str(),
+                        },
+                    )
+                })))
+            }
+            (_, Kind::StringValue(s)) => visitor.visit_borrowed_str(s),
+            (_, Kind::BoolValue(v)) => visitor.visit_bool(*v),
+            (_, Kind::NumberValue(v)) => visitor.visit_f64(*v),
+            (_, Kind::NullValue(_)) => unreachable!(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.is_null() {
+            visitor.visit_unit()
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        // Vec<u8> is deserialized as the sequence of u8
+        match (self.code(), &self.value.kind) {
+            (TypeCode::Bytes | TypeCode::Proto, Some(Kind::StringValue(s))) => {
+                let bytes = self.decode_bytes(s)?;
+                visitor.visit_seq(serde::de::value::SeqDeserializer::new(bytes.into_iter()))
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match &self.value.kind {
+            Some(Kind::StringValue(s)) if self.code() != TypeCode::Json => {
+                let variant: StrDeserializer<'_, Error> = s.as_str().into_deserializer();
+                visitor.visit_enum(variant)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit_struct tuple tuple_struct map struct identifier
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for ValueDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use google_cloud_googleapis::spanner::v1::struct_type::Field;
+    use google_cloud_googleapis::spanner::v1::Type;
+    use prost_types::value::Kind;
+    use prost_types::Value;
+    use serde::Deserialize;
+
+    use crate::de::Error;
+    use crate::row::Row;
+    use crate::statement::{Kinds, ToKind, ToStruct, Types};
+
+    fn row(columns: Vec<(&str, Kind, Type)>) -> Row {
+        let mut index = HashMap::new();
+        let mut fields = vec![];
+        let mut values = vec![];
+        for (i, (name, kind, tp)) in columns.into_iter().enumerate() {
+            index.insert(name.to_string(), i);
+            fields.push(Field {
+                name: name.to_string(),
+                r#type: Some(tp),
+            });
+            values.push(Value { kind: Some(kind) });
+        }
+        Row::new(Arc::new(index), Arc::new(fields), values)
+    }
+
+    struct Item {
+        item_id: i64,
+        name: String,
+    }
+
+    impl ToStruct for Item {
+        fn to_kinds(&self) -> Kinds {
+            vec![("ItemId", self.item_id.to_kind()), ("Name", self.name.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![("ItemId", i64::get_type()), ("Name", String::get_type())]
+        }
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct DeItem {
+        item_id: u32,
+        name: String,
+    }
+
+    // the same as serde_bytes::ByteBuf
+    #[derive(Debug, PartialEq)]
+    struct ByteBuf(Vec<u8>);
+
+    impl<'de> Deserialize<'de> for ByteBuf {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct ByteBufVisitor;
+            impl<'de> serde::de::Visitor<'de> for ByteBufVisitor {
+                type Value = ByteBuf;
+                fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str("bytes")
+                }
+                fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                    Ok(ByteBuf(v))
+                }
+            }
+            deserializer.deserialize_byte_buf(ByteBufVisitor)
+        }
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Status {
+        Active,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct User {
+        user_id: String,
+        level: u8,
+        premium: bool,
+        score: f64,
+        nickname: Option<String>,
+        icon: ByteBuf,
+        raw: Vec<u8>,
+        tags: Vec<Option<String>>,
+        items: Vec<DeItem>,
+        status: Status,
+        #[serde(rename = "NaN")]
+        nan: f64,
+    }
+
+    fn user_row(level: i64) -> Row {
+        row(vec![
+            ("UserId", "user1".to_kind(), String::get_type()),
+            ("Level", level.to_kind(), i64::get_type()),
+            ("Premium", true.to_kind(), bool::get_type()),
+            ("Score", 1.5_f64.to_kind(), f64::get_type()),
+            ("Nickname", Option::<String>::None.to_kind(), String::get_type()),
+            ("Icon", vec![1_u8, 2].to_kind(), Vec::<u8>::get_type()),
+            ("Raw", vec![3_u8].to_kind(), Vec::<u8>::get_type()),
+            ("Tags", vec![Some("a"), None].to_kind(), Vec::<&str>::get_type()),
+            (
+                "Items",
+                vec![Item {
+                    item_id: 1,
+                    name: "sword".to_string(),
+                }]
+                .to_kind(),
+                Vec::<Item>::get_type(),
+            ),
+            ("Status", "Active".to_kind(), String::get_type()),
+            ("NaN", f64::NAN.to_kind(), f64::get_type()),
+            ("Unknown", "ignored".to_kind(), String::get_type()),
+            ("UnknownNull", Option::<i64>::None.to_kind(), i64::get_type()),
+        ])
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let user: User = user_row(10).deserialize().unwrap();
+        assert_eq!(user.user_id, "user1");
+        assert_eq!(user.level, 10);
+        assert!(user.premium);
+        assert_eq!(user.score, 1.5);
+        assert_eq!(user.nickname, None);
+        assert_eq!(user.icon.0, vec![1, 2]);
+        assert_eq!(user.raw, vec![3]);
+        assert_eq!(user.tags, vec![Some("a".to_string()), None]);
+        assert_eq!(
+            user.items,
+            vec![DeItem {
+                item_id: 1,
+                name: "sword".to_string()
+            }]
+        );
+        assert_eq!(user.status, Status::Active);
+        assert!(user.nan.is_nan());
+
+        let tuple: (String, i64) = row(vec![
+            ("UserId", "user1".to_kind(), String::get_type()),
+            ("Level", 3_i64.to_kind(), i64::get_type()),
+        ])
+        .deserialize()
+        .unwrap();
+        assert_eq!(tuple, ("user1".to_string(), 3));
+    }
+
+    #[test]
+    fn test_deserialize_error() {
+        match user_row(256).deserialize::<User>() {
+            Err(Error::Column(column, e)) => {
+                assert_eq!(column, "Level");
+                assert!(e.to_string().contains("256"), "{e}");
+            }
+            r => panic!("must be column error {:?}", r),
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        #[allow(dead_code)]
+        struct NotNull {
+            nickname: String,
+        }
+        match user_row(1).deserialize::<NotNull>() {
+            Err(Error::UnexpectedNull(column)) => assert_eq!(column, "Nickname"),
+            r => panic!("must be unexpected null {:?}", r),
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        #[allow(dead_code)]
+        struct Missing {
+            user_id: String,
+            not_selected: String,
+        }
+        assert!(user_row(1)
+            .deserialize::<Missing>()
+            .unwrap_err()
+            .to_string()
+            .contains("NotSelected"));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_deserialize_json() {
+        use crate::statement::single_type;
+        use google_cloud_googleapis::spanner::v1::TypeCode;
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Payload {
+            name: String,
+            tags: Vec<String>,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(rename_all = "PascalCase")]
+        struct Document {
+            payload: Payload,
+            raw: serde_json::Value,
+        }
+
+        let document: Document = row(vec![
+            ("Payload", r#"{"name":"a","tags":["b"]}"#.to_kind(), single_type(TypeCode::Json)),
+            ("Raw", "[1,null]".to_kind(), single_type(TypeCode::Json)),
+        ])
+        .deserialize()
+        .unwrap();
+        assert_eq!(
+            document.payload,
+            Payload {
+                name: "a".to_string(),
+                tags: vec!["b".to_string()]
+            }
+        );
+        assert_eq!(document.raw, serde_json::json!([1, null]));
+    }
+}
@@ -30,12 +30,30 @@ use crate::statement::ToKind;
 /// Cloud Spanner types. For convenience, Key type supports a range of Rust
 /// types:
 ///   - i64 and Option<i64> are mapped to Cloud Spanner's INT64 type.
-///   - f64 and Option<f64> are mapped to Cloud Spanner's FLOAT64 type.
+///   - i32, i16, i8, u32, u16 and their Option forms are widened to Cloud Spanner's INT64 type.
+///   - u64 and usize are mapped to Cloud Spanner's INT64 type. Values greater than i64::MAX are rejected.
+///   - std::num::NonZeroI64, NonZeroI32, NonZeroI16, NonZeroI8, NonZeroU32, NonZeroU16, NonZeroU8 and their Option forms are mapped to Cloud Spanner's INT64 type.
+///   - f64, f32, Option<f64> and Option<f32> are mapped to Cloud Spanner's FLOAT64 type.
+///   - google_cloud_spanner::value::Float32 and Option<google_cloud_spanner::value::Float32> are mapped to Cloud Spanner's FLOAT32 type.
 ///   - bool and Option<bool> are mapped to Cloud Spanner's BOOL type.
-///   - Vec<u8>, &[u8], Option<Vec<u8>> and Option<&[u8]> is mapped to Cloud Spanner's BYTES type.
-///   - String, &str, Option<String>, Option<&str> are mapped to Cloud Spanner's STRING type.
+///   - Vec<u8>, &[u8], [u8; N], bytes::Bytes, Option<Vec<u8>> and Option<&[u8]> is mapped to Cloud Spanner's BYTES type.
+///   - Vec<T>, &[T] and [T; N] where T is any of the above except u8 are mapped to Cloud Spanner's ARRAY type.
+///   - String, &str, char, Option<String>, Option<&str> are mapped to Cloud Spanner's STRING type.
 ///   - time::OffsetDateTime and Option<time::OffsetDateTime> are mapped to Cloud Spanner's TIMESTAMP type.
+///   - std::time::SystemTime and Option<std::time::SystemTime> are mapped to Cloud Spanner's TIMESTAMP type.
+///   - google_cloud_spanner::value::CommitTimestamp and google_cloud_spanner::value::TimestampValue are mapped to Cloud Spanner's TIMESTAMP type. They can write the commit timestamp.
+///   - google_cloud_spanner::value::Interval and Option<google_cloud_spanner::value::Interval> are mapped to Cloud Spanner's INTERVAL type.
+///   - google_cloud_spanner::value::SpannerNumeric is mapped to Cloud Spanner's NUMERIC type. bigdecimal::BigDecimal is converted to it by SpannerNumeric::try_from, which checks the NUMERIC range (requires the `bigdecimal` feature).
+///   - google_cloud_spanner::value::Numeric and Option<google_cloud_spanner::value::Numeric> are mapped to Cloud Spanner's NUMERIC type (requires the `rust_decimal` feature).
+///   - google_cloud_spanner::value::PgNumeric and google_cloud_spanner::value::PgJsonb are mapped to the PostgreSQL dialect's NUMERIC and JSONB types.
+///   - chrono::DateTime<Tz> and Option<chrono::DateTime<Tz>> are mapped to Cloud Spanner's TIMESTAMP type (requires the `chrono` feature).
+///   - chrono::NaiveDateTime is mapped to Cloud Spanner's TIMESTAMP type in UTC and chrono::NaiveDate to the DATE type (requires the `chrono` feature).
 ///   - time::Date and Option<time::Date> are mapped to Cloud Spanner's DATE type.
+///   - serde_json::Value and Option<serde_json::Value> are mapped to Cloud Spanner's JSON type (requires the `serde_json` feature).
+///   - HashMap<String, V> and BTreeMap<String, V> where V: serde::Serialize are mapped to Cloud Spanner's JSON type (requires the `serde` and `serde_json` features).
+///   - google_cloud_spanner::value::Json<T> where T: serde::Serialize is mapped to Cloud Spanner's JSON type (requires the `serde` and `serde_json` features).
+///   - uuid::Uuid is mapped to Cloud Spanner's STRING type and google_cloud_spanner::value::UuidBytes to the BYTES type (requires the `uuid` feature).
+///   - google_cloud_spanner::value::Proto<T> and google_cloud_spanner::value::EnumValue<T> are mapped to Cloud Spanner's PROTO and ENUM types.
 ///   - google_cloud_spanner::value::CommitTimestamp and Option<google_cloud_spanner::value::CommitTimestamp> are mapped to Cloud Spanner's TIMESTAMP type.
 #[derive(Clone)]
 pub struct Key {
@@ -185,12 +203,67 @@ pub struct KeyRange {
 
 /// all_keys returns a KeySet that represents all Keys of a table or a index.
 pub fn all_keys() -> KeySet {
-    KeySet {
-        inner: InternalKeySet {
-            keys: vec![],
-            ranges: vec![],
-            all: true,
-        },
+    KeySet::all()
+}
+
+impl KeySet {
+    /// all returns a KeySet that represents all Keys of a table or a index.
+    pub fn all() -> KeySet {
+        KeySet {
+            inner: InternalKeySet {
+                keys: vec![],
+                ranges: vec![],
+                all: true,
+            },
+        }
+    }
+
+    /// union returns a KeySet that contains the keys and the ranges of both KeySets.
+    /// # Examples
+    /// ```
+    ///    use google_cloud_spanner::key::{Key, KeyRange, KeySet, RangeKind};
+    ///    let key_set = KeySet::from(Key::new(&"a"))
+    ///        .union(vec![Key::new(&"b"), Key::new(&"c")])
+    ///        .union(KeyRange::new(Key::new(&"x"), Key::new(&"z"), RangeKind::ClosedOpen));
+    /// ```
+    pub fn union(mut self, other: impl Into<KeySet>) -> KeySet {
+        let other = other.into().inner;
+        self.inner.keys.extend(other.keys);
+        self.inner.ranges.extend(other.ranges);
+        self.inner.all |= other.all;
+        self
+    }
+
+    /// from_keys returns a KeySet of the keys.
+    /// # Examples
+    /// ```
+    ///    use google_cloud_spanner::key::KeySet;
+    ///    let key_set = KeySet::from_keys(vec![("tenant-1", 1), ("tenant-1", 2)]);
+    ///    assert_eq!(key_set.len(), 2);
+    /// ```
+    pub fn from_keys<T: ToKey>(keys: impl IntoIterator<Item = T>) -> KeySet {
+        KeySet {
+            inner: InternalKeySet {
+                keys: keys.into_iter().map(|key| key.to_key().values).collect(),
+                ranges: vec![],
+                all: false,
+            },
+        }
+    }
+
+    /// is_all returns true if the KeySet represents all Keys of a table or a index.
+    pub fn is_all(&self) -> bool {
+        self.inner.all
+    }
+
+    /// len returns the number of the keys and the ranges in the KeySet, not including all.
+    pub fn len(&self) -> usize {
+        self.inner.keys.len() + self.inner.ranges.len()
+    }
+
+    /// is_empty returns true if the KeySet has neither keys, ranges nor all, which matches no rows.
+    pub fn is_empty(&self) -> bool {
+        !self.is_all() && self.len() == 0
     }
 }
 
@@ -204,6 +277,20 @@ impl KeyRange {
     pub fn new(start: Key, end: Key, kind: RangeKind) -> KeyRange {
         KeyRange { start, end, kind }
     }
+
+    /// prefix returns the KeyRange of all the keys that start with the prefix.
+    /// The prefix has fewer parts than the key, and the range is closed on both sides so that the keys
+    /// that exactly match the provided parts are included.
+    /// # Examples
+    /// ```
+    ///    use google_cloud_spanner::key::KeyRange;
+    ///    // All the rows of the tenant in the table with PRIMARY KEY(TenantId, UserId).
+    ///    let range = KeyRange::prefix(("tenant-1",));
+    /// ```
+    pub fn prefix(prefix: impl ToKey) -> KeyRange {
+        let key = prefix.to_key();
+        KeyRange::new(key.clone(), key, RangeKind::ClosedClosed)
+    }
 }
 
 impl From<KeyRange> for InternalKeyRange {
@@ -277,8 +364,67 @@ impl Key {
             },
         }
     }
+
+    /// add appends the value to the Key.
+    /// # Examples
+    /// ```
+    ///    use google_cloud_spanner::key::Key;
+    ///    let multi_key = Key::new(&"a").add(&1);
+    /// ```
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, value: &dyn ToKind) -> Key {
+        self.values.values.push(Value {
+            kind: Some(value.to_kind()),
+        });
+        self
+    }
 }
 
+/// ToKey converts the tuple into the Key.
+/// The composite primary key can be written as the tuple of the key parts.
+/// # Examples
+/// ```
+///    use google_cloud_spanner::key::{Key, ToKey};
+///    let key: Key = ("tenant-1", 42).to_key();
+///    let key: Key = ("tenant-1", 42).into();
+/// ```
+pub trait ToKey {
+    fn to_key(&self) -> Key;
+}
+
+impl ToKey for Key {
+    fn to_key(&self) -> Key {
+        self.clone()
+    }
+}
+
+macro_rules! impl_to_key {
+    ($($name:ident),+) => {
+        impl<$($name: ToKind),+> ToKey for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn to_key(&self) -> Key {
+                let ($($name,)+) = self;
+                Key::composite(&[$($name),+])
+            }
+        }
+
+        impl<$($name: ToKind),+> From<($($name,)+)> for Key {
+            fn from(value: ($($name,)+)) -> Self {
+                value.to_key()
+            }
+        }
+    };
+}
+
+impl_to_key!(A);
+impl_to_key!(A, B);
+impl_to_key!(A, B, C);
+impl_to_key!(A, B, C, D);
+impl_to_key!(A, B, C, D, E);
+impl_to_key!(A, B, C, D, E, F);
+impl_to_key!(A, B, C, D, E, F, G);
+impl_to_key!(A, B, C, D, E, F, G, H);
+
 impl From<Key> for KeySet {
     fn from(key: Key) -> Self {
         KeySet {
@@ -291,6 +437,19 @@ impl From<Key> for KeySet {
     }
 }
 
+impl From<Vec<KeyRange>> for KeySet {
+    fn from(ranges: Vec<KeyRange>) -> Self {
+        let ranges = ranges.into_iter().map(|range| range.into()).collect();
+        KeySet {
+            inner: InternalKeySet {
+                keys: vec![],
+                ranges,
+                all: false,
+            },
+        }
+    }
+}
+
 impl From<Vec<Key>> for KeySet {
     fn from(keys: Vec<Key>) -> Self {
         let keys = keys.into_iter().map(|key| key.values).collect();
@@ -360,4 +519,148 @@ mod tests {
             _ => panic!("invalid end key trype"),
         }
     }
+
+    fn string_values(values: &ListValue) -> Vec<String> {
+        values
+            .values
+            .iter()
+            .map(|v| match v.kind.as_ref().unwrap() {
+                Kind::StringValue(s) => s.clone(),
+                Kind::BoolValue(b) => b.to_string(),
+                _ => panic!("invalid kind"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_key_add() {
+        let key = Key::new(&"tenant-1").add(&42).add(&true);
+        assert_eq!(string_values(&key.values), vec!["tenant-1", "42", "true"]);
+        assert_eq!(
+            string_values(&key.values),
+            string_values(&Key::composite(&[&"tenant-1", &42, &true]).values)
+        );
+    }
+
+    #[test]
+    fn test_to_key() {
+        assert_eq!(string_values(&("tenant-1",).to_key().values), vec!["tenant-1"]);
+        assert_eq!(string_values(&Key::from(("tenant-1", 42)).values), vec!["tenant-1", "42"]);
+        let key: Key = (1, 2, 3, 4, 5, 6, 7, "h").into();
+        assert_eq!(string_values(&key.values), vec!["1", "2", "3", "4", "5", "6", "7", "h"]);
+        assert_eq!(string_values(&Key::new(&"a").add(&1).to_key().values), vec!["a", "1"]);
+    }
+
+    #[test]
+    fn test_key_range_kinds() {
+        let cases = vec![
+            (RangeKind::ClosedClosed, true, true),
+            (RangeKind::ClosedOpen, true, false),
+            (RangeKind::OpenClosed, false, true),
+            (RangeKind::OpenOpen, false, false),
+        ];
+        for (kind, closed_start, closed_end) in cases {
+            // The prefix key has fewer parts than the primary key.
+            let range: v1::KeyRange = KeyRange::new(("Bob",).into(), ("Bob", "2000-01-01").into(), kind).into();
+            match range.start_key_type.unwrap() {
+                v1::key_range::StartKeyType::StartClosed(v) if closed_start => {
+                    assert_eq!(string_values(&v), vec!["Bob"])
+                }
+                v1::key_range::StartKeyType::StartOpen(v) if !closed_start => {
+                    assert_eq!(string_values(&v), vec!["Bob"])
+                }
+                _ => panic!("invalid start key type"),
+            }
+            match range.end_key_type.unwrap() {
+                v1::key_range::EndKeyType::EndClosed(v) if closed_end => {
+                    assert_eq!(string_values(&v), vec!["Bob", "2000-01-01"])
+                }
+                v1::key_range::EndKeyType::EndOpen(v) if !closed_end => {
+                    assert_eq!(string_values(&v), vec!["Bob", "2000-01-01"])
+                }
+                _ => panic!("invalid end key type"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_set() {
+        let all: v1::KeySet = KeySet::all().into();
+        assert!(all.all);
+        assert!(all.keys.is_empty());
+        assert!(all.ranges.is_empty());
+
+        let keys: v1::KeySet = KeySet::from(vec![Key::new(&"a"), ("b", 1).into()]).into();
+        assert!(!keys.all);
+        assert_eq!(keys.keys.len(), 2);
+        assert_eq!(string_values(&keys.keys[1]), vec!["b", "1"]);
+        assert!(keys.ranges.is_empty());
+
+        let ranges: v1::KeySet = KeySet::from(vec![
+            KeyRange::new(Key::new(&1), Key::new(&10), RangeKind::ClosedOpen),
+            KeyRange::new(Key::new(&20), Key::new(&30), RangeKind::OpenClosed),
+        ])
+        .into();
+        assert!(!ranges.all);
+        assert!(ranges.keys.is_empty());
+        assert_eq!(ranges.ranges.len(), 2);
+
+        let union: v1::KeySet = KeySet::from(Key::new(&"a"))
+            .union(vec![Key::new(&"b"), Key::new(&"c")])
+            .union(KeyRange::new(Key::new(&"x"), Key::new(&"z"), RangeKind::ClosedOpen))
+            .into();
+        assert!(!union.all);
+        assert_eq!(
+            union.keys.iter().map(string_values).collect::<Vec<_>>(),
+            vec![vec!["a"], vec!["b"], vec!["c"]]
+        );
+        assert_eq!(union.ranges.len(), 1);
+
+        let union: v1::KeySet = KeySet::from(Key::new(&"a")).union(KeySet::all()).into();
+        assert!(union.all);
+        assert_eq!(union.keys.len(), 1);
+    }
+
+    #[test]
+    fn test_key_range_prefix() {
+        let range: v1::KeyRange = KeyRange::prefix(("tenant-1",)).into();
+        match (range.start_key_type.unwrap(), range.end_key_type.unwrap()) {
+            (v1::key_range::StartKeyType::StartClosed(start), v1::key_range::EndKeyType::EndClosed(end)) => {
+                assert_eq!(string_values(&start), vec!["tenant-1"]);
+                assert_eq!(string_values(&end), vec!["tenant-1"]);
+            }
+            _ => panic!("invalid key types"),
+        }
+
+        let range: v1::KeyRange = KeyRange::prefix(("tenant-1", 2)).into();
+        match (range.start_key_type.unwrap(), range.end_key_type.unwrap()) {
+            (v1::key_range::StartKeyType::StartClosed(start), v1::key_range::EndKeyType::EndClosed(end)) => {
+                assert_eq!(string_values(&start), vec!["tenant-1", "2"]);
+                assert_eq!(string_values(&end), vec!["tenant-1", "2"]);
+            }
+            _ => panic!("invalid key types"),
+        }
+    }
+
+    #[test]
+    fn test_key_set_inspection() {
+        let keys = KeySet::from_keys(vec![("tenant-1", 1), ("tenant-1", 2)]);
+        assert_eq!(keys.len(), 2);
+        assert!(!keys.is_all());
+        assert!(!keys.is_empty());
+        let raw: v1::KeySet = keys.clone().into();
+        assert_eq!(
+            raw.keys.iter().map(string_values).collect::<Vec<_>>(),
+            vec![vec!["tenant-1", "1"], vec!["tenant-1", "2"]]
+        );
+
+        let union = keys.union(KeyRange::prefix(("tenant-2",)));
+        assert_eq!(union.len(), 3);
+        assert!(!union.is_all());
+
+        assert!(KeySet::all().is_all());
+        assert!(!KeySet::all().is_empty());
+        assert_eq!(KeySet::all().len(), 0);
+        assert!(KeySet::from_keys(Vec::<Key>::new()).is_empty());
+    }
 }
@@ -249,6 +249,36 @@
 //! }
 //! ```
 //!
+//! RowIterator can be converted into a futures::Stream to use the stream combinators:
+//!
+//! ```
+//! use futures_util::{StreamExt, TryStreamExt};
+//! use google_cloud_spanner::client::Client;
+//! use google_cloud_spanner::statement::Statement;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), anyhow::Error> {
+//!     const DATABASE: &str = "projects/local-project/instances/test-instance/databases/local-database";
+//!     let client = Client::new(DATABASE).await?;
+//!
+//!     let mut tx = client.single().await?;
+//!     let mut stream = tx.query(Statement::new("SELECT GuildID FROM Guild")).await?.into_stream();
+//!     while let Some(row) = stream.try_next().await? {
+//!         let guild_id = row.column_by_name::<String>("GuildID");
+//!         //do something
+//!     }
+//!
+//!     // process the rows in batches of 500
+//!     let mut tx = client.single().await?;
+//!     let mut batches = tx.query(Statement::new("SELECT GuildID FROM Guild")).await?.into_stream().chunks(500);
+//!     while let Some(batch) = batches.next().await {
+//!         let rows = batch.into_iter().collect::<Result<Vec<_>, _>>()?;
+//!         //do something
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
 //! * The used session is returned to the drop timing session pool, so unlike Go, there is no need to call Stop.
 //!
 //! * To read rows with an index, use `client.read_with_option`.
@@ -589,14 +619,26 @@ pub const SPANNER: &str = "spanner.googleapis.com";
 pub mod admin;
 pub mod apiv1;
 pub mod client;
+#[cfg(feature = "serde")]
+pub mod de;
 pub mod key;
 pub mod mutation;
 pub mod reader;
 pub mod retry;
 pub mod row;
+#[cfg(feature = "serde")]
+pub mod ser;
 pub mod session;
+pub mod sql;
 pub mod statement;
+pub mod stats;
 pub mod transaction;
 pub mod transaction_ro;
 pub mod transaction_rw;
 pub mod value;
+
+/// The derive macros of google-cloud-spanner-derive.
+#[cfg(feature = "derive")]
+pub mod derive {
+    pub use google_cloud_spanner_derive::{statement, Query, Table, ToSpannerValue, ToStruct};
+}
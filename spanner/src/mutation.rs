@@ -1,11 +1,22 @@
+use prost_types::value::Kind;
 use prost_types::{ListValue, Value};
 
-use crate::key::KeySet;
+use crate::key::{KeySet, ToKey};
 use crate::statement::{ToKind, ToStruct};
+use google_cloud_gax::grpc::{Code, Status};
+use google_cloud_googleapis::spanner::v1::batch_write_request;
+use google_cloud_googleapis::spanner::v1::key_range::{EndKeyType, StartKeyType};
 use google_cloud_googleapis::spanner::v1::mutation::{Delete, Operation, Write};
-use google_cloud_googleapis::spanner::v1::Mutation;
+use google_cloud_googleapis::spanner::v1::{KeyRange as KeyRangeProto, KeySet as KeySetProto, Mutation};
 
-fn write(table: &str, columns: &[&str], values: &[&dyn ToKind]) -> Write {
+/// The maximum number of the mutations committed in a transaction, which is counted in the same way as mutation_count.
+pub const MAX_MUTATIONS_PER_GROUP: usize = 80_000;
+
+/// The maximum size of a commit request in bytes.
+pub const MAX_COMMIT_SIZE: usize = 100 * 1024 * 1024;
+
+/// write takes the same number of the columns and the values, because the server rejects the other rows.
+fn write<const N: usize>(table: &str, columns: &[&str; N], values: &[&dyn ToKind; N]) -> Write {
     let values = values
         .iter()
         .map(|x| Value {
@@ -51,9 +62,82 @@ fn write_struct(table: &str, to_struct: impl ToStruct) -> Write {
     }
 }
 
+/// WriteBuilder builds the Mutation to write a row by setting the columns one by one,
+/// which keeps each column next to its value for the wide tables.
+/// ```
+/// use google_cloud_spanner::mutation::WriteBuilder;
+/// use google_cloud_spanner::value::CommitTimestamp;
+///
+/// let mutation = WriteBuilder::insert("User")
+///     .set("UserId", &"user1")
+///     .set("Name", &Option::<String>::None)
+///     .set("UpdatedAt", &CommitTimestamp::new())
+///     .build();
+/// ```
+pub struct WriteBuilder {
+    operation: fn(Write) -> Operation,
+    write: Write,
+}
+
+impl WriteBuilder {
+    fn new(table: &str, operation: fn(Write) -> Operation) -> Self {
+        WriteBuilder {
+            operation,
+            write: Write {
+                table: table.to_string(),
+                columns: vec![],
+                values: vec![ListValue { values: vec![] }],
+            },
+        }
+    }
+
+    /// insert returns the WriteBuilder of the Mutation built by insert.
+    pub fn insert(table: &str) -> Self {
+        WriteBuilder::new(table, Operation::Insert)
+    }
+
+    /// update returns the WriteBuilder of the Mutation built by update.
+    pub fn update(table: &str) -> Self {
+        WriteBuilder::new(table, Operation::Update)
+    }
+
+    /// insert_or_update returns the WriteBuilder of the Mutation built by insert_or_update.
+    pub fn insert_or_update(table: &str) -> Self {
+        WriteBuilder::new(table, Operation::InsertOrUpdate)
+    }
+
+    /// replace returns the WriteBuilder of the Mutation built by replace.
+    pub fn replace(table: &str) -> Self {
+        WriteBuilder::new(table, Operation::Replace)
+    }
+
+    /// set sets the value of the column.
+    pub fn set(mut self, column: &str, value: &dyn ToKind) -> Self {
+        self.write.columns.push(column.to_string());
+        self.write.values[0].values.push(Value {
+            kind: Some(value.to_kind()),
+        });
+        self
+    }
+
+    /// build returns the Mutation.
+    pub fn build(self) -> Mutation {
+        Mutation {
+            operation: Some((self.operation)(self.write)),
+        }
+    }
+}
+
 /// Insert returns a Mutation to insert a row into a table. If the row already
 /// exists, the write or transaction fails with codes.AlreadyExists.
-pub fn insert(table: &str, columns: &[&str], values: &[&dyn ToKind]) -> Mutation {
+///
+/// The number of the columns and the values must be the same, which is checked at compile time.
+/// ```compile_fail
+/// use google_cloud_spanner::mutation::insert;
+///
+/// let mutation = insert("Guild", &["GuildId", "UserId"], &[&"guild1"]);
+/// ```
+pub fn insert<const N: usize>(table: &str, columns: &[&str; N], values: &[&dyn ToKind; N]) -> Mutation {
     Mutation {
         operation: Some(Operation::Insert(write(table, columns, values))),
     }
@@ -79,7 +163,9 @@ pub fn insert_struct(table: &str, to_struct: impl ToStruct) -> Mutation {
 
 /// update returns a Mutation to update a row in a table. If the row does not
 /// already exist, the write or transaction fails.
-pub fn update(table: &str, columns: &[&str], values: &[&dyn ToKind]) -> Mutation {
+///
+/// The number of the columns and the values must be the same, which is checked at compile time.
+pub fn update<const N: usize>(table: &str, columns: &[&str; N], values: &[&dyn ToKind; N]) -> Mutation {
     Mutation {
         operation: Some(Operation::Update(write(table, columns, values))),
     }
@@ -102,12 +188,61 @@ pub fn update_struct(table: &str, to_struct: impl ToStruct) -> Mutation {
     }
 }
 
+#[cfg(feature = "serde")]
+fn write_serde<T: serde::Serialize + ?Sized>(table: &str, value: &T) -> Result<Write, crate::ser::Error> {
+    let (columns, values) = crate::ser::to_columns(value)?;
+    Ok(Write {
+        table: table.to_string(),
+        columns,
+        values: vec![ListValue { values }],
+    })
+}
+
+/// insert_serde returns a Mutation to insert a row, whose columns are the fields of the value serialized by serde.
+/// See the ser module for how the values are mapped.
+#[cfg(feature = "serde")]
+pub fn insert_serde<T: serde::Serialize + ?Sized>(table: &str, value: &T) -> Result<Mutation, crate::ser::Error> {
+    Ok(Mutation {
+        operation: Some(Operation::Insert(write_serde(table, value)?)),
+    })
+}
+
+/// update_serde returns a Mutation to update a row, whose columns are the fields of the value serialized by serde.
+#[cfg(feature = "serde")]
+pub fn update_serde<T: serde::Serialize + ?Sized>(table: &str, value: &T) -> Result<Mutation, crate::ser::Error> {
+    Ok(Mutation {
+        operation: Some(Operation::Update(write_serde(table, value)?)),
+    })
+}
+
+/// replace_serde returns a Mutation to replace a row, whose columns are the fields of the value serialized by serde.
+#[cfg(feature = "serde")]
+pub fn replace_serde<T: serde::Serialize + ?Sized>(table: &str, value: &T) -> Result<Mutation, crate::ser::Error> {
+    Ok(Mutation {
+        operation: Some(Operation::Replace(write_serde(table, value)?)),
+    })
+}
+
+/// insert_or_update_serde returns a Mutation to insert or update a row,
+/// whose columns are the fields of the value serialized by serde.
+#[cfg(feature = "serde")]
+pub fn insert_or_update_serde<T: serde::Serialize + ?Sized>(
+    table: &str,
+    value: &T,
+) -> Result<Mutation, crate::ser::Error> {
+    Ok(Mutation {
+        operation: Some(Operation::InsertOrUpdate(write_serde(table, value)?)),
+    })
+}
+
 /// replace returns a Mutation to insert a row into a table, deleting any
 /// existing row. Unlike InsertOrUpdate, this means any values not explicitly
 /// written become NULL.
 ///
 /// For a similar example, See Update.
-pub fn replace(table: &str, columns: &[&str], values: &[&dyn ToKind]) -> Mutation {
+///
+/// The number of the columns and the values must be the same, which is checked at compile time.
+pub fn replace<const N: usize>(table: &str, columns: &[&str; N], values: &[&dyn ToKind; N]) -> Mutation {
     Mutation {
         operation: Some(Operation::Replace(write(table, columns, values))),
     }
@@ -138,7 +273,9 @@ pub fn replace_struct(table: &str, to_struct: impl ToStruct) -> Mutation {
 /// written are preserved.
 ///
 /// For a similar example, See update.
-pub fn insert_or_update(table: &str, columns: &[&str], values: &[&dyn ToKind]) -> Mutation {
+///
+/// The number of the columns and the values must be the same, which is checked at compile time.
+pub fn insert_or_update<const N: usize>(table: &str, columns: &[&str; N], values: &[&dyn ToKind; N]) -> Mutation {
     Mutation {
         operation: Some(Operation::InsertOrUpdate(write(table, columns, values))),
     }
@@ -165,6 +302,14 @@ pub fn insert_or_update_struct(table: &str, to_struct: impl ToStruct) -> Mutatio
     }
 }
 
+/// insert_or_update_structs returns the Mutations built by insert_or_update_struct, one for each struct.
+pub fn insert_or_update_structs<T: ToStruct>(table: &str, to_structs: &[T]) -> Vec<Mutation> {
+    to_structs
+        .iter()
+        .map(|to_struct| insert_or_update_struct(table, to_struct))
+        .collect()
+}
+
 /// delete removes the rows described by the KeySet from the table. It succeeds
 /// whether or not the keys were present.
 pub fn delete(table: &str, key_set: impl Into<KeySet>) -> Mutation {
@@ -176,6 +321,267 @@ pub fn delete(table: &str, key_set: impl Into<KeySet>) -> Mutation {
     }
 }
 
+/// delete_key returns a Mutation to delete the row of the key.
+pub fn delete_key(table: &str, key: impl ToKey) -> Mutation {
+    delete(table, key.to_key())
+}
+
+/// check_delete_all_keys returns FailedPrecondition if any of the mutations deletes all the keys of a table,
+/// which is rejected with ClientConfig::reject_delete_all_keys unless the deletes are explicitly allowed.
+pub(crate) fn check_delete_all_keys(mutations: &[Mutation]) -> Result<(), Status> {
+    let table = mutations.iter().find_map(|m| match &m.operation {
+        Some(Operation::Delete(delete)) if delete.key_set.as_ref().map(|k| k.all).unwrap_or(false) => {
+            Some(delete.table.as_str())
+        }
+        _ => None,
+    });
+    match table {
+        Some(table) => Err(Status::new(
+            Code::FailedPrecondition,
+            format!("deleting all the keys of {table} is rejected: allow it with allow_delete_all_keys"),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// MutationGroup is a group of the mutations to be committed atomically by the BatchWrite.
+/// The mutations across the groups are committed non-atomically in an unspecified order.
+#[derive(Clone, Debug, Default)]
+pub struct MutationGroup {
+    pub mutations: Vec<Mutation>,
+}
+
+impl MutationGroup {
+    pub fn new(mutations: Vec<Mutation>) -> Self {
+        MutationGroup { mutations }
+    }
+}
+
+impl From<Vec<Mutation>> for MutationGroup {
+    fn from(mutations: Vec<Mutation>) -> Self {
+        MutationGroup::new(mutations)
+    }
+}
+
+impl From<MutationGroup> for batch_write_request::MutationGroup {
+    fn from(group: MutationGroup) -> Self {
+        batch_write_request::MutationGroup {
+            mutations: group.mutations,
+        }
+    }
+}
+
+/// mutation_count returns the number of the mutations counted by the server without the secondary indexes.
+/// The write counts the number of the cells, and the delete counts one.
+pub fn mutation_count(mutation: &Mutation) -> usize {
+    match &mutation.operation {
+        Some(Operation::Insert(w))
+        | Some(Operation::Update(w))
+        | Some(Operation::InsertOrUpdate(w))
+        | Some(Operation::Replace(w)) => w.columns.len() * w.values.len(),
+        Some(Operation::Delete(_)) => 1,
+        None => 0,
+    }
+}
+
+/// group_mutations partitions the mutations into the groups in order, so that each group is under the limits,
+/// such as the default ones of MAX_MUTATIONS_PER_GROUP and MAX_COMMIT_SIZE.
+/// The mutation which exceeds the limits by itself is put in its own group.
+pub fn group_mutations(mutations: Vec<Mutation>, limits: &CommitLimits) -> Vec<MutationGroup> {
+    let mut groups = vec![];
+    let mut current = MutationGroup::default();
+    let mut count = 0;
+    let mut size = 0;
+    for mutation in mutations {
+        let n = mutation_count(&mutation) * limits.index_multiplier;
+        let bytes = estimate_mutation_size(&mutation);
+        if !current.mutations.is_empty() && (count + n > limits.max_mutations || size + bytes > limits.max_size) {
+            groups.push(std::mem::take(&mut current));
+            count = 0;
+            size = 0;
+        }
+        count += n;
+        size += bytes;
+        current.mutations.push(mutation);
+    }
+    if !current.mutations.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+fn varint_size(n: usize) -> usize {
+    (usize::BITS - (n | 1).leading_zeros()).div_ceil(7) as usize
+}
+
+/// field_size returns the encoded size of the length-delimited field whose tag is less than 16.
+fn field_size(len: usize) -> usize {
+    1 + varint_size(len) + len
+}
+
+fn string_size(s: &str) -> usize {
+    if s.is_empty() {
+        0
+    } else {
+        field_size(s.len())
+    }
+}
+
+fn value_size(value: &Value) -> usize {
+    match &value.kind {
+        None => 0,
+        Some(Kind::NullValue(v)) => 1 + varint_size(*v as usize),
+        Some(Kind::NumberValue(_)) => 9,
+        Some(Kind::StringValue(s)) => field_size(s.len()),
+        Some(Kind::BoolValue(_)) => 2,
+        Some(Kind::StructValue(s)) => field_size(
+            s.fields
+                .iter()
+                .map(|(k, v)| {
+                    let value = if v.kind.is_none() { 0 } else { field_size(value_size(v)) };
+                    field_size(string_size(k) + value)
+                })
+                .sum(),
+        ),
+        Some(Kind::ListValue(l)) => field_size(list_value_size(l)),
+    }
+}
+
+fn list_value_size(list: &ListValue) -> usize {
+    list.values.iter().map(|v| field_size(value_size(v))).sum()
+}
+
+fn key_range_size(range: &KeyRangeProto) -> usize {
+    let start = match &range.start_key_type {
+        Some(StartKeyType::StartClosed(l)) | Some(StartKeyType::StartOpen(l)) => field_size(list_value_size(l)),
+        None => 0,
+    };
+    let end = match &range.end_key_type {
+        Some(EndKeyType::EndClosed(l)) | Some(EndKeyType::EndOpen(l)) => field_size(list_value_size(l)),
+        None => 0,
+    };
+    start + end
+}
+
+fn key_set_size(key_set: &KeySetProto) -> usize {
+    let keys: usize = key_set.keys.iter().map(|k| field_size(list_value_size(k))).sum();
+    let ranges: usize = key_set.ranges.iter().map(|r| field_size(key_range_size(r))).sum();
+    keys + ranges + if key_set.all { 2 } else { 0 }
+}
+
+/// estimate_mutation_size returns the number of the bytes the mutation takes in the commit request.
+/// The total of the mutations is compared with MAX_COMMIT_SIZE, leaving the other fields of the request out.
+pub fn estimate_mutation_size(mutation: &Mutation) -> usize {
+    let operation = match &mutation.operation {
+        Some(Operation::Insert(w))
+        | Some(Operation::Update(w))
+        | Some(Operation::InsertOrUpdate(w))
+        | Some(Operation::Replace(w)) => {
+            let columns: usize = w.columns.iter().map(|c| field_size(c.len())).sum();
+            let values: usize = w.values.iter().map(|v| field_size(list_value_size(v))).sum();
+            field_size(string_size(&w.table) + columns + values)
+        }
+        Some(Operation::Delete(d)) => {
+            let key_set = d.key_set.as_ref().map(|k| field_size(key_set_size(k))).unwrap_or(0);
+            field_size(string_size(&d.table) + key_set)
+        }
+        None => 0,
+    };
+    field_size(operation)
+}
+
+/// CommitLimits is the limits of a commit checked on the client before the mutations are sent.
+#[derive(Clone, Debug)]
+pub struct CommitLimits {
+    /// The maximum number of the mutations in a commit counted by mutation_count multiplied by index_multiplier.
+    pub max_mutations: usize,
+    /// The maximum size of the mutations in a commit estimated by estimate_mutation_size.
+    pub max_size: usize,
+    /// The multiplier of mutation_count for the secondary indexes, which the server counts as the additional mutations.
+    /// For example, 2 for the table whose written columns are all in one secondary index.
+    pub index_multiplier: usize,
+}
+
+impl Default for CommitLimits {
+    fn default() -> Self {
+        CommitLimits {
+            max_mutations: MAX_MUTATIONS_PER_GROUP,
+            max_size: MAX_COMMIT_SIZE,
+            index_multiplier: 1,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum CommitLimitError {
+    #[error("too many mutations in a commit: count={0}, max={1}")]
+    TooManyMutations(usize, usize),
+    #[error("too large commit: size={0}, max={1}")]
+    TooLarge(usize, usize),
+}
+
+/// CommitSplitter accumulates the mutations of a commit within the limits.
+pub(crate) struct CommitSplitter {
+    limits: CommitLimits,
+    split: bool,
+    mutations: Vec<Mutation>,
+    count: usize,
+    size: usize,
+}
+
+impl CommitSplitter {
+    pub(crate) fn new(limits: CommitLimits, split: bool) -> Self {
+        CommitSplitter {
+            limits,
+            split,
+            mutations: vec![],
+            count: 0,
+            size: 0,
+        }
+    }
+
+    fn check(&self, count: usize, size: usize) -> Result<(), CommitLimitError> {
+        if count > self.limits.max_mutations {
+            Err(CommitLimitError::TooManyMutations(count, self.limits.max_mutations))
+        } else if size > self.limits.max_size {
+            Err(CommitLimitError::TooLarge(size, self.limits.max_size))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// push adds the mutation to the commit.
+    /// If the mutation does not fit in the commit and split is enabled, the accumulated mutations are returned
+    /// to be committed first and the mutation starts the next commit.
+    pub(crate) fn push(&mut self, mutation: Mutation) -> Result<Option<Vec<Mutation>>, CommitLimitError> {
+        let count = mutation_count(&mutation) * self.limits.index_multiplier;
+        let size = estimate_mutation_size(&mutation);
+        let flushed = match self.check(self.count + count, self.size + size) {
+            Ok(()) => None,
+            Err(e) if !self.split || self.mutations.is_empty() => return Err(e),
+            Err(_) => {
+                self.check(count, size)?;
+                self.count = 0;
+                self.size = 0;
+                Some(std::mem::take(&mut self.mutations))
+            }
+        };
+        self.count += count;
+        self.size += size;
+        self.mutations.push(mutation);
+        Ok(flushed)
+    }
+
+    /// finish returns the remaining mutations to be committed last.
+    pub(crate) fn finish(self) -> Option<Vec<Mutation>> {
+        if self.mutations.is_empty() {
+            None
+        } else {
+            Some(self.mutations)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::key::*;
@@ -385,6 +791,73 @@ mod tests {
         }
     }
 
+    fn delete_key_set(mutation: Mutation) -> v1::KeySet {
+        match mutation.operation.unwrap() {
+            v1::mutation::Operation::Delete(w) => {
+                assert_eq!("UserItem", w.table);
+                w.key_set.unwrap()
+            }
+            _ => panic!("invalid operation"),
+        }
+    }
+
+    #[test]
+    fn test_delete_key() {
+        let key_set = delete_key_set(delete_key("UserItem", ("user1", 1)));
+        assert!(!key_set.all);
+        assert!(key_set.ranges.is_empty());
+        assert_eq!(1, key_set.keys.len());
+        assert_eq!(2, key_set.keys[0].values.len());
+    }
+
+    #[test]
+    fn test_delete_open_ended_range() {
+        // From the key of user1 to the end of the table.
+        let range = KeyRange::new(Key::new(&"user1"), Key::composite(&[]), RangeKind::ClosedClosed);
+        let key_set = delete_key_set(delete("UserItem", range));
+        assert!(!key_set.all);
+        assert!(key_set.keys.is_empty());
+        assert_eq!(1, key_set.ranges.len());
+        let range = &key_set.ranges[0];
+        match range.start_key_type.as_ref().unwrap() {
+            v1::key_range::StartKeyType::StartClosed(v) => {
+                assert_eq!(v.values[0].kind, Some(Kind::StringValue("user1".to_string())))
+            }
+            _ => panic!("invalid start key type"),
+        }
+        match range.end_key_type.as_ref().unwrap() {
+            v1::key_range::EndKeyType::EndClosed(v) => assert!(v.values.is_empty()),
+            _ => panic!("invalid end key type"),
+        }
+    }
+
+    #[test]
+    fn test_delete_union() {
+        let key_set = KeySet::from_keys(vec![("user1", 1), ("user1", 2)]).union(KeyRange::prefix(("user2",)));
+        let key_set = delete_key_set(delete("UserItem", key_set));
+        assert!(!key_set.all);
+        assert_eq!(2, key_set.keys.len());
+        assert_eq!(1, key_set.ranges.len());
+    }
+
+    #[test]
+    fn test_check_delete_all_keys() {
+        let mutations = vec![
+            delete_key("UserItem", ("user1", 1)),
+            delete("UserItem", KeyRange::prefix(("user2",))),
+            insert("User", &["UserId"], &[&"user3"]),
+        ];
+        assert!(check_delete_all_keys(&mutations).is_ok());
+
+        let mutations = vec![delete_key("UserItem", ("user1", 1)), delete("User", KeySet::all())];
+        let err = check_delete_all_keys(&mutations).unwrap_err();
+        assert_eq!(err.code(), Code::FailedPrecondition);
+        assert_eq!(
+            err.message(),
+            "deleting all the keys of User is rejected: allow it with allow_delete_all_keys"
+        );
+    }
+
     fn assert_struct(mut w: Write) {
         assert_eq!("Guild", w.table);
         assert_eq!("StructField", w.columns.pop().unwrap());
@@ -396,4 +869,344 @@ mod tests {
             }
         );
     }
+
+    struct TestRow {
+        pub id: i64,
+        pub name: Option<String>,
+        pub updated_at: CommitTimestamp,
+    }
+
+    impl ToStruct for TestRow {
+        fn to_kinds(&self) -> Kinds {
+            vec![
+                ("Id", self.id.to_kind()),
+                ("Name", self.name.to_kind()),
+                ("UpdatedAt", self.updated_at.to_kind()),
+            ]
+        }
+
+        fn get_types() -> Types {
+            vec![
+                ("Id", i64::get_type()),
+                ("Name", Option::<String>::get_type()),
+                ("UpdatedAt", CommitTimestamp::get_type()),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_insert_or_update_structs() {
+        let rows = vec![
+            TestRow {
+                id: 1,
+                name: Some("a".to_string()),
+                updated_at: CommitTimestamp::new(),
+            },
+            TestRow {
+                id: 2,
+                name: None,
+                updated_at: CommitTimestamp::new(),
+            },
+        ];
+        let mutations = insert_or_update_structs("Test", &rows);
+        assert_eq!(2, mutations.len());
+        let names = [Kind::StringValue("a".to_string()), Kind::NullValue(0)];
+        for (i, mutation) in mutations.into_iter().enumerate() {
+            match mutation.operation.unwrap() {
+                v1::mutation::Operation::InsertOrUpdate(w) => {
+                    assert_eq!("Test", w.table);
+                    assert_eq!(vec!["Id", "Name", "UpdatedAt"], w.columns);
+                    let kinds: Vec<Kind> = w.values[0].values.iter().map(|v| v.kind.clone().unwrap()).collect();
+                    assert_eq!(
+                        kinds,
+                        vec![
+                            Kind::StringValue((i + 1).to_string()),
+                            names[i].clone(),
+                            Kind::StringValue("spanner.commit_timestamp()".to_string()),
+                        ]
+                    );
+                }
+                _ => panic!("invalid operation"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_builder() {
+        let cases = vec![
+            (WriteBuilder::insert("User"), "insert"),
+            (WriteBuilder::update("User"), "update"),
+            (WriteBuilder::insert_or_update("User"), "insert_or_update"),
+            (WriteBuilder::replace("User"), "replace"),
+        ];
+        for (builder, name) in cases {
+            let mutation = builder
+                .set("UserId", &"user1")
+                .set("Age", &1)
+                .set("Name", &Option::<String>::None)
+                .set("Tags", &vec!["a", "b"])
+                .set("UpdatedAt", &CommitTimestamp::new())
+                .build();
+            let w = match (mutation.operation.unwrap(), name) {
+                (v1::mutation::Operation::Insert(w), "insert") => w,
+                (v1::mutation::Operation::Update(w), "update") => w,
+                (v1::mutation::Operation::InsertOrUpdate(w), "insert_or_update") => w,
+                (v1::mutation::Operation::Replace(w), "replace") => w,
+                (operation, name) => panic!("invalid operation {operation:?} for {name}"),
+            };
+            assert_eq!("User", w.table);
+            assert_eq!(vec!["UserId", "Age", "Name", "Tags", "UpdatedAt"], w.columns);
+            assert_eq!(1, w.values.len());
+            let kinds: Vec<Kind> = w.values[0].values.iter().map(|v| v.kind.clone().unwrap()).collect();
+            assert_eq!(kinds[0], Kind::StringValue("user1".to_string()));
+            assert_eq!(kinds[1], Kind::StringValue("1".to_string()));
+            assert_eq!(kinds[2], Kind::NullValue(0));
+            match &kinds[3] {
+                Kind::ListValue(list) => assert_eq!(2, list.values.len()),
+                kind => panic!("invalid kind {kind:?}"),
+            }
+            assert_eq!(kinds[4], Kind::StringValue("spanner.commit_timestamp()".to_string()));
+        }
+
+        // The same mutation as the one built with the parallel slices.
+        let built = WriteBuilder::insert("User")
+            .set("UserId", &"user1")
+            .set("Name", &Option::<String>::None)
+            .set("UpdatedAt", &CommitTimestamp::new())
+            .build();
+        let expected = insert(
+            "User",
+            &["UserId", "Name", "UpdatedAt"],
+            &[&"user1", &Option::<String>::None, &CommitTimestamp::new()],
+        );
+        assert_eq!(expected, built);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        #[derive(serde::Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Guild {
+            guild_id: &'static str,
+            owner_user_id: Option<&'static str>,
+        }
+        let guild = Guild {
+            guild_id: "1",
+            owner_user_id: None,
+        };
+        let columns = ["GuildId", "OwnerUserId"];
+        let values: [&dyn ToKind; 2] = [&"1", &Option::<&str>::None];
+        assert_eq!(insert("Guild", &columns, &values), insert_serde("Guild", &guild).unwrap());
+        assert_eq!(update("Guild", &columns, &values), update_serde("Guild", &guild).unwrap());
+        assert_eq!(replace("Guild", &columns, &values), replace_serde("Guild", &guild).unwrap());
+        assert_eq!(
+            insert_or_update("Guild", &columns, &values),
+            insert_or_update_serde("Guild", &guild).unwrap()
+        );
+        assert!(insert_serde("Guild", &"1").is_err());
+    }
+
+    #[test]
+    fn test_commit_timestamp() {
+        let sentinel = Kind::StringValue("spanner.commit_timestamp()".to_string());
+        let kinds = |m: Mutation| match m.operation.unwrap() {
+            v1::mutation::Operation::Insert(w) => w.values[0]
+                .values
+                .iter()
+                .map(|v| v.kind.clone().unwrap())
+                .collect::<Vec<Kind>>(),
+            operation => panic!("invalid operation {operation:?}"),
+        };
+        let m = insert(
+            "Guild",
+            &["GuildId", "UpdatedAt", "DeletedAt"],
+            &[&"1", &Some(CommitTimestamp::new()), &Option::<CommitTimestamp>::None],
+        );
+        assert_eq!(
+            vec![Kind::StringValue("1".to_string()), sentinel.clone(), Kind::NullValue(0)],
+            kinds(m)
+        );
+
+        let m = WriteBuilder::insert("Guild")
+            .set("UpdatedAt", &CommitTimestamp::new())
+            .set("DeletedAt", &Option::<CommitTimestamp>::None)
+            .build();
+        assert_eq!(vec![sentinel.clone(), Kind::NullValue(0)], kinds(m));
+
+        struct Guild {
+            updated_at: CommitTimestamp,
+            deleted_at: Option<CommitTimestamp>,
+        }
+        impl ToStruct for Guild {
+            fn to_kinds(&self) -> Kinds {
+                vec![
+                    ("UpdatedAt", self.updated_at.to_kind()),
+                    ("DeletedAt", self.deleted_at.to_kind()),
+                ]
+            }
+            fn get_types() -> Types {
+                vec![
+                    ("UpdatedAt", CommitTimestamp::get_type()),
+                    ("DeletedAt", Option::<CommitTimestamp>::get_type()),
+                ]
+            }
+        }
+        let guild = Guild {
+            updated_at: CommitTimestamp::new(),
+            deleted_at: None,
+        };
+        assert_eq!(vec![sentinel, Kind::NullValue(0)], kinds(insert_struct("Guild", guild)));
+    }
+
+    #[test]
+    fn test_group_mutations() {
+        let ms = vec![
+            insert("Guild", &["GuildId", "UserId"], &[&"1", &"2"]),
+            update("Guild", &["GuildId", "UserId"], &[&"1", &"3"]),
+            delete("Guild", Key::new(&"1")),
+            replace("Guild", &["GuildId", "UserId", "Name"], &[&"2", &"2", &"a"]),
+        ];
+        assert_eq!(vec![2, 2, 1, 3], ms.iter().map(mutation_count).collect::<Vec<usize>>());
+
+        let lens = |groups: &[MutationGroup]| groups.iter().map(|g| g.mutations.len()).collect::<Vec<usize>>();
+        let by_count = |max_mutations| CommitLimits {
+            max_mutations,
+            ..Default::default()
+        };
+        let groups = group_mutations(ms.clone(), &by_count(4));
+        assert_eq!(vec![2, 2], lens(&groups));
+        assert_eq!(ms, groups.into_iter().flat_map(|g| g.mutations).collect::<Vec<Mutation>>());
+
+        let groups = group_mutations(ms.clone(), &by_count(2));
+        assert_eq!(vec![1, 1, 1, 1], lens(&groups));
+
+        // the index multiplier doubles the count.
+        let limits = CommitLimits {
+            max_mutations: 6,
+            index_multiplier: 2,
+            ..Default::default()
+        };
+        assert_eq!(vec![1, 2, 1], lens(&group_mutations(ms.clone(), &limits)));
+
+        // the groups are split by the size as well as the count.
+        let sizes: Vec<usize> = ms.iter().map(estimate_mutation_size).collect();
+        let limits = CommitLimits {
+            max_size: sizes[0] + sizes[1],
+            ..Default::default()
+        };
+        assert_eq!(vec![2, 2], lens(&group_mutations(ms.clone(), &limits)));
+        let limits = CommitLimits {
+            max_size: 1,
+            ..Default::default()
+        };
+        assert_eq!(vec![1, 1, 1, 1], lens(&group_mutations(ms.clone(), &limits)));
+
+        let groups = group_mutations(ms, &CommitLimits::default());
+        assert_eq!(1, groups.len());
+        assert!(group_mutations(vec![], &by_count(10)).is_empty());
+    }
+
+    #[test]
+    fn test_mutation_count_with_index_multiplier() {
+        // Inserting 1000 rows of 10 columns counts 10,000 mutations, and deleting a range counts one.
+        let values: [&dyn ToKind; 10] = [&1_i64; 10];
+        let columns = ["C0", "C1", "C2", "C3", "C4", "C5", "C6", "C7", "C8", "C9"];
+        let ms: Vec<Mutation> = (0..1000).map(|_| insert("Table", &columns, &values)).collect();
+        assert_eq!(10_000, ms.iter().map(mutation_count).sum::<usize>());
+        assert_eq!(1, mutation_count(&delete("Table", all_keys())));
+
+        // The index which contains all the columns doubles the count, so that 8000 rows fill a commit.
+        let limits = CommitLimits {
+            index_multiplier: 2,
+            ..Default::default()
+        };
+        let mut splitter = CommitSplitter::new(limits.clone(), false);
+        let row = insert("Table", &columns, &values);
+        for _ in 0..4000 {
+            assert_eq!(None, splitter.push(row.clone()).unwrap());
+        }
+        assert_eq!(
+            Err(CommitLimitError::TooManyMutations(80_020, MAX_MUTATIONS_PER_GROUP)),
+            splitter.push(row.clone())
+        );
+
+        let mut splitter = CommitSplitter::new(limits, true);
+        let mut commits = vec![];
+        for _ in 0..10_000 {
+            commits.extend(splitter.push(row.clone()).unwrap());
+        }
+        commits.extend(splitter.finish());
+        assert_eq!(vec![4000, 4000, 2000], commits.iter().map(|c| c.len()).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_commit_splitter_size() {
+        let limits = CommitLimits {
+            max_size: 100,
+            ..Default::default()
+        };
+        let large = insert("Table", &["Name"], &[&"a".repeat(200)]);
+        let small = insert("Table", &["Name"], &[&"a"]);
+        let size = estimate_mutation_size(&small);
+
+        let mut splitter = CommitSplitter::new(limits.clone(), true);
+        assert_eq!(
+            Err(CommitLimitError::TooLarge(estimate_mutation_size(&large), 100)),
+            splitter.push(large)
+        );
+        let mut commits = vec![];
+        for _ in 0..(100 / size + 1) {
+            commits.extend(splitter.push(small.clone()).unwrap());
+        }
+        commits.extend(splitter.finish());
+        assert_eq!(vec![100 / size, 1], commits.iter().map(|c| c.len()).collect::<Vec<usize>>());
+
+        let splitter = CommitSplitter::new(limits, false);
+        assert!(splitter.finish().is_none());
+    }
+
+    #[test]
+    fn test_estimate_mutation_size() {
+        use prost::Message;
+        use prost_types::Struct;
+
+        let mut fields = std::collections::BTreeMap::new();
+        fields.insert("a".to_string(), Value { kind: None });
+        fields.insert(
+            "b".to_string(),
+            Value {
+                kind: Some(Kind::NumberValue(1.0)),
+            },
+        );
+        let st = Value {
+            kind: Some(Kind::StructValue(Struct { fields })),
+        };
+        let ms = vec![
+            insert("Guild", &["GuildId", "OwnerUserId", "Flag"], &[&"1", &Some(2_i64), &true]),
+            update("Guild", &["Name"], &[&"a".repeat(300)]),
+            insert_or_update("Guild", &["Rate", "Tags"], &[&1.5_f64, &vec!["a", "b"]]),
+            replace("Guild", &["Data"], &[&Option::<String>::None]),
+            Mutation {
+                operation: Some(Operation::Insert(Write {
+                    table: "Guild".to_string(),
+                    columns: vec!["Struct".to_string()],
+                    values: vec![ListValue { values: vec![st] }],
+                })),
+            },
+            delete("Guild", Key::new(&"1")),
+            delete("Guild", all_keys()),
+            delete(
+                "Guild",
+                vec![
+                    KeyRange::new(Key::new(&"1"), Key::new(&"2"), RangeKind::ClosedOpen),
+                    KeyRange::new(Key::new(&"3"), Key::new(&"4"), RangeKind::OpenClosed),
+                ],
+            ),
+        ];
+        for m in ms {
+            let len = m.encoded_len();
+            assert_eq!(1 + prost::length_delimiter_len(len) + len, estimate_mutation_size(&m), "{m:?}");
+        }
+    }
 }
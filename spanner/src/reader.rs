@@ -1,16 +1,26 @@
 use std::collections::{HashMap, VecDeque};
+use std::iter::Take;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use futures_util::{Stream, StreamExt};
 use prost_types::{value::Kind, Value};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
 
 use google_cloud_gax::grpc::{Code, Response, Status, Streaming};
+use google_cloud_gax::retry::{Condition, ExponentialBackoff, Retry};
 use google_cloud_googleapis::spanner::v1::struct_type::Field;
-use google_cloud_googleapis::spanner::v1::{ExecuteSqlRequest, PartialResultSet, ReadRequest, ResultSetMetadata};
+use google_cloud_googleapis::spanner::v1::{
+    ExecuteSqlRequest, PartialResultSet, ReadRequest, ResultSetMetadata, ResultSetStats, TransactionSelector,
+};
 
-use crate::row::Row;
+use crate::retry::StreamResumeSetting;
+use crate::row::{column_index, column_metadata, ColumnMetadata, Row};
 use crate::session::SessionHandle;
+use crate::stats::QueryStats;
 use crate::transaction::CallOptions;
+use crate::value::Timestamp;
 
 #[async_trait]
 pub trait AsyncIterator {
@@ -28,7 +38,16 @@ pub trait Reader {
 
     fn update_token(&mut self, resume_token: Vec<u8>);
 
+    /// set_transaction replaces the transaction of the request, so that the stream begun with the inline begin
+    /// is resumed in the transaction.
+    fn set_transaction(&mut self, transaction: TransactionSelector);
+
     fn can_retry(&self) -> bool;
+
+    /// data_boost_enabled returns true if the request is executed with Data Boost.
+    fn data_boost_enabled(&self) -> bool {
+        false
+    }
 }
 
 pub struct StatementReader {
@@ -54,9 +73,17 @@ impl Reader for StatementReader {
         self.request.resume_token = resume_token;
     }
 
+    fn set_transaction(&mut self, transaction: TransactionSelector) {
+        self.request.transaction = Some(transaction);
+    }
+
     fn can_retry(&self) -> bool {
         !self.request.resume_token.is_empty()
     }
+
+    fn data_boost_enabled(&self) -> bool {
+        self.request.data_boost_enabled
+    }
 }
 
 pub struct TableReader {
@@ -82,9 +109,17 @@ impl Reader for TableReader {
         self.request.resume_token = resume_token;
     }
 
+    fn set_transaction(&mut self, transaction: TransactionSelector) {
+        self.request.transaction = Some(transaction);
+    }
+
     fn can_retry(&self) -> bool {
         !self.request.resume_token.is_empty()
     }
+
+    fn data_boost_enabled(&self) -> bool {
+        self.request.data_boost_enabled
+    }
 }
 
 pub struct ResultSet {
@@ -113,55 +148,71 @@ impl ResultSet {
         None
     }
 
-    /// Merge tries to combine two protobuf Values if possible.
+    /// merge combines the chunked last value of the previous result set and the first value of the current one.
+    ///
+    /// The strings are concatenated. The lists are concatenated, and the last element of the previous list is
+    /// merged with the first element of the current list by these rules recursively if it is a string, a list or an object.
+    /// The objects are concatenated, and the values of the duplicated field names are merged recursively.
     fn merge(previous_last: Value, current_first: Value) -> Result<Value, Status> {
-        match previous_last.kind.unwrap() {
-            Kind::StringValue(last) => match current_first.kind.unwrap() {
-                Kind::StringValue(first) => {
-                    tracing::trace!("previous_last={}, current_first={}", &last, first);
-                    Ok(Value {
-                        kind: Some(Kind::StringValue(last + &first)),
-                    })
+        let kind = match (previous_last.kind, current_first.kind) {
+            (Some(Kind::StringValue(last)), Some(Kind::StringValue(first))) => {
+                tracing::trace!("previous_last={}, current_first={}", &last, first);
+                Kind::StringValue(last + &first)
+            }
+            (Some(Kind::ListValue(mut last)), Some(Kind::ListValue(first))) => {
+                let mut first = first.values.into_iter();
+                match (last.values.pop(), first.next()) {
+                    (Some(last_value), Some(first_value)) if is_mergeable(&last_value) => {
+                        last.values.push(ResultSet::merge(last_value, first_value)?)
+                    }
+                    // the bool, the number and the null are not chunked, and the empty list has nothing to merge.
+                    (last_value, first_value) => {
+                        last.values.extend(last_value);
+                        last.values.extend(first_value);
+                    }
                 }
-                _ => Err(Status::new(
-                    Code::Internal,
-                    "chunks kind mismatch: current_first must be StringKind",
-                )),
-            },
-            Kind::ListValue(mut last) => match current_first.kind.unwrap() {
-                Kind::ListValue(mut first) => {
-                    let first_value_of_current = first.values.remove(0);
-                    let merged = match last.values.pop() {
-                        Some(last_value_of_previous) => {
-                            ResultSet::merge(last_value_of_previous, first_value_of_current)?
-                        }
-                        // last record can be empty
-                        None => first_value_of_current,
+                last.values.extend(first);
+                Kind::ListValue(last)
+            }
+            (Some(Kind::StructValue(mut last)), Some(Kind::StructValue(first))) => {
+                for (name, first_value) in first.fields {
+                    let merged = match last.fields.remove(&name) {
+                        Some(last_value) => ResultSet::merge(last_value, first_value)?,
+                        None => first_value,
                     };
-                    last.values.push(merged);
-                    last.values.extend(first.values);
-                    Ok(Value {
-                        kind: Some(Kind::ListValue(last)),
-                    })
+                    last.fields.insert(name, merged);
                 }
-                _ => Err(Status::new(
+                Kind::StructValue(last)
+            }
+            (Some(Kind::StringValue(_)), _) => {
+                return Err(Status::new(
+                    Code::Internal,
+                    "chunks kind mismatch: current_first must be StringKind",
+                ))
+            }
+            (Some(Kind::ListValue(_)), _) => {
+                return Err(Status::new(
                     Code::Internal,
                     "chunks kind mismatch: current_first must be ListValue",
-                )),
-            },
-            _ => Err(Status::new(
-                Code::Internal,
-                "previous_last kind mismatch: only StringValue and ListValue can be chunked",
-            )),
-        }
+                ))
+            }
+            (Some(Kind::StructValue(_)), _) => {
+                return Err(Status::new(
+                    Code::Internal,
+                    "chunks kind mismatch: current_first must be StructValue",
+                ))
+            }
+            _ => {
+                return Err(Status::new(
+                    Code::Internal,
+                    "previous_last kind mismatch: only StringValue, ListValue and StructValue can be chunked",
+                ))
+            }
+        };
+        Ok(Value { kind: Some(kind) })
     }
 
-    fn add(
-        &mut self,
-        metadata: Option<ResultSetMetadata>,
-        mut values: Vec<Value>,
-        chunked_value: bool,
-    ) -> Result<bool, Status> {
+    fn set_metadata(&mut self, metadata: Option<ResultSetMetadata>) -> Result<(), Status> {
         // get metadata only once.
         if self.fields.is_empty() {
             if let Some(metadata) = metadata {
@@ -169,19 +220,34 @@ impl ResultSet {
                     .row_type
                     .map(|e| Arc::new(e.fields))
                     .ok_or_else(|| Status::new(Code::Internal, "no field metadata found"))?;
-                // create index for Row::column_by_name("column_name")
-                let mut index = HashMap::new();
-                for (i, f) in self.fields.iter().enumerate() {
-                    index.insert(f.name.clone(), i);
-                }
-                self.index = Arc::new(index);
+                // create index for Row::column_by_name("column_name") once, shared by all the rows
+                self.index = Arc::new(column_index(&self.fields));
             }
         }
+        Ok(())
+    }
+
+    fn add(
+        &mut self,
+        metadata: Option<ResultSetMetadata>,
+        values: Vec<Value>,
+        chunked_value: bool,
+    ) -> Result<bool, Status> {
+        self.set_metadata(metadata)?;
 
+        if values.is_empty() {
+            // the chunked value continues to the next result set.
+            return Ok(true);
+        }
+        let mut values = values.into_iter();
         if self.chunked_value {
             tracing::trace!("now chunked value found previous={}, current={}", self.rows.len(), values.len());
             //merge when the chunked value is found.
-            let merged = ResultSet::merge(self.rows.pop_back().unwrap(), values.remove(0))?;
+            let previous_last = self
+                .rows
+                .pop_back()
+                .ok_or_else(|| Status::new(Code::Internal, "no chunked value found"))?;
+            let merged = ResultSet::merge(previous_last, values.next().unwrap())?;
             self.rows.push_back(merged);
         }
         self.rows.extend(values);
@@ -190,12 +256,205 @@ impl ResultSet {
     }
 }
 
+/// is_mergeable returns true if the value can be chunked.
+fn is_mergeable(value: &Value) -> bool {
+    matches!(
+        value.kind,
+        Some(Kind::StringValue(_)) | Some(Kind::ListValue(_)) | Some(Kind::StructValue(_))
+    )
+}
+
+/// The maximum size of the values buffered until the resume token is received.
+/// The values are released without the resume token if the buffer is full, and the stream can not be resumed until the next token.
+const MAX_BYTES_BETWEEN_RESUME_TOKENS: usize = 128 * 1024 * 1024;
+
+/// ResumeBuffer keeps the partial result sets until the resume token is received.
+/// When the stream is resumed with the last resume token, the server sends the values after the token again,
+/// so the values received after the token must be discarded.
+struct ResumeBuffer {
+    pending: Vec<PartialResultSet>,
+    pending_bytes: usize,
+    max_bytes: usize,
+    /// false if the values without the resume token are released because the buffer is full.
+    resumable: bool,
+}
+
+impl ResumeBuffer {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            pending: vec![],
+            pending_bytes: 0,
+            max_bytes,
+            resumable: true,
+        }
+    }
+
+    /// push returns the partial result sets that can be read.
+    fn push(&mut self, result_set: PartialResultSet) -> Vec<PartialResultSet> {
+        let has_token = !result_set.resume_token.is_empty();
+        self.pending_bytes += result_set.values.iter().map(value_size).sum::<usize>();
+        self.pending.push(result_set);
+        if has_token {
+            self.resumable = true;
+            self.release()
+        } else if self.pending_bytes > self.max_bytes {
+            tracing::debug!("too many values without resume token, the stream can not be resumed");
+            self.resumable = false;
+            self.release()
+        } else {
+            vec![]
+        }
+    }
+
+    fn release(&mut self) -> Vec<PartialResultSet> {
+        self.pending_bytes = 0;
+        std::mem::take(&mut self.pending)
+    }
+
+    /// discard drops the values after the last resume token to resume the stream.
+    fn discard(&mut self) {
+        self.pending_bytes = 0;
+        self.pending.clear();
+    }
+}
+
+fn value_size(value: &Value) -> usize {
+    match &value.kind {
+        Some(Kind::StringValue(s)) => s.len(),
+        Some(Kind::ListValue(list)) => list.values.iter().map(value_size).sum(),
+        _ => 8,
+    }
+}
+
+/// TransactionMetadata is the transaction begun by the read or the query with the `begin` transaction selector.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionMetadata {
+    pub id: Vec<u8>,
+    /// The timestamp at which all the reads in the read-only transaction are performed.
+    pub read_timestamp: Option<Timestamp>,
+}
+
+/// Metadata is the metadata of the result set sent with the first response.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub columns: Vec<ColumnMetadata>,
+    /// The transaction begun by the request. It is None unless the transaction is begun inline.
+    pub transaction: Option<TransactionMetadata>,
+}
+
+impl From<&ResultSetMetadata> for Metadata {
+    fn from(metadata: &ResultSetMetadata) -> Self {
+        Metadata {
+            columns: metadata
+                .row_type
+                .as_ref()
+                .map(|row_type| column_metadata(&row_type.fields))
+                .unwrap_or_default(),
+            transaction: metadata.transaction.as_ref().map(|tx| TransactionMetadata {
+                id: tx.id.clone(),
+                read_timestamp: tx.read_timestamp.clone().map(Timestamp::from),
+            }),
+        }
+    }
+}
+
+type PrefetchResult = Result<(PartialResultSet, OwnedSemaphorePermit), Status>;
+
+/// Prefetch receives the partial result sets in the background task ahead of the consumer,
+/// so that receiving the next messages overlaps processing the rows.
+/// The task waits while prefetch_messages messages or max_buffered_bytes bytes are buffered,
+/// and it is aborted on drop, which drops the stream and cancels the RPC.
+pub struct Prefetch {
+    receiver: mpsc::Receiver<PrefetchResult>,
+    task: JoinHandle<()>,
+}
+
+impl Prefetch {
+    /// new spawns the task receiving the stream. max_buffered_bytes 0 means no limit.
+    pub fn new<S>(mut stream: S, prefetch_messages: usize, max_buffered_bytes: usize) -> Self
+    where
+        S: Stream<Item = Result<PartialResultSet, Status>> + Send + Unpin + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(prefetch_messages.max(1));
+        let max_bytes = match max_buffered_bytes {
+            0 => u32::MAX as usize,
+            v => v.min(u32::MAX as usize),
+        };
+        let bytes = Arc::new(Semaphore::new(max_bytes));
+        let task = tokio::spawn(async move {
+            while let Some(result) = stream.next().await {
+                let result = match result {
+                    Ok(message) => {
+                        // the message larger than max_buffered_bytes waits until the buffer is empty.
+                        let size = message.values.iter().map(value_size).sum::<usize>().clamp(1, max_bytes);
+                        match Arc::clone(&bytes).acquire_many_owned(size as u32).await {
+                            Ok(permit) => Ok((message, permit)),
+                            Err(_) => return,
+                        }
+                    }
+                    Err(e) => Err(e),
+                };
+                let failed = result.is_err();
+                if sender.send(result).await.is_err() || failed {
+                    return;
+                }
+            }
+        });
+        Self { receiver, task }
+    }
+
+    /// message returns the next partial result set, or None if the stream is finished.
+    pub async fn message(&mut self) -> Result<Option<PartialResultSet>, Status> {
+        match self.receiver.recv().await {
+            // the permit is released here, so the task can receive the next message.
+            Some(Ok((message, _permit))) => Ok(Some(message)),
+            Some(Err(e)) => Err(e),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Drop for Prefetch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+enum Source {
+    Direct(Streaming<PartialResultSet>),
+    Prefetch(Prefetch),
+}
+
+impl Source {
+    fn new(streaming: Streaming<PartialResultSet>, prefetch_messages: usize, max_buffered_bytes: usize) -> Self {
+        if prefetch_messages == 0 {
+            Source::Direct(streaming)
+        } else {
+            Source::Prefetch(Prefetch::new(streaming, prefetch_messages, max_buffered_bytes))
+        }
+    }
+
+    async fn message(&mut self) -> Result<Option<PartialResultSet>, Status> {
+        match self {
+            Source::Direct(streaming) => streaming.message().await,
+            Source::Prefetch(prefetch) => prefetch.message().await,
+        }
+    }
+}
+
 pub struct RowIterator<'a> {
-    streaming: Streaming<PartialResultSet>,
+    streaming: Source,
+    prefetch_messages: usize,
+    max_buffered_bytes: usize,
     session: &'a mut SessionHandle,
     reader: Box<dyn Reader + Sync + Send>,
     rs: ResultSet,
     reader_option: Option<CallOptions>,
+    buffer: ResumeBuffer,
+    resume_setting: StreamResumeSetting,
+    resume_backoff: Take<ExponentialBackoff>,
+    stats: Option<QueryStats>,
+    metadata: Option<Metadata>,
 }
 
 impl<'a> RowIterator<'a> {
@@ -205,57 +464,200 @@ impl<'a> RowIterator<'a> {
         option: Option<CallOptions>,
     ) -> Result<RowIterator<'a>, Status> {
         let streaming = reader.read(session, option).await?.into_inner();
+        Ok(Self::from_streaming(session, reader, streaming))
+    }
+
+    /// with_first_message returns the iterator of the stream whose first response is already received.
+    pub(crate) fn with_first_message(
+        session: &'a mut SessionHandle,
+        reader: Box<dyn Reader + Sync + Send>,
+        streaming: Streaming<PartialResultSet>,
+        first: PartialResultSet,
+    ) -> Result<RowIterator<'a>, Status> {
+        let mut iter = Self::from_streaming(session, reader, streaming);
+        iter.receive(first)?;
+        Ok(iter)
+    }
+
+    fn from_streaming(
+        session: &'a mut SessionHandle,
+        reader: Box<dyn Reader + Sync + Send>,
+        streaming: Streaming<PartialResultSet>,
+    ) -> RowIterator<'a> {
         let rs = ResultSet {
             fields: Arc::new(vec![]),
             index: Arc::new(HashMap::new()),
             rows: VecDeque::new(),
             chunked_value: false,
         };
-        Ok(Self {
-            streaming,
+        let resume_setting = StreamResumeSetting::default();
+        Self {
+            streaming: Source::Direct(streaming),
+            prefetch_messages: 0,
+            max_buffered_bytes: 0,
             session,
             reader,
             rs,
             reader_option: None,
-        })
+            buffer: ResumeBuffer::new(MAX_BYTES_BETWEEN_RESUME_TOKENS),
+            resume_backoff: Retry::<Status, _>::strategy(&resume_setting),
+            resume_setting,
+            stats: None,
+            metadata: None,
+        }
+    }
+
+    /// with_prefetch receives prefetch_messages partial result sets ahead of reading the rows in the background task,
+    /// up to max_buffered_bytes bytes of the values. prefetch_messages 0 disables it, and max_buffered_bytes 0 means no limit.
+    pub fn with_prefetch(mut self, prefetch_messages: usize, max_buffered_bytes: usize) -> Self {
+        self.prefetch_messages = prefetch_messages;
+        self.max_buffered_bytes = max_buffered_bytes;
+        self.streaming = match self.streaming {
+            Source::Direct(streaming) => Source::new(streaming, prefetch_messages, max_buffered_bytes),
+            prefetch => prefetch,
+        };
+        self
     }
 
     pub fn set_call_options(&mut self, option: CallOptions) {
         self.reader_option = Some(option);
     }
 
+    /// set_resume_setting sets the error codes, the number of the attempts and the backoff
+    /// to resume the broken stream with the resume token.
+    pub fn set_resume_setting(&mut self, setting: StreamResumeSetting) {
+        self.resume_backoff = Retry::<Status, _>::strategy(&setting);
+        self.resume_setting = setting;
+    }
+
+    /// columns returns the metadata of the columns in order.
+    /// It is empty until the first response is received, use metadata to receive it before reading the rows.
+    pub fn columns(&self) -> Vec<ColumnMetadata> {
+        column_metadata(&self.rs.fields)
+    }
+
+    /// metadata returns the metadata of the columns and the transaction, receiving the first response if it is not received yet.
+    /// It is returned even if the result set has no rows. The rows in the response are kept and returned by next in order.
+    pub async fn metadata(&mut self) -> Result<Metadata, Status> {
+        while self.metadata.is_none() {
+            if !self.try_recv(self.reader_option.clone()).await? {
+                break;
+            }
+        }
+        Ok(self.metadata.clone().unwrap_or_default())
+    }
+
+    /// stats returns the query plan and the execution statistics of the query in the PLAN or PROFILE mode,
+    /// and the row count of the DML statement.
+    /// They are sent with the last response, so it is None until all the rows are read.
+    pub fn stats(&self) -> Option<&QueryStats> {
+        self.stats.as_ref()
+    }
+
+    /// into_stream returns the rows as a futures::Stream, so that the StreamExt and TryStreamExt combinators can be used.
+    ///
+    /// The row being received is kept in the stream, so dropping the future of StreamExt::next in `select!` does not lose it.
+    /// Dropping the stream drops the gRPC stream and returns the session, so the session is not broken and can be used again.
+    /// In the read-write transaction, the transaction can still be committed after dropping the stream in the middle,
+    /// and the locks of the rows already read are held until the transaction finishes.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Row, Status>> + Send + Unpin + 'a {
+        Box::pin(futures_util::stream::try_unfold(self, |mut iter| async move {
+            Ok(iter.next().await?.map(|row| (row, iter)))
+        }))
+    }
+
+    fn set_stats(&mut self, stats: Option<ResultSetStats>) {
+        if let Some(stats) = stats {
+            self.stats = Some(stats.into());
+        }
+    }
+
+    /// receive adds the partial result set received from the stream.
+    fn receive(&mut self, result_set: PartialResultSet) -> Result<(), Status> {
+        // keep the metadata even if the result set is empty.
+        if self.metadata.is_none() {
+            self.metadata = result_set.metadata.as_ref().map(Metadata::from);
+        }
+        self.rs.set_metadata(result_set.metadata.clone())?;
+        let released = self.buffer.push(result_set);
+        if !released.is_empty() {
+            // the resume token is received, so the stream made progress.
+            self.resume_backoff = Retry::<Status, _>::strategy(&self.resume_setting);
+        }
+        let stats = release(&mut self.rs, self.reader.as_mut(), released)?;
+        self.set_stats(stats);
+        Ok(())
+    }
+
+    /// try_recv receives the next partial result set. It returns false if the stream is finished.
     async fn try_recv(&mut self, option: Option<CallOptions>) -> Result<bool, Status> {
-        // try getting records from server
-        let maybe_result_set = match self.streaming.message().await {
-            Ok(s) => s,
-            Err(e) => {
-                if !self.reader.can_retry() {
-                    return Err(e);
+        loop {
+            match self.streaming.message().await {
+                Ok(Some(result_set)) => {
+                    self.receive(result_set)?;
+                    return Ok(true);
+                }
+                Ok(None) => {
+                    let released = self.buffer.release();
+                    if released.is_empty() {
+                        return Ok(false);
+                    }
+                    let stats = release(&mut self.rs, self.reader.as_mut(), released)?;
+                    self.set_stats(stats);
+                    return Ok(true);
                 }
-                tracing::debug!("streaming error: {}. resume reading by resume_token", e);
-                let result = self.reader.read(self.session, option).await?;
-                self.streaming = result.into_inner();
-                self.streaming.message().await?
+                Err(e) => self.resume(e, option.clone()).await?,
             }
-        };
+        }
+    }
 
-        match maybe_result_set {
-            Some(result_set) => {
-                if result_set.values.is_empty() {
-                    return Ok(false);
-                }
-                //if resume_token changes set new resume_token
-                if !result_set.resume_token.is_empty() {
-                    self.reader.update_token(result_set.resume_token);
+    /// resume reads the stream again from the last resume token after the backoff.
+    /// The reconnect failing with the retryable error is retried with the same setting and attempts as the broken stream.
+    async fn resume(&mut self, mut error: Status, option: Option<CallOptions>) -> Result<(), Status> {
+        loop {
+            if !self.buffer.resumable || !Retry::<Status, _>::condition(&self.resume_setting).should_retry(&error) {
+                return Err(error);
+            }
+            let delay = match self.resume_backoff.next() {
+                Some(delay) => delay,
+                None => return Err(error),
+            };
+            tracing::debug!("streaming error: {}. resume reading by resume_token after {:?}", error, delay);
+            tokio::time::sleep(delay).await;
+            self.buffer.discard();
+            match self.reader.read(self.session, option.clone()).await {
+                Ok(result) => {
+                    self.streaming = Source::new(result.into_inner(), self.prefetch_messages, self.max_buffered_bytes);
+                    return Ok(());
                 }
-                self.rs
-                    .add(result_set.metadata, result_set.values, result_set.chunked_value)
+                Err(e) => error = e,
             }
-            None => Ok(false),
         }
     }
 }
 
+/// release adds the values to the result set and updates the resume token of the reader.
+/// It returns the stats if they are in the released result sets.
+fn release(
+    rs: &mut ResultSet,
+    reader: &mut dyn Reader,
+    released: Vec<PartialResultSet>,
+) -> Result<Option<ResultSetStats>, Status> {
+    let mut stats = None;
+    for result_set in released {
+        if result_set.stats.is_some() {
+            stats = result_set.stats;
+        }
+        if !result_set.resume_token.is_empty() {
+            reader.update_token(result_set.resume_token);
+        }
+        if !result_set.values.is_empty() {
+            rs.add(result_set.metadata, result_set.values, result_set.chunked_value)?;
+        }
+    }
+    Ok(stats)
+}
+
 #[async_trait]
 impl<'a> AsyncIterator for RowIterator<'a> {
     fn column_metadata(&self, column_name: &str) -> Option<(usize, Field)> {
@@ -284,15 +686,39 @@ impl<'a> AsyncIterator for RowIterator<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::reader::ResultSet;
+    use crate::apiv1::spanner_client::Client;
+    use crate::reader::{release, AsyncIterator, Metadata, Prefetch, Reader, ResultSet, ResumeBuffer, RowIterator};
+    use crate::retry::StreamResumeSetting;
     use crate::row::{Row, TryFromValue};
+    use crate::session::SessionHandle;
     use crate::statement::ToKind;
+    use crate::transaction::CallOptions;
+    use crate::value::Timestamp;
+    use async_trait::async_trait;
+    use futures_util::StreamExt;
+    use google_cloud_gax::conn::Channel;
+    use google_cloud_gax::grpc::codec::{Codec, ProstCodec};
+    use google_cloud_gax::grpc::codegen::http::HeaderMap;
+    use google_cloud_gax::grpc::codegen::{Body, Bytes};
+    use google_cloud_gax::grpc::transport::Endpoint;
+    use google_cloud_gax::grpc::{Code, Response, Status, Streaming};
+    use google_cloud_gax::retry::RetrySetting;
+    use google_cloud_googleapis::spanner::v1::spanner_client::SpannerClient;
     use google_cloud_googleapis::spanner::v1::struct_type::Field;
-    use google_cloud_googleapis::spanner::v1::{ResultSetMetadata, StructType};
+    use google_cloud_googleapis::spanner::v1::{PartialResultSet, QueryPlan, ResultSetStats};
+    use google_cloud_googleapis::spanner::v1::{
+        ResultSetMetadata, Session, StructType, Transaction, TransactionSelector,
+    };
+    use parking_lot::Mutex;
+    use prost::Message;
     use prost_types::value::Kind;
-    use prost_types::Value;
+    use prost_types::{ListValue, Value};
     use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
     use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use std::time::Instant;
 
     fn empty_rs() -> ResultSet {
         ResultSet {
@@ -467,6 +893,263 @@ mod tests {
         }
     }
 
+    fn list(values: Vec<Value>) -> Value {
+        Value {
+            kind: Some(Kind::ListValue(ListValue { values })),
+        }
+    }
+
+    fn object(fields: Vec<(&str, Value)>) -> Value {
+        Value {
+            kind: Some(Kind::StructValue(prost_types::Struct {
+                fields: fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            })),
+        }
+    }
+
+    fn number(v: f64) -> Value {
+        Value {
+            kind: Some(Kind::NumberValue(v)),
+        }
+    }
+
+    #[test]
+    fn test_rs_merge_documented_examples() {
+        // the examples in the document of PartialResultSet
+        let cases = vec![
+            (value("foo"), value("bar"), value("foobar")),
+            (
+                list(vec![number(2.0), number(3.0)]),
+                list(vec![number(4.0)]),
+                list(vec![number(2.0), number(3.0), number(4.0)]),
+            ),
+            (
+                list(vec![value("a"), value("b")]),
+                list(vec![value("c"), value("d")]),
+                list(vec![value("a"), value("bc"), value("d")]),
+            ),
+            (
+                list(vec![value("a"), list(vec![value("b"), value("c")])]),
+                list(vec![list(vec![value("d")]), value("e")]),
+                list(vec![value("a"), list(vec![value("b"), value("cd")]), value("e")]),
+            ),
+            (
+                object(vec![("a", value("1"))]),
+                object(vec![("b", value("2"))]),
+                object(vec![("a", value("1")), ("b", value("2"))]),
+            ),
+            (
+                object(vec![("a", value("1"))]),
+                object(vec![("a", value("2"))]),
+                object(vec![("a", value("12"))]),
+            ),
+            // the null and the bool are not chunked
+            (
+                list(vec![
+                    value("a"),
+                    Value {
+                        kind: Some(Kind::NullValue(0)),
+                    },
+                ]),
+                list(vec![Value {
+                    kind: Some(Kind::BoolValue(true)),
+                }]),
+                list(vec![
+                    value("a"),
+                    Value {
+                        kind: Some(Kind::NullValue(0)),
+                    },
+                    Value {
+                        kind: Some(Kind::BoolValue(true)),
+                    },
+                ]),
+            ),
+            // the empty lists
+            (list(vec![]), list(vec![value("a")]), list(vec![value("a")])),
+            (list(vec![value("a")]), list(vec![]), list(vec![value("a")])),
+        ];
+        for (previous_last, current_first, expected) in cases {
+            assert_eq!(ResultSet::merge(previous_last, current_first).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_rs_merge_kind_mismatch() {
+        assert!(ResultSet::merge(value("a"), list(vec![])).is_err());
+        assert!(ResultSet::merge(list(vec![]), value("a")).is_err());
+        assert!(ResultSet::merge(object(vec![]), value("a")).is_err());
+        assert!(ResultSet::merge(number(1.0), number(2.0)).is_err());
+        assert!(ResultSet::merge(Value { kind: None }, value("a")).is_err());
+        assert!(ResultSet::merge(list(vec![value("a")]), list(vec![number(1.0)])).is_err());
+    }
+
+    #[test]
+    fn test_rs_add_documented_example() {
+        let mut rs = empty_rs();
+        let metadata = Some(ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("column1")],
+            }),
+            transaction: None,
+            undeclared_parameters: None,
+        });
+        assert!(rs.add(metadata, vec![value("Hello"), value("W")], true).unwrap());
+        assert_some_one_column(rs.next(), "Hello".to_string());
+        assert!(rs.next().is_none());
+        assert!(rs.add(None, vec![value("orl")], true).unwrap());
+        assert!(rs.next().is_none());
+        assert!(rs.add(None, vec![value("d")], false).unwrap());
+        assert_some_one_column(rs.next(), "World".to_string());
+        assert!(rs.next().is_none());
+    }
+
+    #[test]
+    fn test_rs_add_chunked_value_in_many_chunks() {
+        let mut rs = empty_rs();
+        let metadata = Some(ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("column1"), field("column2")],
+            }),
+            transaction: None,
+            undeclared_parameters: None,
+        });
+        // 3 MB string split into chunks of 1 MB.
+        let chunk = "a".repeat(1024 * 1024);
+        assert!(rs
+            .add(metadata, vec![value("id1"), value(chunk.as_str())], true)
+            .unwrap());
+        // the chunked value without the values keeps the state
+        assert!(rs.add(None, vec![], false).unwrap());
+        assert!(rs.chunked_value);
+        assert!(rs.add(None, vec![value(chunk.as_str())], true).unwrap());
+        assert!(rs.next().is_none());
+        assert!(rs.add(None, vec![value(chunk.as_str()), value("id2")], true).unwrap());
+        assert!(rs.add(None, vec![value("b")], false).unwrap());
+        assert_some_multi_column(rs.next(), "id1".to_string(), chunk.repeat(3));
+        assert!(rs.next().is_none());
+        assert!(rs.add(None, vec![value("c")], false).unwrap());
+        assert_some_multi_column(rs.next(), "id2b".to_string(), "c".to_string());
+        assert!(rs.next().is_none());
+    }
+
+    #[test]
+    fn test_rs_add_chunked_nested_list() {
+        let mut rs = empty_rs();
+        let metadata = Some(ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("column1")],
+            }),
+            transaction: None,
+            undeclared_parameters: None,
+        });
+        // ARRAY<STRUCT<Name STRING, Scores ARRAY<FLOAT64>>> split in the middle of the nested lists.
+        let chunks = vec![
+            list(vec![
+                list(vec![value("name1"), list(vec![number(1.0)])]),
+                list(vec![value("na")]),
+            ]),
+            list(vec![list(vec![value("me2"), list(vec![number(2.0)])])]),
+            list(vec![list(vec![list(vec![number(3.0)])])]),
+            list(vec![list(vec![]), list(vec![value("name3"), list(vec![])])]),
+        ];
+        let last = chunks.len() - 1;
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            assert!(rs.add(metadata.clone(), vec![chunk], i != last).unwrap());
+            if i != last {
+                assert!(rs.next().is_none());
+            }
+        }
+        let expected = list(vec![
+            list(vec![value("name1"), list(vec![number(1.0)])]),
+            list(vec![value("name2"), list(vec![number(2.0), number(3.0)])]),
+            list(vec![value("name3"), list(vec![])]),
+        ]);
+        assert_eq!(rs.rows, VecDeque::from(vec![expected]));
+        assert!(rs.next().is_some());
+        assert!(rs.next().is_none());
+    }
+
+    #[test]
+    fn test_metadata() {
+        let metadata = ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("column1"), field("column2")],
+            }),
+            transaction: Some(Transaction {
+                id: vec![1, 2, 3],
+                read_timestamp: Some(prost_types::Timestamp { seconds: 10, nanos: 1 }),
+            }),
+            undeclared_parameters: None,
+        };
+        let metadata = Metadata::from(&metadata);
+        let columns: Vec<(&str, usize)> = metadata.columns.iter().map(|c| (c.name.as_str(), c.index)).collect();
+        assert_eq!(columns, vec![("column1", 0), ("column2", 1)]);
+        let transaction = metadata.transaction.unwrap();
+        assert_eq!(transaction.id, vec![1, 2, 3]);
+        assert_eq!(transaction.read_timestamp, Some(Timestamp { seconds: 10, nanos: 1 }));
+
+        // the result set without the rows still has the columns
+        let metadata = ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("column1")],
+            }),
+            transaction: None,
+            undeclared_parameters: None,
+        };
+        let metadata = Metadata::from(&metadata);
+        assert_eq!(metadata.columns.len(), 1);
+        assert!(metadata.transaction.is_none());
+        assert_eq!(Metadata::from(&ResultSetMetadata::default()), Metadata::default());
+    }
+
+    #[test]
+    fn test_rs_set_metadata_without_values() {
+        let mut rs = empty_rs();
+        let metadata = Some(ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("column1")],
+            }),
+            transaction: None,
+            undeclared_parameters: None,
+        });
+        rs.set_metadata(metadata).unwrap();
+        assert_one_column(&rs);
+        assert!(rs.next().is_none());
+
+        // metadata is set only once
+        let other = Some(ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("column1"), field("column2")],
+            }),
+            transaction: None,
+            undeclared_parameters: None,
+        });
+        rs.set_metadata(other).unwrap();
+        assert_one_column(&rs);
+    }
+
+    #[test]
+    fn test_rs_duplicate_column_name() {
+        let mut rs = empty_rs();
+        let metadata = Some(ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("Id"), field("Name"), field("Id")],
+            }),
+            transaction: None,
+            undeclared_parameters: None,
+        });
+        let values = vec![value("1"), value("name"), value("2")];
+        assert!(rs.add(metadata, values, false).unwrap());
+        assert_eq!(rs.index.len(), 2);
+        assert_eq!(*rs.index.get("Id").unwrap(), 0);
+
+        let row = rs.next().unwrap();
+        assert_eq!(row.column_by_name::<String>("Id").unwrap(), "1");
+        assert_eq!(row.column_indices("Id"), vec![0, 2]);
+        assert_eq!(row.column::<String>(2).unwrap(), "2");
+        assert!(row.column_indices("Unknown").is_empty());
+    }
+
     #[test]
     fn test_rs_add_one_column_no_chunked_value() {
         let mut rs = empty_rs();
@@ -707,4 +1390,375 @@ mod tests {
         );
         assert!(rs.next().is_none());
     }
+
+    /// Script is the behavior of the fake server for a read: the stream sent from the message after the resume token.
+    enum Script {
+        /// the read itself fails.
+        Fail(Status),
+        /// the stream is broken with the status before the message at the position.
+        Break(usize, Status),
+    }
+
+    /// FakeReader streams the messages of server, resuming from the message after the resume token.
+    /// Each read follows the next script, and the stream is sent to the end after the scripts run out.
+    struct FakeReader {
+        resume_token: Vec<u8>,
+        scripts: Mutex<VecDeque<Script>>,
+        reads: Arc<AtomicUsize>,
+    }
+
+    impl FakeReader {
+        fn new(scripts: Vec<Script>) -> Self {
+            FakeReader {
+                resume_token: vec![],
+                scripts: Mutex::new(scripts.into()),
+                reads: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Reader for FakeReader {
+        async fn read(
+            &self,
+            _session: &mut SessionHandle,
+            _option: Option<CallOptions>,
+        ) -> Result<Response<Streaming<PartialResultSet>>, Status> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            let messages = server();
+            let start = match messages.iter().position(|m| m.resume_token == self.resume_token) {
+                Some(i) if !self.resume_token.is_empty() => i + 1,
+                _ => 0,
+            };
+            let (end, error) = match self.scripts.lock().pop_front() {
+                Some(Script::Fail(status)) => return Err(status),
+                Some(Script::Break(at, status)) => (at, Some(status)),
+                None => (messages.len(), None),
+            };
+            assert!(start <= end, "the stream is broken at {end} before the resume position {start}");
+            let frames = messages[start..end].iter().map(|m| Ok(frame(m))).chain(error.map(Err));
+            Ok(Response::new(Streaming::new_request(
+                ProstCodec::<PartialResultSet, PartialResultSet>::default().decoder(),
+                FakeBody(frames.collect()),
+                None,
+            )))
+        }
+
+        fn update_token(&mut self, resume_token: Vec<u8>) {
+            self.resume_token = resume_token;
+        }
+
+        fn set_transaction(&mut self, _transaction: TransactionSelector) {}
+
+        fn can_retry(&self) -> bool {
+            !self.resume_token.is_empty()
+        }
+    }
+
+    /// frame encodes the message as the uncompressed gRPC message.
+    fn frame(message: &PartialResultSet) -> Bytes {
+        let encoded = message.encode_to_vec();
+        let mut frame = vec![0];
+        frame.extend((encoded.len() as u32).to_be_bytes());
+        frame.extend(encoded);
+        frame.into()
+    }
+
+    /// FakeBody is the response body of the gRPC frames, which fails with the status in place of a frame.
+    struct FakeBody(VecDeque<Result<Bytes, Status>>);
+
+    impl Body for FakeBody {
+        type Data = Bytes;
+        type Error = Status;
+
+        fn poll_data(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, Status>>> {
+            Poll::Ready(self.0.pop_front())
+        }
+
+        fn poll_trailers(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<Option<HeaderMap>, Status>> {
+            Poll::Ready(Ok(None))
+        }
+    }
+
+    fn fake_session() -> SessionHandle {
+        let channel = Endpoint::from_static("http://localhost:1").connect_lazy();
+        let client = Client::new(SpannerClient::new(Channel::B(channel)));
+        SessionHandle::new(Session::default(), client, Instant::now())
+    }
+
+    /// server returns the partial result sets of the rows "row0".."row9" in the column "column1".
+    /// The odd ones have the resume token, and the value is chunked between the 4th and the 5th.
+    fn server() -> Vec<PartialResultSet> {
+        let metadata = Some(ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("column1")],
+            }),
+            transaction: None,
+            undeclared_parameters: None,
+        });
+        (0..10)
+            .map(|i| PartialResultSet {
+                metadata: if i == 0 { metadata.clone() } else { None },
+                values: match i {
+                    4 => vec![value("row4"), value("ro")],
+                    5 => vec![value("w5")],
+                    _ => vec![value(format!("row{i}"))],
+                },
+                chunked_value: i == 4,
+                resume_token: if i % 2 == 1 {
+                    format!("token{i}").into_bytes()
+                } else {
+                    vec![]
+                },
+                stats: None,
+            })
+            .collect()
+    }
+
+    /// read_all reads the rows of the fake server by RowIterator, following the scripts of the reads.
+    /// It returns the rows and the number of the reads.
+    async fn read_all(scripts: Vec<Script>, max_bytes: usize) -> (Result<Vec<String>, Status>, usize) {
+        let reader = FakeReader::new(scripts);
+        let reads = Arc::clone(&reader.reads);
+        let mut session = fake_session();
+        let result = async {
+            let mut iter = RowIterator::new(&mut session, Box::new(reader), None).await?;
+            iter.buffer = ResumeBuffer::new(max_bytes);
+            iter.set_resume_setting(StreamResumeSetting {
+                inner: RetrySetting {
+                    from_millis: 1,
+                    ..StreamResumeSetting::default().inner
+                },
+            });
+            let mut rows = vec![];
+            while let Some(row) = iter.next().await? {
+                rows.push(row.column::<String>(0).unwrap());
+            }
+            Ok(rows)
+        }
+        .await;
+        (result, reads.load(Ordering::SeqCst))
+    }
+
+    fn expected_rows() -> Vec<String> {
+        (0..10).map(|i| format!("row{i}")).collect()
+    }
+
+    #[tokio::test]
+    async fn test_resume_no_duplicate_no_missing() {
+        let unavailable = || Status::new(Code::Unavailable, "GOAWAY");
+        let (rows, reads) = read_all(vec![], usize::MAX).await;
+        assert_eq!((rows.unwrap(), reads), (expected_rows(), 1));
+        // broken before the first resume token, so the stream is read from the beginning.
+        let (rows, reads) = read_all(vec![Script::Break(1, unavailable())], usize::MAX).await;
+        assert_eq!((rows.unwrap(), reads), (expected_rows(), 2));
+        // broken in the middle of the chunked value
+        let (rows, reads) = read_all(vec![Script::Break(5, unavailable())], usize::MAX).await;
+        assert_eq!((rows.unwrap(), reads), (expected_rows(), 2));
+        // broken several times, even just after the token
+        let scripts = vec![
+            Script::Break(3, unavailable()),
+            Script::Break(4, unavailable()),
+            Script::Break(7, Status::new(Code::Internal, "stream terminated by RST_STREAM")),
+            Script::Break(9, unavailable()),
+        ];
+        let (rows, reads) = read_all(scripts, usize::MAX).await;
+        assert_eq!((rows.unwrap(), reads), (expected_rows(), 5));
+    }
+
+    #[tokio::test]
+    async fn test_resume_reconnect_failure() {
+        let unavailable = || Status::new(Code::Unavailable, "connection refused");
+        // the failed reconnect is retried with the backoff.
+        let scripts = vec![
+            Script::Break(5, unavailable()),
+            Script::Fail(unavailable()),
+            Script::Fail(unavailable()),
+        ];
+        let (rows, reads) = read_all(scripts, usize::MAX).await;
+        assert_eq!((rows.unwrap(), reads), (expected_rows(), 4));
+
+        // the reconnect failing with the error not retryable is not retried.
+        let scripts = vec![
+            Script::Break(5, unavailable()),
+            Script::Fail(Status::new(Code::PermissionDenied, "denied")),
+        ];
+        let (rows, reads) = read_all(scripts, usize::MAX).await;
+        assert_eq!((rows.unwrap_err().code(), reads), (Code::PermissionDenied, 2));
+
+        // the reconnects share the attempts with the broken streams.
+        let attempts = StreamResumeSetting::default().inner.take;
+        let mut scripts = vec![Script::Break(5, unavailable())];
+        scripts.extend((0..attempts).map(|_| Script::Fail(unavailable())));
+        let (rows, reads) = read_all(scripts, usize::MAX).await;
+        assert_eq!((rows.unwrap_err().code(), reads), (Code::Unavailable, attempts + 1));
+    }
+
+    #[tokio::test]
+    async fn test_resume_not_retryable() {
+        let (rows, reads) = read_all(
+            vec![Script::Break(5, Status::new(Code::InvalidArgument, "invalid"))],
+            usize::MAX,
+        )
+        .await;
+        assert_eq!((rows.unwrap_err().code(), reads), (Code::InvalidArgument, 1));
+        let (rows, reads) = read_all(vec![Script::Break(5, Status::new(Code::Internal, "internal"))], usize::MAX).await;
+        assert_eq!((rows.unwrap_err().code(), reads), (Code::Internal, 1));
+    }
+
+    #[test]
+    fn test_release_stats() {
+        let mut rs = empty_rs();
+        let mut reader = FakeReader::new(vec![]);
+        let mut messages = server();
+        assert!(release(&mut rs, &mut reader, messages.clone()).unwrap().is_none());
+
+        let stats = ResultSetStats {
+            query_plan: Some(QueryPlan::default()),
+            query_stats: None,
+            row_count: None,
+        };
+        messages.last_mut().unwrap().stats = Some(stats.clone());
+        let mut rs = empty_rs();
+        assert_eq!(release(&mut rs, &mut reader, messages).unwrap(), Some(stats));
+    }
+
+    #[tokio::test]
+    async fn test_resume_buffer_full() {
+        // the values without the resume token are released when the buffer is full,
+        // and the stream can not be resumed until the next resume token.
+        let mut buffer = ResumeBuffer::new(4);
+        let message = |v: &str, token: &str| PartialResultSet {
+            values: vec![value(v)],
+            resume_token: token.as_bytes().to_vec(),
+            ..Default::default()
+        };
+        assert!(buffer.push(message("abc", "")).is_empty());
+        assert!(buffer.resumable);
+        assert_eq!(buffer.push(message("def", "")).len(), 2);
+        assert!(!buffer.resumable);
+        assert_eq!(buffer.push(message("g", "token")).len(), 1);
+        assert!(buffer.resumable);
+
+        let (rows, _) = read_all(vec![Script::Break(5, Status::new(Code::Unavailable, ""))], 1).await;
+        assert_eq!(rows.unwrap_err().code(), Code::Unavailable);
+        let (rows, _) = read_all(vec![Script::Break(4, Status::new(Code::Unavailable, ""))], 1).await;
+        assert_eq!(rows.unwrap(), expected_rows());
+    }
+
+    fn message(row: &str) -> PartialResultSet {
+        PartialResultSet {
+            metadata: None,
+            values: vec![value(row)],
+            chunked_value: false,
+            resume_token: vec![],
+            stats: None,
+        }
+    }
+
+    /// counting returns the stream of the messages which counts the received messages.
+    fn counting(
+        messages: Vec<Result<PartialResultSet, Status>>,
+    ) -> (
+        impl futures_util::Stream<Item = Result<PartialResultSet, Status>> + Send + Unpin + 'static,
+        Arc<AtomicUsize>,
+    ) {
+        let received = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&received);
+        let stream = futures_util::stream::iter(messages).inspect(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        (stream, received)
+    }
+
+    async fn wait_for_task() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    fn row_of(message: Option<PartialResultSet>) -> String {
+        match message.unwrap().values.remove(0).kind {
+            Some(Kind::StringValue(v)) => v,
+            _ => unreachable!("must be string value"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_order_and_error() {
+        let messages = vec![
+            Ok(message("row1")),
+            Ok(message("row2")),
+            Err(Status::new(Code::Unavailable, "broken")),
+            Ok(message("row3")),
+        ];
+        let (stream, received) = counting(messages);
+        let mut prefetch = Prefetch::new(stream, 4, 0);
+        assert_eq!(row_of(prefetch.message().await.unwrap()), "row1");
+        assert_eq!(row_of(prefetch.message().await.unwrap()), "row2");
+        assert_eq!(prefetch.message().await.unwrap_err().code(), Code::Unavailable);
+        // the stream is not received after the error
+        assert!(prefetch.message().await.unwrap().is_none());
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_backpressure() {
+        let messages = (0..10).map(|i| Ok(message(&format!("row{i}")))).collect();
+        let (stream, received) = counting(messages);
+        let mut prefetch = Prefetch::new(stream, 2, 0);
+        wait_for_task().await;
+        // 2 messages in the channel and 1 message waiting to be sent
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+        assert_eq!(row_of(prefetch.message().await.unwrap()), "row0");
+        wait_for_task().await;
+        assert_eq!(received.load(Ordering::SeqCst), 4);
+        for i in 1..10 {
+            assert_eq!(row_of(prefetch.message().await.unwrap()), format!("row{i}"));
+        }
+        assert!(prefetch.message().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_max_buffered_bytes() {
+        let row = "a".repeat(100);
+        let messages = (0..10).map(|_| Ok(message(&row))).collect();
+        let (stream, received) = counting(messages);
+        let mut prefetch = Prefetch::new(stream, 10, 150);
+        wait_for_task().await;
+        // 1 message in the channel and 1 message waiting for the buffer
+        assert_eq!(received.load(Ordering::SeqCst), 2);
+        assert_eq!(row_of(prefetch.message().await.unwrap()), row);
+        wait_for_task().await;
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+
+        // the message larger than the limit is received when the buffer is empty.
+        let messages = vec![Ok(message(&row)), Ok(message(&row))];
+        let mut prefetch = Prefetch::new(counting(messages).0, 10, 10);
+        assert_eq!(row_of(prefetch.message().await.unwrap()), row);
+        assert_eq!(row_of(prefetch.message().await.unwrap()), row);
+        assert!(prefetch.message().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_drop() {
+        struct Guard(Arc<AtomicBool>);
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+        let dropped = Arc::new(AtomicBool::new(false));
+        let guard = Guard(Arc::clone(&dropped));
+        // the stream which never finishes like the RPC waiting for the server
+        let stream = futures_util::stream::pending::<Result<PartialResultSet, Status>>().inspect(move |_| {
+            let _ = &guard;
+        });
+        let prefetch = Prefetch::new(Box::pin(stream), 4, 0);
+        wait_for_task().await;
+        assert!(!dropped.load(Ordering::SeqCst));
+        drop(prefetch);
+        wait_for_task().await;
+        assert!(dropped.load(Ordering::SeqCst));
+    }
 }
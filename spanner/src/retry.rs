@@ -1,8 +1,16 @@
+use std::future::Future;
 use std::iter::Take;
 use std::marker::PhantomData;
+use std::time::{Duration, Instant};
 
+use google_cloud_gax::cancel::CancellationToken;
 use google_cloud_gax::grpc::{Code, Status};
-use google_cloud_gax::retry::{CodeCondition, Condition, ExponentialBackoff, Retry, RetrySetting, TryAs};
+use google_cloud_gax::retry::{jitter, CodeCondition, Condition, ExponentialBackoff, Retry, RetrySetting, TryAs};
+use prost::Message;
+
+/// The trailer in which Spanner sends the RetryInfo of the ABORTED error.
+const RETRY_INFO_KEY: &str = "google.rpc.retryinfo-bin";
+const RETRY_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.RetryInfo";
 
 pub struct TransactionCondition<E>
 where
@@ -41,33 +49,44 @@ where
 {
     strategy: Take<ExponentialBackoff>,
     condition: TransactionCondition<E>,
+    deadline: Option<Instant>,
 }
 
 impl<E> TransactionRetry<E>
 where
     E: TryAs<Status>,
 {
+    /// next waits the delay suggested by the RetryInfo of the error, or the exponential backoff with jitter without it.
+    /// It returns the error if the error is not retryable or the attempts or the timeout of the setting are exhausted.
     pub async fn next(&mut self, status: E) -> Result<(), E> {
-        let duration = if self.condition.should_retry(&status) {
-            self.strategy.next()
-        } else {
-            None
+        if !self.condition.should_retry(&status) {
+            return Err(status);
+        }
+        // the attempt is counted even if the delay is suggested by the server.
+        let backoff = match self.strategy.next() {
+            Some(backoff) => backoff,
+            None => return Err(status),
         };
-        match duration {
-            Some(duration) => {
-                tokio::time::sleep(duration).await;
-                Ok(())
+        let duration = status.try_as().and_then(retry_delay).unwrap_or_else(|| jitter(backoff));
+        if let Some(deadline) = self.deadline {
+            if Instant::now() + duration > deadline {
+                return Err(status);
             }
-            None => Err(status),
         }
+        tokio::time::sleep(duration).await;
+        Ok(())
     }
 
     pub fn new() -> Self {
-        let setting = TransactionRetrySetting::default();
+        Self::with_setting(TransactionRetrySetting::default())
+    }
+
+    pub fn with_setting(setting: TransactionRetrySetting) -> Self {
         let strategy = <TransactionRetrySetting as Retry<E, TransactionCondition<E>>>::strategy(&setting);
         Self {
             strategy,
             condition: setting.condition(),
+            deadline: setting.timeout.map(|timeout| Instant::now() + timeout),
         }
     }
 }
@@ -84,6 +103,8 @@ where
 #[derive(Clone, Debug)]
 pub struct TransactionRetrySetting {
     pub inner: RetrySetting,
+    /// The total time to retry the transaction. None means the retries are limited only by the attempts.
+    pub timeout: Option<Duration>,
 }
 
 impl<E> Retry<E, TransactionCondition<E>> for TransactionRetrySetting
@@ -109,22 +130,139 @@ impl TransactionRetrySetting {
                 codes,
                 ..Default::default()
             },
+            timeout: None,
         }
     }
 }
 
+impl TransactionRetrySetting {
+    /// optimistic returns the setting for the transactions with the optimistic read lock mode,
+    /// which abort more often at the commit because the reads are validated at the commit.
+    /// It retries twice as many times as the default.
+    pub fn optimistic() -> Self {
+        let mut setting = Self::default();
+        setting.inner.take *= 2;
+        setting
+    }
+}
+
 impl Default for TransactionRetrySetting {
     fn default() -> Self {
         TransactionRetrySetting::new(vec![Code::Aborted])
     }
 }
 
+/// invoke_transaction_fn calls f until it succeeds, retrying it by TransactionRetry with the setting.
+/// The value returned with the error, such as the session, is passed to the next call.
+pub(crate) async fn invoke_transaction_fn<R, V, A, E>(
+    cancel: Option<CancellationToken>,
+    setting: TransactionRetrySetting,
+    mut f: impl FnMut(V) -> A,
+    mut v: V,
+) -> Result<R, E>
+where
+    E: TryAs<Status> + From<Status>,
+    A: Future<Output = Result<R, (E, V)>>,
+{
+    let fn_loop = async {
+        let mut retry = TransactionRetry::with_setting(setting);
+        loop {
+            match f(v).await {
+                Ok(result) => return Ok(result),
+                Err((err, next)) => {
+                    v = next;
+                    retry.next(err).await?;
+                    tracing::trace!("retry transaction");
+                }
+            }
+        }
+    };
+    match cancel {
+        Some(cancel) => {
+            tokio::select! {
+                _ = cancel.cancelled() => Err(Status::cancelled("client cancel").into()),
+                v = fn_loop => v
+            }
+        }
+        None => fn_loop.await,
+    }
+}
+
+/// RetryInfo is google.rpc.RetryInfo, which is not generated in google_cloud_googleapis.
+#[derive(Clone, PartialEq, prost::Message)]
+struct RetryInfo {
+    #[prost(message, optional, tag = "1")]
+    retry_delay: Option<prost_types::Duration>,
+}
+
+/// retry_delay returns the delay suggested by the server in the RetryInfo of the status.
+pub fn retry_delay(status: &Status) -> Option<Duration> {
+    let retry_info = match status.metadata().get_bin(RETRY_INFO_KEY) {
+        Some(value) => RetryInfo::decode(value.to_bytes().ok()?).ok()?,
+        None => {
+            let details = google_cloud_googleapis::rpc::Status::decode(status.details()).ok()?;
+            let any = details
+                .details
+                .into_iter()
+                .find(|any| any.type_url == RETRY_INFO_TYPE_URL)?;
+            RetryInfo::decode(any.value.as_slice()).ok()?
+        }
+    };
+    let delay = retry_info.retry_delay?;
+    if delay.seconds < 0 || delay.nanos < 0 {
+        return None;
+    }
+    Some(Duration::new(delay.seconds as u64, delay.nanos as u32))
+}
+
+/// StreamResumeSetting is the setting to resume the streaming reads with the resume token
+/// when the stream is broken by UNAVAILABLE or the reset of the HTTP/2 stream.
+#[derive(Clone, Debug)]
+pub struct StreamResumeSetting {
+    pub inner: RetrySetting,
+}
+
+impl<E> Retry<E, TransactionCondition<E>> for StreamResumeSetting
+where
+    E: TryAs<Status>,
+{
+    fn strategy(&self) -> Take<ExponentialBackoff> {
+        self.inner.strategy()
+    }
+
+    fn condition(&self) -> TransactionCondition<E> {
+        TransactionCondition {
+            inner: CodeCondition::new(self.inner.codes.clone()),
+            _marker: PhantomData::default(),
+        }
+    }
+}
+
+impl Default for StreamResumeSetting {
+    fn default() -> Self {
+        Self {
+            inner: RetrySetting {
+                codes: vec![Code::Unavailable, Code::Internal],
+                ..Default::default()
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::client::{RunInTxError, TxError};
-    use crate::retry::TransactionRetrySetting;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::retry::{
+        invoke_transaction_fn, retry_delay, RetryInfo, StreamResumeSetting, TransactionRetry, TransactionRetrySetting,
+        RETRY_INFO_KEY, RETRY_INFO_TYPE_URL,
+    };
+    use google_cloud_gax::grpc::metadata::{MetadataMap, MetadataValue};
     use google_cloud_gax::grpc::{Code, Status};
     use google_cloud_gax::retry::{Condition, Retry};
+    use prost::Message;
 
     #[test]
     fn test_transaction_condition() {
@@ -135,4 +273,122 @@ mod tests {
         let err = &RunInTxError::GRPC(Status::new(Code::Aborted, ""));
         assert!(default.condition().should_retry(err));
     }
+
+    #[test]
+    fn test_stream_resume_condition() {
+        let setting = StreamResumeSetting::default();
+        let should_retry = |status: Status| Retry::<Status, _>::condition(&setting).should_retry(&status);
+        assert!(should_retry(Status::new(Code::Unavailable, "GOAWAY")));
+        assert!(should_retry(Status::new(Code::Internal, "stream terminated by RST_STREAM")));
+        assert!(!should_retry(Status::new(Code::Internal, "internal")));
+        assert!(!should_retry(Status::new(Code::InvalidArgument, "invalid")));
+        assert!(!should_retry(Status::new(Code::DeadlineExceeded, "deadline")));
+    }
+
+    fn retry_info(seconds: i64, nanos: i32) -> Vec<u8> {
+        RetryInfo {
+            retry_delay: Some(prost_types::Duration { seconds, nanos }),
+        }
+        .encode_to_vec()
+    }
+
+    fn aborted_with_details(details: Vec<u8>) -> Status {
+        Status::with_details(Code::Aborted, "aborted", details.into())
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let mut metadata = MetadataMap::new();
+        metadata.insert_bin(RETRY_INFO_KEY, MetadataValue::from_bytes(&retry_info(1, 500_000_000)));
+        let status = Status::with_metadata(Code::Aborted, "aborted", metadata);
+        assert_eq!(retry_delay(&status), Some(Duration::from_millis(1500)));
+
+        // google.rpc.Status with the other details before the RetryInfo
+        let details = google_cloud_googleapis::rpc::Status {
+            code: Code::Aborted as i32,
+            message: "aborted".to_string(),
+            details: vec![
+                prost_types::Any {
+                    type_url: "type.googleapis.com/google.rpc.DebugInfo".to_string(),
+                    value: b"debug".to_vec(),
+                },
+                prost_types::Any {
+                    type_url: RETRY_INFO_TYPE_URL.to_string(),
+                    value: retry_info(0, 20_000_000),
+                },
+            ],
+        };
+        let status = aborted_with_details(details.encode_to_vec());
+        assert_eq!(retry_delay(&status), Some(Duration::from_millis(20)));
+
+        assert_eq!(retry_delay(&Status::new(Code::Aborted, "aborted")), None);
+        assert_eq!(retry_delay(&aborted_with_details(vec![0xff])), None);
+        assert_eq!(retry_delay(&aborted_with_details(retry_info(-1, 0))), None);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_retry() {
+        let setting = TransactionRetrySetting::default();
+        let mut retry = TransactionRetry::<Status>::with_setting(setting.clone());
+        for _ in 0..setting.inner.take {
+            assert!(retry.next(Status::new(Code::Aborted, "aborted")).await.is_ok());
+        }
+        assert!(retry.next(Status::new(Code::Aborted, "aborted")).await.is_err());
+
+        let mut retry = TransactionRetry::<Status>::new();
+        let err = retry
+            .next(Status::new(Code::InvalidArgument, "invalid"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        // the delay suggested by the server is longer than the timeout
+        let setting = TransactionRetrySetting {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let mut retry = TransactionRetry::<Status>::with_setting(setting);
+        let mut metadata = MetadataMap::new();
+        metadata.insert_bin(RETRY_INFO_KEY, MetadataValue::from_bytes(&retry_info(10, 0)));
+        let status = Status::with_metadata(Code::Aborted, "aborted", metadata);
+        assert!(retry.next(status).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invoke_transaction_fn() {
+        let calls = AtomicUsize::new(0);
+        let result: Result<usize, Status> = invoke_transaction_fn(
+            None,
+            TransactionRetrySetting::default(),
+            |session: usize| {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if call < 2 {
+                        Err((Status::new(Code::Aborted, "aborted"), session + 1))
+                    } else {
+                        Ok(session)
+                    }
+                }
+            },
+            0,
+        )
+        .await;
+        // the value returned with the error is passed to the next call.
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = AtomicUsize::new(0);
+        let result: Result<(), Status> = invoke_transaction_fn(
+            None,
+            TransactionRetrySetting::default(),
+            |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err((Status::new(Code::FailedPrecondition, "application error"), ())) }
+            },
+            (),
+        )
+        .await;
+        assert_eq!(result.unwrap_err().code(), Code::FailedPrecondition);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
 }
@@ -1,17 +1,24 @@
 use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
+use prost_types::value;
 use prost_types::value::Kind;
-use prost_types::{value, Value};
 use time::format_description::well_known::Rfc3339;
 use time::macros::format_description;
 use time::{Date, OffsetDateTime};
 
-use crate::value::{CommitTimestamp, SpannerNumeric};
+use crate::statement::type_name;
+use crate::value::{
+    CommitTimestamp, Float32, Interval, IntervalError, PgNumeric, SpannerNumeric, SpannerValue, TimestampValue,
+};
 use base64::DecodeError;
-use google_cloud_googleapis::spanner::v1::struct_type::Field;
-use google_cloud_googleapis::spanner::v1::StructType;
-use std::num::ParseIntError;
+use google_cloud_googleapis::spanner::v1::{StructType, Type, TypeAnnotationCode, TypeCode};
+use std::num::{NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU8, ParseIntError};
+use std::time::SystemTime;
+
+// re-exported to implement TryFromValue without depending on the generated crates directly
+pub use google_cloud_googleapis::spanner::v1::struct_type::Field;
+pub use prost_types::Value;
 
 #[derive(Clone)]
 pub struct Row {
@@ -20,30 +27,211 @@ pub struct Row {
     values: Vec<Value>,
 }
 
+/// SpannerType is the type of the column converted from the Type in the result set metadata.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpannerType {
+    Bool,
+    Int64,
+    Float32,
+    Float64,
+    Numeric,
+    PgNumeric,
+    String,
+    Json,
+    PgJsonb,
+    Bytes,
+    Date,
+    Timestamp,
+    Interval,
+    /// Proto has the fully qualified name of the proto message.
+    Proto(String),
+    /// Enum has the fully qualified name of the proto enum.
+    Enum(String),
+    /// Array has the type of the elements.
+    Array(Box<SpannerType>),
+    /// Struct has the names and the types of the fields in order.
+    Struct(Vec<(String, SpannerType)>),
+    /// Unknown is the type code that this client does not know.
+    Unknown(i32),
+}
+
+impl From<&Type> for SpannerType {
+    fn from(tp: &Type) -> Self {
+        let annotation = TypeAnnotationCode::from_i32(tp.type_annotation);
+        match TypeCode::from_i32(tp.code) {
+            Some(TypeCode::Bool) => SpannerType::Bool,
+            Some(TypeCode::Int64) => SpannerType::Int64,
+            Some(TypeCode::Float32) => SpannerType::Float32,
+            Some(TypeCode::Float64) => SpannerType::Float64,
+            Some(TypeCode::Numeric) if annotation == Some(TypeAnnotationCode::PgNumeric) => SpannerType::PgNumeric,
+            Some(TypeCode::Numeric) => SpannerType::Numeric,
+            Some(TypeCode::String) => SpannerType::String,
+            Some(TypeCode::Json) if annotation == Some(TypeAnnotationCode::PgJsonb) => SpannerType::PgJsonb,
+            Some(TypeCode::Json) => SpannerType::Json,
+            Some(TypeCode::Bytes) => SpannerType::Bytes,
+            Some(TypeCode::Date) => SpannerType::Date,
+            Some(TypeCode::Timestamp) => SpannerType::Timestamp,
+            Some(TypeCode::Interval) => SpannerType::Interval,
+            Some(TypeCode::Proto) => SpannerType::Proto(tp.proto_type_fqn.to_string()),
+            Some(TypeCode::Enum) => SpannerType::Enum(tp.proto_type_fqn.to_string()),
+            Some(TypeCode::Array) => match &tp.array_element_type {
+                Some(element) => SpannerType::Array(Box::new(element.as_ref().into())),
+                None => SpannerType::Unknown(tp.code),
+            },
+            Some(TypeCode::Struct) => SpannerType::Struct(
+                tp.struct_type
+                    .iter()
+                    .flat_map(|s| s.fields.iter())
+                    .map(|f| (f.name.to_string(), SpannerType::from(f)))
+                    .collect(),
+            ),
+            Some(TypeCode::Unspecified) | None => SpannerType::Unknown(tp.code),
+        }
+    }
+}
+
+impl From<&Field> for SpannerType {
+    fn from(field: &Field) -> Self {
+        match &field.r#type {
+            Some(tp) => tp.into(),
+            None => SpannerType::Unknown(TypeCode::Unspecified as i32),
+        }
+    }
+}
+
+impl std::fmt::Display for SpannerType {
+    /// fmt writes the type in the SQL notation such as ARRAY<STRUCT<Id INT64>>.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpannerType::Bool => f.write_str("BOOL"),
+            SpannerType::Int64 => f.write_str("INT64"),
+            SpannerType::Float32 => f.write_str("FLOAT32"),
+            SpannerType::Float64 => f.write_str("FLOAT64"),
+            SpannerType::Numeric => f.write_str("NUMERIC"),
+            SpannerType::PgNumeric => f.write_str("NUMERIC(PG_NUMERIC)"),
+            SpannerType::String => f.write_str("STRING"),
+            SpannerType::Json => f.write_str("JSON"),
+            SpannerType::PgJsonb => f.write_str("JSON(PG_JSONB)"),
+            SpannerType::Bytes => f.write_str("BYTES"),
+            SpannerType::Date => f.write_str("DATE"),
+            SpannerType::Timestamp => f.write_str("TIMESTAMP"),
+            SpannerType::Interval => f.write_str("INTERVAL"),
+            SpannerType::Proto(name) => write!(f, "PROTO<{name}>"),
+            SpannerType::Enum(name) => write!(f, "ENUM<{name}>"),
+            SpannerType::Array(element) => write!(f, "ARRAY<{element}>"),
+            SpannerType::Struct(fields) => {
+                f.write_str("STRUCT<")?;
+                for (i, (name, tp)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    if name.is_empty() {
+                        write!(f, "{tp}")?;
+                    } else {
+                        write!(f, "{name} {tp}")?;
+                    }
+                }
+                f.write_str(">")
+            }
+            SpannerType::Unknown(code) => write!(f, "UNKNOWN({code})"),
+        }
+    }
+}
+
+/// ColumnMetadata is the name, the type and the position of the column in the result set.
+/// Spanner does not return whether the column is nullable, so any column can be NULL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnMetadata {
+    pub name: String,
+    pub type_: SpannerType,
+    pub index: usize,
+}
+
+/// column_index returns the index of the columns by name.
+/// The first column wins if the name is duplicated, use the column index to read the others.
+pub(crate) fn column_index(fields: &[Field]) -> HashMap<String, usize> {
+    let mut index = HashMap::with_capacity(fields.len());
+    for (i, f) in fields.iter().enumerate() {
+        index.entry(f.name.to_string()).or_insert(i);
+    }
+    index
+}
+
+pub(crate) fn column_metadata(fields: &[Field]) -> Vec<ColumnMetadata> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, f)| ColumnMetadata {
+            name: f.name.to_string(),
+            type_: f.into(),
+            index,
+        })
+        .collect()
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Illegal Kind: field={0}, kind={1}")]
     KindMismatch(String, String),
+    #[error("Type mismatch: column={1}, index={0}, expected={2}, actual={3}, kind={4}")]
+    ColumnTypeMismatch(usize, String, &'static str, String, String),
+    #[error("Unexpected NULL: column={0}, index={1}, type={2}, use Option to read the nullable column")]
+    UnexpectedNull(String, usize, &'static str),
     #[error("No kind found: field={0}")]
     NoKind(String),
+    #[error("No type found: field={0}")]
+    NoType(String),
     #[error("Parse field: field={0}")]
     IntParseError(String, #[source] ParseIntError),
     #[error("Failed to parse as Date|DateTime {0}")]
     DateParseError(String, #[source] time::error::Parse),
-    #[error("Failed to parse as ByteArray {0}")]
+    #[cfg(feature = "chrono")]
+    #[error("Failed to parse as chrono::DateTime {0}")]
+    ChronoParseError(String, #[source] chrono::ParseError),
+    #[cfg(feature = "serde_json")]
+    #[error("Failed to parse as JSON {0}")]
+    JsonParseError(String, #[source] serde_json::Error),
+    #[cfg(feature = "uuid")]
+    #[error("Failed to parse as UUID {0}")]
+    UuidParseError(String, #[source] uuid::Error),
+    #[cfg(feature = "bigdecimal")]
+    #[error("Failed to parse as BigDecimal {0}")]
+    BigDecimalParseError(String, #[source] bigdecimal::ParseBigDecimalError),
+    #[cfg(feature = "rust_decimal")]
+    #[error("Failed to parse as Decimal {0}")]
+    DecimalParseError(String, #[source] rust_decimal::Error),
+    #[error("Failed to parse as Interval {0}")]
+    IntervalParseError(String, #[source] IntervalError),
+    #[error("Failed to decode as Proto {0}")]
+    ProtoDecodeError(String, #[source] prost::DecodeError),
+    #[error("Proto type mismatch: column={0}, expected={1}, actual={2}")]
+    ProtoTypeMismatch(String, String, String),
+    #[error("Failed to parse as ByteArray {0}: {1}")]
     ByteParseError(String, #[source] DecodeError),
+    #[error("Invalid byte length: field={0}, expected={1}, actual={2}")]
+    InvalidByteLength(String, usize, usize),
     #[error("Failed to parse as Struct name={0}, {1}")]
     StructParseError(String, &'static str),
     #[error("Failed to parse as Custom Type {0}")]
     CustomParseError(String),
+    #[error("Failed to read field: field={0}, column={1}, {2}")]
+    FieldError(String, String, #[source] Box<Error>),
     #[error("No column found: name={0}")]
     NoColumnFound(String),
+    #[error("Ambiguous column name: name={0}")]
+    AmbiguousColumn(String),
     #[error("invalid column index: index={0}, length={1}")]
     InvalidColumnIndex(usize, usize),
     #[error("invalid struct column index: index={0}")]
     InvalidStructColumnIndex(usize),
     #[error("No column found in struct: name={0}")]
     NoColumnFoundInStruct(String),
+    #[error("Value out of range: field={0}, value={1}, type={2}")]
+    OutOfRange(String, String, &'static str),
+    #[error("NULL array element: field={0}, index={1}")]
+    NullArrayElement(String, usize),
+    #[error("Unknown enum value: field={0}, value={1}")]
+    UnknownEnumValue(String, String),
 }
 
 impl Row {
@@ -58,12 +246,145 @@ impl Row {
         column(&self.values, &self.fields, column_index)
     }
 
+    /// columns returns the metadata of the columns in order.
+    pub fn columns(&self) -> Vec<ColumnMetadata> {
+        column_metadata(&self.fields)
+    }
+
+    /// column_count returns the number of the columns.
+    pub fn column_count(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// column_name returns the name of the column at the index, or None if the index is out of range.
+    pub fn column_name(&self, column_index: usize) -> Option<&str> {
+        self.fields.get(column_index).map(|f| f.name.as_str())
+    }
+
+    /// column_by_name returns the value of the first column with the name.
     pub fn column_by_name<T>(&self, column_name: &str) -> Result<T, Error>
     where
         T: TryFromValue,
     {
         self.column(index(&self.index, column_name)?)
     }
+
+    /// column_indices returns the indices of all the columns with the name, such as `SELECT a.Id, b.Id`.
+    /// Use column to read each of them.
+    pub fn column_indices(&self, column_name: &str) -> Vec<usize> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.name == column_name)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// column_by_name_ci is the same as column_by_name, except that the name is
+    /// compared case-insensitively if no column matches exactly.
+    pub fn column_by_name_ci<T>(&self, column_name: &str) -> Result<T, Error>
+    where
+        T: TryFromValue,
+    {
+        self.column(index_ci(&self.index, column_name)?)
+    }
+
+    /// column_numeric_lossy reads the NUMERIC column as rust_decimal::Decimal, rounding the fractional digits
+    /// that exceed the precision of rust_decimal. It is still an error if the integer part is too large.
+    #[cfg(feature = "rust_decimal")]
+    pub fn column_numeric_lossy(&self, column_name: &str) -> Result<rust_decimal::Decimal, Error> {
+        let column_index = index(&self.index, column_name)?;
+        let value = &self.values[column_index];
+        let field = &self.fields[column_index];
+        if let Some(Kind::NullValue(_)) = value.kind {
+            return Err(Error::UnexpectedNull(
+                field.name.to_string(),
+                column_index,
+                std::any::type_name::<rust_decimal::Decimal>(),
+            ));
+        }
+        parse_decimal(value, field, std::str::FromStr::from_str)
+    }
+
+    /// value returns the value of the column decoded with the column type.
+    pub fn value(&self, column_index: usize) -> Result<SpannerValue, Error> {
+        self.column(column_index)
+    }
+
+    /// to_map returns the values of all the columns by name.
+    /// The first column wins if the name is duplicated, use value to read the others.
+    pub fn to_map(&self) -> Result<BTreeMap<String, SpannerValue>, Error> {
+        let mut map = BTreeMap::new();
+        for (i, field) in self.fields.iter().enumerate() {
+            if !map.contains_key(&field.name) {
+                map.insert(field.name.to_string(), self.value(i)?);
+            }
+        }
+        Ok(map)
+    }
+
+    /// proto_column decodes the PROTO column into the message T.
+    /// It is an error if the proto type of the column is not T::FULL_NAME.
+    pub fn proto_column<T>(&self, column_name: &str) -> Result<T, Error>
+    where
+        T: prost::Message + Default + crate::value::ProtoName,
+    {
+        let column_index = index(&self.index, column_name)?;
+        let field = &self.fields[column_index];
+        check_proto_type(field, field.r#type.as_ref(), T::FULL_NAME)?;
+        let message: crate::value::Proto<T> = self.column(column_index)?;
+        Ok(message.0)
+    }
+
+    /// proto_array_column decodes the ARRAY<PROTO> column into the messages T.
+    /// It is an error if the proto type of the elements is not T::FULL_NAME.
+    pub fn proto_array_column<T>(&self, column_name: &str) -> Result<Vec<T>, Error>
+    where
+        T: prost::Message + Default + crate::value::ProtoName,
+    {
+        let column_index = index(&self.index, column_name)?;
+        let field = &self.fields[column_index];
+        let element_type = field.r#type.as_ref().and_then(|tp| tp.array_element_type.as_deref());
+        check_proto_type(field, element_type, T::FULL_NAME)?;
+        let messages: Vec<crate::value::Proto<T>> = self.column(column_index)?;
+        Ok(messages.into_iter().map(|v| v.0).collect())
+    }
+
+    /// json_column deserializes the JSON column into T.
+    /// NULL is deserialized as the JSON null, so that Option<T> is None.
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    pub fn json_column<T>(&self, column_name: &str) -> Result<T, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let column_index = index(&self.index, column_name)?;
+        let field = &self.fields[column_index];
+        let json = match as_ref(&self.values[column_index], field)? {
+            Kind::StringValue(s) => s.as_str(),
+            Kind::NullValue(_) => "null",
+            v => return kind_to_error(v, field),
+        };
+        serde_json::from_str(json).map_err(|e| Error::JsonParseError(field.name.to_string(), e))
+    }
+
+    /// deserialize deserializes the row with serde. See the de module for how the columns are mapped.
+    #[cfg(feature = "serde")]
+    pub fn deserialize<'a, T>(&'a self) -> Result<T, crate::de::Error>
+    where
+        T: serde::Deserialize<'a>,
+    {
+        T::deserialize(crate::de::RowDeserializer::new(self))
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn values(&self) -> &[Value] {
+        &self.values
+    }
 }
 
 //don't use TryFrom trait to avoid the conflict
@@ -86,10 +407,7 @@ pub struct Struct<'a> {
 impl<'a> Struct<'a> {
     pub fn new(metadata: &'a StructType, item: &'a Value, field: &'a Field) -> Result<Struct<'a>, Error> {
         let kind = as_ref(item, field)?;
-        let mut index = HashMap::new();
-        for (i, f) in metadata.fields.iter().enumerate() {
-            index.insert(f.name.to_string(), i);
-        }
+        let index = column_index(&metadata.fields);
         match kind {
             Kind::ListValue(s) => Ok(Struct {
                 metadata,
@@ -118,7 +436,7 @@ impl<'a> Struct<'a> {
                     let field = &self.metadata.fields[column_index];
                     let name = &field.name;
                     match values.get(name) {
-                        Some(value) => T::try_from(value, field),
+                        Some(value) => column_value(value, field, column_index),
                         None => Err(Error::NoColumnFoundInStruct(name.to_string())),
                     }
                 }
@@ -133,6 +451,15 @@ impl<'a> Struct<'a> {
     {
         self.column(index(&self.index, column_name)?)
     }
+
+    /// column_by_name_ci is the same as column_by_name, except that the name is
+    /// compared case-insensitively if no column matches exactly.
+    pub fn column_by_name_ci<T>(&self, column_name: &str) -> Result<T, Error>
+    where
+        T: TryFromValue,
+    {
+        self.column(index_ci(&self.index, column_name)?)
+    }
 }
 
 impl TryFromValue for i64 {
@@ -144,15 +471,158 @@ impl TryFromValue for i64 {
     }
 }
 
+/// The narrower integers are read from INT64 with the range check.
+/// The value that does not fit is an OutOfRange error instead of being truncated.
+fn try_from_int64<T: TryFrom<i64>>(item: &Value, field: &Field) -> Result<T, Error> {
+    let v = <i64 as TryFromValue>::try_from(item, field)?;
+    T::try_from(v).map_err(|_| out_of_range::<T>(field, v))
+}
+
+impl TryFromValue for i32 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        try_from_int64(item, field)
+    }
+}
+
+impl TryFromValue for i16 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        try_from_int64(item, field)
+    }
+}
+
+impl TryFromValue for i8 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        try_from_int64(item, field)
+    }
+}
+
+impl TryFromValue for isize {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        try_from_int64(item, field)
+    }
+}
+
+/// The negative value is an OutOfRange error.
+impl TryFromValue for u64 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        try_from_int64(item, field)
+    }
+}
+
+impl TryFromValue for u32 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        try_from_int64(item, field)
+    }
+}
+
+impl TryFromValue for u16 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        try_from_int64(item, field)
+    }
+}
+
+// u8 is not implemented, so that Vec<u8> is read from BYTES.
+
+impl TryFromValue for usize {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        try_from_int64(item, field)
+    }
+}
+
+impl TryFromValue for NonZeroI64 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let v = <i64 as TryFromValue>::try_from(item, field)?;
+        NonZeroI64::new(v).ok_or_else(|| out_of_range::<Self>(field, v))
+    }
+}
+
+impl TryFromValue for NonZeroI32 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let v = <i64 as TryFromValue>::try_from(item, field)?;
+        <i32 as TryFrom<i64>>::try_from(v)
+            .ok()
+            .and_then(NonZeroI32::new)
+            .ok_or_else(|| out_of_range::<Self>(field, v))
+    }
+}
+
+impl TryFromValue for NonZeroI16 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let v = <i64 as TryFromValue>::try_from(item, field)?;
+        <i16 as TryFrom<i64>>::try_from(v)
+            .ok()
+            .and_then(NonZeroI16::new)
+            .ok_or_else(|| out_of_range::<Self>(field, v))
+    }
+}
+
+impl TryFromValue for NonZeroI8 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let v = <i64 as TryFromValue>::try_from(item, field)?;
+        <i8 as TryFrom<i64>>::try_from(v)
+            .ok()
+            .and_then(NonZeroI8::new)
+            .ok_or_else(|| out_of_range::<Self>(field, v))
+    }
+}
+
+impl TryFromValue for NonZeroU32 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let v = <i64 as TryFromValue>::try_from(item, field)?;
+        <u32 as TryFrom<i64>>::try_from(v)
+            .ok()
+            .and_then(NonZeroU32::new)
+            .ok_or_else(|| out_of_range::<Self>(field, v))
+    }
+}
+
+impl TryFromValue for NonZeroU16 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let v = <i64 as TryFromValue>::try_from(item, field)?;
+        <u16 as TryFrom<i64>>::try_from(v)
+            .ok()
+            .and_then(NonZeroU16::new)
+            .ok_or_else(|| out_of_range::<Self>(field, v))
+    }
+}
+
+impl TryFromValue for NonZeroU8 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let v = <i64 as TryFromValue>::try_from(item, field)?;
+        u8::try_from(v)
+            .ok()
+            .and_then(NonZeroU8::new)
+            .ok_or_else(|| out_of_range::<Self>(field, v))
+    }
+}
+
 impl TryFromValue for f64 {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
             Kind::NumberValue(s) => Ok(*s),
+            Kind::StringValue(s) => match s.as_str() {
+                "NaN" => Ok(f64::NAN),
+                "Infinity" => Ok(f64::INFINITY),
+                "-Infinity" => Ok(f64::NEG_INFINITY),
+                _ => Err(Error::KindMismatch(field.name.to_string(), "StringValue".to_string())),
+            },
             v => kind_to_error(v, field),
         }
     }
 }
 
+impl TryFromValue for f32 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        Ok(<f64 as TryFromValue>::try_from(item, field)? as f32)
+    }
+}
+
+impl TryFromValue for Float32 {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        Ok(Float32(TryFromValue::try_from(item, field)?))
+    }
+}
+
 impl TryFromValue for bool {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
@@ -173,6 +643,43 @@ impl TryFromValue for OffsetDateTime {
     }
 }
 
+#[cfg(feature = "chrono")]
+impl TryFromValue for chrono::DateTime<chrono::Utc> {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => Ok(chrono::DateTime::parse_from_rfc3339(s)
+                .map_err(|e| Error::ChronoParseError(field.name.to_string(), e))?
+                .with_timezone(&chrono::Utc)),
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
+/// The timestamp is read in UTC.
+#[cfg(feature = "chrono")]
+impl TryFromValue for chrono::NaiveDateTime {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        Ok(<chrono::DateTime<chrono::Utc> as TryFromValue>::try_from(item, field)?.naive_utc())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFromValue for chrono::NaiveDate {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .map_err(|e| Error::ChronoParseError(field.name.to_string(), e)),
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
+impl TryFromValue for SystemTime {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        Ok(<OffsetDateTime as TryFromValue>::try_from(item, field)?.into())
+    }
+}
+
 impl TryFromValue for CommitTimestamp {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         Ok(CommitTimestamp {
@@ -181,6 +688,12 @@ impl TryFromValue for CommitTimestamp {
     }
 }
 
+impl TryFromValue for TimestampValue {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        Ok(TimestampValue::At(TryFromValue::try_from(item, field)?))
+    }
+}
+
 impl TryFromValue for Date {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
@@ -191,15 +704,41 @@ impl TryFromValue for Date {
     }
 }
 
+/// The invalid base64 is an error with the offset of the first invalid byte reported by the decoder.
 impl TryFromValue for Vec<u8> {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
-            Kind::StringValue(s) => base64::decode(s).map_err(|e| Error::ByteParseError(field.name.to_string(), e)),
+            Kind::StringValue(s) => decode_base64(s).map_err(|e| Error::ByteParseError(field.name.to_string(), e)),
             v => kind_to_error(v, field),
         }
     }
 }
 
+impl TryFromValue for bytes::Bytes {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        Ok(<Vec<u8> as TryFromValue>::try_from(item, field)?.into())
+    }
+}
+
+/// decode_base64 decodes into the buffer sized from the length of the base64 string,
+/// so that the large value is decoded without reallocation.
+pub(crate) fn decode_base64(s: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut buffer = vec![0; s.len().div_ceil(4) * 3];
+    let length = base64::decode_config_slice(s, base64::STANDARD, &mut buffer)?;
+    buffer.truncate(length);
+    Ok(buffer)
+}
+
+impl<const N: usize> TryFromValue for [u8; N] {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let bytes = <Vec<u8> as TryFromValue>::try_from(item, field)?;
+        let length = bytes.len();
+        bytes
+            .try_into()
+            .map_err(|_| Error::InvalidByteLength(field.name.to_string(), N, length))
+    }
+}
+
 impl TryFromValue for SpannerNumeric {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
@@ -209,99 +748,386 @@ impl TryFromValue for SpannerNumeric {
     }
 }
 
-impl TryFromValue for String {
+/// The NUMERIC value that exceeds the 96-bit mantissa of rust_decimal is an OutOfRange error.
+/// Use Row::column_numeric_lossy to round the fractional digits instead.
+#[cfg(feature = "rust_decimal")]
+impl TryFromValue for rust_decimal::Decimal {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        parse_decimal(item, field, rust_decimal::Decimal::from_str_exact)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl TryFromValue for crate::value::Numeric {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let v: rust_decimal::Decimal = TryFromValue::try_from(item, field)?;
+        crate::value::Numeric::new(v).map_err(|_| out_of_range::<Self>(field, v))
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+fn parse_decimal(
+    item: &Value,
+    field: &Field,
+    parse: fn(&str) -> Result<rust_decimal::Decimal, rust_decimal::Error>,
+) -> Result<rust_decimal::Decimal, Error> {
+    match as_ref(item, field)? {
+        Kind::StringValue(s) => parse(s).map_err(|e| {
+            // a valid NUMERIC with too many digits for Decimal is reported as OutOfRange, not as the parse error
+            let digits = s.strip_prefix('-').unwrap_or(s);
+            let numeric = !digits.is_empty()
+                && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+                && digits.matches('.').count() <= 1;
+            if numeric {
+                out_of_range::<rust_decimal::Decimal>(field, s)
+            } else {
+                Error::DecimalParseError(field.name.to_string(), e)
+            }
+        }),
+        v => kind_to_error(v, field),
+    }
+}
+
+impl TryFromValue for Interval {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
-            Kind::StringValue(s) => Ok(s.to_string()),
+            Kind::StringValue(s) => s
+                .parse()
+                .map_err(|e| Error::IntervalParseError(field.name.to_string(), e)),
             v => kind_to_error(v, field),
         }
     }
 }
 
-impl<T> TryFromValue for T
-where
-    T: TryFromStruct,
-{
+impl TryFromValue for PgNumeric {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
-        let maybe_array = match field.r#type.as_ref() {
-            None => return Err(Error::StructParseError(field.name.to_string(), "field type must not be none")),
-            Some(tp) => tp.array_element_type.as_ref(),
-        };
-        let maybe_struct_type = match maybe_array {
-            None => return Err(Error::StructParseError(field.name.to_string(), "array must not be none")),
-            Some(tp) => tp.struct_type.as_ref(),
-        };
-        let struct_type = match maybe_struct_type {
-            None => {
-                return Err(Error::StructParseError(
-                    field.name.to_string(),
-                    "struct type in array must not be none ",
-                ))
-            }
-            Some(struct_type) => struct_type,
-        };
-
-        T::try_from_struct(Struct::new(struct_type, item, field)?)
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => Ok(PgNumeric::new(s.to_string())),
+            v => kind_to_error(v, field),
+        }
     }
 }
 
-impl<T> TryFromValue for Option<T>
-where
-    T: TryFromValue,
-{
+#[cfg(feature = "bigdecimal")]
+impl TryFromValue for bigdecimal::BigDecimal {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
-            Kind::NullValue(_i) => Ok(None),
-            _ => Ok(Some(T::try_from(item, field)?)),
+            Kind::StringValue(s) => s
+                .parse()
+                .map_err(|e| Error::BigDecimalParseError(field.name.to_string(), e)),
+            v => kind_to_error(v, field),
         }
     }
 }
 
-impl<T> TryFromValue for Vec<T>
-where
-    T: TryFromValue,
-{
+impl TryFromValue for String {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
-            Kind::ListValue(s) => s.values.iter().map(|v| T::try_from(v, field)).collect(),
+            Kind::StringValue(s) => Ok(s.to_string()),
             v => kind_to_error(v, field),
         }
     }
 }
 
-fn index(index: &HashMap<String, usize>, column_name: &str) -> Result<usize, Error> {
-    match index.get(column_name) {
-        Some(column_index) => Ok(*column_index),
-        None => Err(Error::NoColumnFound(column_name.to_string())),
+#[cfg(feature = "serde_json")]
+impl TryFromValue for serde_json::Value {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => {
+                serde_json::from_str(s).map_err(|e| Error::JsonParseError(field.name.to_string(), e))
+            }
+            v => kind_to_error(v, field),
+        }
     }
 }
 
-fn column<T>(values: &[Value], fields: &[Field], column_index: usize) -> Result<T, Error>
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+impl<T> TryFromValue for crate::value::Json<T>
 where
-    T: TryFromValue,
+    T: serde::de::DeserializeOwned,
 {
-    if values.len() <= column_index {
-        return Err(Error::InvalidColumnIndex(column_index, values.len()));
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => serde_json::from_str(s)
+                .map(crate::value::Json)
+                .map_err(|e| Error::JsonParseError(field.name.to_string(), e)),
+            v => kind_to_error(v, field),
+        }
     }
-    let value = &values[column_index];
-    T::try_from(value, &fields[column_index])
 }
 
-pub fn as_ref<'a>(item: &'a Value, field: &'a Field) -> Result<&'a Kind, Error> {
-    return match item.kind.as_ref() {
-        Some(v) => Ok(v),
-        None => Err(Error::NoKind(field.name.to_string())),
-    };
+#[cfg(feature = "serde_json")]
+impl TryFromValue for crate::value::PgJsonb {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        Ok(crate::value::PgJsonb(TryFromValue::try_from(item, field)?))
+    }
 }
 
-pub fn kind_to_error<'a, T>(v: &'a value::Kind, field: &'a Field) -> Result<T, Error> {
-    let actual = match v {
+#[cfg(feature = "uuid")]
+impl TryFromValue for uuid::Uuid {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => {
+                uuid::Uuid::parse_str(s).map_err(|e| Error::UuidParseError(field.name.to_string(), e))
+            }
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl TryFromValue for crate::value::UuidBytes {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let bytes = <Vec<u8> as TryFromValue>::try_from(item, field)?;
+        uuid::Uuid::from_slice(&bytes)
+            .map(crate::value::UuidBytes)
+            .map_err(|e| Error::UuidParseError(field.name.to_string(), e))
+    }
+}
+
+impl<T> TryFromValue for crate::value::Proto<T>
+where
+    T: prost::Message + Default,
+{
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let bytes = <Vec<u8> as TryFromValue>::try_from(item, field)?;
+        T::decode(bytes.as_slice())
+            .map(crate::value::Proto)
+            .map_err(|e| Error::ProtoDecodeError(field.name.to_string(), e))
+    }
+}
+
+impl<T> TryFromValue for crate::value::EnumValue<T>
+where
+    T: TryFrom<i32>,
+{
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let v = <i64 as TryFromValue>::try_from(item, field)?;
+        <i32 as TryFrom<i64>>::try_from(v)
+            .ok()
+            .and_then(|v| T::try_from(v).ok())
+            .map(crate::value::EnumValue)
+            .ok_or_else(|| Error::UnknownEnumValue(field.name.to_string(), v.to_string()))
+    }
+}
+
+/// SpannerValue is decoded with the type in the result set metadata, so that INT64 is Int64, not String.
+/// INTERVAL is read as String, PROTO as Bytes and ENUM as Int64.
+/// The PostgreSQL NUMERIC and JSONB are read as Numeric and Json.
+impl TryFromValue for SpannerValue {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let tp = field
+            .r#type
+            .as_ref()
+            .ok_or_else(|| Error::NoType(field.name.to_string()))?;
+        let kind = as_ref(item, field)?;
+        if let Kind::NullValue(_) = kind {
+            return Ok(SpannerValue::Null(tp.clone()));
+        }
+        let value = match TypeCode::from_i32(tp.code) {
+            Some(TypeCode::Bool) => SpannerValue::Bool(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::Int64 | TypeCode::Enum) => SpannerValue::Int64(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::Float64) => SpannerValue::Float64(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::Float32) => SpannerValue::Float32(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::String | TypeCode::Interval) => SpannerValue::String(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::Json) => SpannerValue::Json(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::Bytes | TypeCode::Proto) => SpannerValue::Bytes(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::Timestamp) => SpannerValue::Timestamp(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::Date) => SpannerValue::Date(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::Numeric) => SpannerValue::Numeric(TryFromValue::try_from(item, field)?),
+            Some(TypeCode::Array) => {
+                let element_type = tp
+                    .array_element_type
+                    .as_deref()
+                    .ok_or_else(|| Error::NoType(field.name.to_string()))?;
+                let element_field = Field {
+                    name: field.name.to_string(),
+                    r#type: Some(element_type.clone()),
+                };
+                let values = match kind {
+                    Kind::ListValue(s) => s
+                        .values
+                        .iter()
+                        .map(|v| TryFromValue::try_from(v, &element_field))
+                        .collect::<Result<_, _>>()?,
+                    v => return kind_to_error(v, field),
+                };
+                SpannerValue::Array(element_type.clone(), values)
+            }
+            Some(TypeCode::Struct) => {
+                let struct_type = tp
+                    .struct_type
+                    .as_ref()
+                    .ok_or_else(|| Error::NoType(field.name.to_string()))?;
+                let s = Struct::new(struct_type, item, field)?;
+                let fields = struct_type
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| Ok((f.name.to_string(), s.column(i)?)))
+                    .collect::<Result<_, Error>>()?;
+                SpannerValue::Struct(fields)
+            }
+            Some(TypeCode::Unspecified) | None => return Err(Error::NoType(field.name.to_string())),
+        };
+        Ok(value)
+    }
+}
+
+/// The STRUCT column and the elements of the ARRAY<STRUCT> column are read with the StructType
+/// in the result metadata, so that the fields can be read by name.
+/// Use `Vec<Option<T>>` to read the arrays with NULL elements.
+impl<T> TryFromValue for T
+where
+    T: TryFromStruct,
+{
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let tp = match field.r#type.as_ref() {
+            None => return Err(Error::StructParseError(field.name.to_string(), "field type must not be none")),
+            Some(tp) => tp,
+        };
+        // STRUCT column, or the element of the ARRAY<STRUCT> column
+        let struct_type = match tp.struct_type.as_ref() {
+            Some(struct_type) => struct_type,
+            None => {
+                let maybe_struct_type = match tp.array_element_type.as_ref() {
+                    None => return Err(Error::StructParseError(field.name.to_string(), "array must not be none")),
+                    Some(tp) => tp.struct_type.as_ref(),
+                };
+                match maybe_struct_type {
+                    None => {
+                        return Err(Error::StructParseError(
+                            field.name.to_string(),
+                            "struct type in array must not be none ",
+                        ))
+                    }
+                    Some(struct_type) => struct_type,
+                }
+            }
+        };
+
+        T::try_from_struct(Struct::new(struct_type, item, field)?)
+    }
+}
+
+impl<T> TryFromValue for Option<T>
+where
+    T: TryFromValue,
+{
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::NullValue(_i) => Ok(None),
+            _ => Ok(Some(T::try_from(item, field)?)),
+        }
+    }
+}
+
+impl<T> TryFromValue for Vec<T>
+where
+    T: TryFromValue,
+{
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::ListValue(s) => s
+                .values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    T::try_from(v, field).map_err(|e| match v.kind {
+                        // Use Vec<Option<T>> to read the arrays with NULL elements.
+                        Some(Kind::NullValue(_)) => Error::NullArrayElement(field.name.to_string(), i),
+                        _ => e,
+                    })
+                })
+                .collect(),
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
+fn index(index: &HashMap<String, usize>, column_name: &str) -> Result<usize, Error> {
+    match index.get(column_name) {
+        Some(column_index) => Ok(*column_index),
+        None => Err(Error::NoColumnFound(column_name.to_string())),
+    }
+}
+
+fn column<T>(values: &[Value], fields: &[Field], column_index: usize) -> Result<T, Error>
+where
+    T: TryFromValue,
+{
+    if values.len() <= column_index {
+        return Err(Error::InvalidColumnIndex(column_index, values.len()));
+    }
+    column_value(&values[column_index], &fields[column_index], column_index)
+}
+
+fn column_value<T>(value: &Value, field: &Field, column_index: usize) -> Result<T, Error>
+where
+    T: TryFromValue,
+{
+    T::try_from(value, field).map_err(|e| match e {
+        // the error of the nested value such as the struct field is returned as is
+        Error::KindMismatch(..) if matches!(value.kind, Some(Kind::NullValue(_))) => {
+            Error::UnexpectedNull(field.name.to_string(), column_index, std::any::type_name::<T>())
+        }
+        Error::KindMismatch(_, kind) if kind != "NullValue" => {
+            let actual = match &field.r#type {
+                Some(tp) => type_name(tp),
+                None => "UNKNOWN".to_string(),
+            };
+            Error::ColumnTypeMismatch(column_index, field.name.to_string(), std::any::type_name::<T>(), actual, kind)
+        }
+        e => e,
+    })
+}
+
+fn check_proto_type(field: &Field, tp: Option<&Type>, expected: &str) -> Result<(), Error> {
+    match tp {
+        Some(tp) if tp.code == TypeCode::Proto as i32 && tp.proto_type_fqn == expected => Ok(()),
+        _ => Err(Error::ProtoTypeMismatch(
+            field.name.to_string(),
+            expected.to_string(),
+            field
+                .r#type
+                .as_ref()
+                .map(type_name)
+                .unwrap_or_else(|| "UNKNOWN".to_string()),
+        )),
+    }
+}
+
+fn index_ci(index: &HashMap<String, usize>, column_name: &str) -> Result<usize, Error> {
+    if let Some(column_index) = index.get(column_name) {
+        return Ok(*column_index);
+    }
+    let mut found = index.iter().filter(|(name, _)| name.eq_ignore_ascii_case(column_name));
+    match (found.next(), found.next()) {
+        (Some((_, column_index)), None) => Ok(*column_index),
+        (Some(_), Some(_)) => Err(Error::AmbiguousColumn(column_name.to_string())),
+        _ => Err(Error::NoColumnFound(column_name.to_string())),
+    }
+}
+
+fn out_of_range<T>(field: &Field, value: impl ToString) -> Error {
+    Error::OutOfRange(field.name.to_string(), value.to_string(), std::any::type_name::<T>())
+}
+
+pub fn as_ref<'a>(item: &'a Value, field: &'a Field) -> Result<&'a Kind, Error> {
+    return match item.kind.as_ref() {
+        Some(v) => Ok(v),
+        None => Err(Error::NoKind(field.name.to_string())),
+    };
+}
+
+pub fn kind_to_error<'a, T>(v: &'a value::Kind, field: &'a Field) -> Result<T, Error> {
+    let actual = match v {
         Kind::StringValue(_s) => "StringValue".to_string(),
         Kind::BoolValue(_s) => "BoolValue".to_string(),
         Kind::NumberValue(_s) => "NumberValue".to_string(),
         Kind::ListValue(_s) => "ListValue".to_string(),
         Kind::StructValue(_s) => "StructValue".to_string(),
-        _ => "unknown".to_string(),
+        Kind::NullValue(_s) => "NullValue".to_string(),
     };
     Err(Error::KindMismatch(field.name.to_string(), actual))
 }
@@ -310,8 +1136,9 @@ pub fn kind_to_error<'a, T>(v: &'a value::Kind, field: &'a Field) -> Result<T, E
 mod tests {
     use crate::row::{Error, Row, Struct as RowStruct, TryFromStruct};
     use crate::statement::{Kinds, ToKind, ToStruct, Types};
-    use crate::value::CommitTimestamp;
+    use crate::value::{CommitTimestamp, Float32, Interval, PgNumeric};
     use google_cloud_googleapis::spanner::v1::struct_type::Field;
+    use prost_types::value::Kind;
     use prost_types::Value;
     use std::collections::HashMap;
     use std::sync::Arc;
@@ -417,4 +1244,1152 @@ mod tests {
         assert_eq!(struct_data[1].struct_field_time, now);
         assert_eq!(struct_data[1].commit_timestamp.timestamp, now);
     }
+
+    #[test]
+    fn test_columns() {
+        use crate::row::{ColumnMetadata, SpannerType};
+        use crate::value::SpannerNumeric;
+        use google_cloud_googleapis::spanner::v1::{Type, TypeAnnotationCode, TypeCode};
+
+        let columns: Vec<(&str, Type)> = vec![
+            ("Id", i64::get_type()),
+            ("Amount", SpannerNumeric::get_type()),
+            ("PgAmount", PgNumeric::get_type()),
+            (
+                "Doc",
+                Type {
+                    code: TypeCode::Json.into(),
+                    type_annotation: TypeAnnotationCode::PgJsonb.into(),
+                    ..Default::default()
+                },
+            ),
+            ("Tags", Vec::<String>::get_type()),
+            ("Items", Vec::<TestStruct>::get_type()),
+        ];
+        let fields: Vec<Field> = columns
+            .into_iter()
+            .map(|(name, tp)| Field {
+                name: name.to_string(),
+                r#type: Some(tp),
+            })
+            .collect();
+        let row = Row::new(Arc::new(HashMap::new()), Arc::new(fields), vec![]);
+
+        assert_eq!(row.column_count(), 6);
+        assert_eq!(row.column_name(0), Some("Id"));
+        assert_eq!(row.column_name(5), Some("Items"));
+        assert_eq!(row.column_name(6), None);
+        let columns = row.columns();
+        assert_eq!(
+            columns[0],
+            ColumnMetadata {
+                name: "Id".to_string(),
+                type_: SpannerType::Int64,
+                index: 0
+            }
+        );
+        assert_eq!(columns[1].type_, SpannerType::Numeric);
+        assert_eq!(columns[2].type_, SpannerType::PgNumeric);
+        assert_eq!(columns[3].type_, SpannerType::PgJsonb);
+        assert_eq!(columns[4].type_, SpannerType::Array(Box::new(SpannerType::String)));
+        assert_eq!(
+            columns[5].type_,
+            SpannerType::Array(Box::new(SpannerType::Struct(vec![
+                ("struct_field".to_string(), SpannerType::String),
+                ("struct_field_time".to_string(), SpannerType::Timestamp),
+                ("commit_timestamp".to_string(), SpannerType::Timestamp),
+            ])))
+        );
+        assert_eq!(
+            columns.iter().map(|c| c.type_.to_string()).collect::<Vec<_>>(),
+            vec![
+                "INT64",
+                "NUMERIC",
+                "NUMERIC(PG_NUMERIC)",
+                "JSON(PG_JSONB)",
+                "ARRAY<STRING>",
+                "ARRAY<STRUCT<struct_field STRING, struct_field_time TIMESTAMP, commit_timestamp TIMESTAMP>>"
+            ]
+        );
+        let unknown = Field {
+            name: "Unknown".to_string(),
+            r#type: None,
+        };
+        assert_eq!(SpannerType::from(&unknown), SpannerType::Unknown(0));
+    }
+
+    #[test]
+    fn test_to_map() {
+        use crate::value::{SpannerNumeric, SpannerValue};
+        use time::macros::{date, datetime};
+
+        let now = datetime!(2022-09-01 12:34:56.123456789 UTC);
+        let test_struct = TestStruct {
+            struct_field: "aaa".to_string(),
+            struct_field_time: now,
+            commit_timestamp: CommitTimestamp { timestamp: now },
+        };
+        let columns: Vec<(&str, Kind, google_cloud_googleapis::spanner::v1::Type)> = vec![
+            ("Int64", 123_i64.to_kind(), i64::get_type()),
+            ("Float64", 0.5_f64.to_kind(), f64::get_type()),
+            ("Bool", true.to_kind(), bool::get_type()),
+            ("String", "123".to_kind(), String::get_type()),
+            ("Bytes", vec![1_u8, 2].to_kind(), Vec::<u8>::get_type()),
+            ("Numeric", SpannerNumeric::new("1.5").to_kind(), SpannerNumeric::get_type()),
+            ("Timestamp", now.to_kind(), OffsetDateTime::get_type()),
+            ("Date", date!(2022 - 09 - 01).to_kind(), time::Date::get_type()),
+            ("Null", Option::<i64>::None.to_kind(), i64::get_type()),
+            ("Array", vec![Some(1_i64), None].to_kind(), Vec::<i64>::get_type()),
+            ("Struct", vec![test_struct].to_kind(), Vec::<TestStruct>::get_type()),
+            ("Int64", 456_i64.to_kind(), i64::get_type()),
+        ];
+        let mut fields = vec![];
+        let mut values = vec![];
+        for (name, kind, tp) in columns {
+            fields.push(Field {
+                name: name.to_string(),
+                r#type: Some(tp),
+            });
+            values.push(Value { kind: Some(kind) });
+        }
+        let index = crate::row::column_index(&fields);
+        let row = Row::new(Arc::new(index), Arc::new(fields), values);
+
+        assert_eq!(row.value(0).unwrap(), SpannerValue::Int64(123));
+        assert_eq!(row.value(11).unwrap(), SpannerValue::Int64(456));
+        let map = row.to_map().unwrap();
+        assert_eq!(map.len(), 11);
+        assert_eq!(map["Int64"], SpannerValue::Int64(123));
+        assert_eq!(map["Float64"], SpannerValue::Float64(0.5));
+        assert_eq!(map["Bool"], SpannerValue::Bool(true));
+        assert_eq!(map["String"], SpannerValue::String("123".to_string()));
+        assert_eq!(map["Bytes"], SpannerValue::Bytes(vec![1, 2]));
+        assert_eq!(map["Numeric"], SpannerValue::Numeric(SpannerNumeric::new("1.5")));
+        assert_eq!(map["Timestamp"], SpannerValue::Timestamp(now));
+        assert_eq!(map["Date"], SpannerValue::Date(date!(2022 - 09 - 01)));
+        assert_eq!(map["Null"], SpannerValue::null::<i64>());
+        assert_eq!(
+            map["Array"],
+            SpannerValue::Array(i64::get_type(), vec![SpannerValue::Int64(1), SpannerValue::null::<i64>()])
+        );
+        assert_eq!(
+            map["Struct"],
+            SpannerValue::Array(
+                TestStruct::get_type(),
+                vec![SpannerValue::Struct(vec![
+                    ("struct_field".to_string(), SpannerValue::String("aaa".to_string())),
+                    ("struct_field_time".to_string(), SpannerValue::Timestamp(now)),
+                    ("commit_timestamp".to_string(), SpannerValue::Timestamp(now)),
+                ])]
+            )
+        );
+
+        #[cfg(all(feature = "serde", feature = "serde_json"))]
+        assert_eq!(
+            serde_json::to_value(&map).unwrap(),
+            serde_json::json!({
+                "Int64": 123,
+                "Float64": 0.5,
+                "Bool": true,
+                "String": "123",
+                "Bytes": "AQI=",
+                "Numeric": "1.5",
+                "Timestamp": "2022-09-01T12:34:56.123456789Z",
+                "Date": "2022-09-01",
+                "Null": null,
+                "Array": [1, null],
+                "Struct": [{
+                    "struct_field": "aaa",
+                    "struct_field_time": "2022-09-01T12:34:56.123456789Z",
+                    "commit_timestamp": "2022-09-01T12:34:56.123456789Z"
+                }]
+            })
+        );
+
+        let untyped = Row::new(
+            Arc::new(HashMap::new()),
+            Arc::new(vec![Field {
+                name: "Untyped".to_string(),
+                r#type: None,
+            }]),
+            vec![Value {
+                kind: Some(1_i64.to_kind()),
+            }],
+        );
+        assert!(matches!(untyped.value(0), Err(Error::NoType(_))));
+    }
+
+    #[test]
+    fn test_column_access() {
+        use crate::value::SpannerNumeric;
+        use time::macros::{date, datetime};
+
+        let now = datetime!(2022-09-01 12:34:56.123 UTC);
+        let test_struct = TestStruct {
+            struct_field: "aaa".to_string(),
+            struct_field_time: now,
+            commit_timestamp: CommitTimestamp { timestamp: now },
+        };
+        let columns: Vec<(&str, Kind, google_cloud_googleapis::spanner::v1::Type)> = vec![
+            ("Int64", 1_i64.to_kind(), i64::get_type()),
+            ("Float64", 0.5_f64.to_kind(), f64::get_type()),
+            ("Bool", true.to_kind(), bool::get_type()),
+            ("String", "a".to_kind(), String::get_type()),
+            ("Bytes", vec![1_u8, 2].to_kind(), Vec::<u8>::get_type()),
+            ("Numeric", SpannerNumeric::new("1.5").to_kind(), SpannerNumeric::get_type()),
+            ("Timestamp", now.to_kind(), OffsetDateTime::get_type()),
+            ("Date", date!(2022 - 09 - 01).to_kind(), time::Date::get_type()),
+            ("Null", Option::<String>::None.to_kind(), String::get_type()),
+            ("Array", vec![Some(1_i64), None].to_kind(), Vec::<i64>::get_type()),
+            ("Struct", test_struct.to_kind(), TestStruct::get_type()),
+        ];
+        let mut index = HashMap::new();
+        let mut fields = vec![];
+        let mut values = vec![];
+        for (i, (name, kind, tp)) in columns.into_iter().enumerate() {
+            index.insert(name.to_string(), i);
+            fields.push(Field {
+                name: name.to_string(),
+                r#type: Some(tp),
+            });
+            values.push(Value { kind: Some(kind) });
+        }
+        let row = Row::new(Arc::new(index), Arc::new(fields), values);
+
+        assert_eq!(row.column_by_name::<i64>("Int64").unwrap(), 1);
+        assert_eq!(row.column::<i64>(0).unwrap(), 1);
+        assert_eq!(row.column_by_name::<Option<i64>>("Int64").unwrap(), Some(1));
+        assert_eq!(row.column_by_name::<f64>("Float64").unwrap(), 0.5);
+        assert!(row.column_by_name::<bool>("Bool").unwrap());
+        assert_eq!(row.column_by_name::<String>("String").unwrap(), "a");
+        assert_eq!(row.column::<Option<String>>(3).unwrap().as_deref(), Some("a"));
+        assert_eq!(row.column_by_name::<Vec<u8>>("Bytes").unwrap(), vec![1, 2]);
+        assert_eq!(row.column_by_name::<SpannerNumeric>("Numeric").unwrap().as_str(), "1.5");
+        assert_eq!(row.column_by_name::<OffsetDateTime>("Timestamp").unwrap(), now);
+        assert_eq!(row.column_by_name::<time::Date>("Date").unwrap(), date!(2022 - 09 - 01));
+        assert_eq!(row.column_by_name::<Option<String>>("Null").unwrap(), None);
+        assert_eq!(row.column_by_name::<Vec<Option<i64>>>("Array").unwrap(), vec![Some(1), None]);
+        let decoded = row.column_by_name::<TestStruct>("Struct").unwrap();
+        assert_eq!(decoded.struct_field, "aaa");
+        assert_eq!(decoded.struct_field_time, now);
+
+        // names are case-sensitive
+        assert!(matches!(row.column_by_name::<i64>("int64"), Err(Error::NoColumnFound(_))));
+        assert_eq!(row.column_by_name_ci::<i64>("int64").unwrap(), 1);
+        assert_eq!(row.column_by_name_ci::<i64>("Int64").unwrap(), 1);
+        assert!(matches!(row.column_by_name_ci::<i64>("unknown"), Err(Error::NoColumnFound(_))));
+        assert!(matches!(row.column::<i64>(11), Err(Error::InvalidColumnIndex(11, 11))));
+
+        match row.column_by_name::<bool>("Int64") {
+            Err(Error::ColumnTypeMismatch(index, name, expected, actual, kind)) => {
+                assert_eq!((index, name.as_str(), expected, actual.as_str()), (0, "Int64", "bool", "INT64"));
+                assert_eq!(kind, "StringValue");
+            }
+            r => panic!("must be type mismatch {:?}", r.map(|_| ())),
+        }
+        match row.column::<Option<Vec<String>>>(4) {
+            Err(Error::ColumnTypeMismatch(index, _, expected, actual, _)) => {
+                assert_eq!((index, actual.as_str()), (4, "BYTES"));
+                assert!(expected.contains("Vec<alloc::string::String>"));
+            }
+            r => panic!("must be type mismatch {:?}", r.map(|_| ())),
+        }
+        match row.column_by_name::<bool>("Struct") {
+            Err(Error::ColumnTypeMismatch(index, _, _, actual, _)) => {
+                assert_eq!(
+                    (index, actual.as_str()),
+                    (
+                        10,
+                        "STRUCT<struct_field STRING, struct_field_time TIMESTAMP, commit_timestamp TIMESTAMP>"
+                    )
+                );
+            }
+            r => panic!("must be type mismatch {:?}", r.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_column_by_name_ci_ambiguous() {
+        let mut index = HashMap::new();
+        index.insert("Name".to_string(), 0);
+        index.insert("NAME".to_string(), 1);
+        let row = Row::new(
+            Arc::new(index),
+            Arc::new(vec![Field::default(), Field::default()]),
+            vec![
+                Value {
+                    kind: Some("a".to_kind()),
+                },
+                Value {
+                    kind: Some("b".to_kind()),
+                },
+            ],
+        );
+        assert_eq!(row.column_by_name_ci::<String>("NAME").unwrap(), "b");
+        assert!(matches!(
+            row.column_by_name_ci::<String>("name"),
+            Err(Error::AmbiguousColumn(_))
+        ));
+    }
+
+    struct Child {
+        child_id: i64,
+        name: Option<String>,
+    }
+
+    impl ToStruct for Child {
+        fn to_kinds(&self) -> Kinds {
+            vec![("ChildId", self.child_id.to_kind()), ("Name", self.name.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![("ChildId", i64::get_type()), ("Name", String::get_type())]
+        }
+    }
+
+    impl TryFromStruct for Child {
+        fn try_from_struct(s: RowStruct<'_>) -> Result<Self, Error> {
+            Ok(Child {
+                // resolved by name regardless of the order of the fields
+                name: s.column_by_name("Name")?,
+                child_id: s.column_by_name("ChildId")?,
+            })
+        }
+    }
+
+    struct Parent {
+        parent_id: i64,
+        children: Vec<Child>,
+    }
+
+    impl ToStruct for Parent {
+        fn to_kinds(&self) -> Kinds {
+            vec![
+                ("ParentId", self.parent_id.to_kind()),
+                ("Children", self.children.to_kind()),
+            ]
+        }
+
+        fn get_types() -> Types {
+            vec![("ParentId", i64::get_type()), ("Children", Vec::<Child>::get_type())]
+        }
+    }
+
+    impl TryFromStruct for Parent {
+        fn try_from_struct(s: RowStruct<'_>) -> Result<Self, Error> {
+            Ok(Parent {
+                parent_id: s.column_by_name("ParentId")?,
+                children: s.column_by_name("Children")?,
+            })
+        }
+    }
+
+    #[test]
+    fn test_try_from_struct_array() {
+        // SELECT ARRAY(SELECT AS STRUCT ParentId, ARRAY(SELECT AS STRUCT * FROM Child WHERE ...) AS Children FROM Parent)
+        let parents = vec![
+            Parent {
+                parent_id: 1,
+                children: vec![
+                    Child {
+                        child_id: 10,
+                        name: Some("a".to_string()),
+                    },
+                    Child {
+                        child_id: 11,
+                        name: None,
+                    },
+                ],
+            },
+            Parent {
+                parent_id: 2,
+                children: vec![],
+            },
+        ];
+        let row = single_column_row(&parents);
+        let decoded = row.column::<Vec<Parent>>(0).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].parent_id, 1);
+        assert_eq!(decoded[0].children.len(), 2);
+        assert_eq!(decoded[0].children[0].child_id, 10);
+        assert_eq!(decoded[0].children[0].name.as_deref(), Some("a"));
+        assert_eq!(decoded[0].children[1].child_id, 11);
+        assert_eq!(decoded[0].children[1].name, None);
+        assert!(decoded[1].children.is_empty());
+
+        // NULL elements of the array
+        let mut row = single_column_row(&parents);
+        if let Some(Kind::ListValue(list)) = &mut row.values[0].kind {
+            list.values[1] = Value {
+                kind: Some(Option::<i64>::None.to_kind()),
+            };
+        }
+        let nullable = row.column::<Vec<Option<Parent>>>(0).unwrap();
+        assert!(nullable[0].is_some());
+        assert!(nullable[1].is_none());
+        match row.column::<Vec<Parent>>(0) {
+            Err(Error::NullArrayElement(field, index)) => assert_eq!((field.as_str(), index), ("value", 1)),
+            r => panic!("must be null array element {:?}", r.map(|_| ())),
+        }
+    }
+
+    fn single_column_row<T: ToKind>(value: &T) -> Row {
+        let mut index = HashMap::new();
+        index.insert("value".to_string(), 0);
+        Row {
+            index: Arc::new(index),
+            fields: Arc::new(vec![Field {
+                name: "value".to_string(),
+                r#type: Some(T::get_type()),
+            }]),
+            values: vec![Value {
+                kind: Some(value.to_kind()),
+            }],
+        }
+    }
+
+    fn assert_null_matrix<T>(tp: google_cloud_googleapis::spanner::v1::Type)
+    where
+        T: crate::row::TryFromValue,
+    {
+        let rust_type = std::any::type_name::<T>();
+        let mut index = HashMap::new();
+        index.insert("value".to_string(), 0);
+        let row = Row {
+            index: Arc::new(index),
+            fields: Arc::new(vec![Field {
+                name: "value".to_string(),
+                r#type: Some(tp),
+            }]),
+            values: vec![Value {
+                kind: Some(Kind::NullValue(0)),
+            }],
+        };
+        match row.column_by_name::<T>("value") {
+            Err(Error::UnexpectedNull(column, index, actual)) => {
+                assert_eq!(column, "value");
+                assert_eq!(index, 0);
+                assert_eq!(actual, rust_type);
+            }
+            r => panic!("{rust_type}: must be unexpected null {:?}", r.err()),
+        }
+        assert!(
+            row.column_by_name::<Option<T>>("value").unwrap().is_none(),
+            "{rust_type}: must be None"
+        );
+    }
+
+    #[test]
+    fn test_try_from_null() {
+        use crate::value::{SpannerNumeric, TimestampValue};
+        use std::num::{NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU8};
+        use std::time::SystemTime;
+
+        assert_null_matrix::<i64>(i64::get_type());
+        assert_null_matrix::<NonZeroI64>(i64::get_type());
+        assert_null_matrix::<NonZeroI32>(i64::get_type());
+        assert_null_matrix::<NonZeroI16>(i64::get_type());
+        assert_null_matrix::<NonZeroI8>(i64::get_type());
+        assert_null_matrix::<NonZeroU32>(i64::get_type());
+        assert_null_matrix::<NonZeroU16>(i64::get_type());
+        assert_null_matrix::<NonZeroU8>(i64::get_type());
+        assert_null_matrix::<f64>(f64::get_type());
+        assert_null_matrix::<f32>(Float32::get_type());
+        assert_null_matrix::<Float32>(Float32::get_type());
+        assert_null_matrix::<bool>(bool::get_type());
+        assert_null_matrix::<String>(String::get_type());
+        assert_null_matrix::<Vec<u8>>(Vec::<u8>::get_type());
+        assert_null_matrix::<[u8; 4]>(Vec::<u8>::get_type());
+        assert_null_matrix::<OffsetDateTime>(OffsetDateTime::get_type());
+        assert_null_matrix::<SystemTime>(OffsetDateTime::get_type());
+        assert_null_matrix::<CommitTimestamp>(CommitTimestamp::get_type());
+        assert_null_matrix::<TimestampValue>(OffsetDateTime::get_type());
+        assert_null_matrix::<time::Date>(time::Date::get_type());
+        assert_null_matrix::<SpannerNumeric>(SpannerNumeric::get_type());
+        assert_null_matrix::<PgNumeric>(PgNumeric::get_type());
+        assert_null_matrix::<Interval>(Interval::get_type());
+        assert_null_matrix::<Vec<i64>>(Vec::<i64>::get_type());
+        assert_null_matrix::<Vec<Option<String>>>(Vec::<String>::get_type());
+        assert_null_matrix::<TestStruct>(TestStruct::get_type());
+        assert_null_matrix::<Vec<TestStruct>>(Vec::<TestStruct>::get_type());
+        #[cfg(feature = "chrono")]
+        assert_null_matrix::<chrono::DateTime<chrono::Utc>>(OffsetDateTime::get_type());
+        #[cfg(feature = "serde_json")]
+        assert_null_matrix::<crate::value::PgJsonb>(crate::value::PgJsonb::get_type());
+        #[cfg(all(feature = "serde", feature = "serde_json"))]
+        assert_null_matrix::<crate::value::Json<Vec<i64>>>(crate::value::PgJsonb::get_type());
+        #[cfg(feature = "uuid")]
+        assert_null_matrix::<uuid::Uuid>(String::get_type());
+        #[cfg(feature = "uuid")]
+        assert_null_matrix::<crate::value::UuidBytes>(Vec::<u8>::get_type());
+        #[cfg(feature = "bigdecimal")]
+        assert_null_matrix::<bigdecimal::BigDecimal>(SpannerNumeric::get_type());
+        #[cfg(feature = "rust_decimal")]
+        assert_null_matrix::<rust_decimal::Decimal>(SpannerNumeric::get_type());
+        #[cfg(feature = "rust_decimal")]
+        assert_null_matrix::<crate::value::Numeric>(SpannerNumeric::get_type());
+    }
+
+    #[test]
+    fn test_try_from_null_struct_field() {
+        // NULL in the struct field is reported with the name of the field
+        let items = vec![TestStruct {
+            struct_field: "aaa".to_string(),
+            struct_field_time: OffsetDateTime::now_utc(),
+            commit_timestamp: CommitTimestamp::new(),
+        }];
+        let mut row = single_column_row(&items);
+        if let Some(Kind::ListValue(items)) = &mut row.values[0].kind {
+            if let Some(Kind::ListValue(fields)) = &mut items.values[0].kind {
+                fields.values[0].kind = Some(Kind::NullValue(0));
+            }
+        }
+        match row.column::<Vec<TestStruct>>(0) {
+            Err(Error::UnexpectedNull(column, 0, "alloc::string::String")) => assert_eq!(column, "struct_field"),
+            r => panic!("must be unexpected null {:?}", r.err()),
+        }
+    }
+
+    #[test]
+    fn test_try_from_nullable_array() {
+        let names = vec![Some("a".to_string()), None, Some("c".to_string())];
+        let row = single_column_row(&names);
+        match &row.values[0].kind {
+            Some(Kind::ListValue(v)) => assert!(matches!(v.values[1].kind, Some(Kind::NullValue(_)))),
+            _ => panic!("invalid kind"),
+        }
+        assert_eq!(Vec::<Option<String>>::get_type(), Vec::<String>::get_type());
+        assert_eq!(row.column::<Vec<Option<String>>>(0).unwrap(), names);
+        match row.column::<Vec<String>>(0) {
+            Err(Error::NullArrayElement(field, index)) => assert_eq!((field.as_str(), index), ("value", 1)),
+            r => panic!("must be null array element {:?}", r),
+        }
+        assert_eq!(
+            single_column_row(&Option::<Vec<Option<i64>>>::None)
+                .column::<Option<Vec<Option<i64>>>>(0)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_try_from_non_zero() {
+        use std::num::{NonZeroI64, NonZeroU16, NonZeroU8};
+
+        let value = NonZeroI64::new(-3).unwrap();
+        assert_eq!(single_column_row(&value).column::<NonZeroI64>(0).unwrap(), value);
+        assert_eq!(
+            single_column_row(&Option::<NonZeroI64>::None)
+                .column::<Option<NonZeroI64>>(0)
+                .unwrap(),
+            None
+        );
+        assert_eq!(single_column_row(&300_i64).column::<NonZeroU16>(0).unwrap().get(), 300);
+        match single_column_row(&0_i64).column::<Option<NonZeroI64>>(0) {
+            Err(Error::OutOfRange(field, value, _)) => assert_eq!((field.as_str(), value.as_str()), ("value", "0")),
+            r => panic!("must be out of range {:?}", r),
+        }
+        assert!(single_column_row(&256_i64).column::<NonZeroU8>(0).is_err());
+        assert!(single_column_row(&-1_i64).column::<NonZeroU16>(0).is_err());
+    }
+
+    #[test]
+    fn test_try_from_non_finite_float() {
+        assert!(single_column_row(&f64::NAN).column::<f64>(0).unwrap().is_nan());
+        assert_eq!(single_column_row(&f64::INFINITY).column::<f64>(0).unwrap(), f64::INFINITY);
+        assert_eq!(
+            single_column_row(&f32::NEG_INFINITY).column::<f64>(0).unwrap(),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(single_column_row(&1.5_f32).column::<f64>(0).unwrap(), 1.5);
+        assert!(single_column_row(&"1.5").column::<f64>(0).is_err());
+    }
+
+    #[test]
+    fn test_try_from_time() {
+        let ts = time::macros::datetime!(2016-12-31 23:59:59.999999999 UTC);
+        assert_eq!(single_column_row(&ts).column::<OffsetDateTime>(0).unwrap(), ts);
+        let ts = time::macros::datetime!(2017-01-01 05:29:59.999999999 +05:30);
+        assert_eq!(single_column_row(&ts).column::<OffsetDateTime>(0).unwrap(), ts);
+        let date = time::macros::date!(0999 - 01 - 05);
+        assert_eq!(single_column_row(&date).column::<time::Date>(0).unwrap(), date);
+    }
+
+    #[test]
+    fn test_try_from_timestamp_value() {
+        use crate::value::TimestampValue;
+
+        let now = OffsetDateTime::now_utc();
+        let row = single_column_row(&now);
+        assert_eq!(row.column::<TimestampValue>(0).unwrap(), TimestampValue::At(now));
+        assert_eq!(row.column::<Option<CommitTimestamp>>(0).unwrap().map(|v| *v), Some(now));
+        let row = single_column_row(&Option::<TimestampValue>::None);
+        assert_eq!(row.column::<Option<TimestampValue>>(0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_try_from_system_time() {
+        let before = std::time::UNIX_EPOCH - std::time::Duration::new(86_400, 1);
+        let now = std::time::SystemTime::now();
+        assert_eq!(single_column_row(&before).column::<std::time::SystemTime>(0).unwrap(), before);
+        assert_eq!(single_column_row(&now).column::<std::time::SystemTime>(0).unwrap(), now);
+    }
+
+    #[test]
+    fn test_try_from_bytes() {
+        // 5MB pseudo random blob, so that every byte value appears.
+        let mut seed = 88172645463325252_u64;
+        let blob: Vec<u8> = (0..5 * 1024 * 1024 + 1)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as u8
+            })
+            .collect();
+        let row = single_column_row(&blob);
+        let decoded = row.column::<Vec<u8>>(0).unwrap();
+        assert_eq!(decoded.capacity(), blob.len().div_ceil(3) * 3);
+        assert_eq!(decoded, blob);
+        assert_eq!(row.column::<bytes::Bytes>(0).unwrap(), blob);
+
+        assert_eq!(
+            single_column_row(&Vec::<u8>::new()).column::<Vec<u8>>(0).unwrap(),
+            Vec::<u8>::new()
+        );
+        assert_eq!(
+            single_column_row(&Some(vec![1_u8]))
+                .column::<Option<Vec<u8>>>(0)
+                .unwrap(),
+            Some(vec![1])
+        );
+        assert_eq!(
+            single_column_row(&Option::<Vec<u8>>::None)
+                .column::<Option<bytes::Bytes>>(0)
+                .unwrap(),
+            None
+        );
+        let array = vec![vec![1_u8, 2], vec![], vec![255]];
+        assert_eq!(single_column_row(&array).column::<Vec<Vec<u8>>>(0).unwrap(), array);
+        let nullable = vec![Some(vec![1_u8]), None];
+        assert_eq!(
+            single_column_row(&nullable).column::<Vec<Option<Vec<u8>>>>(0).unwrap(),
+            nullable
+        );
+    }
+
+    #[test]
+    fn test_try_from_invalid_bytes() {
+        // the STRING column written by another client
+        let mut row = single_column_row(&vec![1_u8]);
+        row.values[0].kind = Some(Kind::StringValue("abc!efgh".to_string()));
+        match row.column::<Vec<u8>>(0) {
+            Err(e @ Error::ByteParseError(_, base64::DecodeError::InvalidByte(3, b'!'))) => {
+                assert_eq!(e.to_string(), "Failed to parse as ByteArray value: Invalid byte 33, offset 3.")
+            }
+            r => panic!("must be invalid byte {r:?}"),
+        }
+        row.values[0].kind = Some(Kind::StringValue("abcde".to_string()));
+        assert!(matches!(row.column::<Vec<u8>>(0), Err(Error::ByteParseError(_, _))));
+        assert!(matches!(row.column::<bytes::Bytes>(0), Err(Error::ByteParseError(_, _))));
+    }
+
+    #[test]
+    fn test_try_from_narrow_int() {
+        let out_of_range = |r: Result<(), Error>, value: &str, target_type: &str| match r {
+            Err(Error::OutOfRange(column, v, t)) => {
+                assert_eq!(column, "value");
+                assert_eq!(v, value);
+                assert_eq!(t, target_type);
+            }
+            r => panic!("must be out of range {value} {target_type} {r:?}"),
+        };
+        let max = single_column_row(&i64::MAX);
+        let min = single_column_row(&i64::MIN);
+        let minus = single_column_row(&-1_i64);
+        out_of_range(max.column::<i32>(0).map(|_| ()), "9223372036854775807", "i32");
+        out_of_range(min.column::<i32>(0).map(|_| ()), "-9223372036854775808", "i32");
+        out_of_range(max.column::<i16>(0).map(|_| ()), "9223372036854775807", "i16");
+        out_of_range(max.column::<i8>(0).map(|_| ()), "9223372036854775807", "i8");
+        out_of_range(minus.column::<u32>(0).map(|_| ()), "-1", "u32");
+        out_of_range(minus.column::<u64>(0).map(|_| ()), "-1", "u64");
+        out_of_range(minus.column::<u16>(0).map(|_| ()), "-1", "u16");
+        out_of_range(minus.column::<usize>(0).map(|_| ()), "-1", "usize");
+        out_of_range(single_column_row(&65536_i64).column::<u16>(0).map(|_| ()), "65536", "u16");
+
+        assert_eq!(single_column_row(&i32::MAX).column::<i32>(0).unwrap(), i32::MAX);
+        assert_eq!(single_column_row(&i32::MIN).column::<i32>(0).unwrap(), i32::MIN);
+        assert_eq!(single_column_row(&i16::MIN).column::<i16>(0).unwrap(), i16::MIN);
+        assert_eq!(single_column_row(&i8::MAX).column::<i8>(0).unwrap(), i8::MAX);
+        assert_eq!(single_column_row(&u32::MAX).column::<u32>(0).unwrap(), u32::MAX);
+        assert_eq!(single_column_row(&u16::MAX).column::<u16>(0).unwrap(), u16::MAX);
+        assert_eq!(max.column::<u64>(0).unwrap(), i64::MAX as u64);
+        assert_eq!(max.column::<isize>(0).unwrap(), isize::MAX);
+        assert_eq!(
+            single_column_row(&Option::<i32>::None)
+                .column::<Option<i32>>(0)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            single_column_row(&vec![1_i64, 2]).column::<Vec<u16>>(0).unwrap(),
+            vec![1_u16, 2]
+        );
+        out_of_range(
+            single_column_row(&vec![1_i64, -2]).column::<Vec<u32>>(0).map(|_| ()),
+            "-2",
+            "u32",
+        );
+    }
+
+    #[test]
+    fn test_try_from_fixed_size_bytes() {
+        let hash = [9_u8; 32];
+        assert_eq!(single_column_row(&hash).column::<[u8; 32]>(0).unwrap(), hash);
+        match single_column_row(&hash).column::<[u8; 16]>(0) {
+            Err(Error::InvalidByteLength(column, 16, 32)) => assert_eq!(column, "value"),
+            _ => panic!("invalid result"),
+        }
+    }
+
+    #[test]
+    fn test_try_from_float32() {
+        assert_eq!(single_column_row(&Float32(0.5)).column::<f32>(0).unwrap(), 0.5);
+        assert_eq!(single_column_row(&Float32(-2.0)).column::<Float32>(0).unwrap(), Float32(-2.0));
+        assert!(single_column_row(&Float32(f32::NAN)).column::<f32>(0).unwrap().is_nan());
+        assert_eq!(
+            single_column_row(&vec![Float32(f32::INFINITY), Float32(1.0)])
+                .column::<Vec<Float32>>(0)
+                .unwrap(),
+            vec![Float32(f32::INFINITY), Float32(1.0)]
+        );
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn test_try_from_big_decimal() {
+        use crate::value::SpannerNumeric;
+        use bigdecimal::BigDecimal;
+        use std::str::FromStr;
+
+        let max = BigDecimal::from_str(&format!("{}.{}", "9".repeat(29), "9".repeat(9))).unwrap();
+        let negative = BigDecimal::from_str("-0.000000001").unwrap();
+        let row = |v: &BigDecimal| single_column_row(&SpannerNumeric::try_from(v).unwrap());
+        assert_eq!(row(&max).column::<BigDecimal>(0).unwrap(), max);
+        assert_eq!(row(&negative).column::<BigDecimal>(0).unwrap(), negative);
+        assert!(single_column_row(&"1.2.3").column::<BigDecimal>(0).is_err());
+    }
+
+    #[test]
+    fn test_try_from_interval() {
+        let mixed = Interval::new(-14, 3, -(4 * 3_600_000_000_000 + 6_500_000_000));
+        assert_eq!(single_column_row(&mixed).column::<Interval>(0).unwrap(), mixed);
+        let zero = Interval::default();
+        assert_eq!(single_column_row(&zero).column::<Interval>(0).unwrap(), zero);
+        assert_eq!(
+            single_column_row(&"P1Y-2M3W-4DT+5H-0.5S")
+                .column::<Interval>(0)
+                .unwrap(),
+            Interval::new(10, 17, 5 * 3_600_000_000_000 - 500_000_000)
+        );
+        for invalid in [
+            "",
+            "P",
+            "1Y",
+            "PT",
+            "P1H",
+            "PT1D",
+            "P1.5Y",
+            "PT1.1234567891S",
+            "PTT1S",
+            "P-Y",
+        ] {
+            assert!(single_column_row(&invalid).column::<Interval>(0).is_err(), "{}", invalid);
+        }
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    struct Singer {
+        #[prost(string, tag = "1")]
+        name: String,
+        #[prost(int64, tag = "2")]
+        age: i64,
+    }
+
+    impl crate::value::ProtoName for Singer {
+        const FULL_NAME: &'static str = "examples.music.Singer";
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, ::prost::Enumeration)]
+    #[repr(i32)]
+    enum Genre {
+        Pop = 0,
+        Jazz = 1,
+    }
+
+    impl crate::value::ProtoName for Genre {
+        const FULL_NAME: &'static str = "examples.music.Genre";
+    }
+
+    impl TryFrom<i32> for Genre {
+        type Error = ();
+        fn try_from(value: i32) -> Result<Self, Self::Error> {
+            Genre::from_i32(value).ok_or(())
+        }
+    }
+
+    #[test]
+    fn test_try_from_proto() {
+        use crate::value::{EnumValue, Proto};
+        use google_cloud_googleapis::spanner::v1::TypeCode;
+
+        let singer = Proto(Singer {
+            name: "singer".to_string(),
+            age: 20,
+        });
+        let proto_type = Proto::<Singer>::get_type();
+        assert_eq!(proto_type.code, TypeCode::Proto as i32);
+        assert_eq!(proto_type.proto_type_fqn, "examples.music.Singer");
+        let array_type = Vec::<Proto<Singer>>::get_type();
+        assert_eq!(array_type.array_element_type.unwrap().proto_type_fqn, "examples.music.Singer");
+        assert_eq!(single_column_row(&singer).column::<Proto<Singer>>(0).unwrap(), singer);
+        assert!(single_column_row(&vec![0xff_u8]).column::<Proto<Singer>>(0).is_err());
+
+        assert_eq!(single_column_row(&singer).proto_column::<Singer>("value").unwrap(), singer.0);
+        let singers = vec![singer.clone(), Proto(Singer::default())];
+        assert_eq!(
+            single_column_row(&singers)
+                .proto_array_column::<Singer>("value")
+                .unwrap(),
+            vec![singer.0.clone(), Singer::default()]
+        );
+        let mismatch = |r: Result<(), Error>, actual: &str| match r {
+            Err(Error::ProtoTypeMismatch(column, expected, a)) => {
+                assert_eq!(column, "value");
+                assert_eq!(expected, "examples.music.Singer");
+                assert_eq!(a, actual);
+            }
+            r => panic!("must be proto type mismatch {r:?}"),
+        };
+        // the column written as BYTES or as the other message
+        mismatch(
+            single_column_row(&prost::Message::encode_to_vec(&singer.0))
+                .proto_column::<Singer>("value")
+                .map(|_| ()),
+            "BYTES",
+        );
+        let mut other = single_column_row(&singer);
+        other.fields = Arc::new(vec![Field {
+            name: "value".to_string(),
+            r#type: Some(google_cloud_googleapis::spanner::v1::Type {
+                proto_type_fqn: "examples.music.Album".to_string(),
+                ..Proto::<Singer>::get_type()
+            }),
+        }]);
+        mismatch(other.proto_column::<Singer>("value").map(|_| ()), "PROTO<examples.music.Album>");
+        mismatch(
+            single_column_row(&singer)
+                .proto_array_column::<Singer>("value")
+                .map(|_| ()),
+            "PROTO<examples.music.Singer>",
+        );
+        mismatch(
+            single_column_row(&singers).proto_column::<Singer>("value").map(|_| ()),
+            "ARRAY<PROTO<examples.music.Singer>>",
+        );
+        assert!(matches!(
+            single_column_row(&Option::<Proto<Singer>>::None).proto_column::<Singer>("value"),
+            Err(Error::UnexpectedNull(_, 0, _))
+        ));
+
+        let genre = EnumValue(Genre::Jazz);
+        let enum_type = EnumValue::<Genre>::get_type();
+        assert_eq!(enum_type.code, TypeCode::Enum as i32);
+        assert_eq!(enum_type.proto_type_fqn, "examples.music.Genre");
+        assert_eq!(genre.to_kind(), "1".to_kind());
+        assert_eq!(single_column_row(&genre).column::<EnumValue<Genre>>(0).unwrap(), genre);
+        match single_column_row(&5_i64).column::<EnumValue<Genre>>(0) {
+            Err(Error::UnknownEnumValue(_, v)) => assert_eq!(v, "5"),
+            r => panic!("must be unknown enum value {:?}", r),
+        }
+    }
+
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[test]
+    fn test_try_from_json_wrapper() {
+        use crate::value::Json;
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Payload {
+            tags: Vec<String>,
+        }
+
+        let payload = Payload {
+            tags: vec!["a".to_string()],
+        };
+        let row = single_column_row(&Json(&payload));
+        assert_eq!(row.column::<Json<Payload>>(0).unwrap().0, payload);
+        let row = single_column_row(&Option::<Json<Payload>>::None);
+        assert_eq!(row.column::<Option<Json<Payload>>>(0).unwrap(), None);
+        assert!(single_column_row(&"{}").column::<Json<Payload>>(0).is_err());
+    }
+
+    #[test]
+    fn test_try_from_pg_numeric() {
+        let nan = single_column_row(&PgNumeric::new("NaN"))
+            .column::<PgNumeric>(0)
+            .unwrap();
+        assert!(nan.is_nan());
+        let value = single_column_row(&PgNumeric::new("-1.5"))
+            .column::<PgNumeric>(0)
+            .unwrap();
+        assert_eq!(value.as_str(), "-1.5");
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_try_from_json() {
+        let value = serde_json::json!({"name": "spanner", "tags": [1, 2]});
+        assert_eq!(single_column_row(&value).column::<serde_json::Value>(0).unwrap(), value);
+        let jsonb = crate::value::PgJsonb(value.clone());
+        assert_eq!(single_column_row(&jsonb).column::<crate::value::PgJsonb>(0).unwrap(), jsonb);
+        assert_eq!(
+            single_column_row(&serde_json::Value::Null)
+                .column::<Option<serde_json::Value>>(0)
+                .unwrap(),
+            Some(serde_json::Value::Null)
+        );
+        assert_eq!(
+            single_column_row(&Option::<serde_json::Value>::None)
+                .column::<Option<serde_json::Value>>(0)
+                .unwrap(),
+            None
+        );
+        assert!(single_column_row(&"{invalid").column::<serde_json::Value>(0).is_err());
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn test_try_from_decimal() {
+        use crate::value::{Numeric, SpannerNumeric};
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        let row = |v: &str| single_column_row(&SpannerNumeric::new(v));
+        let max_scale = "12345678901234567890.123456789";
+        assert_eq!(
+            row(max_scale).column::<Decimal>(0).unwrap(),
+            Decimal::from_str(max_scale).unwrap()
+        );
+        assert_eq!(row("-0.000000001").column::<Decimal>(0).unwrap().to_string(), "-0.000000001");
+        assert_eq!(row("1.5").column::<Numeric>(0).unwrap().value().to_string(), "1.5");
+        assert_eq!(
+            single_column_row(&Option::<SpannerNumeric>::None)
+                .column::<Option<Decimal>>(0)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            single_column_row(&vec![SpannerNumeric::new("1"), SpannerNumeric::new("-2.5")])
+                .column::<Vec<Decimal>>(0)
+                .unwrap(),
+            vec![Decimal::ONE, Decimal::from_str("-2.5").unwrap()]
+        );
+
+        // more significant digits than the 96-bit mantissa
+        let too_precise = "99999999999999999999999.999999999";
+        match row(too_precise).column::<Decimal>(0) {
+            Err(Error::OutOfRange(field, value, _)) => {
+                assert_eq!((field.as_str(), value.as_str()), ("value", too_precise))
+            }
+            r => panic!("must be out of range {:?}", r),
+        }
+        assert_eq!(
+            row(too_precise).column_numeric_lossy("value").unwrap().to_string(),
+            "100000000000000000000000.00000"
+        );
+
+        // the integer part exceeds rust_decimal
+        let too_large = "99999999999999999999999999999.999999999";
+        assert!(matches!(row(too_large).column::<Decimal>(0), Err(Error::OutOfRange(_, _, _))));
+        assert!(matches!(
+            row(too_large).column_numeric_lossy("value"),
+            Err(Error::OutOfRange(_, _, _))
+        ));
+        assert!(matches!(row("NaN").column::<Decimal>(0), Err(Error::DecimalParseError(_, _))));
+        assert!(matches!(
+            single_column_row(&Option::<SpannerNumeric>::None).column_numeric_lossy("value"),
+            Err(Error::UnexpectedNull(_, 0, _))
+        ));
+    }
+
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[test]
+    fn test_json_column() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Payload {
+            name: String,
+        }
+
+        let value = serde_json::json!({"name": "spanner"});
+        let row = single_column_row(&value);
+        assert_eq!(
+            row.json_column::<Payload>("value").unwrap(),
+            Payload {
+                name: "spanner".to_string()
+            }
+        );
+        assert!(row.json_column::<Option<Payload>>("value").unwrap().is_some());
+        assert_eq!(row.json_column::<serde_json::Value>("value").unwrap(), value);
+
+        let row = single_column_row(&Option::<serde_json::Value>::None);
+        assert_eq!(row.json_column::<Option<Payload>>("value").unwrap(), None);
+        assert!(matches!(row.json_column::<Payload>("value"), Err(Error::JsonParseError(_, _))));
+
+        let values = vec![serde_json::json!([1]), serde_json::Value::Null];
+        assert_eq!(single_column_row(&values).column::<Vec<serde_json::Value>>(0).unwrap(), values);
+        let payloads = vec![Some(serde_json::json!({"name": "a"})), None];
+        assert_eq!(
+            single_column_row(&payloads)
+                .column::<Vec<Option<crate::value::Json<Payload>>>>(0)
+                .unwrap(),
+            vec![Some(crate::value::Json(Payload { name: "a".to_string() })), None]
+        );
+
+        match single_column_row(&"{invalid").json_column::<serde_json::Value>("value") {
+            Err(Error::JsonParseError(field, e)) => {
+                assert_eq!(field, "value");
+                assert!(e.is_syntax());
+            }
+            r => panic!("must be json parse error {:?}", r),
+        }
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_try_from_uuid() {
+        use crate::value::UuidBytes;
+
+        let id = uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        assert_eq!(single_column_row(&id).column::<uuid::Uuid>(0).unwrap(), id);
+        assert_eq!(
+            single_column_row(&vec![id, id]).column::<Vec<uuid::Uuid>>(0).unwrap(),
+            vec![id, id]
+        );
+        assert_eq!(single_column_row(&UuidBytes(id)).column::<UuidBytes>(0).unwrap(), UuidBytes(id));
+        match single_column_row(&"not-a-uuid").column::<uuid::Uuid>(0) {
+            Err(Error::UuidParseError(column, _)) => assert_eq!(column, "value"),
+            _ => panic!("invalid result"),
+        }
+        assert!(single_column_row(&vec![1_u8, 2, 3]).column::<UuidBytes>(0).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_try_from_chrono_date_time() {
+        use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+        let offset = FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+        let local = offset.with_ymd_and_hms(2022, 12, 1, 10, 30, 15).unwrap() + chrono::Duration::nanoseconds(123);
+        let row = single_column_row(&local);
+        let value = row.column_by_name::<DateTime<Utc>>("value").unwrap();
+        assert_eq!(value, local);
+        assert_eq!(value.timezone(), Utc);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_try_from_chrono_naive() {
+        use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+        let timestamp_row = |s: &str| {
+            let mut row = single_column_row(&OffsetDateTime::now_utc());
+            row.values[0].kind = Some(Kind::StringValue(s.to_string()));
+            row
+        };
+        let min = NaiveDate::from_ymd_opt(1, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let max = NaiveDate::from_ymd_opt(9999, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 999_999_999)
+            .unwrap();
+        assert_eq!(
+            timestamp_row("0001-01-01T00:00:00Z")
+                .column::<NaiveDateTime>(0)
+                .unwrap(),
+            min
+        );
+        assert_eq!(
+            timestamp_row("9999-12-31T23:59:59.999999999Z")
+                .column::<NaiveDateTime>(0)
+                .unwrap(),
+            max
+        );
+        assert_eq!(
+            timestamp_row("2022-12-01T10:30:15.123+05:30")
+                .column::<NaiveDateTime>(0)
+                .unwrap(),
+            NaiveDate::from_ymd_opt(2022, 12, 1)
+                .unwrap()
+                .and_hms_milli_opt(5, 0, 15, 123)
+                .unwrap()
+        );
+        assert_eq!(
+            timestamp_row("0001-01-01T00:00:00Z")
+                .column::<DateTime<Utc>>(0)
+                .unwrap()
+                .naive_utc(),
+            min
+        );
+        assert!(matches!(
+            timestamp_row("2022-12-01").column::<NaiveDateTime>(0),
+            Err(Error::ChronoParseError(_, _))
+        ));
+
+        let date = NaiveDate::from_ymd_opt(9999, 12, 31).unwrap();
+        assert_eq!(single_column_row(&date).column::<NaiveDate>(0).unwrap(), date);
+        assert_eq!(
+            single_column_row(&time::macros::date!(0001 - 01 - 01))
+                .column::<NaiveDate>(0)
+                .unwrap(),
+            NaiveDate::from_ymd_opt(1, 1, 1).unwrap()
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_round_trip() {
+        use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+
+        let min = NaiveDate::from_ymd_opt(1, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let max = NaiveDate::from_ymd_opt(9999, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 999_999_999)
+            .unwrap();
+        // walk from the min to the max timestamp with the prime steps so that every digit of the nanoseconds varies
+        let step = Duration::days(3_652_058 / 997) + Duration::nanoseconds(123_456_789_011);
+        let mut value = min;
+        while value <= max {
+            let naive = single_column_row(&value).column::<NaiveDateTime>(0).unwrap();
+            assert_eq!(naive, value);
+            let utc: DateTime<Utc> = value.and_utc();
+            assert_eq!(single_column_row(&utc).column::<DateTime<Utc>>(0).unwrap(), utc);
+            let offset_date_time = single_column_row(&utc).column::<OffsetDateTime>(0).unwrap();
+            assert_eq!(offset_date_time.unix_timestamp(), utc.timestamp());
+            assert_eq!(offset_date_time.nanosecond(), utc.timestamp_subsec_nanos());
+            value += step;
+        }
+        assert_eq!(single_column_row(&max).column::<NaiveDateTime>(0).unwrap(), max);
+    }
+
+    #[test]
+    fn test_time_min_max_timestamp() {
+        use time::macros::datetime;
+
+        for value in [
+            datetime!(0001-01-01 00:00:00 UTC),
+            datetime!(9999-12-31 23:59:59.999999999 UTC),
+        ] {
+            assert_eq!(single_column_row(&value).column::<OffsetDateTime>(0).unwrap(), value);
+        }
+    }
 }
@@ -0,0 +1,755 @@
+//! serde Serializer for the mutations.
+//!
+//! The fields of the struct, or the entries of the map with the string keys, are serialized as the columns.
+//! The names of the columns follow the serde attributes such as `rename` and `rename_all`.
+//! * bool, the integers, the floats, char and str are written in the same way as ToKind. The u64 greater than i64::MAX is rejected.
+//! * The bytes are written as BYTES. Note that `Vec<u8>` is serialized as the sequence unless `serde_bytes` is used.
+//! * None and the unit are written as NULL, and the unit variant of the enum is written as the string of its name.
+//! * The sequence and the tuple are written as ARRAY.
+//! * The nested map, struct and the enum variant with the data are written as JSON with the `serde_json` feature.
+//!
+//! ```
+//! use google_cloud_spanner::mutation::insert_serde;
+//!
+//! #[derive(serde::Serialize)]
+//! #[serde(rename_all = "PascalCase")]
+//! struct User {
+//!     user_id: String,
+//!     level: u32,
+//!     nickname: Option<String>,
+//! }
+//!
+//! let user = User {
+//!     user_id: "user1".to_string(),
+//!     level: 1,
+//!     nickname: None,
+//! };
+//! let mutation = insert_serde("User", &user).unwrap();
+//! ```
+
+use std::fmt::Display;
+
+use prost_types::value::Kind;
+use prost_types::{ListValue, Value};
+use serde::ser::{Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple, SerializeTupleStruct};
+#[cfg(feature = "serde_json")]
+use serde::ser::{SerializeStructVariant, SerializeTupleVariant};
+use serde::Serialize;
+
+use crate::statement::ToKind;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Message(String),
+    #[error("Unsupported top level value: {0}, a struct or a map is required")]
+    UnsupportedTopLevel(&'static str),
+    #[error("Unsupported map key: {0}, a string is required")]
+    UnsupportedKey(&'static str),
+    #[error("Unsupported value: {0} is written as JSON, which requires the serde_json feature")]
+    JsonRequired(&'static str),
+    #[error("Failed to serialize column: column={0}, {1}")]
+    Column(String, #[source] Box<Error>),
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Message(e.to_string())
+    }
+}
+
+/// with_column adds the column name to the error unless it already has one.
+fn with_column(e: Error, column: &str) -> Error {
+    match e {
+        Error::Column(..) => e,
+        e => Error::Column(column.to_string(), Box::new(e)),
+    }
+}
+
+/// to_columns returns the columns and the values of the row serialized from the value.
+pub(crate) fn to_columns<T: Serialize + ?Sized>(value: &T) -> Result<(Vec<String>, Vec<Value>), Error> {
+    value.serialize(RowSerializer)
+}
+
+/// reject implements the methods of serde::Serializer that return the error of the variant with the name of the value.
+macro_rules! reject {
+    ($variant:path; $($method:ident($($arg:ident: $ty:ty),*) -> $ret:ty, $name:literal;)*) => {
+        $(
+            fn $method(self, $($arg: $ty),*) -> Result<$ret, Error> {
+                Err($variant($name))
+            }
+        )*
+    };
+}
+
+struct RowSerializer;
+
+type Columns = (Vec<String>, Vec<Value>);
+
+impl serde::Serializer for RowSerializer {
+    type Ok = Columns;
+    type Error = Error;
+    type SerializeSeq = Impossible<Columns, Error>;
+    type SerializeTuple = Impossible<Columns, Error>;
+    type SerializeTupleStruct = Impossible<Columns, Error>;
+    type SerializeTupleVariant = Impossible<Columns, Error>;
+    type SerializeMap = ColumnsSerializer;
+    type SerializeStruct = ColumnsSerializer;
+    type SerializeStructVariant = Impossible<Columns, Error>;
+
+    reject! {
+        Error::UnsupportedTopLevel;
+        serialize_bool(_v: bool) -> Columns, "bool";
+        serialize_i8(_v: i8) -> Columns, "i8";
+        serialize_i16(_v: i16) -> Columns, "i16";
+        serialize_i32(_v: i32) -> Columns, "i32";
+        serialize_i64(_v: i64) -> Columns, "i64";
+        serialize_u8(_v: u8) -> Columns, "u8";
+        serialize_u16(_v: u16) -> Columns, "u16";
+        serialize_u32(_v: u32) -> Columns, "u32";
+        serialize_u64(_v: u64) -> Columns, "u64";
+        serialize_f32(_v: f32) -> Columns, "f32";
+        serialize_f64(_v: f64) -> Columns, "f64";
+        serialize_char(_v: char) -> Columns, "char";
+        serialize_str(_v: &str) -> Columns, "str";
+        serialize_bytes(_v: &[u8]) -> Columns, "bytes";
+        serialize_none() -> Columns, "none";
+        serialize_unit() -> Columns, "unit";
+        serialize_unit_struct(_name: &'static str) -> Columns, "unit struct";
+        serialize_unit_variant(_name: &'static str, _index: u32, _variant: &'static str) -> Columns, "unit variant";
+        serialize_seq(_len: Option<usize>) -> Self::SerializeSeq, "sequence";
+        serialize_tuple(_len: usize) -> Self::SerializeTuple, "tuple";
+        serialize_tuple_struct(_name: &'static str, _len: usize) -> Self::SerializeTupleStruct, "tuple struct";
+        serialize_tuple_variant(_name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Self::SerializeTupleVariant, "tuple variant";
+        serialize_struct_variant(_name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Self::SerializeStructVariant, "struct variant";
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<Columns, Error> {
+        Err(Error::UnsupportedTopLevel("some"))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Columns, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Columns, Error> {
+        Err(Error::UnsupportedTopLevel("newtype variant"))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<ColumnsSerializer, Error> {
+        Ok(ColumnsSerializer::new(len.unwrap_or_default()))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<ColumnsSerializer, Error> {
+        Ok(ColumnsSerializer::new(len))
+    }
+}
+
+struct ColumnsSerializer {
+    columns: Vec<String>,
+    values: Vec<Value>,
+}
+
+impl ColumnsSerializer {
+    fn new(len: usize) -> Self {
+        ColumnsSerializer {
+            columns: Vec::with_capacity(len),
+            values: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, column: String, value: &T) -> Result<(), Error> {
+        let kind = value.serialize(ValueSerializer).map_err(|e| with_column(e, &column))?;
+        self.columns.push(column);
+        self.values.push(Value { kind: Some(kind) });
+        Ok(())
+    }
+}
+
+impl SerializeStruct for ColumnsSerializer {
+    type Ok = Columns;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.push(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Columns, Error> {
+        Ok((self.columns, self.values))
+    }
+}
+
+impl SerializeMap for ColumnsSerializer {
+    type Ok = Columns;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.columns.push(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let column = self.columns.pop().unwrap_or_default();
+        self.push(column, value)
+    }
+
+    fn end(self) -> Result<Columns, Error> {
+        Ok((self.columns, self.values))
+    }
+}
+
+struct KeySerializer;
+
+impl serde::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    reject! {
+        Error::UnsupportedKey;
+        serialize_bool(_v: bool) -> String, "bool";
+        serialize_i8(_v: i8) -> String, "i8";
+        serialize_i16(_v: i16) -> String, "i16";
+        serialize_i32(_v: i32) -> String, "i32";
+        serialize_i64(_v: i64) -> String, "i64";
+        serialize_u8(_v: u8) -> String, "u8";
+        serialize_u16(_v: u16) -> String, "u16";
+        serialize_u32(_v: u32) -> String, "u32";
+        serialize_u64(_v: u64) -> String, "u64";
+        serialize_f32(_v: f32) -> String, "f32";
+        serialize_f64(_v: f64) -> String, "f64";
+        serialize_bytes(_v: &[u8]) -> String, "bytes";
+        serialize_none() -> String, "none";
+        serialize_unit() -> String, "unit";
+        serialize_unit_struct(_name: &'static str) -> String, "unit struct";
+        serialize_seq(_len: Option<usize>) -> Self::SerializeSeq, "sequence";
+        serialize_tuple(_len: usize) -> Self::SerializeTuple, "tuple";
+        serialize_tuple_struct(_name: &'static str, _len: usize) -> Self::SerializeTupleStruct, "tuple struct";
+        serialize_tuple_variant(_name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Self::SerializeTupleVariant, "tuple variant";
+        serialize_map(_len: Option<usize>) -> Self::SerializeMap, "map";
+        serialize_struct(_name: &'static str, _len: usize) -> Self::SerializeStruct, "struct";
+        serialize_struct_variant(_name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Self::SerializeStructVariant, "struct variant";
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String, Error> {
+        Err(Error::UnsupportedKey("some"))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(Error::UnsupportedKey("newtype variant"))
+    }
+}
+
+struct ValueSerializer;
+
+impl serde::Serializer for ValueSerializer {
+    type Ok = Kind;
+    type Error = Error;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    #[cfg(feature = "serde_json")]
+    type SerializeTupleVariant = Json<<serde_json::value::Serializer as serde::Serializer>::SerializeTupleVariant>;
+    #[cfg(not(feature = "serde_json"))]
+    type SerializeTupleVariant = Impossible<Kind, Error>;
+    #[cfg(feature = "serde_json")]
+    type SerializeMap = Json<<serde_json::value::Serializer as serde::Serializer>::SerializeMap>;
+    #[cfg(not(feature = "serde_json"))]
+    type SerializeMap = Impossible<Kind, Error>;
+    #[cfg(feature = "serde_json")]
+    type SerializeStruct = Json<<serde_json::value::Serializer as serde::Serializer>::SerializeMap>;
+    #[cfg(not(feature = "serde_json"))]
+    type SerializeStruct = Impossible<Kind, Error>;
+    #[cfg(feature = "serde_json")]
+    type SerializeStructVariant = Json<<serde_json::value::Serializer as serde::Serializer>::SerializeStructVariant>;
+    #[cfg(not(feature = "serde_json"))]
+    type SerializeStructVariant = Impossible<Kind, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Kind, Error> {
+        Ok(i64::from(v).to_kind())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Kind, Error> {
+        v.try_to_kind().map_err(|e| Error::Message(e.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Kind, Error> {
+        Ok(v.to_kind())
+    }
+
+    fn serialize_none(self) -> Result<Kind, Error> {
+        Ok(Option::<i64>::None.to_kind())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Kind, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Kind, Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Kind, Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Kind, Error> {
+        Ok(variant.to_kind())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Kind, Error> {
+        value.serialize(self)
+    }
+
+    #[cfg(feature = "serde_json")]
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Kind, Error> {
+        let json = serde_json::value::Serializer.serialize_newtype_variant(name, index, variant, value)?;
+        Ok(json.to_kind())
+    }
+
+    #[cfg(not(feature = "serde_json"))]
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Kind, Error> {
+        Err(Error::JsonRequired("newtype variant"))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListSerializer, Error> {
+        Ok(ListSerializer(Vec::with_capacity(len.unwrap_or_default())))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ListSerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    #[cfg(feature = "serde_json")]
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Ok(Json(
+            serde_json::value::Serializer.serialize_tuple_variant(name, index, variant, len)?,
+        ))
+    }
+
+    #[cfg(not(feature = "serde_json"))]
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::JsonRequired("tuple variant"))
+    }
+
+    #[cfg(feature = "serde_json")]
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(Json(serde_json::value::Serializer.serialize_map(len)?))
+    }
+
+    #[cfg(not(feature = "serde_json"))]
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::JsonRequired("map"))
+    }
+
+    #[cfg(feature = "serde_json")]
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    #[cfg(not(feature = "serde_json"))]
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::JsonRequired("struct"))
+    }
+
+    #[cfg(feature = "serde_json")]
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Ok(Json(
+            serde_json::value::Serializer.serialize_struct_variant(name, index, variant, len)?,
+        ))
+    }
+
+    #[cfg(not(feature = "serde_json"))]
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::JsonRequired("struct variant"))
+    }
+}
+
+struct ListSerializer(Vec<Value>);
+
+impl ListSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let kind = value.serialize(ValueSerializer)?;
+        self.0.push(Value { kind: Some(kind) });
+        Ok(())
+    }
+
+    fn end(self) -> Result<Kind, Error> {
+        Ok(Kind::ListValue(ListValue { values: self.0 }))
+    }
+}
+
+impl SerializeSeq for ListSerializer {
+    type Ok = Kind;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Kind, Error> {
+        ListSerializer::end(self)
+    }
+}
+
+impl SerializeTuple for ListSerializer {
+    type Ok = Kind;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Kind, Error> {
+        ListSerializer::end(self)
+    }
+}
+
+impl SerializeTupleStruct for ListSerializer {
+    type Ok = Kind;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Kind, Error> {
+        ListSerializer::end(self)
+    }
+}
+
+/// Json serializes the nested value with serde_json and writes it as JSON.
+#[cfg(feature = "serde_json")]
+struct Json<S>(S);
+
+#[cfg(feature = "serde_json")]
+impl<S> SerializeMap for Json<S>
+where
+    S: SerializeMap<Ok = serde_json::Value, Error = serde_json::Error>,
+{
+    type Ok = Kind;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        Ok(self.0.serialize_key(key)?)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        Ok(self.0.serialize_value(value)?)
+    }
+
+    fn end(self) -> Result<Kind, Error> {
+        Ok(self.0.end()?.to_kind())
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl<S> SerializeStruct for Json<S>
+where
+    S: SerializeStruct<Ok = serde_json::Value, Error = serde_json::Error>,
+{
+    type Ok = Kind;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        Ok(self.0.serialize_field(key, value)?)
+    }
+
+    fn end(self) -> Result<Kind, Error> {
+        Ok(self.0.end()?.to_kind())
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl<S> SerializeTupleVariant for Json<S>
+where
+    S: SerializeTupleVariant<Ok = serde_json::Value, Error = serde_json::Error>,
+{
+    type Ok = Kind;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        Ok(self.0.serialize_field(value)?)
+    }
+
+    fn end(self) -> Result<Kind, Error> {
+        Ok(self.0.end()?.to_kind())
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl<S> SerializeStructVariant for Json<S>
+where
+    S: SerializeStructVariant<Ok = serde_json::Value, Error = serde_json::Error>,
+{
+    type Ok = Kind;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        Ok(self.0.serialize_field(key, value)?)
+    }
+
+    fn end(self) -> Result<Kind, Error> {
+        Ok(self.0.end()?.to_kind())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use prost_types::value::Kind;
+    use serde::Serialize;
+
+    use crate::ser::{to_columns, Error};
+    use crate::statement::ToKind;
+
+    #[derive(Serialize)]
+    enum Status {
+        Active,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct User {
+        user_id: String,
+        level: u8,
+        premium: bool,
+        score: f64,
+        nickname: Option<String>,
+        tags: Vec<Option<&'static str>>,
+        status: Status,
+        #[serde(rename = "Id")]
+        numeric_id: u64,
+    }
+
+    fn user() -> User {
+        User {
+            user_id: "user1".to_string(),
+            level: 10,
+            premium: true,
+            score: 1.5,
+            nickname: None,
+            tags: vec![Some("a"), None],
+            status: Status::Active,
+            numeric_id: 1,
+        }
+    }
+
+    #[test]
+    fn test_to_columns() {
+        let (columns, values) = to_columns(&user()).unwrap();
+        assert_eq!(
+            vec!["UserId", "Level", "Premium", "Score", "Nickname", "Tags", "Status", "Id"],
+            columns
+        );
+        let kinds: Vec<Kind> = values.into_iter().map(|v| v.kind.unwrap()).collect();
+        assert_eq!(
+            vec![
+                "user1".to_kind(),
+                10_i64.to_kind(),
+                true.to_kind(),
+                1.5_f64.to_kind(),
+                Option::<String>::None.to_kind(),
+                vec![Some("a"), None].to_kind(),
+                "Active".to_kind(),
+                1_i64.to_kind(),
+            ],
+            kinds
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert("UserId", "user1");
+        let (columns, values) = to_columns(&map).unwrap();
+        assert_eq!(vec!["UserId"], columns);
+        assert_eq!(Some("user1".to_kind()), values[0].kind);
+    }
+
+    #[test]
+    fn test_to_columns_error() {
+        let mut user = user();
+        user.numeric_id = u64::MAX;
+        match to_columns(&user).unwrap_err() {
+            Error::Column(column, _) => assert_eq!("Id", column),
+            e => panic!("unexpected error {e:?}"),
+        }
+        assert!(matches!(to_columns(&1_i64), Err(Error::UnsupportedTopLevel("i64"))));
+        assert!(matches!(to_columns(&vec![user]), Err(Error::UnsupportedTopLevel("sequence"))));
+
+        let mut map = BTreeMap::new();
+        map.insert(1, "user1");
+        assert!(matches!(to_columns(&map), Err(Error::UnsupportedKey("i32"))));
+    }
+
+    #[derive(Serialize)]
+    struct Item {
+        name: &'static str,
+        count: i64,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Inventory {
+        user_id: &'static str,
+        item: Item,
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_to_columns_json() {
+        let inventory = Inventory {
+            user_id: "user1",
+            item: Item {
+                name: "sword",
+                count: 1,
+            },
+        };
+        let (columns, values) = to_columns(&inventory).unwrap();
+        assert_eq!(vec!["UserId", "Item"], columns);
+        assert_eq!(Some(r#"{"count":1,"name":"sword"}"#.to_kind()), values[1].kind);
+    }
+
+    #[cfg(not(feature = "serde_json"))]
+    #[test]
+    fn test_to_columns_json_required() {
+        let inventory = Inventory {
+            user_id: "user1",
+            item: Item {
+                name: "sword",
+                count: 1,
+            },
+        };
+        match to_columns(&inventory).unwrap_err() {
+            Error::Column(column, e) => {
+                assert_eq!("Item", column);
+                assert!(matches!(*e, Error::JsonRequired("struct")));
+            }
+            e => panic!("unexpected error {e:?}"),
+        }
+    }
+}
@@ -1,5 +1,7 @@
-use std::collections::VecDeque;
+use std::backtrace::Backtrace;
+use std::collections::{HashMap, VecDeque};
 use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -7,7 +9,9 @@ use parking_lot::Mutex;
 use thiserror;
 use tokio::select;
 
-use google_cloud_googleapis::spanner::v1::{BatchCreateSessionsRequest, DeleteSessionRequest, Session};
+use google_cloud_googleapis::spanner::v1::{
+    BatchCreateSessionsRequest, CreateSessionRequest, DeleteSessionRequest, RollbackRequest, Session,
+};
 
 use crate::apiv1::conn_pool::ConnectionManager;
 use crate::apiv1::spanner_client::{ping_query_request, Client};
@@ -21,12 +25,26 @@ use tokio::task::JoinHandle;
 use tokio::time::{sleep, timeout, Duration};
 
 type Waiters = Mutex<VecDeque<oneshot::Sender<SessionHandle>>>;
+type Checkouts = Mutex<HashMap<u64, Checkout>>;
+
+/// The maximum number of the sessions created by a BatchCreateSessions call.
+const MAX_BATCH_CREATE_SESSIONS: usize = 100;
+
+/// The age of the multiplexed session to be replaced with the new one, before it expires in about 7 days.
+const MULTIPLEXED_SESSION_REFRESH_AGE: Duration = Duration::from_secs(6 * 24 * 60 * 60);
+
+/// The period after the failure of a channel in which the sessions are not moved to the channel.
+const CHANNEL_FAILURE_PERIOD: Duration = Duration::from_secs(60);
 
 /// Session
 pub struct SessionHandle {
     pub session: Session,
     pub spanner_client: Client,
     valid: bool,
+    /// Whether the channel of the session failed, after which the session is moved to another channel.
+    channel_failed: bool,
+    /// The ID of the checkout recorded by the leak detection.
+    checkout_id: u64,
     last_used_at: std::time::Instant,
     last_checked_at: std::time::Instant,
     last_pong_at: std::time::Instant,
@@ -38,6 +56,8 @@ impl SessionHandle {
             session,
             spanner_client,
             valid: true,
+            channel_failed: false,
+            checkout_id: 0,
             last_used_at: now,
             last_checked_at: now,
             last_pong_at: now,
@@ -50,6 +70,9 @@ impl SessionHandle {
             Err(e) => {
                 if e.code() == Code::NotFound && e.message().contains("Session not found:") {
                     self.invalidate().await;
+                } else if e.code() == Code::Unavailable {
+                    // the request is already retried, the session is moved to another channel when it is recycled.
+                    self.channel_failed = true;
                 }
                 Err(e)
             }
@@ -58,6 +81,11 @@ impl SessionHandle {
 
     async fn invalidate(&mut self) {
         tracing::debug!("session invalidate {}", self.session.name);
+        if self.session.multiplexed {
+            // the multiplexed session can not be deleted, it is replaced on the next refresh.
+            self.valid = false;
+            return;
+        }
         let request = DeleteSessionRequest {
             name: self.session.name.to_string(),
         };
@@ -70,25 +98,61 @@ impl SessionHandle {
     }
 }
 
+enum SessionOwner {
+    Pool(SessionPool),
+    Multiplexed(Arc<MultiplexedSession>),
+    /// Detached is the session owned by another client, such as the session of the batch read-only transaction
+    /// begun in another process. It is neither recycled nor deleted by this client.
+    Detached,
+}
+
 /// ManagedSession
 pub struct ManagedSession {
-    session_pool: SessionPool,
+    owner: SessionOwner,
     session: Option<SessionHandle>,
 }
 
 impl ManagedSession {
-    pub(crate) fn new(session_pool: SessionPool, session: SessionHandle) -> Self {
+    pub(crate) fn new(session_pool: SessionPool, mut session: SessionHandle) -> Self {
+        session_pool.check_out(&mut session);
         ManagedSession {
-            session_pool,
+            owner: SessionOwner::Pool(session_pool),
             session: Some(session),
         }
     }
+
+    pub(crate) fn detached(session: SessionHandle) -> Self {
+        ManagedSession {
+            owner: SessionOwner::Detached,
+            session: Some(session),
+        }
+    }
+
+    /// is_multiplexed returns true if the session is the multiplexed session shared by the read-only requests.
+    pub fn is_multiplexed(&self) -> bool {
+        matches!(self.owner, SessionOwner::Multiplexed(_))
+    }
+
+    /// begun records the read-write transaction begun on the session, which is rolled back if the session is leaked.
+    pub(crate) fn begun(&self, transaction_id: &[u8]) {
+        if let SessionOwner::Pool(session_pool) = &self.owner {
+            session_pool.begun(self, transaction_id);
+        }
+    }
 }
 
 impl Drop for ManagedSession {
     fn drop(&mut self) {
         let session = self.session.take().unwrap();
-        self.session_pool.recycle(session);
+        match &self.owner {
+            SessionOwner::Pool(session_pool) => session_pool.recycle(session),
+            SessionOwner::Multiplexed(multiplexed) => {
+                if !session.valid {
+                    multiplexed.invalidate(&session.session.name);
+                }
+            }
+            SessionOwner::Detached => {}
+        }
     }
 }
 
@@ -109,6 +173,8 @@ impl DerefMut for ManagedSession {
 pub struct Sessions {
     sessions: VecDeque<SessionHandle>,
     inuse: usize,
+    /// Whether the pool is closed, after which the returned sessions are deleted instead of pooled.
+    closed: bool,
 }
 
 impl Sessions {
@@ -120,6 +186,10 @@ impl Sessions {
         self.inuse + self.sessions.len()
     }
 
+    fn stats(&self) -> (usize, usize) {
+        (self.sessions.len(), self.inuse)
+    }
+
     fn take(&mut self) -> Option<SessionHandle> {
         match self.sessions.pop_front() {
             None => None,
@@ -142,50 +212,122 @@ pub struct SessionPool {
     inner: Arc<Mutex<Sessions>>,
     waiters: Arc<Waiters>,
     allocation_request_sender: broadcast::Sender<bool>,
+    counters: Arc<PoolCounters>,
+    /// The sessions checked out from the pool, which are recorded only with the leak detection.
+    checkouts: Option<Arc<Checkouts>>,
+    /// The ID of the last checkout.
+    checkout_id: Arc<AtomicU64>,
+    /// The total number of the sessions created for the pool.
+    created: Arc<AtomicU64>,
+    conn_pool: Arc<ConnectionManager>,
+    /// The time each of the channels failed last.
+    channel_failures: Arc<Mutex<Vec<Option<Instant>>>>,
+}
+
+/// Checkout is the record of the session checked out from the pool for the leak detection.
+struct Checkout {
+    session: Session,
+    checked_out_at: Instant,
+    backtrace: Option<Arc<Backtrace>>,
+    spanner_client: Client,
+    /// The read-write transaction begun on the session last.
+    transaction_id: Option<Vec<u8>>,
+    /// Set when the session is evicted, after which the requests of the holder fail.
+    revoked: Arc<AtomicBool>,
+    reported: bool,
+    evicted: bool,
+}
+
+/// CheckedOutSession is the session checked out from the pool, which is recorded with the leak detection.
+#[derive(Clone, Debug)]
+pub struct CheckedOutSession {
+    pub name: String,
+    /// The time elapsed since the session was checked out.
+    pub age: Duration,
+    /// The backtrace of the checkout, which is captured with the session-leak-backtrace feature.
+    pub backtrace: Option<String>,
+    /// Whether the session was taken back from the holder as leaked with SessionConfig::evict_leaked_sessions.
+    pub evicted: bool,
+}
+
+#[cfg(feature = "session-leak-backtrace")]
+fn capture_backtrace() -> Option<Arc<Backtrace>> {
+    Some(Arc::new(Backtrace::force_capture()))
+}
+
+#[cfg(not(feature = "session-leak-backtrace"))]
+fn capture_backtrace() -> Option<Arc<Backtrace>> {
+    None
+}
+
+/// The upper bounds of the buckets of the acquisition wait histogram, followed by the bucket without the bound.
+const ACQUISITION_WAIT_BOUNDS: [Duration; 8] = [
+    Duration::from_millis(1),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(5),
+];
+
+/// PoolCounters counts the events of the pool, which only costs the atomic additions.
+#[derive(Default)]
+struct PoolCounters {
+    pings: AtomicU64,
+    evictions: AtomicU64,
+    leak_evictions: AtomicU64,
+    deleted: AtomicU64,
+    rehomed: AtomicU64,
+    acquisition_timeouts: AtomicU64,
+    acquisition_wait_nanos: AtomicU64,
+    acquisition_wait_buckets: [AtomicU64; ACQUISITION_WAIT_BOUNDS.len() + 1],
+}
+
+impl PoolCounters {
+    fn record_acquisition(&self, wait: Duration) {
+        self.acquisition_wait_buckets[acquisition_wait_bucket(wait)].fetch_add(1, Ordering::Relaxed);
+        self.acquisition_wait_nanos
+            .fetch_add(wait.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// acquisition_wait_bucket returns the index of the bucket of the acquisition wait histogram.
+fn acquisition_wait_bucket(wait: Duration) -> usize {
+    ACQUISITION_WAIT_BOUNDS
+        .iter()
+        .position(|bound| wait <= *bound)
+        .unwrap_or(ACQUISITION_WAIT_BOUNDS.len())
 }
 
 impl SessionPool {
     async fn new(
         database: String,
-        conn_pool: &ConnectionManager,
+        conn_pool: Arc<ConnectionManager>,
         min_opened: usize,
         allocation_request_sender: broadcast::Sender<bool>,
-    ) -> Result<Self, Status> {
-        let init_pool = Self::init_pool(database, conn_pool, min_opened).await?;
+        leak_detection: bool,
+    ) -> Self {
+        let (init_pool, _) = create_sessions_on_channels(&database, &conn_pool, min_opened).await;
+        tracing::debug!("initial session created count = {}", init_pool.len());
         let waiters = Arc::new(Waiters::new(VecDeque::new()));
 
-        Ok(SessionPool {
+        SessionPool {
+            created: Arc::new(AtomicU64::new(init_pool.len() as u64)),
             inner: Arc::new(Mutex::new(Sessions {
-                sessions: init_pool,
+                sessions: init_pool.into(),
                 inuse: 0,
+                closed: false,
             })),
             waiters,
             allocation_request_sender,
-        })
-    }
-
-    async fn init_pool(
-        database: String,
-        conn_pool: &ConnectionManager,
-        min_opened: usize,
-    ) -> Result<VecDeque<SessionHandle>, Status> {
-        let channel_num = conn_pool.num();
-        let creation_count_per_channel = min_opened / channel_num;
-
-        let mut sessions = Vec::<SessionHandle>::new();
-        for _ in 0..channel_num {
-            let next_client = conn_pool.conn();
-            match batch_create_session(next_client, database.clone(), creation_count_per_channel).await {
-                Ok(r) => {
-                    for i in r {
-                        sessions.push(i);
-                    }
-                }
-                Err(e) => return Err(e),
-            }
+            counters: Arc::new(PoolCounters::default()),
+            checkouts: leak_detection.then(|| Arc::new(Mutex::new(HashMap::new()))),
+            checkout_id: Arc::new(AtomicU64::new(0)),
+            channel_failures: Arc::new(Mutex::new(vec![None; conn_pool.num()])),
+            conn_pool,
         }
-        tracing::debug!("initial session created count = {}", sessions.len());
-        Ok(sessions.into())
     }
 
     fn request(&self) -> oneshot::Receiver<SessionHandle> {
@@ -206,6 +348,7 @@ impl SessionPool {
     }
 
     fn grow(&self, mut sessions: Vec<SessionHandle>) {
+        self.created.fetch_add(sessions.len() as u64, Ordering::Relaxed);
         while let Some(session) = sessions.pop() {
             match { self.waiters.lock().pop_front() } {
                 Some(c) => {
@@ -223,19 +366,130 @@ impl SessionPool {
         }
     }
 
-    fn recycle(&self, session: SessionHandle) {
+    fn check_out(&self, session: &mut SessionHandle) {
+        if let Some(checkouts) = &self.checkouts {
+            // the ID distinguishes the checkout from the next one of the session taken back as leaked.
+            session.checkout_id = self.checkout_id.fetch_add(1, Ordering::Relaxed) + 1;
+            let revoked = Arc::new(AtomicBool::new(false));
+            let checkout = Checkout {
+                session: session.session.clone(),
+                checked_out_at: Instant::now(),
+                backtrace: capture_backtrace(),
+                spanner_client: session.spanner_client.clone(),
+                transaction_id: None,
+                revoked: Arc::clone(&revoked),
+                reported: false,
+                evicted: false,
+            };
+            session.spanner_client = session.spanner_client.clone().revocable(revoked);
+            checkouts.lock().insert(session.checkout_id, checkout);
+        }
+    }
+
+    /// check_in returns true if the session returned to the pool was evicted as leaked.
+    fn check_in(&self, session: &SessionHandle) -> bool {
+        match &self.checkouts {
+            Some(checkouts) => checkouts
+                .lock()
+                .remove(&session.checkout_id)
+                .map(|c| c.evicted)
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+
+    fn begun(&self, session: &SessionHandle, transaction_id: &[u8]) {
+        if let Some(checkouts) = &self.checkouts {
+            if let Some(checkout) = checkouts.lock().get_mut(&session.checkout_id) {
+                checkout.transaction_id = Some(transaction_id.to_vec());
+            }
+        }
+    }
+
+    /// release returns the session to the pool, or deletes it if the pool is already closed.
+    fn release(&self, mut session: SessionHandle) {
+        let mut inner = self.inner.lock();
+        if !inner.closed || !session.valid {
+            inner.release(session);
+            return;
+        }
+        inner.inuse -= 1;
+        drop(inner);
+        tracing::trace!("session returned after close name={}", session.session.name);
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let session_pool = self.clone();
+            handle.spawn(async move { delete_session(&mut session, &session_pool).await });
+        }
+    }
+
+    /// hand_over passes the valid session to the first waiter, or returns it to the pool if no one is waiting.
+    fn hand_over(&self, session: SessionHandle) {
+        match { self.waiters.lock().pop_front() } {
+            Some(c) => {
+                if let Err(session) = c.send(session) {
+                    self.release(session)
+                }
+            }
+            None => self.release(session),
+        };
+    }
+
+    /// idle_per_channel returns the number of the idle sessions on each of the channels.
+    fn idle_per_channel(&self) -> Vec<usize> {
+        let mut idle_per_channel = vec![0; self.conn_pool.num()];
+        for session in &self.inner.lock().sessions {
+            idle_per_channel[session.spanner_client.channel()] += 1;
+        }
+        idle_per_channel
+    }
+
+    /// rehome moves the session whose channel failed to the healthy channel with the fewest idle sessions,
+    /// so that the sessions are not kept on the broken connection while the channel reconnects.
+    /// The session stays on its channel if no other channel is healthy.
+    fn rehome(&self, session: &mut SessionHandle) {
+        if !session.channel_failed {
+            return;
+        }
+        session.channel_failed = false;
+        let failed = session.spanner_client.channel();
+        let now = Instant::now();
+        let healthy: Vec<bool> = {
+            let mut failures = self.channel_failures.lock();
+            failures[failed] = Some(now);
+            failures
+                .iter()
+                .map(|failed_at| failed_at.is_none_or(|at| at + CHANNEL_FAILURE_PERIOD <= now))
+                .collect()
+        };
+        let channel = match choose_channel(failed, &self.idle_per_channel(), &healthy) {
+            Some(channel) => channel,
+            None => {
+                tracing::debug!("no healthy channel to move the session from channel {}", failed);
+                return;
+            }
+        };
+        session.spanner_client = self.conn_pool.conn_at(channel);
+        self.counters.rehomed.fetch_add(1, Ordering::Relaxed);
+        tracing::debug!(
+            "session moved from channel {} to {} name={}",
+            failed,
+            channel,
+            session.session.name
+        );
+    }
+
+    fn recycle(&self, mut session: SessionHandle) {
+        if self.check_in(&session) {
+            // the evicted session is already returned to the pool or deleted, and no longer counted as in use.
+            tracing::trace!("evicted session returned name={}", session.session.name);
+            return;
+        }
         if session.valid {
+            self.rehome(&mut session);
             tracing::trace!("recycled name={}", session.session.name);
-            match { self.waiters.lock().pop_front() } {
-                Some(c) => {
-                    if let Err(session) = c.send(session) {
-                        self.inner.lock().release(session)
-                    }
-                }
-                None => self.inner.lock().release(session),
-            };
+            self.hand_over(session);
         } else {
-            self.inner.lock().release(session);
+            self.release(session);
 
             // request session creation
             let _ = self.allocation_request_sender.send(true);
@@ -243,12 +497,79 @@ impl SessionPool {
     }
 }
 
+/// MultiplexedSession holds the multiplexed session, which can be used by any number of the read-only requests
+/// at the same time, and is not checked out from the pool.
+struct MultiplexedSession {
+    current: Mutex<Option<(Session, Client, Instant)>>,
+    uses: AtomicU64,
+}
+
+impl MultiplexedSession {
+    fn new() -> Self {
+        MultiplexedSession {
+            current: Mutex::new(None),
+            uses: AtomicU64::new(0),
+        }
+    }
+
+    fn get(self: &Arc<Self>) -> Option<ManagedSession> {
+        let (session, client) = {
+            let current = self.current.lock();
+            let (session, client, _) = current.as_ref()?;
+            (session.clone(), client.clone())
+        };
+        self.uses.fetch_add(1, Ordering::Relaxed);
+        Some(ManagedSession {
+            owner: SessionOwner::Multiplexed(Arc::clone(self)),
+            session: Some(SessionHandle::new(session, client, Instant::now())),
+        })
+    }
+
+    fn replace(&self, session: Session, client: Client, now: Instant) {
+        tracing::debug!("multiplexed session created name={}", session.name);
+        *self.current.lock() = Some((session, client, now));
+    }
+
+    /// invalidate removes the session unless it is already replaced, so that the pool is used until the next refresh.
+    fn invalidate(&self, name: &str) {
+        let mut current = self.current.lock();
+        if matches!(current.as_ref(), Some((session, _, _)) if session.name == name) {
+            tracing::debug!("multiplexed session invalidated name={}", name);
+            *current = None;
+        }
+    }
+
+    fn needs_refresh(&self, now: Instant) -> bool {
+        match self.current.lock().as_ref() {
+            Some((_, _, created_at)) => needs_refresh(*created_at, now),
+            None => true,
+        }
+    }
+}
+
+/// choose_channel returns the healthy channel other than the failed one with the fewest idle sessions.
+fn choose_channel(failed: usize, idle_per_channel: &[usize], healthy: &[bool]) -> Option<usize> {
+    (0..idle_per_channel.len())
+        .filter(|channel| *channel != failed && healthy[*channel])
+        .min_by_key(|channel| idle_per_channel[*channel])
+}
+
+fn needs_refresh(created_at: Instant, now: Instant) -> bool {
+    created_at + MULTIPLEXED_SESSION_REFRESH_AGE <= now
+}
+
 impl Clone for SessionPool {
     fn clone(&self) -> Self {
         SessionPool {
             inner: Arc::clone(&self.inner),
             waiters: Arc::clone(&self.waiters),
             allocation_request_sender: self.allocation_request_sender.clone(),
+            counters: Arc::clone(&self.counters),
+            checkouts: self.checkouts.clone(),
+            checkout_id: Arc::clone(&self.checkout_id),
+            created: Arc::clone(&self.created),
+            conn_pool: Arc::clone(&self.conn_pool),
+            channel_failures: Arc::clone(&self.channel_failures),
         }
     }
 }
@@ -277,14 +598,44 @@ pub struct SessionConfig {
     /// However, if the number of sessions is less than or equal to min_opened, it will not be discarded.
     pub idle_timeout: std::time::Duration,
 
+    /// session_alive_trust_duration is the duration for which an idle session is trusted to be alive.
+    /// The health check pings the idle sessions not used nor pinged within this duration by SELECT 1,
+    /// so that they are not deleted by the server, which deletes the sessions idle for about an hour.
+    /// The sessions are pinged one by one with a short interval, and the ones failing the ping are deleted
+    /// and replaced with new sessions.
     pub session_alive_trust_duration: std::time::Duration,
 
+    /// health_check_enabled enables the health check run every refresh_interval.
+    pub health_check_enabled: bool,
+
     /// session_get_timeout is the maximum value of the waiting time that occurs when retrieving from the connection pool when there is no idle session.
     pub session_get_timeout: std::time::Duration,
 
     /// refresh_interval is the interval of cleanup and health check functions.
     pub refresh_interval: std::time::Duration,
 
+    /// leak_detection_threshold enables the leak detection, which records the sessions checked out from the pool
+    /// and logs a warning for each session checked out longer than the threshold, such as the one held by
+    /// a transaction that is never completed. The backtrace of the checkout is logged with the
+    /// session-leak-backtrace feature. Client::checked_out_sessions returns the recorded sessions.
+    pub leak_detection_threshold: Option<std::time::Duration>,
+
+    /// evict_leaked_sessions takes back the sessions detected as leaked from their holders, whose requests fail
+    /// with FailedPrecondition after that. The session is returned to the pool if its read-write transaction is
+    /// rolled back. Otherwise, such as the session of the read-only transaction or the session whose rollback fails,
+    /// it is deleted so that a new session can be created in place of it.
+    pub evict_leaked_sessions: bool,
+
+    /// use_multiplexed_session enables the multiplexed session for the single-use reads and the read-only transactions,
+    /// so that they don't check out the sessions from the pool. The read-write transactions and the partitioned
+    /// reads and queries still use the pool. The pool is used too while the multiplexed session is not available.
+    pub use_multiplexed_session: bool,
+
+    /// close_timeout is the maximum time Client::close waits for the checked-out sessions to be returned
+    /// and the background tasks to stop, before deleting the sessions in the pool.
+    /// The sessions returned later are deleted as they are returned.
+    pub close_timeout: std::time::Duration,
+
     /// incStep is the number of sessions to create in one batch when at least
     /// one more session is needed.
     inc_step: usize,
@@ -301,12 +652,58 @@ impl Default for SessionConfig {
             session_alive_trust_duration: std::time::Duration::from_secs(55 * 60),
             session_get_timeout: std::time::Duration::from_secs(1),
             refresh_interval: std::time::Duration::from_secs(5 * 60),
+            health_check_enabled: true,
+            leak_detection_threshold: None,
+            evict_leaked_sessions: false,
+            use_multiplexed_session: false,
+            close_timeout: std::time::Duration::from_secs(5),
         }
     }
 }
 
+/// SessionPoolStats is the snapshot of the session counts of the pool.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionPoolStats {
+    /// The number of the opened sessions, which is idle + in_use.
+    pub opened: usize,
+    /// The number of the sessions in the pool waiting to be used.
+    pub idle: usize,
+    /// The number of the sessions checked out from the pool.
+    pub in_use: usize,
+    /// The number of the requests waiting for a session.
+    pub waiters: usize,
+    /// The total number of the pings sent by the health check to keep the idle sessions alive.
+    pub health_check_pings: u64,
+    /// The total number of the sessions removed from the pool because their pings failed.
+    pub health_check_evictions: u64,
+    /// The total number of the sessions created for the pool, including the ones created at the start.
+    pub sessions_created: u64,
+    /// The total number of the sessions deleted by the pool, such as the idle sessions above max_idle.
+    pub sessions_deleted: u64,
+    /// The total number of the sessions taken back as leaked with SessionConfig::evict_leaked_sessions.
+    pub leak_evictions: u64,
+    /// The total number of the sessions acquired from the pool.
+    pub acquisitions: u64,
+    /// The total number of the acquisitions failed because no session was available within session_get_timeout.
+    pub acquisition_timeouts: u64,
+    /// The total time the acquisitions waited for a session.
+    pub acquisition_wait_total: Duration,
+    /// The histogram of the time the acquisitions waited for a session, as the pairs of the upper bound of the bucket
+    /// and the number of the acquisitions in it. The last bucket is unbounded and has Duration::MAX.
+    /// The growing counts of the slower buckets are the early warning of the pool exhaustion.
+    pub acquisition_wait_buckets: Vec<(Duration, u64)>,
+    /// The number of the idle sessions on each of the gRPC channels, indexed by the channel.
+    pub idle_per_channel: Vec<usize>,
+    /// The total number of the sessions moved to another channel because their channel failed.
+    pub sessions_rehomed: u64,
+}
+
 pub struct SessionManager {
     session_pool: SessionPool,
+    config: SessionConfig,
+    database: String,
+    conn_pool: Arc<ConnectionManager>,
+    multiplexed: Option<Arc<MultiplexedSession>>,
     session_get_timeout: Duration,
     cancel: CancellationToken,
     tasks: Vec<JoinHandle<()>>,
@@ -318,6 +715,8 @@ pub enum SessionError {
     SessionGetTimeout,
     #[error("failed to create session")]
     FailedToCreateSession,
+    #[error("client closed")]
+    Closed,
     #[error(transparent)]
     GRPC(#[from] Status),
 }
@@ -339,25 +738,66 @@ impl SessionManager {
     ) -> Result<SessionManager, Status> {
         let database = database.into();
         let (sender, receiver) = broadcast::channel(1);
-        let session_pool = SessionPool::new(database.clone(), &conn_pool, config.min_opened, sender).await?;
+        let conn_pool = Arc::new(conn_pool);
+        let session_pool = SessionPool::new(
+            database.clone(),
+            Arc::clone(&conn_pool),
+            config.min_opened,
+            sender,
+            config.leak_detection_threshold.is_some(),
+        )
+        .await;
 
         let cancel = CancellationToken::new();
         let session_get_timeout = config.session_get_timeout;
+        let mut tasks = vec![];
+        let multiplexed = if config.use_multiplexed_session {
+            let multiplexed = Arc::new(MultiplexedSession::new());
+            refresh_multiplexed_session(&multiplexed, conn_pool.conn(), database.clone()).await;
+            tasks.push(schedule_multiplexed_session_refresh(
+                config.refresh_interval,
+                Arc::clone(&multiplexed),
+                conn_pool.conn(),
+                database.clone(),
+                cancel.clone(),
+            ));
+            Some(multiplexed)
+        } else {
+            None
+        };
         let task_cleaner = schedule_refresh(config.clone(), session_pool.clone(), cancel.clone());
         let task_listener = listen_session_creation_request(
-            config,
+            config.clone(),
             session_pool.clone(),
-            database,
-            conn_pool,
+            database.clone(),
+            Arc::clone(&conn_pool),
             receiver,
             cancel.clone(),
         );
 
+        tasks.push(task_cleaner);
+        tasks.push(task_listener);
+        if let Some(threshold) = config.leak_detection_threshold {
+            tasks.push(schedule_leak_detection(
+                threshold,
+                config.evict_leaked_sessions,
+                session_pool.clone(),
+                cancel.clone(),
+            ));
+        }
+        if session_pool.num_opened() < config.min_opened {
+            // the rest of the initial sessions are created in the background.
+            let _ = session_pool.allocation_request_sender.send(true);
+        }
         let sm = SessionManager {
             session_get_timeout,
             session_pool,
+            config,
+            database,
+            conn_pool,
+            multiplexed,
             cancel,
-            tasks: vec![task_cleaner, task_listener],
+            tasks,
         };
         Ok(sm)
     }
@@ -370,9 +810,105 @@ impl SessionManager {
         self.session_pool.num_waiting()
     }
 
+    /// config returns the SessionConfig the pool is running with.
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    /// stats returns the current session counts of the pool.
+    pub fn stats(&self) -> SessionPoolStats {
+        let idle_per_channel = self.session_pool.idle_per_channel();
+        let (idle, in_use) = self.session_pool.inner.lock().stats();
+        let counters = &self.session_pool.counters;
+        let acquisition_wait_buckets: Vec<(Duration, u64)> = ACQUISITION_WAIT_BOUNDS
+            .iter()
+            .copied()
+            .chain(std::iter::once(Duration::MAX))
+            .zip(counters.acquisition_wait_buckets.iter())
+            .map(|(bound, count)| (bound, count.load(Ordering::Relaxed)))
+            .collect();
+        SessionPoolStats {
+            opened: idle + in_use,
+            idle,
+            in_use,
+            waiters: self.session_pool.num_waiting(),
+            health_check_pings: counters.pings.load(Ordering::Relaxed),
+            health_check_evictions: counters.evictions.load(Ordering::Relaxed),
+            sessions_created: self.session_pool.created.load(Ordering::Relaxed),
+            sessions_deleted: counters.deleted.load(Ordering::Relaxed),
+            leak_evictions: counters.leak_evictions.load(Ordering::Relaxed),
+            acquisitions: acquisition_wait_buckets.iter().map(|(_, count)| count).sum(),
+            acquisition_timeouts: counters.acquisition_timeouts.load(Ordering::Relaxed),
+            acquisition_wait_total: Duration::from_nanos(counters.acquisition_wait_nanos.load(Ordering::Relaxed)),
+            acquisition_wait_buckets,
+            idle_per_channel,
+            sessions_rehomed: counters.rehomed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// warmup creates the sessions missing from SessionConfig::min_opened, such as the ones failed to be created
+    /// when the pool started, and returns the number of the sessions created.
+    /// The sessions are created by BatchCreateSessions spread over the channels.
+    /// The sessions created before an error are kept in the pool even if the error is returned.
+    pub async fn warmup(&self) -> Result<usize, SessionError> {
+        let num_opened = self.session_pool.num_opened();
+        let count = std::cmp::min(
+            self.config.min_opened.saturating_sub(num_opened),
+            self.config.max_opened.saturating_sub(num_opened),
+        );
+        let (sessions, error) = create_sessions_on_channels(&self.database, &self.conn_pool, count).await;
+        let created = sessions.len();
+        self.session_pool.grow(sessions);
+        match error {
+            Some(e) => Err(e.into()),
+            None => Ok(created),
+        }
+    }
+
+    /// checked_out_sessions returns the sessions checked out from the pool in the descending order of their ages.
+    /// It is empty unless SessionConfig::leak_detection_threshold is set.
+    pub fn checked_out_sessions(&self) -> Vec<CheckedOutSession> {
+        let now = Instant::now();
+        let mut sessions: Vec<CheckedOutSession> = match &self.session_pool.checkouts {
+            Some(checkouts) => checkouts
+                .lock()
+                .values()
+                .map(|c| CheckedOutSession {
+                    name: c.session.name.clone(),
+                    age: now.saturating_duration_since(c.checked_out_at),
+                    backtrace: c.backtrace.as_ref().map(|b| b.to_string()),
+                    evicted: c.evicted,
+                })
+                .collect(),
+            None => vec![],
+        };
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.age));
+        sessions
+    }
+
+    /// multiplexed_session_uses returns the number of the requests which used the multiplexed session.
+    pub fn multiplexed_session_uses(&self) -> u64 {
+        self.multiplexed
+            .as_ref()
+            .map(|m| m.uses.load(Ordering::Relaxed))
+            .unwrap_or_default()
+    }
+
     pub async fn get(&self) -> Result<ManagedSession, SessionError> {
-        if let Some(mut s) = self.session_pool.inner.lock().take() {
-            s.last_used_at = Instant::now();
+        if self.cancel.is_cancelled() {
+            return Err(SessionError::Closed);
+        }
+        let start = Instant::now();
+        let session = {
+            let mut s = self.session_pool.inner.lock().take();
+            if let Some(s) = s.as_mut() {
+                s.last_used_at = Instant::now();
+            }
+            s
+        };
+        if let Some(s) = session {
+            tracing::trace!("use pooled session name={}", s.session.name);
+            self.session_pool.counters.record_acquisition(start.elapsed());
             return Ok(ManagedSession::new(self.session_pool.clone(), s));
         }
 
@@ -380,34 +916,91 @@ impl SessionManager {
         match timeout(self.session_get_timeout, self.session_pool.request()).await {
             Ok(Ok(mut session)) => {
                 session.last_used_at = Instant::now();
-                Ok(ManagedSession {
-                    session_pool: self.session_pool.clone(),
-                    session: Some(session),
-                })
+                tracing::trace!("use pooled session name={}", session.session.name);
+                self.session_pool
+                    .counters
+                    .record_acquisition(session.last_used_at - start);
+                Ok(ManagedSession::new(self.session_pool.clone(), session))
+            }
+            _ if self.cancel.is_cancelled() => Err(SessionError::Closed),
+            _ => {
+                self.session_pool
+                    .counters
+                    .acquisition_timeouts
+                    .fetch_add(1, Ordering::Relaxed);
+                Err(SessionError::SessionGetTimeout)
             }
-            _ => Err(SessionError::SessionGetTimeout),
         }
     }
 
+    /// get_read_only returns the multiplexed session if it is enabled and available, otherwise the session in the pool.
+    pub async fn get_read_only(&self) -> Result<ManagedSession, SessionError> {
+        if self.cancel.is_cancelled() {
+            return Err(SessionError::Closed);
+        }
+        if let Some(session) = self.multiplexed.as_ref().and_then(|m| m.get()) {
+            tracing::trace!("use multiplexed session");
+            return Ok(session);
+        }
+        self.get().await
+    }
+
+    /// get_detached returns the session of the name owned by another client, which uses the connection of this client.
+    pub(crate) async fn get_detached(&self, name: String) -> Result<ManagedSession, SessionError> {
+        let spanner_client = self.get().await?.spanner_client.clone();
+        let session = Session {
+            name,
+            ..Default::default()
+        };
+        Ok(ManagedSession::detached(SessionHandle::new(
+            session,
+            spanner_client,
+            Instant::now(),
+        )))
+    }
+
+    /// close stops the background tasks, waits up to close_timeout for the checked-out sessions to be returned,
+    /// and deletes all the sessions in the pool. The subsequent requests fail with SessionError::Closed.
     pub(crate) async fn close(&self) {
         if self.cancel.is_cancelled() {
             return;
         }
         self.cancel.cancel();
-        sleep(Duration::from_secs(1)).await;
+        // fail the requests waiting for a session.
+        self.session_pool.waiters.lock().clear();
+
+        let deadline = Instant::now() + self.config.close_timeout;
+        while Instant::now() < deadline
+            && (self.session_pool.inner.lock().inuse > 0 || self.tasks.iter().any(|t| !t.is_finished()))
+        {
+            sleep(Duration::from_millis(10)).await;
+        }
         for task in &self.tasks {
             task.abort();
         }
         let deleting_sessions = {
             let mut lock = self.session_pool.inner.lock();
-            let mut deleting_sessions = Vec::with_capacity(lock.sessions.len());
-            while let Some(session) = lock.sessions.pop_front() {
-                deleting_sessions.push(session);
-            }
-            deleting_sessions
+            // the sessions returned from now on are deleted by SessionPool::release.
+            lock.closed = true;
+            tracing::debug!("closing session pool idle={} in_use={}", lock.sessions.len(), lock.inuse);
+            lock.sessions.drain(..).collect::<Vec<_>>()
         };
-        for mut session in deleting_sessions {
-            delete_session(&mut session).await;
+        let session_pool = &self.session_pool;
+        futures_util::future::join_all(
+            deleting_sessions
+                .into_iter()
+                .map(|mut session| async move { delete_session(&mut session, session_pool).await }),
+        )
+        .await;
+    }
+}
+
+impl Drop for SessionManager {
+    fn drop(&mut self) {
+        // stop the background tasks even if the client is dropped without close.
+        self.cancel.cancel();
+        for task in &self.tasks {
+            task.abort();
         }
     }
 }
@@ -416,7 +1009,7 @@ fn listen_session_creation_request(
     config: SessionConfig,
     session_pool: SessionPool,
     database: String,
-    conn_pool: ConnectionManager,
+    conn_pool: Arc<ConnectionManager>,
     mut rx: broadcast::Receiver<bool>,
     cancel: CancellationToken,
 ) -> JoinHandle<()> {
@@ -432,28 +1025,21 @@ fn listen_session_creation_request(
                 continue;
             }
 
-            let mut creation_count = config.max_opened - num_opened;
-            if creation_count > config.inc_step {
-                creation_count = config.inc_step;
-            }
+            // the sessions missing from min_opened are created at once.
+            let step = std::cmp::max(config.inc_step, config.min_opened.saturating_sub(num_opened));
+            let creation_count = std::cmp::min(config.max_opened.saturating_sub(num_opened), step);
             if creation_count == 0 {
                 continue;
             }
             allocation_request_size += creation_count;
 
-            let database = database.clone();
             let next_client = conn_pool.conn();
-
-            match batch_create_session(next_client, database, creation_count).await {
-                Ok(fresh_sessions) => {
-                    allocation_request_size -= creation_count;
-                    session_pool.grow(fresh_sessions)
-                }
-                Err(e) => {
-                    allocation_request_size -= creation_count;
-                    tracing::error!("failed to create new sessions {:?}", e)
-                }
-            };
+            let (fresh_sessions, error) = create_sessions(next_client, &database, creation_count).await;
+            allocation_request_size -= creation_count;
+            session_pool.grow(fresh_sessions);
+            if let Some(e) = error {
+                tracing::error!("failed to create new sessions {:?}", e)
+            }
         }
         tracing::trace!("stop session creating listener")
     })
@@ -469,27 +1055,18 @@ fn schedule_refresh(config: SessionConfig, session_pool: SessionPool, cancel: Ca
                 _ = interval.tick() => {},
                 _ = cancel.cancelled() => break
             }
+            if session_pool.num_opened() < config.min_opened {
+                // retry the creation of the sessions failed to be created.
+                let _ = session_pool.allocation_request_sender.send(true);
+            }
             let now = Instant::now();
-            let max_removing_count = session_pool.num_opened() as i64 - config.max_idle as i64;
-            if max_removing_count < 0 {
-                health_check(
-                    now + Duration::from_nanos(1),
-                    config.session_alive_trust_duration,
-                    &session_pool,
-                    cancel.clone(),
-                )
-                .await;
+            let max_removing_count = max_shrink_count(session_pool.num_opened(), &config);
+            if max_removing_count > 0 {
+                shrink_idle_sessions(now, config.idle_timeout, &session_pool, max_removing_count, cancel.clone()).await;
+            }
+            if !config.health_check_enabled {
                 continue;
             }
-
-            shrink_idle_sessions(
-                now,
-                config.idle_timeout,
-                &session_pool,
-                max_removing_count as usize,
-                cancel.clone(),
-            )
-            .await;
             health_check(
                 now + Duration::from_nanos(1),
                 config.session_alive_trust_duration,
@@ -502,6 +1079,114 @@ fn schedule_refresh(config: SessionConfig, session_pool: SessionPool, cancel: Ca
     })
 }
 
+fn schedule_leak_detection(
+    threshold: Duration,
+    evict: bool,
+    session_pool: SessionPool,
+    cancel: CancellationToken,
+) -> JoinHandle<()> {
+    let period = std::cmp::max(threshold / 2, Duration::from_millis(1));
+    let mut interval = tokio::time::interval_at((Instant::now() + period).into(), period);
+
+    tokio::spawn(async move {
+        loop {
+            select! {
+                _ = interval.tick() => {},
+                _ = cancel.cancelled() => break
+            }
+            detect_leaked_sessions(Instant::now(), threshold, evict, &session_pool).await;
+        }
+        tracing::trace!("stop session leak detection")
+    })
+}
+
+/// detect_leaked_sessions logs a warning once for each session checked out longer than the threshold.
+/// With evict, the leaked sessions are taken back from their holders, whose requests fail after that.
+/// The session is returned to the pool only if its read-write transaction is rolled back. Otherwise, such as
+/// the session of the read-only transaction or the session whose rollback fails, it is deleted and no longer
+/// counted as in use, so that a new session can be created.
+async fn detect_leaked_sessions(now: Instant, threshold: Duration, evict: bool, session_pool: &SessionPool) {
+    let checkouts = match &session_pool.checkouts {
+        Some(checkouts) => checkouts,
+        None => return,
+    };
+    let evicted: Vec<(Session, Client, Option<Vec<u8>>)> = {
+        let mut locked = checkouts.lock();
+        locked
+            .values_mut()
+            .filter(|c| !c.evicted && c.checked_out_at + threshold <= now)
+            .filter_map(|c| {
+                if !c.reported {
+                    c.reported = true;
+                    let backtrace = c
+                        .backtrace
+                        .as_ref()
+                        .map(|b| b.to_string())
+                        .unwrap_or_else(|| "unknown, enable the session-leak-backtrace feature".to_string());
+                    tracing::warn!(
+                        "session {} has been checked out for {:?}, which may be leaked. checked out at: {}",
+                        c.session.name,
+                        now.saturating_duration_since(c.checked_out_at),
+                        backtrace
+                    );
+                }
+                if evict {
+                    c.evicted = true;
+                    c.revoked.store(true, Ordering::Release);
+                    Some((c.session.clone(), c.spanner_client.clone(), c.transaction_id.clone()))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    };
+    for (session, mut spanner_client, transaction_id) in evicted {
+        tracing::warn!("evict leaked session {}", session.name);
+        session_pool.counters.leak_evictions.fetch_add(1, Ordering::Relaxed);
+        let rolled_back = match transaction_id {
+            Some(transaction_id) => {
+                let request = RollbackRequest {
+                    session: session.name.clone(),
+                    transaction_id,
+                };
+                match spanner_client.rollback(request, None, None).await {
+                    Ok(_) => true,
+                    Err(e) => {
+                        tracing::error!("failed to roll back leaked session {}, {:?}", session.name, e);
+                        false
+                    }
+                }
+            }
+            None => false,
+        };
+        if rolled_back {
+            tracing::debug!("leaked session returned to the pool name={}", session.name);
+            session_pool.hand_over(SessionHandle::new(session, spanner_client, Instant::now()));
+            continue;
+        }
+        {
+            let mut inner = session_pool.inner.lock();
+            inner.inuse -= 1;
+        }
+        let _ = session_pool.allocation_request_sender.send(true);
+        let request = DeleteSessionRequest {
+            name: session.name.clone(),
+        };
+        match spanner_client.delete_session(request, None, None).await {
+            Ok(_) => {
+                session_pool.counters.deleted.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => tracing::error!("failed to delete leaked session {}, {:?}", session.name, e),
+        }
+    }
+}
+
+/// max_shrink_count returns the number of the idle sessions which can be deleted,
+/// keeping at most max_idle sessions and at least min_opened sessions.
+fn max_shrink_count(num_opened: usize, config: &SessionConfig) -> usize {
+    num_opened.saturating_sub(std::cmp::max(config.max_idle, config.min_opened))
+}
+
 async fn health_check(
     now: Instant,
     session_alive_trust_duration: Duration,
@@ -536,14 +1221,22 @@ async fn health_check(
         };
 
         let request = ping_query_request(s.session.name.clone());
+        sessions.counters.pings.fetch_add(1, Ordering::Relaxed);
         match s.spanner_client.execute_sql(request, None, None).await {
             Ok(_) => {
                 s.last_checked_at = now;
                 s.last_pong_at = now;
                 sessions.recycle(s);
             }
+            Err(e) if e.code() == Code::Unavailable => {
+                // the session is kept and moved to another channel, since the failure is of the channel.
+                s.last_checked_at = now;
+                s.channel_failed = true;
+                sessions.recycle(s);
+            }
             Err(_) => {
-                delete_session(&mut s).await;
+                sessions.counters.evictions.fetch_add(1, Ordering::Relaxed);
+                delete_session(&mut s, sessions).await;
                 s.valid = false;
                 sessions.recycle(s);
             }
@@ -593,23 +1286,111 @@ async fn shrink_idle_sessions(
         };
 
         removed_count += 1;
-        delete_session(&mut s).await;
+        delete_session(&mut s, session_pool).await;
         s.valid = false;
         session_pool.recycle(s);
     }
 }
 
-async fn delete_session(session: &mut SessionHandle) {
+fn schedule_multiplexed_session_refresh(
+    refresh_interval: Duration,
+    multiplexed: Arc<MultiplexedSession>,
+    spanner_client: Client,
+    database: String,
+    cancel: CancellationToken,
+) -> JoinHandle<()> {
+    let start = Instant::now() + refresh_interval;
+    let mut interval = tokio::time::interval_at(start.into(), refresh_interval);
+
+    tokio::spawn(async move {
+        loop {
+            select! {
+                _ = interval.tick() => {},
+                _ = cancel.cancelled() => break
+            }
+            if multiplexed.needs_refresh(Instant::now()) {
+                refresh_multiplexed_session(&multiplexed, spanner_client.clone(), database.clone()).await;
+            }
+        }
+        tracing::trace!("stop multiplexed session refresher")
+    })
+}
+
+/// refresh_multiplexed_session replaces the multiplexed session with the new one.
+/// The current session is kept if the creation fails, and the pool is used while there is no session.
+async fn refresh_multiplexed_session(multiplexed: &MultiplexedSession, mut spanner_client: Client, database: String) {
+    let request = CreateSessionRequest {
+        database,
+        session: Some(Session {
+            multiplexed: true,
+            ..Default::default()
+        }),
+    };
+    match spanner_client.create_session(request, None, None).await {
+        Ok(response) => multiplexed.replace(response.into_inner(), spanner_client, Instant::now()),
+        Err(e) => tracing::error!("failed to create multiplexed session {:?}", e),
+    }
+}
+
+async fn delete_session(session: &mut SessionHandle, session_pool: &SessionPool) {
     let session_name = &session.session.name;
     let request = DeleteSessionRequest {
         name: session_name.to_string(),
     };
     match session.spanner_client.delete_session(request, None, None).await {
-        Ok(_) => {}
+        Ok(_) => {
+            session_pool.counters.deleted.fetch_add(1, Ordering::Relaxed);
+        }
         Err(e) => tracing::error!("failed to delete session {}, {:?}", session_name, e),
     }
 }
 
+/// creation_counts returns the number of the sessions to create on each of the channels, spreading count evenly.
+fn creation_counts(count: usize, channel_num: usize) -> Vec<usize> {
+    (0..channel_num)
+        .map(|i| count / channel_num + usize::from(i < count % channel_num))
+        .collect()
+}
+
+/// create_sessions_on_channels creates count sessions spread over the channels of the connection pool.
+/// The sessions created are returned with the last error, if any.
+async fn create_sessions_on_channels(
+    database: &str,
+    conn_pool: &ConnectionManager,
+    count: usize,
+) -> (Vec<SessionHandle>, Option<Status>) {
+    let mut sessions = Vec::with_capacity(count);
+    let mut error = None;
+    for count in creation_counts(count, conn_pool.num()) {
+        if count == 0 {
+            continue;
+        }
+        let (created, e) = create_sessions(conn_pool.conn(), database, count).await;
+        sessions.extend(created);
+        if let Some(e) = e {
+            tracing::error!("failed to create sessions, the rest are created later {:?}", e);
+            error = Some(e);
+        }
+    }
+    (sessions, error)
+}
+
+/// create_sessions creates count sessions by BatchCreateSessions, which creates at most MAX_BATCH_CREATE_SESSIONS
+/// sessions in a call and may return fewer sessions than requested.
+/// The sessions created before an error are returned with the error.
+async fn create_sessions(spanner_client: Client, database: &str, count: usize) -> (Vec<SessionHandle>, Option<Status>) {
+    let mut sessions = Vec::with_capacity(count);
+    while sessions.len() < count {
+        let n = std::cmp::min(count - sessions.len(), MAX_BATCH_CREATE_SESSIONS);
+        match batch_create_session(spanner_client.clone(), database.to_string(), n).await {
+            Ok(created) if created.is_empty() => break,
+            Ok(created) => sessions.extend(created),
+            Err(e) => return (sessions, Some(e)),
+        }
+    }
+    (sessions, None)
+}
+
 async fn batch_create_session(
     mut spanner_client: Client,
     database: String,
@@ -638,11 +1419,19 @@ async fn batch_create_session(
 #[cfg(test)]
 mod tests {
     use crate::apiv1::conn_pool::ConnectionManager;
-    use crate::session::{health_check, shrink_idle_sessions, SessionConfig, SessionManager};
+    use crate::apiv1::fake_spanner::FakeSpanner;
+    use crate::apiv1::spanner_client::ping_query_request;
+    use crate::session::{
+        acquisition_wait_bucket, choose_channel, creation_counts, detect_leaked_sessions, health_check,
+        max_shrink_count, needs_refresh, shrink_idle_sessions, ManagedSession, PoolCounters, SessionConfig,
+        SessionError, SessionManager, MULTIPLEXED_SESSION_REFRESH_AGE,
+    };
     use serial_test::serial;
 
     use google_cloud_gax::cancel::CancellationToken;
     use google_cloud_gax::conn::Environment;
+    use google_cloud_gax::grpc::{Code, Status};
+    use std::ops::Deref;
     use std::sync::atomic::{AtomicI64, Ordering};
     use std::sync::Arc;
     use std::time::Instant;
@@ -749,6 +1538,8 @@ mod tests {
         health_check(Instant::now(), session_alive_trust_duration, &sm.session_pool, cancel.clone()).await;
 
         assert_eq!(sm.num_opened(), 5);
+        assert_eq!(sm.stats().health_check_pings, 5);
+        assert_eq!(sm.stats().health_check_evictions, 0);
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         cancel.cancel();
     }
@@ -773,10 +1564,346 @@ mod tests {
         health_check(Instant::now(), session_alive_trust_duration, &sm.session_pool, cancel.clone()).await;
 
         assert_eq!(sm.num_opened(), 5);
+        assert_eq!(sm.stats().health_check_pings, 0);
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
         cancel.cancel();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_detect_leaked_sessions() {
+        let fake = FakeSpanner::default();
+        let host = fake.start().await;
+        let cm = ConnectionManager::new(1, &Environment::Emulator(host), "")
+            .await
+            .unwrap();
+        let threshold = Duration::from_secs(60);
+        let config = SessionConfig {
+            min_opened: 2,
+            max_opened: 2,
+            leak_detection_threshold: Some(threshold),
+            evict_leaked_sessions: true,
+            ..Default::default()
+        };
+        let sm = SessionManager::new(DATABASE, cm, config).await.unwrap();
+        let mut leaked = sm.get().await.unwrap();
+        leaked.begun(b"leaked");
+        let used = sm.get().await.unwrap();
+        drop(used);
+        let checked_out = sm.checked_out_sessions();
+        assert_eq!(1, checked_out.len());
+        assert_eq!(leaked.deref().session.name, checked_out[0].name);
+        assert!(!checked_out[0].evicted);
+        assert_eq!(cfg!(feature = "session-leak-backtrace"), checked_out[0].backtrace.is_some());
+
+        // not leaked yet
+        detect_leaked_sessions(Instant::now(), threshold, true, &sm.session_pool).await;
+        assert!(!sm.checked_out_sessions()[0].evicted);
+        assert_eq!(sm.stats().in_use, 1);
+
+        // the transaction is rolled back and the session is returned to the pool.
+        detect_leaked_sessions(Instant::now() + threshold, threshold, true, &sm.session_pool).await;
+        assert!(sm.checked_out_sessions()[0].evicted);
+        let rollbacks = fake.rollbacks();
+        assert_eq!(rollbacks.len(), 1);
+        assert_eq!(rollbacks[0].session, leaked.deref().session.name);
+        assert_eq!(rollbacks[0].transaction_id, b"leaked".to_vec());
+        let stats = sm.stats();
+        assert_eq!((stats.idle, stats.in_use, stats.leak_evictions), (2, 0, 1));
+        assert_eq!(fake.deleted_sessions(), 0);
+
+        // the holder can not use the session taken back from it.
+        let name = leaked.deref().session.name.clone();
+        let error = leaked
+            .spanner_client
+            .execute_sql(ping_query_request(name.clone()), None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::FailedPrecondition);
+
+        // the session is checked out again while the holder still has it, and the new holder can use it.
+        let mut reused = vec![sm.get().await.unwrap(), sm.get().await.unwrap()];
+        let session = reused
+            .iter_mut()
+            .find(|s| s.deref().deref().session.name == name)
+            .unwrap();
+        session
+            .spanner_client
+            .execute_sql(ping_query_request(name), None, None)
+            .await
+            .unwrap();
+        drop(leaked);
+        let checked_out = sm.checked_out_sessions();
+        assert_eq!(checked_out.len(), 2);
+        assert!(checked_out.iter().all(|s| !s.evicted));
+        drop(reused);
+        assert!(sm.checked_out_sessions().is_empty());
+        assert_eq!((sm.stats().idle, sm.stats().in_use), (2, 0));
+
+        // the session without the read-write transaction, such as the one of the read-only transaction, is deleted.
+        let leaked = sm.get().await.unwrap();
+        detect_leaked_sessions(Instant::now() + threshold, threshold, true, &sm.session_pool).await;
+        assert_eq!(fake.rollbacks().len(), 1);
+        assert_eq!(fake.deleted_sessions(), 1);
+        let stats = sm.stats();
+        assert_eq!((stats.in_use, stats.leak_evictions), (0, 2));
+        drop(leaked);
+
+        // the session whose rollback fails is deleted.
+        let leaked = sm.get().await.unwrap();
+        leaked.begun(b"leaked");
+        fake.set_error("Rollback", Status::internal("rollback failed"));
+        detect_leaked_sessions(Instant::now() + threshold, threshold, true, &sm.session_pool).await;
+        assert_eq!(fake.deleted_sessions(), 2);
+        let stats = sm.stats();
+        assert_eq!((stats.in_use, stats.leak_evictions), (0, 3));
+
+        // the evicted session is not returned to the pool.
+        drop(leaked);
+        assert!(sm.checked_out_sessions().is_empty());
+        assert_eq!(sm.stats().in_use, 0);
+        sm.close().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_warmup() {
+        let cm = ConnectionManager::new(2, &Environment::Emulator("localhost:9010".to_string()), "")
+            .await
+            .unwrap();
+        let config = SessionConfig {
+            min_opened: 5,
+            max_opened: 10,
+            ..Default::default()
+        };
+        let sm = SessionManager::new(DATABASE, cm, config).await.unwrap();
+        assert_eq!(sm.num_opened(), 5);
+        assert_eq!(sm.stats().sessions_created, 5);
+        assert_eq!(sm.warmup().await.unwrap(), 0);
+        sm.close().await;
+    }
+
+    #[test]
+    fn test_acquisition_wait_bucket() {
+        assert_eq!(0, acquisition_wait_bucket(Duration::ZERO));
+        assert_eq!(0, acquisition_wait_bucket(Duration::from_millis(1)));
+        assert_eq!(1, acquisition_wait_bucket(Duration::from_micros(1001)));
+        assert_eq!(6, acquisition_wait_bucket(Duration::from_secs(1)));
+        assert_eq!(8, acquisition_wait_bucket(Duration::from_secs(6)));
+
+        let counters = PoolCounters::default();
+        counters.record_acquisition(Duration::from_millis(20));
+        counters.record_acquisition(Duration::from_millis(30));
+        counters.record_acquisition(Duration::from_secs(10));
+        let counts: Vec<u64> = counters
+            .acquisition_wait_buckets
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .collect();
+        assert_eq!(counts, vec![0, 0, 0, 2, 0, 0, 0, 0, 1]);
+        assert_eq!(
+            counters.acquisition_wait_nanos.load(Ordering::Relaxed),
+            Duration::from_millis(10_050).as_nanos() as u64
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_acquisition_stats() {
+        let cm = ConnectionManager::new(1, &Environment::Emulator("localhost:9010".to_string()), "")
+            .await
+            .unwrap();
+        let config = SessionConfig {
+            min_opened: 1,
+            max_opened: 1,
+            session_get_timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let sm = Arc::new(SessionManager::new(DATABASE, cm, config).await.unwrap());
+        let session = sm.get().await.unwrap();
+
+        // the second acquisition waits for the session in use, and the third one times out.
+        let waiter = {
+            let sm = Arc::clone(&sm);
+            tokio::spawn(async move { sm.get().await.is_ok() })
+        };
+        sleep(Duration::from_millis(20)).await;
+        drop(session);
+        assert!(waiter.await.unwrap());
+        let session = sm.get().await.unwrap();
+        assert!(sm.get().await.is_err());
+        drop(session);
+
+        // the waits depend on the scheduling, so only the counts are checked.
+        let stats = sm.stats();
+        assert_eq!(stats.acquisitions, 3);
+        assert_eq!(stats.acquisition_timeouts, 1);
+        assert_eq!(stats.acquisition_wait_buckets.len(), 9);
+        assert_eq!(
+            stats
+                .acquisition_wait_buckets
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<u64>(),
+            3
+        );
+        sm.close().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_close_waits_for_sessions() {
+        let fake = FakeSpanner::default();
+        let host = fake.start().await;
+        let new_session_manager = |close_timeout| {
+            let host = host.clone();
+            async move {
+                let cm = ConnectionManager::new(1, &Environment::Emulator(host), "")
+                    .await
+                    .unwrap();
+                let config = SessionConfig {
+                    min_opened: 5,
+                    max_opened: 5,
+                    close_timeout,
+                    ..Default::default()
+                };
+                Arc::new(SessionManager::new(DATABASE, cm, config).await.unwrap())
+            }
+        };
+
+        // close waits for the session in use to be returned.
+        let sm = new_session_manager(Duration::from_secs(1)).await;
+        let session = sm.get().await.unwrap();
+        let closing = {
+            let sm = Arc::clone(&sm);
+            tokio::spawn(async move { sm.close().await })
+        };
+        sleep(Duration::from_millis(50)).await;
+        assert!(matches!(sm.get().await, Err(SessionError::Closed)));
+        drop(session);
+        closing.await.unwrap();
+        assert_eq!(fake.deleted_sessions(), 5);
+        assert_eq!(sm.stats().opened, 0);
+        assert!(matches!(sm.get_read_only().await, Err(SessionError::Closed)));
+
+        // the session returned after close times out is deleted when it is returned.
+        let sm = new_session_manager(Duration::from_millis(50)).await;
+        let session = sm.get().await.unwrap();
+        sm.close().await;
+        assert_eq!(fake.deleted_sessions(), 9);
+        drop(session);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while fake.deleted_sessions() < 10 && Instant::now() < deadline {
+            sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(fake.deleted_sessions(), 10);
+        assert_eq!(sm.stats().opened, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sessions_spread_over_channels() {
+        let fake = FakeSpanner::default();
+        let host = fake.start().await;
+        let cm = ConnectionManager::new(4, &Environment::Emulator(host), "")
+            .await
+            .unwrap();
+        let config = SessionConfig {
+            min_opened: 8,
+            max_opened: 8,
+            ..Default::default()
+        };
+        let sm = Arc::new(SessionManager::new(DATABASE, cm, config).await.unwrap());
+        let tasks: Vec<_> = (0..64)
+            .map(|_| {
+                let sm = Arc::clone(&sm);
+                tokio::spawn(async move {
+                    let mut session = sm.get().await.unwrap();
+                    let request = ping_query_request(session.deref().session.name.clone());
+                    session.spanner_client.execute_sql(request, None, None).await.unwrap();
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        // the queries are sent over the 4 connections by the sessions bound to them.
+        let queries = fake.requests_per_connection("ExecuteSql");
+        assert_eq!(queries.len(), 4, "{queries:?}");
+        assert!(queries.iter().all(|count| *count > 0), "{queries:?}");
+        assert_eq!(queries.iter().sum::<usize>(), 64);
+        assert_eq!(sm.stats().idle_per_channel, vec![2, 2, 2, 2]);
+
+        // the session whose channel failed is moved to the healthy channel with the fewest idle sessions.
+        let fail = |session: &mut ManagedSession, channel: usize| {
+            session.spanner_client = sm.conn_pool.conn_at(channel);
+            session.channel_failed = true;
+        };
+        let channel_of = |name: &str| {
+            let idle = sm.session_pool.inner.lock();
+            let moved = idle.sessions.iter().find(|s| s.session.name == name).unwrap();
+            assert!(!moved.channel_failed);
+            moved.spanner_client.channel()
+        };
+        let fewest_idle = |candidates: &[usize]| {
+            let idle = sm.stats().idle_per_channel;
+            *candidates.iter().min_by_key(|c| idle[**c]).unwrap()
+        };
+        let mut session = sm.get().await.unwrap();
+        fail(&mut session, 0);
+        let expected = fewest_idle(&[1, 2, 3]);
+        let name = session.deref().session.name.clone();
+        drop(session);
+        assert_eq!(channel_of(&name), expected);
+
+        // the channel 0 failed recently, so the session failed on the channel 1 is not moved back to it.
+        let mut session = sm.get().await.unwrap();
+        fail(&mut session, 1);
+        let expected = fewest_idle(&[2, 3]);
+        let name = session.deref().session.name.clone();
+        drop(session);
+        assert_eq!(channel_of(&name), expected);
+        assert_eq!(sm.stats().sessions_rehomed, 2);
+        sm.close().await;
+        assert_eq!(fake.deleted_sessions(), 8);
+    }
+
+    #[test]
+    fn test_choose_channel() {
+        assert_eq!(choose_channel(0, &[1, 3, 2, 2], &[true; 4]), Some(2));
+        assert_eq!(choose_channel(2, &[1, 3, 0, 2], &[true; 4]), Some(0));
+        // the channels failed recently are not chosen.
+        assert_eq!(choose_channel(0, &[1, 3, 2, 2], &[true, true, false, true]), Some(3));
+        assert_eq!(choose_channel(0, &[1, 3], &[true, false]), None);
+        assert_eq!(choose_channel(0, &[1], &[true]), None);
+    }
+
+    #[test]
+    fn test_creation_counts() {
+        assert_eq!(vec![3, 3, 2, 2], creation_counts(10, 4));
+        assert_eq!(vec![100], creation_counts(100, 1));
+        assert_eq!(vec![1, 0, 0], creation_counts(1, 3));
+        assert_eq!(vec![0, 0], creation_counts(0, 2));
+    }
+
+    #[test]
+    fn test_max_shrink_count() {
+        let config = SessionConfig {
+            min_opened: 10,
+            max_idle: 20,
+            ..Default::default()
+        };
+        assert_eq!(0, max_shrink_count(5, &config));
+        assert_eq!(0, max_shrink_count(20, &config));
+        assert_eq!(25, max_shrink_count(45, &config));
+
+        // min_opened is kept even if max_idle is smaller.
+        let config = SessionConfig {
+            min_opened: 10,
+            max_idle: 0,
+            ..Default::default()
+        };
+        assert_eq!(0, max_shrink_count(10, &config));
+        assert_eq!(5, max_shrink_count(15, &config));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[serial]
     async fn test_schedule_refresh() {
@@ -818,6 +1945,10 @@ mod tests {
         }
         assert_eq!(sm.num_opened(), 20, "num sessions are 20");
         assert_eq!(sm.session_waiters(), 0, "session waiters is 0");
+        let stats = sm.stats();
+        assert_eq!(stats.opened, 20);
+        assert_eq!(stats.in_use, 0);
+        assert_eq!(sm.config().max_idle, 20);
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -890,4 +2021,38 @@ mod tests {
         sm.close().await;
         assert_eq!(sm.num_opened(), 0)
     }
+
+    #[test]
+    fn test_multiplexed_session_needs_refresh() {
+        let created_at = Instant::now();
+        assert!(!needs_refresh(created_at, created_at));
+        assert!(!needs_refresh(created_at, created_at + Duration::from_secs(60 * 60)));
+        assert!(needs_refresh(created_at, created_at + MULTIPLEXED_SESSION_REFRESH_AGE));
+        assert!(needs_refresh(created_at, created_at + Duration::from_secs(7 * 24 * 60 * 60)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_get_read_only_multiplexed() {
+        let cm = ConnectionManager::new(1, &Environment::Emulator("localhost:9010".to_string()), "")
+            .await
+            .unwrap();
+        let config = SessionConfig {
+            use_multiplexed_session: true,
+            ..Default::default()
+        };
+        let sm = SessionManager::new(DATABASE, cm, config.clone()).await.unwrap();
+        let s1 = sm.get_read_only().await.unwrap();
+        let s2 = sm.get_read_only().await.unwrap();
+        assert!(s1.is_multiplexed());
+        assert!(s2.is_multiplexed());
+        assert_eq!(sm.multiplexed_session_uses(), 2);
+        assert_eq!(sm.session_pool.inner.lock().inuse, 0);
+
+        let s3 = sm.get().await.unwrap();
+        assert!(!s3.is_multiplexed());
+        assert_eq!(sm.session_pool.inner.lock().inuse, 1);
+        drop(s3);
+        sm.close().await;
+    }
 }
@@ -0,0 +1,152 @@
+//! Quoting helpers for the SQL that can not be composed only with the parameters.
+//!
+//! Use the parameters of the Statement for the values whenever possible.
+//! These helpers are for the identifiers such as the table names chosen at runtime,
+//! and for the literals in the places where the parameters are not allowed, such as DDL.
+//!
+//! ```
+//! use google_cloud_spanner::sql::{quote_identifier, quote_literal};
+//!
+//! let table = quote_identifier("UserCharacter").unwrap();
+//! let ddl = format!("ALTER TABLE {table} ALTER COLUMN Name SET OPTIONS (description = {})", quote_literal("it's a name"));
+//! assert_eq!(ddl, "ALTER TABLE `UserCharacter` ALTER COLUMN Name SET OPTIONS (description = 'it\\'s a name')");
+//! ```
+
+use std::fmt::Write;
+
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime};
+
+use crate::value::utc_timestamp;
+
+/// The maximum length of the identifier.
+const MAX_IDENTIFIER_LENGTH: usize = 128;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Invalid identifier: {0:?}")]
+    InvalidIdentifier(String),
+    #[error("Timestamp is out of the range of TIMESTAMP: {0}")]
+    TimestampOutOfRange(String),
+}
+
+/// quote_identifier returns the identifier quoted with backticks.
+/// The identifier that is empty, too long, or contains a backtick, a backslash or a control character
+/// such as a newline is rejected instead of being escaped.
+pub fn quote_identifier(identifier: &str) -> Result<String, Error> {
+    let valid = !identifier.is_empty()
+        && identifier.chars().count() <= MAX_IDENTIFIER_LENGTH
+        && !identifier.chars().any(|c| c == '`' || c == '\\' || c.is_control());
+    if valid {
+        Ok(format!("`{identifier}`"))
+    } else {
+        Err(Error::InvalidIdentifier(identifier.to_string()))
+    }
+}
+
+/// quote_literal returns the STRING literal of the value.
+pub fn quote_literal(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        match c {
+            '\'' => quoted.push_str("\\'"),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\U{:08x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// quote_bytes_literal returns the BYTES literal of the value.
+/// The bytes other than the printable ASCII characters are escaped in hexadecimal.
+pub fn quote_bytes_literal(value: &[u8]) -> String {
+    let mut quoted = String::with_capacity(value.len() + 3);
+    quoted.push_str("b'");
+    for b in value {
+        match b {
+            b'\'' => quoted.push_str("\\'"),
+            b'\\' => quoted.push_str("\\\\"),
+            0x20..=0x7e => quoted.push(*b as char),
+            _ => {
+                let _ = write!(quoted, "\\x{b:02x}");
+            }
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// quote_timestamp_literal returns the TIMESTAMP literal of the value in UTC.
+/// The value must be in the range of TIMESTAMP, from 0001-01-01 to 9999-12-31 in UTC.
+pub fn quote_timestamp_literal(value: &OffsetDateTime) -> Result<String, Error> {
+    let out_of_range = || Error::TimestampOutOfRange(value.to_string());
+    let utc = utc_timestamp(value).ok_or_else(out_of_range)?;
+    let formatted = utc.format(&Rfc3339).map_err(|_| out_of_range())?;
+    Ok(format!("TIMESTAMP '{formatted}'"))
+}
+
+/// quote_date_literal returns the DATE literal of the value.
+pub fn quote_date_literal(value: &Date) -> String {
+    format!("DATE '{}'", value.format(format_description!("[year]-[month]-[day]")).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use crate::sql::*;
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(quote_identifier("User").unwrap(), "`User`");
+        assert_eq!(quote_identifier("tenant-1 Users").unwrap(), "`tenant-1 Users`");
+        assert_eq!(quote_identifier("Order").unwrap(), "`Order`");
+        assert_eq!(quote_identifier(&"a".repeat(128)).unwrap().len(), 130);
+        for identifier in ["", "a`; DROP TABLE User; --", "a\nb", "a\\`b", "a\0b", &"a".repeat(129)] {
+            assert_eq!(
+                quote_identifier(identifier),
+                Err(Error::InvalidIdentifier(identifier.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_quote_literal() {
+        assert_eq!(quote_literal("abc"), "'abc'");
+        assert_eq!(quote_literal("it's"), "'it\\'s'");
+        assert_eq!(quote_literal("a\\'); --"), "'a\\\\\\'); --'");
+        assert_eq!(quote_literal("a\nb\r\tc"), "'a\\nb\\r\\tc'");
+        assert_eq!(quote_literal("\u{7}日本"), "'\\U00000007日本'");
+        assert_eq!(quote_literal(""), "''");
+    }
+
+    #[test]
+    fn test_quote_bytes_literal() {
+        assert_eq!(quote_bytes_literal(b"abc"), "b'abc'");
+        assert_eq!(quote_bytes_literal(&[0, 0xff, b'\'', b'\\']), "b'\\x00\\xff\\'\\\\'");
+        assert_eq!(quote_bytes_literal(&[]), "b''");
+    }
+
+    #[test]
+    fn test_quote_time_literal() {
+        assert_eq!(
+            quote_timestamp_literal(&datetime!(2022-09-01 12:34:56.123456789 +09:00)).unwrap(),
+            "TIMESTAMP '2022-09-01T03:34:56.123456789Z'"
+        );
+        let before_year_one = datetime!(0000-12-31 23:59:59 UTC);
+        assert_eq!(
+            quote_timestamp_literal(&before_year_one),
+            Err(Error::TimestampOutOfRange(before_year_one.to_string()))
+        );
+        assert_eq!(quote_date_literal(&date!(2022 - 09 - 01)), "DATE '2022-09-01'");
+    }
+}
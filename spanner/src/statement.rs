@@ -1,16 +1,48 @@
+use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::num::{NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU8};
+use std::sync::Arc;
+use std::time::SystemTime;
 
-use prost_types::value::Kind;
 use prost_types::value::Kind::StringValue;
-use prost_types::{value, ListValue, Struct, Value};
+use prost_types::{value, ListValue, Value};
 
 use google_cloud_googleapis::spanner::v1::struct_type::Field;
-use google_cloud_googleapis::spanner::v1::{StructType, Type, TypeAnnotationCode, TypeCode};
+use google_cloud_googleapis::spanner::v1::{StructType, TypeAnnotationCode, TypeCode};
+use google_cloud_spanner_sql::skip_literal_or_comment;
+
+// re-exported to implement ToKind without depending on the generated crates directly
+pub use google_cloud_googleapis::spanner::v1::Type;
+pub use prost_types::value::Kind;
 use time::format_description::well_known::Rfc3339;
 use time::macros::format_description;
 use time::{Date, OffsetDateTime};
 
-use crate::value::{CommitTimestamp, SpannerNumeric};
+use crate::row::SpannerType;
+use crate::value::{
+    utc_timestamp, CommitTimestamp, Float32, Interval, NumericError, PgNumeric, SpannerNumeric, SpannerValue,
+    TimestampValue,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid NUMERIC parameter: name={0}, {1}")]
+    InvalidNumeric(String, #[source] NumericError),
+    #[error("Invalid parameter name: name={0}")]
+    InvalidParamName(String),
+    #[error("Number of positional parameters does not match: placeholders={0}, values={1}")]
+    PositionalParamCount(usize, usize),
+    #[error("Parameter is already bound: name={0}")]
+    DuplicateParam(String),
+    #[error("Value exceeds the range of INT64: value={0}")]
+    Int64Overflow(u64),
+    #[error("Timestamp is out of the range of TIMESTAMP: value={0}")]
+    TimestampOutOfRange(String),
+    #[cfg(feature = "serde_json")]
+    #[error("Failed to serialize as JSON: {0}")]
+    JsonSerializeError(#[source] serde_json::Error),
+}
 
 /// A Statement is a SQL query with named parameters.
 ///
@@ -24,11 +56,104 @@ use crate::value::{CommitTimestamp, SpannerNumeric};
 ///
 /// See the documentation of the Row type for how Go types are mapped to Cloud
 /// Spanner types.
-#[derive(Clone)]
+///
+/// The Debug output contains the SQL and the names and types of the parameters,
+/// but the values are redacted. Use debug_with_values to print the values.
+#[derive(Clone, PartialEq)]
 pub struct Statement {
     pub(crate) sql: String,
-    pub(crate) params: BTreeMap<String, Value>,
+    // shared with the clones of the statement to bind the large values without copying
+    pub(crate) params: BTreeMap<String, Arc<Value>>,
     pub(crate) param_types: HashMap<String, Type>,
+    pub(crate) infer_types: bool,
+    pub(crate) request_tag: Option<String>,
+}
+
+/// SharedValue is an encoded value that can be bound to more than one statement without copying.
+///
+/// ```
+/// use google_cloud_spanner::statement::{SharedValue, Statement};
+///
+/// let blob = SharedValue::new(&vec![0_u8; 2 * 1024 * 1024]);
+/// let mut insert = Statement::new("INSERT INTO Blob (Id, Data) VALUES (1, @Data)");
+/// insert.add_shared_param("Data", &blob);
+/// let mut update = Statement::new("UPDATE Blob SET Data = @Data WHERE Id = 2");
+/// update.add_shared_param("Data", &blob);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedValue {
+    value: Arc<Value>,
+    param_type: Type,
+}
+
+impl SharedValue {
+    pub fn new<T>(value: &T) -> Self
+    where
+        T: ToKind,
+    {
+        SharedValue {
+            value: Arc::new(Value {
+                kind: Some(value.to_kind()),
+            }),
+            param_type: T::get_type(),
+        }
+    }
+}
+
+impl fmt::Debug for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        StatementDebug {
+            stmt: self,
+            with_values: false,
+        }
+        .fmt(f)
+    }
+}
+
+/// StatementDebug is the Debug formatter of the Statement.
+pub struct StatementDebug<'a> {
+    stmt: &'a Statement,
+    with_values: bool,
+}
+
+impl fmt::Debug for StatementDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params = self.stmt.params.iter().map(|(name, value)| {
+            let param = ParamDebug {
+                param_type: self.stmt.param_types.get(name),
+                value: if self.with_values { Some(value) } else { None },
+            };
+            (name, param)
+        });
+        f.debug_struct("Statement")
+            .field("sql", &self.stmt.sql)
+            .field("params", &params.collect::<BTreeMap<_, _>>())
+            .finish()
+    }
+}
+
+struct ParamDebug<'a> {
+    param_type: Option<&'a Type>,
+    value: Option<&'a Value>,
+}
+
+impl fmt::Debug for ParamDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.param_type {
+            Some(tp) => write!(f, "{}", type_name(tp))?,
+            None => f.write_str("UNKNOWN")?,
+        }
+        match self.value.and_then(|v| v.kind.as_ref()) {
+            Some(kind) => write!(f, "({kind:?})"),
+            None if self.value.is_some() => f.write_str("(None)"),
+            None => f.write_str("(<redacted>)"),
+        }
+    }
+}
+
+/// type_name returns the type in the SQL notation like `ARRAY<STRUCT<Id INT64>>`.
+pub(crate) fn type_name(tp: &Type) -> String {
+    SpannerType::from(tp).to_string()
 }
 
 impl Statement {
@@ -38,26 +163,484 @@ impl Statement {
             sql: sql.into(),
             params: Default::default(),
             param_types: Default::default(),
+            infer_types: false,
+            request_tag: None,
+        }
+    }
+
+    /// set_sql replaces the SQL. It is used to embed the fragments returned by add_in_clause.
+    pub fn set_sql<T: Into<String>>(&mut self, sql: T) {
+        self.sql = sql.into();
+    }
+
+    /// with_request_tag sets the request tag, which is shown in the query statistics tables.
+    /// The tag takes precedence over the request tag of the QueryOptions.
+    pub fn with_request_tag<T: Into<String>>(mut self, tag: T) -> Self {
+        self.request_tag = Some(tag.into());
+        self
+    }
+
+    /// debug_with_values returns the Debug formatter that prints the values of the parameters too.
+    /// The values may contain the sensitive data, so it should only be used for local debugging.
+    pub fn debug_with_values(&self) -> StatementDebug<'_> {
+        StatementDebug {
+            stmt: self,
+            with_values: true,
         }
     }
 
     /// add_params add the bind parameter.
     /// Implement the ToKind trait to use non-predefined types.
+    /// The leading '@' of the name is optional.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not a valid parameter name. Use try_add_param to get an error instead.
     pub fn add_param<T>(&mut self, name: &str, value: &T)
     where
         T: ToKind,
     {
-        self.param_types.insert(name.to_string(), T::get_type());
+        let name = param_name(name).unwrap_or_else(|e| panic!("{}", e));
+        self.param_types.insert(name.clone(), T::get_type());
         self.params.insert(
-            name.to_string(),
-            Value {
+            name,
+            Arc::new(Value {
                 kind: Some(value.to_kind()),
-            },
+            }),
         );
     }
+
+    /// add_shared_param binds the shared value. Only the reference is copied.
+    pub fn add_shared_param(&mut self, name: &str, value: &SharedValue) {
+        let name = param_name(name).unwrap_or_else(|e| panic!("{}", e));
+        self.param_types.insert(name.clone(), value.param_type.clone());
+        self.params.insert(name, Arc::clone(&value.value));
+    }
+
+    /// add_param_untyped adds the bind parameter without its type, so that Spanner infers the type from the value.
+    /// The type of NULL is kept, because it can not be inferred.
+    pub fn add_param_untyped<T>(&mut self, name: &str, value: &T)
+    where
+        T: ToKind,
+    {
+        let name = param_name(name).unwrap_or_else(|e| panic!("{}", e));
+        let kind = value.to_kind();
+        if matches!(kind, Kind::NullValue(_)) {
+            self.param_types.insert(name.clone(), T::get_type());
+        } else {
+            self.param_types.remove(&name);
+        }
+        self.params.insert(name, Arc::new(Value { kind: Some(kind) }));
+    }
+
+    /// infer_types sets whether to let Spanner infer the types of all the parameters.
+    /// If true, the types are not sent except for NULL.
+    pub fn infer_types(&mut self, infer: bool) -> &mut Self {
+        self.infer_types = infer;
+        self
+    }
+
+    /// request_params returns the copy of the params to send.
+    pub(crate) fn request_params(&self) -> BTreeMap<String, Value> {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.clone(), (**value).clone()))
+            .collect()
+    }
+
+    /// request_param_types returns the param_types to send.
+    pub(crate) fn request_param_types(&self) -> HashMap<String, Type> {
+        if !self.infer_types {
+            return self.param_types.clone();
+        }
+        self.param_types
+            .iter()
+            .filter(|(name, _)| {
+                self.params
+                    .get(*name)
+                    .map(|v| matches!(v.kind, Some(Kind::NullValue(_))))
+                    .unwrap_or(false)
+            })
+            .map(|(name, tp)| (name.clone(), tp.clone()))
+            .collect()
+    }
+
+    /// add_params adds the bind parameters whose types are determined at runtime.
+    ///
+    /// ```
+    /// use google_cloud_spanner::statement::Statement;
+    /// use google_cloud_spanner::value::SpannerValue;
+    ///
+    /// let filters: Vec<(&str, SpannerValue)> = vec![
+    ///     ("UserId", "user1".into()),
+    ///     ("MinLevel", 10_i64.into()),
+    ///     ("Tags", SpannerValue::array(vec!["a", "b"])),
+    ///     ("DeletedAt", SpannerValue::null::<time::OffsetDateTime>()),
+    /// ];
+    /// let mut stmt = Statement::new("SELECT * FROM User WHERE UserId = @UserId");
+    /// stmt.add_params(filters);
+    /// ```
+    pub fn add_params<I, N>(&mut self, params: I)
+    where
+        I: IntoIterator<Item = (N, SpannerValue)>,
+        N: Into<String>,
+    {
+        for (name, value) in params {
+            let name = param_name(&name.into()).unwrap_or_else(|e| panic!("{}", e));
+            self.param_types.insert(name.clone(), value.get_type());
+            self.params.insert(
+                name,
+                Arc::new(Value {
+                    kind: Some(value.to_kind()),
+                }),
+            );
+        }
+    }
+
+    /// with_param adds the bind parameter and returns the statement, so that the parameters can be chained.
+    ///
+    /// ```
+    /// use google_cloud_spanner::statement::Statement;
+    ///
+    /// let stmt = Statement::new("SELECT * FROM User WHERE UserId = @UserId AND Premium = @Premium")
+    ///     .with_param("UserId", &"user1")
+    ///     .with_param("Premium", &true);
+    /// ```
+    #[must_use]
+    pub fn with_param<T>(mut self, name: &str, value: &T) -> Self
+    where
+        T: ToKind,
+    {
+        self.add_param(name, value);
+        self
+    }
+
+    /// try_add_param is the same as add_param, except that it returns an error
+    /// instead of panicking if the name is invalid or the value can not be converted.
+    pub fn try_add_param<T>(&mut self, name: &str, value: &T) -> Result<(), Error>
+    where
+        T: ToKind,
+    {
+        let name = param_name(name)?;
+        let kind = value.try_to_kind()?;
+        self.param_types.insert(name.clone(), T::get_type());
+        self.params.insert(name, Arc::new(Value { kind: Some(kind) }));
+        Ok(())
+    }
+
+    /// with_positional_params returns a Statement whose '?' placeholders are rewritten to
+    /// the named parameters @p1, @p2, ... and bound to the values in order.
+    /// '?' in string literals, quoted identifiers and comments are left as they are.
+    ///
+    /// ```
+    /// use google_cloud_spanner::statement::Statement;
+    ///
+    /// let stmt = Statement::with_positional_params("SELECT * FROM User WHERE UserId IN (?, ?)", ["user1", "user2"]).unwrap();
+    /// ```
+    pub fn with_positional_params<T, I>(sql: &str, params: I) -> Result<Self, Error>
+    where
+        T: ToKind,
+        I: IntoIterator<Item = T>,
+    {
+        let (sql, count) = rewrite_positional_params(sql);
+        let mut stmt = Statement::new(sql);
+        let mut values = 0;
+        for value in params {
+            values += 1;
+            if values <= count {
+                stmt.try_add_param(&format!("p{values}"), &value)?;
+            }
+        }
+        if values != count {
+            return Err(Error::PositionalParamCount(count, values));
+        }
+        Ok(stmt)
+    }
+
+    /// with_positional_values is the same as with_positional_params, except that
+    /// the values can be of different types.
+    ///
+    /// ```
+    /// use google_cloud_spanner::statement::Statement;
+    ///
+    /// let stmt = Statement::with_positional_values("SELECT * FROM User WHERE UserId = ? AND Level > ?", vec!["user1".into(), 10_i64.into()]).unwrap();
+    /// ```
+    pub fn with_positional_values<I>(sql: &str, params: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = SpannerValue>,
+    {
+        let (sql, count) = rewrite_positional_params(sql);
+        let values: Vec<(String, SpannerValue)> = params
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| (format!("p{}", i + 1), v))
+            .collect();
+        if values.len() != count {
+            return Err(Error::PositionalParamCount(count, values.len()));
+        }
+        let mut stmt = Statement::new(sql);
+        stmt.add_params(values);
+        Ok(stmt)
+    }
+
+    /// from_struct returns a Statement with the fields of the value bound as the parameters.
+    ///
+    /// ```
+    /// use google_cloud_spanner::statement::{Kinds, Statement, ToKind, ToStruct, Types};
+    ///
+    /// struct User {
+    ///     user_id: String,
+    ///     premium: bool,
+    /// }
+    ///
+    /// impl ToStruct for User {
+    ///     fn to_kinds(&self) -> Kinds {
+    ///         vec![("UserId", self.user_id.to_kind()), ("Premium", self.premium.to_kind())]
+    ///     }
+    ///
+    ///     fn get_types() -> Types {
+    ///         vec![("UserId", String::get_type()), ("Premium", bool::get_type())]
+    ///     }
+    /// }
+    ///
+    /// let user = User { user_id: "user1".to_string(), premium: true };
+    /// let stmt = Statement::from_struct("UPDATE User SET Premium = @Premium WHERE UserId = @UserId", &user).unwrap();
+    /// ```
+    pub fn from_struct<T, S>(sql: S, value: &T) -> Result<Self, Error>
+    where
+        T: ToStruct,
+        S: Into<String>,
+    {
+        let mut stmt = Statement::new(sql);
+        stmt.add_struct_params(value)?;
+        Ok(stmt)
+    }
+
+    /// add_struct_params binds each field of the value as the parameter of the same name.
+    /// It is an error if a parameter of the same name is already bound.
+    pub fn add_struct_params<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ToStruct,
+    {
+        self.add_struct_params_prefixed("", value)
+    }
+
+    /// add_struct_params_prefixed is the same as add_struct_params, except that the names
+    /// of the parameters are prefixed, so that more than one struct can be bound.
+    pub fn add_struct_params_prefixed<T>(&mut self, prefix: &str, value: &T) -> Result<(), Error>
+    where
+        T: ToStruct,
+    {
+        let mut fields = Vec::new();
+        for ((name, kind), (_, tp)) in value.to_kinds().into_iter().zip(T::get_types()) {
+            let name = param_name(&format!("{prefix}{name}"))?;
+            if self.params.contains_key(&name) || fields.iter().any(|(n, _, _)| n == &name) {
+                return Err(Error::DuplicateParam(name));
+            }
+            fields.push((name, kind, tp));
+        }
+        for (name, kind, tp) in fields {
+            self.param_types.insert(name.clone(), tp);
+            self.params.insert(name, Arc::new(Value { kind: Some(kind) }));
+        }
+        Ok(())
+    }
+
+    /// add_in_clause binds the values as an array parameter of a generated name, such as `in_0`, and returns
+    /// the SQL fragment `column IN UNNEST(@in_0)` to be embedded in the query.
+    /// The name is not derived from the column, so that the column can be any expression of any length.
+    /// The fragment is `FALSE` if the values are empty.
+    ///
+    /// ```
+    /// use google_cloud_spanner::statement::Statement;
+    ///
+    /// let mut stmt = Statement::new("");
+    /// let user_ids = stmt.add_in_clause("UserId", vec!["user1", "user2"]);
+    /// let levels = stmt.add_in_clause("Level", vec![1_i64, 2]);
+    /// stmt.set_sql(format!("SELECT * FROM User WHERE {user_ids} AND {levels}"));
+    /// ```
+    pub fn add_in_clause<T>(&mut self, column: &str, values: Vec<T>) -> String
+    where
+        T: ToKind,
+    {
+        if values.is_empty() {
+            return "FALSE".to_string();
+        }
+        let name = (0..)
+            .map(|i| format!("in_{i}"))
+            .find(|name| !self.params.contains_key(name))
+            .unwrap();
+        self.add_param(&name, &values);
+        format!("{column} IN UNNEST(@{name})")
+    }
+
+    /// add_json_param serializes the value and binds it as JSON.
+    /// The serialization error is returned instead of panicking.
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    pub fn add_json_param<T>(&mut self, name: &str, value: &T) -> Result<(), Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.try_add_param(name, &crate::value::Json(value))
+    }
+
+    /// add_json_param_nullable is the same as add_json_param, except that None is bound as a NULL of JSON.
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    pub fn add_json_param_nullable<T>(&mut self, name: &str, value: Option<&T>) -> Result<(), Error>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        self.try_add_param(name, &value.map(crate::value::Json))
+    }
+
+    /// has_param returns whether the parameter is bound. The leading '@' of the name is optional.
+    pub fn has_param(&self, name: &str) -> bool {
+        self.params.contains_key(name.strip_prefix('@').unwrap_or(name))
+    }
+
+    /// remove_param removes the parameter and its type, and returns whether it was bound.
+    pub fn remove_param(&mut self, name: &str) -> bool {
+        let name = name.strip_prefix('@').unwrap_or(name);
+        self.param_types.remove(name);
+        self.params.remove(name).is_some()
+    }
+
+    /// param_names returns the names of the bound parameters in ascending order.
+    pub fn param_names(&self) -> impl Iterator<Item = &str> {
+        self.params.keys().map(String::as_str)
+    }
+
+    /// clear_params removes all the parameters.
+    pub fn clear_params(&mut self) {
+        self.params.clear();
+        self.param_types.clear();
+    }
+
+    /// add_null_param binds a NULL of type T to the parameter.
+    /// It is equivalent to `add_param(name, &Option::<T>::None)`.
+    pub fn add_null_param<T>(&mut self, name: &str)
+    where
+        T: ToKind,
+    {
+        self.add_param(name, &Option::<T>::None)
+    }
+
+    /// add_numeric_param binds the decimal as NUMERIC after checking it against the NUMERIC range.
+    /// Use `add_param(name, &Numeric::rounded(value))` to round the extra fractional digits instead.
+    #[cfg(feature = "rust_decimal")]
+    pub fn add_numeric_param(&mut self, name: &str, value: &rust_decimal::Decimal) -> Result<(), Error> {
+        let numeric = crate::value::Numeric::new(*value).map_err(|e| Error::InvalidNumeric(name.to_string(), e))?;
+        self.add_param(name, &numeric);
+        Ok(())
+    }
+
+    /// add_big_decimal_param binds the decimal as NUMERIC after checking it against the NUMERIC range.
+    /// BigDecimal is bound only through this method or SpannerNumeric::try_from, so that the out-of-range values
+    /// fail before the request is sent.
+    #[cfg(feature = "bigdecimal")]
+    pub fn add_big_decimal_param(&mut self, name: &str, value: &bigdecimal::BigDecimal) -> Result<(), Error> {
+        let numeric = SpannerNumeric::try_from(value).map_err(|e| Error::InvalidNumeric(name.to_string(), e))?;
+        self.add_param(name, &numeric);
+        Ok(())
+    }
+}
+
+/// rewrite_positional_params replaces '?' outside of the string literals, quoted identifiers
+/// and comments with @p1, @p2, ... and returns the rewritten SQL and the number of placeholders.
+fn rewrite_positional_params(sql: &str) -> (String, usize) {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut result = String::with_capacity(sql.len());
+    let mut count = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some(end) = skip_literal_or_comment(&chars, i) {
+            result.extend(&chars[i..end]);
+            i = end;
+        } else if chars[i] == '?' {
+            count += 1;
+            result.push_str(&format!("@p{count}"));
+            i += 1;
+        } else {
+            result.push(chars[i]);
+            i += 1;
+        }
+    }
+    (result, count)
+}
+
+/// sql_keywords returns the words in upper case outside of the string literals, quoted identifiers,
+/// comments, parameters and statement hints.
+pub(crate) fn sql_keywords(sql: &str) -> Vec<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut keywords = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some(end) = skip_literal_or_comment(&chars, i) {
+            i = end;
+        } else if chars[i] == '@' && chars.get(i + 1) == Some(&'{') {
+            i = (i..chars.len())
+                .find(|&j| chars[j] == '}')
+                .map(|j| j + 1)
+                .unwrap_or(chars.len());
+        } else if chars[i] == '@' {
+            i = (i + 1..chars.len())
+                .find(|&j| !is_word(chars[j]))
+                .unwrap_or(chars.len());
+        } else if is_word(chars[i]) {
+            let end = (i..chars.len()).find(|&j| !is_word(chars[j])).unwrap_or(chars.len());
+            keywords.push(chars[i..end].iter().collect::<String>().to_uppercase());
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+    keywords
+}
+
+/// has_then_return returns true if the DML statement returns the rows by THEN RETURN.
+pub(crate) fn has_then_return(sql: &str) -> bool {
+    sql_keywords(sql).windows(2).any(|w| w[0] == "THEN" && w[1] == "RETURN")
+}
+
+/// into_request_params returns the params to send.
+/// The value is copied only if it is shared with the other statements.
+pub(crate) fn into_request_params(params: BTreeMap<String, Arc<Value>>) -> BTreeMap<String, Value> {
+    params
+        .into_iter()
+        .map(|(name, value)| (name, Arc::try_unwrap(value).unwrap_or_else(|v| (*v).clone())))
+        .collect()
+}
+
+/// The maximum length of the identifier.
+const MAX_PARAM_NAME_LENGTH: usize = 128;
+
+/// param_name strips the optional leading '@' and validates the rest as an identifier.
+/// https://cloud.google.com/spanner/docs/reference/standard-sql/lexical#identifiers
+fn param_name(name: &str) -> Result<String, Error> {
+    let stripped = name.strip_prefix('@').unwrap_or(name);
+    let mut chars = stripped.chars();
+    let valid = match chars.next() {
+        Some(c) => c.is_ascii_alphabetic() || c == '_',
+        None => false,
+    } && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && stripped.len() <= MAX_PARAM_NAME_LENGTH;
+    if valid {
+        Ok(stripped.to_string())
+    } else {
+        Err(Error::InvalidParamName(name.to_string()))
+    }
 }
 
 pub fn single_type<T>(code: T) -> Type
+where
+    T: Into<i32>,
+{
+    annotated_type(code, TypeAnnotationCode::Unspecified)
+}
+
+/// annotated_type returns the type with the annotation used by the PostgreSQL dialect.
+pub fn annotated_type<T>(code: T, annotation: TypeAnnotationCode) -> Type
 where
     T: Into<i32>,
 {
@@ -65,8 +648,8 @@ where
         code: code.into(),
         array_element_type: None,
         struct_type: None,
-        //TODO support PG Numeric
-        type_annotation: TypeAnnotationCode::Unspecified.into(),
+        type_annotation: annotation.into(),
+        proto_type_fqn: String::new(),
     }
 }
 
@@ -75,6 +658,12 @@ pub trait ToKind {
     fn get_type() -> Type
     where
         Self: Sized;
+
+    /// try_to_kind returns an error instead of panicking if the value can not be converted.
+    /// It is used by Statement::try_add_param.
+    fn try_to_kind(&self) -> Result<value::Kind, Error> {
+        Ok(self.to_kind())
+    }
 }
 
 pub type Kinds = Vec<(&'static str, Kind)>;
@@ -130,144 +719,1825 @@ impl ToKind for i64 {
     }
 }
 
-impl ToKind for f64 {
+impl ToKind for i32 {
     fn to_kind(&self) -> Kind {
-        value::Kind::NumberValue(*self)
+        i64::from(*self).to_kind()
     }
     fn get_type() -> Type {
-        single_type(TypeCode::Float64)
+        single_type(TypeCode::Int64)
     }
 }
 
-impl ToKind for bool {
+impl ToKind for i16 {
     fn to_kind(&self) -> Kind {
-        value::Kind::BoolValue(*self)
+        i64::from(*self).to_kind()
     }
     fn get_type() -> Type {
-        single_type(TypeCode::Bool)
+        single_type(TypeCode::Int64)
     }
 }
 
-impl ToKind for Date {
+impl ToKind for i8 {
     fn to_kind(&self) -> Kind {
-        self.format(format_description!("[year]-[month]-[day]"))
-            .unwrap()
-            .to_kind()
+        i64::from(*self).to_kind()
     }
     fn get_type() -> Type {
-        single_type(TypeCode::Date)
+        single_type(TypeCode::Int64)
     }
 }
 
-impl ToKind for OffsetDateTime {
+impl ToKind for u32 {
     fn to_kind(&self) -> Kind {
-        self.format(&Rfc3339).unwrap().to_kind()
+        i64::from(*self).to_kind()
     }
     fn get_type() -> Type {
-        single_type(TypeCode::Timestamp)
+        single_type(TypeCode::Int64)
     }
 }
 
-impl ToKind for CommitTimestamp {
+impl ToKind for u16 {
     fn to_kind(&self) -> Kind {
-        "spanner.commit_timestamp()".to_kind()
+        i64::from(*self).to_kind()
     }
     fn get_type() -> Type {
-        single_type(TypeCode::Timestamp)
+        single_type(TypeCode::Int64)
     }
 }
 
-impl ToKind for &[u8] {
+/// u64 and usize are mapped to INT64. The values greater than i64::MAX are sent as is and rejected by Cloud Spanner.
+/// Use Statement::try_add_param to check the range before sending,
+/// or SpannerNumeric::from to store the large values as NUMERIC.
+impl ToKind for u64 {
     fn to_kind(&self) -> Kind {
-        base64::encode(self).to_kind()
+        StringValue(self.to_string())
     }
     fn get_type() -> Type {
-        single_type(TypeCode::Bytes)
+        single_type(TypeCode::Int64)
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        match i64::try_from(*self) {
+            Ok(v) => Ok(v.to_kind()),
+            Err(_) => Err(Error::Int64Overflow(*self)),
+        }
     }
 }
 
-impl ToKind for Vec<u8> {
+impl ToKind for usize {
     fn to_kind(&self) -> Kind {
-        base64::encode(self).to_kind()
+        (*self as u64).to_kind()
     }
     fn get_type() -> Type {
-        single_type(TypeCode::Bytes)
+        single_type(TypeCode::Int64)
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        (*self as u64).try_to_kind()
     }
 }
 
-impl ToKind for SpannerNumeric {
+impl ToKind for NonZeroI64 {
     fn to_kind(&self) -> Kind {
-        self.as_str().to_string().to_kind()
+        self.get().to_kind()
     }
     fn get_type() -> Type {
-        single_type(TypeCode::Numeric)
+        single_type(TypeCode::Int64)
     }
 }
 
-impl<T> ToKind for T
-where
-    T: ToStruct,
-{
+impl ToKind for NonZeroI32 {
     fn to_kind(&self) -> Kind {
-        let mut fields = BTreeMap::<String, Value>::default();
-        self.to_kinds().into_iter().for_each(|e| {
-            fields.insert(e.0.into(), Value { kind: Some(e.1) });
-        });
-        Kind::StructValue(Struct { fields })
+        i64::from(self.get()).to_kind()
     }
     fn get_type() -> Type {
-        Type {
-            code: TypeCode::Struct.into(),
-            array_element_type: None,
-            type_annotation: TypeAnnotationCode::Unspecified.into(),
-            struct_type: Some(StructType {
-                fields: T::get_types()
-                    .into_iter()
-                    .map(|t| Field {
-                        name: t.0.into(),
-                        r#type: Some(t.1),
-                    })
-                    .collect(),
-            }),
-        }
+        single_type(TypeCode::Int64)
     }
 }
 
-impl<T> ToKind for Option<T>
-where
-    T: ToKind,
-{
+impl ToKind for NonZeroI16 {
     fn to_kind(&self) -> Kind {
-        match self {
-            Some(vv) => vv.to_kind(),
-            None => value::Kind::NullValue(prost_types::NullValue::NullValue.into()),
-        }
+        i64::from(self.get()).to_kind()
     }
     fn get_type() -> Type {
-        T::get_type()
+        single_type(TypeCode::Int64)
     }
 }
 
-impl<T> ToKind for Vec<T>
-where
-    T: ToKind,
-{
+impl ToKind for NonZeroI8 {
     fn to_kind(&self) -> Kind {
-        value::Kind::ListValue(ListValue {
-            values: self
-                .iter()
-                .map(|x| Value {
-                    kind: Some(x.to_kind()),
-                })
-                .collect(),
-        })
+        i64::from(self.get()).to_kind()
     }
     fn get_type() -> Type {
-        Type {
-            code: TypeCode::Array.into(),
-            array_element_type: Some(Box::new(T::get_type())),
-            struct_type: None,
-            type_annotation: TypeAnnotationCode::Unspecified.into(),
+        single_type(TypeCode::Int64)
+    }
+}
+
+impl ToKind for NonZeroU32 {
+    fn to_kind(&self) -> Kind {
+        i64::from(self.get()).to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Int64)
+    }
+}
+
+impl ToKind for NonZeroU16 {
+    fn to_kind(&self) -> Kind {
+        i64::from(self.get()).to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Int64)
+    }
+}
+
+impl ToKind for NonZeroU8 {
+    fn to_kind(&self) -> Kind {
+        i64::from(self.get()).to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Int64)
+    }
+}
+
+/// NaN and infinities are encoded as the strings "NaN", "Infinity" and "-Infinity"
+/// because they are not representable as a JSON number.
+pub(crate) fn float_to_kind(value: f64) -> Kind {
+    if value.is_nan() {
+        StringValue("NaN".to_string())
+    } else if value.is_infinite() {
+        let v = if value.is_sign_positive() {
+            "Infinity"
+        } else {
+            "-Infinity"
+        };
+        StringValue(v.to_string())
+    } else {
+        value::Kind::NumberValue(value)
+    }
+}
+
+impl ToKind for f64 {
+    fn to_kind(&self) -> Kind {
+        float_to_kind(*self)
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Float64)
+    }
+}
+
+impl ToKind for f32 {
+    fn to_kind(&self) -> Kind {
+        float_to_kind(f64::from(*self))
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Float64)
+    }
+}
+
+impl ToKind for Float32 {
+    fn to_kind(&self) -> Kind {
+        float_to_kind(f64::from(self.0))
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Float32)
+    }
+}
+
+impl ToKind for char {
+    fn to_kind(&self) -> Kind {
+        StringValue(self.to_string())
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::String)
+    }
+}
+
+impl ToKind for bool {
+    fn to_kind(&self) -> Kind {
+        value::Kind::BoolValue(*self)
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Bool)
+    }
+}
+
+impl ToKind for Date {
+    fn to_kind(&self) -> Kind {
+        self.format(format_description!("[year]-[month]-[day]"))
+            .unwrap()
+            .to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Date)
+    }
+}
+
+/// The time out of the range of TIMESTAMP, from 0001-01-01 to 9999-12-31 in UTC, is bound as is,
+/// so that Spanner rejects it. Use Statement::try_add_param to get the error before sending it.
+impl ToKind for OffsetDateTime {
+    fn to_kind(&self) -> Kind {
+        self.try_to_kind().unwrap_or_else(|_| self.to_string().to_kind())
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Timestamp)
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        let out_of_range = || Error::TimestampOutOfRange(self.to_string());
+        let utc = utc_timestamp(self).ok_or_else(out_of_range)?;
+        utc.format(&Rfc3339).map(StringValue).map_err(|_| out_of_range())
+    }
+}
+
+/// The time out of the range of TIMESTAMP is bound as is in the same way as OffsetDateTime.
+impl ToKind for SystemTime {
+    fn to_kind(&self) -> Kind {
+        self.try_to_kind().unwrap_or_else(|_| format!("{self:?}").to_kind())
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Timestamp)
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        let nanos = match self.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(d) => d.as_nanos() as i128,
+            Err(e) => -(e.duration().as_nanos() as i128),
+        };
+        OffsetDateTime::from_unix_timestamp_nanos(nanos)
+            .map_err(|_| Error::TimestampOutOfRange(format!("{self:?}")))?
+            .try_to_kind()
+    }
+}
+
+/// DateTime values are normalized to UTC before formatting, as OffsetDateTime is,
+/// because Spanner only accepts the "Z" suffix for TIMESTAMP literals.
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> ToKind for chrono::DateTime<Tz> {
+    fn to_kind(&self) -> Kind {
+        self.with_timezone(&chrono::Utc)
+            .to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)
+            .to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Timestamp)
+    }
+}
+
+/// NaiveDateTime values are bound as the timestamps in UTC.
+#[cfg(feature = "chrono")]
+impl ToKind for chrono::NaiveDateTime {
+    fn to_kind(&self) -> Kind {
+        self.and_utc().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Timestamp)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ToKind for chrono::NaiveDate {
+    fn to_kind(&self) -> Kind {
+        self.format("%Y-%m-%d").to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Date)
+    }
+}
+
+/// serde_json::Value is bound as Cloud Spanner's JSON type.
+/// Note that `serde_json::Value::Null` is sent as the JSON literal `null`, not as a SQL NULL.
+/// Use `Option::<serde_json::Value>::None` to bind a SQL NULL.
+#[cfg(feature = "serde_json")]
+impl ToKind for serde_json::Value {
+    fn to_kind(&self) -> Kind {
+        self.to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Json)
+    }
+}
+
+/// json_kind serializes the value as the JSON string.
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+fn json_kind<T: serde::Serialize + ?Sized>(value: &T) -> Result<Kind, Error> {
+    serde_json::to_string(value)
+        .map(StringValue)
+        .map_err(Error::JsonSerializeError)
+}
+
+/// json_kind_or_invalid is the same as json_kind, except that the value which fails to serialize
+/// is bound as the error message, which is never a valid JSON, so that Spanner rejects it instead of panicking.
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+fn json_kind_or_invalid<T: serde::Serialize + ?Sized>(value: &T) -> Kind {
+    json_kind(value).unwrap_or_else(|e| StringValue(format!("invalid JSON: {e}")))
+}
+
+/// Maps are bound as Cloud Spanner's JSON type with the keys in sorted order.
+///
+/// The value which fails to serialize is rejected by Spanner. Use Statement::try_add_param to get the error instead.
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+impl<V> ToKind for BTreeMap<String, V>
+where
+    V: serde::Serialize,
+{
+    fn to_kind(&self) -> Kind {
+        json_kind_or_invalid(self)
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Json)
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        json_kind(self)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+impl<V> ToKind for HashMap<String, V>
+where
+    V: serde::Serialize,
+{
+    fn to_kind(&self) -> Kind {
+        let sorted: BTreeMap<&String, &V> = self.iter().collect();
+        json_kind_or_invalid(&sorted)
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Json)
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        let sorted: BTreeMap<&String, &V> = self.iter().collect();
+        json_kind(&sorted)
+    }
+}
+
+/// The value which fails to serialize is rejected by Spanner. Use Statement::try_add_param to get the error instead.
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+impl<T> ToKind for crate::value::Json<T>
+where
+    T: serde::Serialize,
+{
+    fn to_kind(&self) -> Kind {
+        json_kind_or_invalid(&self.0)
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Json)
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        json_kind(&self.0)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl ToKind for crate::value::PgJsonb {
+    fn to_kind(&self) -> Kind {
+        self.0.to_kind()
+    }
+    fn get_type() -> Type {
+        annotated_type(TypeCode::Json, TypeAnnotationCode::PgJsonb)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl ToKind for uuid::Uuid {
+    fn to_kind(&self) -> Kind {
+        self.hyphenated().to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::String)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl ToKind for crate::value::UuidBytes {
+    fn to_kind(&self) -> Kind {
+        base64::encode(self.0.as_bytes()).to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Bytes)
+    }
+}
+
+impl ToKind for CommitTimestamp {
+    fn to_kind(&self) -> Kind {
+        "spanner.commit_timestamp()".to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Timestamp)
+    }
+}
+
+impl ToKind for TimestampValue {
+    fn to_kind(&self) -> Kind {
+        match self {
+            TimestampValue::Commit => CommitTimestamp::new().to_kind(),
+            TimestampValue::At(v) => v.to_kind(),
+        }
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Timestamp)
+    }
+}
+
+impl ToKind for &[u8] {
+    fn to_kind(&self) -> Kind {
+        base64::encode(self).to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Bytes)
+    }
+}
+
+impl ToKind for Vec<u8> {
+    fn to_kind(&self) -> Kind {
+        base64::encode(self).to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Bytes)
+    }
+}
+
+// base64::encode sizes the output exactly, so the shared buffer is encoded with a single allocation.
+impl ToKind for bytes::Bytes {
+    fn to_kind(&self) -> Kind {
+        StringValue(base64::encode(self))
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Bytes)
+    }
+}
+
+impl ToKind for bytes::BytesMut {
+    fn to_kind(&self) -> Kind {
+        StringValue(base64::encode(self))
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Bytes)
+    }
+}
+
+impl<const N: usize> ToKind for [u8; N] {
+    fn to_kind(&self) -> Kind {
+        base64::encode(self).to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Bytes)
+    }
+}
+
+impl ToKind for SpannerNumeric {
+    fn to_kind(&self) -> Kind {
+        self.as_str().to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Numeric)
+    }
+}
+
+impl ToKind for Interval {
+    fn to_kind(&self) -> Kind {
+        self.to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Interval)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl ToKind for crate::value::Numeric {
+    fn to_kind(&self) -> Kind {
+        self.value().to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Numeric)
+    }
+}
+
+impl ToKind for PgNumeric {
+    fn to_kind(&self) -> Kind {
+        self.as_str().to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        annotated_type(TypeCode::Numeric, TypeAnnotationCode::PgNumeric)
+    }
+}
+
+impl<T> ToKind for crate::value::Proto<T>
+where
+    T: prost::Message + crate::value::ProtoName,
+{
+    fn to_kind(&self) -> Kind {
+        base64::encode(self.0.encode_to_vec()).to_kind()
+    }
+    fn get_type() -> Type {
+        Type {
+            proto_type_fqn: T::FULL_NAME.to_string(),
+            ..single_type(TypeCode::Proto)
+        }
+    }
+}
+
+impl<T> ToKind for crate::value::EnumValue<T>
+where
+    T: crate::value::ProtoName + Copy + Into<i32>,
+{
+    fn to_kind(&self) -> Kind {
+        i64::from(self.0.into()).to_kind()
+    }
+    fn get_type() -> Type {
+        Type {
+            proto_type_fqn: T::FULL_NAME.to_string(),
+            ..single_type(TypeCode::Enum)
+        }
+    }
+}
+
+// A blanket impl for &T would overlap with the ToStruct blanket impl,
+// because &T implements ToStruct whenever T does.
+impl ToKind for &String {
+    fn to_kind(&self) -> Kind {
+        (*self).to_kind()
+    }
+    fn get_type() -> Type {
+        String::get_type()
+    }
+}
+
+impl ToKind for &i64 {
+    fn to_kind(&self) -> Kind {
+        (*self).to_kind()
+    }
+    fn get_type() -> Type {
+        i64::get_type()
+    }
+}
+
+impl ToKind for &f64 {
+    fn to_kind(&self) -> Kind {
+        (*self).to_kind()
+    }
+    fn get_type() -> Type {
+        f64::get_type()
+    }
+}
+
+impl ToKind for &bool {
+    fn to_kind(&self) -> Kind {
+        (*self).to_kind()
+    }
+    fn get_type() -> Type {
+        bool::get_type()
+    }
+}
+
+impl ToKind for &Date {
+    fn to_kind(&self) -> Kind {
+        (*self).to_kind()
+    }
+    fn get_type() -> Type {
+        Date::get_type()
+    }
+}
+
+impl ToKind for &OffsetDateTime {
+    fn to_kind(&self) -> Kind {
+        (*self).to_kind()
+    }
+    fn get_type() -> Type {
+        OffsetDateTime::get_type()
+    }
+}
+
+impl ToKind for &SpannerNumeric {
+    fn to_kind(&self) -> Kind {
+        (*self).to_kind()
+    }
+    fn get_type() -> Type {
+        SpannerNumeric::get_type()
+    }
+}
+
+impl ToKind for Cow<'_, str> {
+    fn to_kind(&self) -> Kind {
+        StringValue(self.to_string())
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::String)
+    }
+}
+
+/// STRUCT values are encoded as a list whose elements are in the order of the struct_type fields.
+/// Therefore `to_kinds` must return the fields in the same order as `get_types`.
+impl<T> ToKind for T
+where
+    T: ToStruct,
+{
+    fn to_kind(&self) -> Kind {
+        Kind::ListValue(ListValue {
+            values: self.to_kinds().into_iter().map(|e| Value { kind: Some(e.1) }).collect(),
+        })
+    }
+    fn get_type() -> Type {
+        Type {
+            code: TypeCode::Struct.into(),
+            array_element_type: None,
+            type_annotation: TypeAnnotationCode::Unspecified.into(),
+            proto_type_fqn: String::new(),
+            struct_type: Some(StructType {
+                fields: T::get_types()
+                    .into_iter()
+                    .map(|t| Field {
+                        name: t.0.into(),
+                        r#type: Some(t.1),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+}
+
+impl<T> ToKind for Option<T>
+where
+    T: ToKind,
+{
+    fn to_kind(&self) -> Kind {
+        match self {
+            Some(vv) => vv.to_kind(),
+            None => value::Kind::NullValue(prost_types::NullValue::NullValue.into()),
+        }
+    }
+    fn get_type() -> Type {
+        T::get_type()
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        match self {
+            Some(vv) => vv.try_to_kind(),
+            None => Ok(self.to_kind()),
+        }
+    }
+}
+
+impl<T> ToKind for Vec<T>
+where
+    T: ToKind,
+{
+    fn to_kind(&self) -> Kind {
+        self.as_slice().to_kind()
+    }
+    fn get_type() -> Type {
+        <&[T]>::get_type()
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        self.as_slice().try_to_kind()
+    }
+}
+
+/// Slices and arrays are mapped to Cloud Spanner's ARRAY type.
+/// u8 does not implement ToKind, so &[u8] and [u8; N] are always mapped to BYTES.
+impl<T> ToKind for &[T]
+where
+    T: ToKind,
+{
+    fn to_kind(&self) -> Kind {
+        value::Kind::ListValue(ListValue {
+            values: self
+                .iter()
+                .map(|x| Value {
+                    kind: Some(x.to_kind()),
+                })
+                .collect(),
+        })
+    }
+    fn get_type() -> Type {
+        Type {
+            code: TypeCode::Array.into(),
+            array_element_type: Some(Box::new(T::get_type())),
+            struct_type: None,
+            type_annotation: TypeAnnotationCode::Unspecified.into(),
+            proto_type_fqn: String::new(),
+        }
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        let values = self
+            .iter()
+            .map(|x| x.try_to_kind().map(|kind| Value { kind: Some(kind) }))
+            .collect::<Result<_, _>>()?;
+        Ok(value::Kind::ListValue(ListValue { values }))
+    }
+}
+
+impl<T, const N: usize> ToKind for [T; N]
+where
+    T: ToKind,
+{
+    fn to_kind(&self) -> Kind {
+        self.as_slice().to_kind()
+    }
+    fn get_type() -> Type {
+        <&[T]>::get_type()
+    }
+    fn try_to_kind(&self) -> Result<Kind, Error> {
+        self.as_slice().try_to_kind()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::statement::{single_type, Kinds, Statement, ToKind, ToStruct, Types};
+    use crate::value::{Float32, Interval, PgNumeric};
+    use google_cloud_googleapis::spanner::v1::struct_type::Field;
+    use google_cloud_googleapis::spanner::v1::{StructType, Type, TypeAnnotationCode, TypeCode};
+    use prost_types::value::Kind;
+    use std::borrow::Cow;
+    use std::collections::BTreeSet;
+
+    struct Item {
+        item_id: i64,
+    }
+
+    impl ToStruct for Item {
+        fn to_kinds(&self) -> Kinds {
+            vec![("ItemId", self.item_id.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![("ItemId", i64::get_type())]
+        }
+    }
+
+    struct User {
+        user_id: String,
+        items: Vec<Item>,
+    }
+
+    impl ToStruct for User {
+        fn to_kinds(&self) -> Kinds {
+            vec![("UserId", self.user_id.to_kind()), ("Items", self.items.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![("UserId", String::get_type()), ("Items", Vec::<Item>::get_type())]
+        }
+    }
+
+    fn array_type(element: Type) -> Type {
+        Type {
+            code: TypeCode::Array.into(),
+            array_element_type: Some(Box::new(element)),
+            struct_type: None,
+            type_annotation: TypeAnnotationCode::Unspecified.into(),
+            proto_type_fqn: String::new(),
+        }
+    }
+
+    fn struct_type(fields: Vec<(&str, Type)>) -> Type {
+        Type {
+            code: TypeCode::Struct.into(),
+            array_element_type: None,
+            struct_type: Some(StructType {
+                fields: fields
+                    .into_iter()
+                    .map(|(name, tp)| Field {
+                        name: name.to_string(),
+                        r#type: Some(tp),
+                    })
+                    .collect(),
+            }),
+            type_annotation: TypeAnnotationCode::Unspecified.into(),
+            proto_type_fqn: String::new(),
+        }
+    }
+
+    fn assert_int64<T: ToKind>(value: T, expected: &str) {
+        assert_eq!(T::get_type().code, TypeCode::Int64 as i32);
+        match value.to_kind() {
+            Kind::StringValue(v) => assert_eq!(v, expected),
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[test]
+    fn test_small_integer_to_kind() {
+        assert_int64(i32::MIN, "-2147483648");
+        assert_int64(i16::MAX, "32767");
+        assert_int64(-8_i8, "-8");
+        assert_int64(u32::MAX, "4294967295");
+        assert_int64(u16::MAX, "65535");
+        assert_int64(Some(1_i32), "1");
+        assert_eq!(Vec::<i32>::get_type().array_element_type.unwrap().code, TypeCode::Int64 as i32);
+        match Option::<i32>::None.to_kind() {
+            Kind::NullValue(_) => {}
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[test]
+    fn test_unsigned_to_kind() {
+        use crate::statement::Error;
+        use crate::value::SpannerNumeric;
+
+        assert_int64(i64::MAX as u64, "9223372036854775807");
+        assert_int64(3_usize, "3");
+        let mut stmt = Statement::new("SELECT @Max, @Len");
+        stmt.try_add_param("Max", &(i64::MAX as u64)).unwrap();
+        stmt.try_add_param("Len", &vec![1, 2].len()).unwrap();
+        assert_eq!(stmt.params["Len"].kind, Some(Kind::StringValue("2".to_string())));
+        match stmt.try_add_param("Overflow", &(i64::MAX as u64 + 1)) {
+            Err(Error::Int64Overflow(v)) => assert_eq!(v, 9223372036854775808),
+            r => panic!("must overflow {:?}", r),
+        }
+        assert!(stmt.try_add_param("Overflow", &vec![Some(u64::MAX)]).is_err());
+        assert!(stmt.try_add_param("Overflow", &usize::MAX).is_err());
+        let far_future = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1 << 40);
+        assert!(matches!(
+            stmt.try_add_param("Time", &far_future),
+            Err(Error::TimestampOutOfRange(_))
+        ));
+        let before_year_one = time::macros::datetime!(0000-12-31 23:59:59 UTC);
+        assert!(matches!(
+            stmt.try_add_param("Time", &before_year_one),
+            Err(Error::TimestampOutOfRange(_))
+        ));
+        // the values out of the range are rejected by Spanner instead of panicking
+        assert_eq!(before_year_one.to_kind(), before_year_one.to_string().to_kind());
+        assert!(matches!(far_future.to_kind(), Kind::StringValue(_)));
+        // the value is never wrapped
+        assert_eq!(u64::MAX.to_kind(), Kind::StringValue("18446744073709551615".to_string()));
+        assert_eq!(SpannerNumeric::from(u64::MAX).as_str(), "18446744073709551615");
+    }
+
+    #[test]
+    fn test_non_zero_to_kind() {
+        use std::num::{NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32};
+
+        assert_int64(NonZeroI64::new(i64::MIN).unwrap(), "-9223372036854775808");
+        assert_int64(NonZeroI8::new(-1).unwrap(), "-1");
+        assert_int64(NonZeroU16::new(u16::MAX).unwrap(), "65535");
+        assert_int64(NonZeroU32::new(u32::MAX).unwrap(), "4294967295");
+        assert_int64(NonZeroI64::new(7), "7");
+        match Option::<NonZeroI64>::None.to_kind() {
+            Kind::NullValue(_) => {}
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[test]
+    fn test_slice_to_kind() {
+        let ids = vec![1_i64, 2, 3];
+        let mut stmt = Statement::new("SELECT * FROM User WHERE UserId IN UNNEST(@Ids)");
+        stmt.add_param("Ids", &ids.as_slice());
+        stmt.add_param("Names", &["a", "b"]);
+        stmt.add_param("Bytes", &[1_u8, 2].as_slice());
+        stmt.add_param("FixedBytes", &[1_u8, 2]);
+        assert_eq!(stmt.params["Ids"].kind, Some(ids.to_kind()));
+        assert_eq!(stmt.param_types["Ids"], Vec::<i64>::get_type());
+        assert_eq!(stmt.params["Names"].kind, Some(vec!["a", "b"].to_kind()));
+        assert_eq!(stmt.param_types["Names"], Vec::<&str>::get_type());
+        // u8 slices and arrays are BYTES, not ARRAY<INT64>
+        assert_eq!(stmt.param_types["Bytes"].code, TypeCode::Bytes as i32);
+        assert_eq!(stmt.param_types["FixedBytes"].code, TypeCode::Bytes as i32);
+        assert_eq!(stmt.params["Bytes"].kind, Some(Kind::StringValue("AQI=".to_string())));
+    }
+
+    #[test]
+    fn test_char_to_kind() {
+        assert_eq!(char::get_type().code, TypeCode::String as i32);
+        assert_eq!('Y'.to_kind(), Kind::StringValue("Y".to_string()));
+        assert_eq!('✓'.to_kind(), Kind::StringValue("✓".to_string()));
+        assert_eq!(Some('N').to_kind(), Kind::StringValue("N".to_string()));
+        assert_eq!(
+            Vec::<char>::get_type().array_element_type.unwrap().code,
+            TypeCode::String as i32
+        );
+        match vec!['a', 'é'].to_kind() {
+            Kind::ListValue(v) => assert_eq!(v.values[1].kind, Some(Kind::StringValue("é".to_string()))),
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[test]
+    fn test_bytes_to_kind() {
+        match Option::<Vec<u8>>::None.to_kind() {
+            Kind::NullValue(_) => {}
+            _ => panic!("invalid kind"),
+        }
+        assert_eq!(Option::<Vec<u8>>::get_type().code, TypeCode::Bytes as i32);
+
+        let array = vec![vec![1_u8, 2], vec![3_u8]];
+        let array_type = Vec::<Vec<u8>>::get_type();
+        assert_eq!(array_type.code, TypeCode::Array as i32);
+        assert_eq!(array_type.array_element_type.unwrap().code, TypeCode::Bytes as i32);
+        match array.to_kind() {
+            Kind::ListValue(v) => {
+                assert_eq!(v.values.len(), 2);
+                match v.values[0].kind.as_ref().unwrap() {
+                    Kind::StringValue(v) => assert_eq!(v, &base64::encode([1_u8, 2])),
+                    _ => panic!("invalid kind"),
+                }
+            }
+            _ => panic!("invalid kind"),
+        }
+
+        let hash = [7_u8; 32];
+        assert_eq!(<[u8; 32]>::get_type().code, TypeCode::Bytes as i32);
+        match hash.to_kind() {
+            Kind::StringValue(v) => assert_eq!(base64::decode(v).unwrap(), hash),
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[test]
+    fn test_shared_bytes_to_kind() {
+        let payload = bytes::Bytes::from(vec![0xAB_u8; 10 * 1024 * 1024]);
+        assert_eq!(bytes::Bytes::get_type().code, TypeCode::Bytes as i32);
+        match payload.to_kind() {
+            Kind::StringValue(v) => {
+                // only the base64 output is allocated, exactly once
+                assert_eq!(v.len(), payload.len().div_ceil(3) * 4);
+                assert_eq!(v.capacity(), v.len());
+                assert_eq!(base64::decode(v).unwrap(), payload);
+            }
+            _ => panic!("invalid kind"),
+        }
+
+        let mut buf = bytes::BytesMut::new();
+        buf.extend_from_slice(b"spanner");
+        assert_eq!(bytes::BytesMut::get_type().code, TypeCode::Bytes as i32);
+        match buf.to_kind() {
+            Kind::StringValue(v) => assert_eq!(v, base64::encode(b"spanner")),
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[test]
+    fn test_reference_to_kind() {
+        struct User {
+            name: String,
+            age: i64,
+        }
+        let user = User {
+            name: "name".to_string(),
+            age: 10,
+        };
+        let mut stmt = Statement::new("SELECT * FROM User WHERE Name = @Name AND Age = @Age");
+        stmt.add_param("Name", &&user.name);
+        stmt.add_param("Age", &Some(&user.age));
+        assert_eq!(stmt.param_types["Name"].code, TypeCode::String as i32);
+        assert_eq!(stmt.param_types["Age"].code, TypeCode::Int64 as i32);
+        match stmt.params["Age"].kind.as_ref().unwrap() {
+            Kind::StringValue(v) => assert_eq!(v, "10"),
+            _ => panic!("invalid kind"),
+        }
+        assert_eq!(user.name, "name");
+
+        let names: Vec<Cow<'_, str>> = vec![Cow::Borrowed("a"), Cow::Owned("b".to_string())];
+        assert_eq!(
+            Vec::<Cow<'_, str>>::get_type().array_element_type.unwrap().code,
+            TypeCode::String as i32
+        );
+        match names.to_kind() {
+            Kind::ListValue(v) => assert_eq!(v.values.len(), 2),
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[test]
+    fn test_time_to_kind() {
+        let ts = time::macros::datetime!(2016-12-31 23:59:59.999999999 +05:30);
+        match ts.to_kind() {
+            Kind::StringValue(v) => assert_eq!(v, "2016-12-31T18:29:59.999999999Z"),
+            _ => panic!("invalid kind"),
+        }
+        match time::macros::date!(0999 - 01 - 05).to_kind() {
+            Kind::StringValue(v) => assert_eq!(v, "0999-01-05"),
+            _ => panic!("invalid kind"),
+        }
+        assert_eq!(time::OffsetDateTime::get_type().code, TypeCode::Timestamp as i32);
+        assert_eq!(time::Date::get_type().code, TypeCode::Date as i32);
+    }
+
+    #[test]
+    fn test_system_time_to_kind() {
+        let after = std::time::UNIX_EPOCH + std::time::Duration::new(1_500_000_000, 123_456_789);
+        let before = std::time::UNIX_EPOCH - std::time::Duration::new(86_400, 500_000_000);
+        assert_eq!(std::time::SystemTime::get_type().code, TypeCode::Timestamp as i32);
+        match (after.to_kind(), before.to_kind()) {
+            (Kind::StringValue(a), Kind::StringValue(b)) => {
+                assert_eq!(a, "2017-07-14T02:40:00.123456789Z");
+                assert_eq!(b, "1969-12-30T23:59:59.5Z");
+            }
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[test]
+    fn test_nested_struct_array_type() {
+        let item = struct_type(vec![("ItemId", single_type(TypeCode::Int64))]);
+        let user = struct_type(vec![("UserId", single_type(TypeCode::String)), ("Items", array_type(item))]);
+        assert_eq!(Vec::<User>::get_type(), array_type(user));
+    }
+
+    #[test]
+    fn test_struct_array_to_kind() {
+        let users = vec![User {
+            user_id: "user".to_string(),
+            items: vec![Item { item_id: 1 }, Item { item_id: 2 }],
+        }];
+        let user = match users.to_kind() {
+            Kind::ListValue(mut v) => v.values.remove(0).kind.unwrap(),
+            _ => panic!("invalid kind"),
+        };
+        // STRUCT is encoded as a list in field order
+        let mut fields = match user {
+            Kind::ListValue(v) => v.values.into_iter().map(|v| v.kind.unwrap()),
+            _ => panic!("invalid kind"),
+        };
+        assert_eq!(fields.next(), Some(Kind::StringValue("user".to_string())));
+        match fields.next() {
+            Some(Kind::ListValue(items)) => {
+                assert_eq!(items.values.len(), 2);
+                match items.values[1].kind.as_ref() {
+                    Some(Kind::ListValue(item)) => {
+                        assert_eq!(item.values[0].kind, Some(Kind::StringValue("2".to_string())))
+                    }
+                    _ => panic!("invalid kind"),
+                }
+            }
+            _ => panic!("invalid kind"),
+        }
+        assert!(fields.next().is_none());
+    }
+
+    #[test]
+    fn test_empty_struct_array_param() {
+        let mut stmt = Statement::new("SELECT * FROM UNNEST(@Users)");
+        stmt.add_param("Users", &Vec::<User>::new());
+        match stmt.params["Users"].kind.as_ref() {
+            Some(Kind::ListValue(v)) => assert!(v.values.is_empty()),
+            _ => panic!("invalid kind"),
+        }
+        let element = stmt.param_types["Users"].array_element_type.as_ref().unwrap();
+        assert_eq!(element.struct_type.as_ref().unwrap().fields.len(), 2);
+    }
+
+    #[test]
+    fn test_with_param() {
+        let premium = true;
+        let stmt = match premium {
+            true => Statement::new("SELECT * FROM User WHERE Premium = @Premium AND UserId = @UserId")
+                .with_param("Premium", &premium)
+                .with_param("UserId", &"user1"),
+            false => Statement::new("SELECT * FROM User"),
+        };
+        let mut expected = Statement::new(stmt.sql.clone());
+        expected.add_param("Premium", &true);
+        expected.add_param("UserId", &"user1");
+        assert_eq!(stmt.params, expected.params);
+        assert_eq!(stmt.param_types, expected.param_types);
+    }
+
+    #[test]
+    fn test_param_name() {
+        use crate::statement::Error;
+
+        let mut stmt = Statement::new("SELECT @Id, @_user_id2");
+        stmt.add_param("@Id", &1_i64);
+        stmt.try_add_param("_user_id2", &"a").unwrap();
+        assert_eq!(stmt.params.keys().collect::<Vec<_>>(), vec!["Id", "_user_id2"]);
+
+        let mut with_at = Statement::new("SELECT @Id");
+        with_at.add_param("@Id", &1_i64);
+        let mut without_at = Statement::new("SELECT @Id");
+        without_at.add_param("Id", &1_i64);
+        assert_eq!(with_at.params, without_at.params);
+        assert_eq!(with_at.param_types, without_at.param_types);
+
+        for name in ["user-id", "1id", "", "@", "@@id", "id name", "日本"] {
+            match stmt.try_add_param(name, &1_i64) {
+                Err(Error::InvalidParamName(v)) => assert_eq!(v, name),
+                r => panic!("{name}: unexpected {r:?}"),
+            }
+        }
+        assert!(stmt.try_add_param(&"a".repeat(129), &1_i64).is_err());
+        stmt.try_add_param(&"a".repeat(128), &1_i64).unwrap();
+        assert_eq!(stmt.params.len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid parameter name: name=user-id")]
+    fn test_add_param_invalid_name() {
+        Statement::new("SELECT 1").add_param("user-id", &1_i64);
+    }
+
+    #[test]
+    fn test_rewrite_positional_params() {
+        use crate::statement::rewrite_positional_params;
+
+        let cases = [
+            ("SELECT ?", "SELECT @p1", 1),
+            (
+                "SELECT * FROM t WHERE a = ? AND b IN (?,?)",
+                "SELECT * FROM t WHERE a = @p1 AND b IN (@p2,@p3)",
+                3,
+            ),
+            ("SELECT '?', \"?\", ?", "SELECT '?', \"?\", @p1", 1),
+            ("SELECT 'it\\'s ?', ?", "SELECT 'it\\'s ?', @p1", 1),
+            ("SELECT r'\\', ?", "SELECT r'\\', @p1", 1),
+            ("SELECT '''a ' ? ''', ?", "SELECT '''a ' ? ''', @p1", 1),
+            ("SELECT `a?b` FROM t WHERE c = ?", "SELECT `a?b` FROM t WHERE c = @p1", 1),
+            ("SELECT ? -- why?\nFROM t", "SELECT @p1 -- why?\nFROM t", 1),
+            ("SELECT ? # why?\n, ?", "SELECT @p1 # why?\n, @p2", 2),
+            ("SELECT /* ? \n ? */ ?", "SELECT /* ? \n ? */ @p1", 1),
+            ("SELECT 1 - ?", "SELECT 1 - @p1", 1),
+            ("SELECT '?", "SELECT '?", 0),
+            ("SELECT /* ?", "SELECT /* ?", 0),
+        ];
+        for (sql, expected, count) in cases {
+            assert_eq!(rewrite_positional_params(sql), (expected.to_string(), count), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_sql_keywords() {
+        use crate::statement::sql_keywords;
+
+        assert_eq!(
+            sql_keywords("update t SET a = 'then return' -- THEN RETURN\nWHERE b = @return"),
+            vec!["UPDATE", "T", "SET", "A", "WHERE", "B"]
+        );
+        assert_eq!(
+            sql_keywords("@{PDML_MAX_PARALLELISM=10} /* SELECT */ (SELECT `then` FROM t)"),
+            vec!["SELECT", "FROM", "T"]
+        );
+        assert!(sql_keywords("  -- comment only").is_empty());
+    }
+
+    #[test]
+    fn test_has_then_return() {
+        use crate::statement::has_then_return;
+
+        assert!(has_then_return("INSERT INTO t (id) VALUES (GENERATE_UUID()) THEN RETURN id"));
+        assert!(has_then_return("delete from t where true then\nreturn *"));
+        assert!(!has_then_return("UPDATE t SET a = 'THEN RETURN' WHERE true"));
+        assert!(!has_then_return("UPDATE t SET a = CASE WHEN b THEN returned END WHERE true"));
+    }
+
+    #[test]
+    fn test_with_positional_params() {
+        use crate::statement::Error;
+        use crate::value::SpannerValue;
+
+        let stmt = Statement::with_positional_params("SELECT * FROM t WHERE a = ? AND b = ?", [1_i64, 2]).unwrap();
+        assert_eq!(stmt.sql, "SELECT * FROM t WHERE a = @p1 AND b = @p2");
+        let mut expected = Statement::new("");
+        expected.add_param("p1", &1_i64);
+        expected.add_param("p2", &2_i64);
+        assert_eq!(stmt.params, expected.params);
+        assert_eq!(stmt.param_types, expected.param_types);
+
+        let stmt =
+            Statement::with_positional_values("SELECT ?, ?", vec!["a".into(), SpannerValue::null::<bool>()]).unwrap();
+        let mut expected = Statement::new("");
+        expected.add_param("p1", &"a");
+        expected.add_null_param::<bool>("p2");
+        assert_eq!(stmt.params, expected.params);
+        assert_eq!(stmt.param_types, expected.param_types);
+
+        match Statement::with_positional_params("SELECT ?, ?", [1_i64]) {
+            Err(Error::PositionalParamCount(2, 1)) => {}
+            r => panic!("unexpected {:?}", r.map(|s| s.sql)),
+        }
+        match Statement::with_positional_params("SELECT '?'", [1_i64]) {
+            Err(Error::PositionalParamCount(0, 1)) => {}
+            r => panic!("unexpected {:?}", r.map(|s| s.sql)),
+        }
+        match Statement::with_positional_values("SELECT ?", vec![]) {
+            Err(Error::PositionalParamCount(1, 0)) => {}
+            r => panic!("unexpected {:?}", r.map(|s| s.sql)),
+        }
+    }
+
+    #[test]
+    fn test_add_struct_params() {
+        use crate::statement::Error;
+
+        let item = Item2 {
+            id: 1,
+            name: Some("a".to_string()),
+        };
+        let stmt = Statement::from_struct("UPDATE t SET Name = @Name WHERE Id = @Id", &item).unwrap();
+        let mut expected = Statement::new("");
+        expected.add_param("Id", &1_i64);
+        expected.add_param("Name", &"a");
+        assert_eq!(stmt.params, expected.params);
+        assert_eq!(stmt.param_types, expected.param_types);
+
+        let mut stmt = Statement::new("SELECT 1");
+        stmt.add_param("Name", &"b");
+        match stmt.add_struct_params(&item) {
+            Err(Error::DuplicateParam(name)) => assert_eq!(name, "Name"),
+            r => panic!("unexpected {r:?}"),
+        }
+        // nothing is bound on error
+        assert_eq!(stmt.params.len(), 1);
+        assert_eq!(stmt.params["Name"].kind, Some("b".to_kind()));
+
+        stmt.add_struct_params_prefixed("new_", &item).unwrap();
+        stmt.add_struct_params_prefixed("old_", &item).unwrap();
+        assert_eq!(
+            stmt.params.keys().collect::<Vec<_>>(),
+            vec!["Name", "new_Id", "new_Name", "old_Id", "old_Name"]
+        );
+        assert_eq!(stmt.param_types["new_Name"], String::get_type());
+        assert!(matches!(
+            stmt.add_struct_params_prefixed("new_", &item),
+            Err(Error::DuplicateParam(_))
+        ));
+        assert!(matches!(
+            stmt.add_struct_params_prefixed("1", &item),
+            Err(Error::InvalidParamName(_))
+        ));
+    }
+
+    #[test]
+    fn test_statement_debug() {
+        let mut stmt = Statement::new("SELECT * FROM User WHERE UserId = @UserId");
+        stmt.add_param("UserId", &"secret");
+        stmt.add_param("Tags", &vec!["a"]);
+        stmt.add_null_param::<i64>("Level");
+        stmt.add_param("Item", &Item2 { id: 1, name: None });
+        stmt.add_param("Price", &PgNumeric::new("1.5".to_string()));
+        assert_eq!(
+            format!("{stmt:?}"),
+            "Statement { sql: \"SELECT * FROM User WHERE UserId = @UserId\", params: {\"Item\": STRUCT<Id INT64, Name STRING>(<redacted>), \"Level\": INT64(<redacted>), \"Price\": NUMERIC(PG_NUMERIC)(<redacted>), \"Tags\": ARRAY<STRING>(<redacted>), \"UserId\": STRING(<redacted>)} }"
+        );
+        assert!(!format!("{stmt:#?}").contains("secret"));
+
+        let mut stmt = Statement::new("SELECT @UserId");
+        stmt.add_param("UserId", &"secret");
+        assert_eq!(
+            format!("{:?}", stmt.debug_with_values()),
+            "Statement { sql: \"SELECT @UserId\", params: {\"UserId\": STRING(StringValue(\"secret\"))} }"
+        );
+    }
+
+    #[test]
+    fn test_statement_eq() {
+        let build = |user_id: &str| Statement::new("SELECT @UserId").with_param("UserId", &user_id);
+        assert_eq!(build("a"), build("a"));
+        assert_ne!(build("a"), build("b"));
+        assert_ne!(build("a"), Statement::new("SELECT @UserId"));
+    }
+
+    #[test]
+    fn test_add_in_clause() {
+        let mut stmt = Statement::new("");
+        stmt.add_param("in_0", &"bound");
+        let ids = stmt.add_in_clause("Id", vec![1_i64, 2]);
+        let other_ids = stmt.add_in_clause("Id", vec![3_i64]);
+        let names = stmt.add_in_clause("u.`Name`", vec!["a"]);
+        let empty = stmt.add_in_clause("Id", Vec::<i64>::new());
+        assert_eq!(ids, "Id IN UNNEST(@in_1)");
+        assert_eq!(other_ids, "Id IN UNNEST(@in_2)");
+        assert_eq!(names, "u.`Name` IN UNNEST(@in_3)");
+        assert_eq!(empty, "FALSE");
+        assert_eq!(stmt.params["in_0"].kind, Some("bound".to_kind()));
+        assert_eq!(stmt.params["in_1"].kind, Some(vec![1_i64, 2].to_kind()));
+        assert_eq!(stmt.params["in_2"].kind, Some(vec![3_i64].to_kind()));
+        assert_eq!(stmt.param_types["in_3"], Vec::<String>::get_type());
+        assert_eq!(stmt.params.len(), 4);
+
+        // the long column does not make the name longer than 128 characters.
+        let column = format!("LOWER({})", "a".repeat(200));
+        assert_eq!(stmt.add_in_clause(&column, vec!["a"]), format!("{column} IN UNNEST(@in_4)"));
+    }
+
+    #[test]
+    fn test_infer_types() {
+        let mut stmt = Statement::new("SELECT @Id, @Name, @DeletedAt, @Nullable");
+        stmt.add_param("Id", &1_i64);
+        stmt.add_param_untyped("Name", &"a");
+        stmt.add_null_param::<time::OffsetDateTime>("DeletedAt");
+        stmt.add_param_untyped("Nullable", &Option::<i64>::None);
+        assert_eq!(
+            stmt.request_param_types().keys().collect::<BTreeSet<_>>(),
+            BTreeSet::from([&"Id".to_string(), &"DeletedAt".to_string(), &"Nullable".to_string()])
+        );
+        assert_eq!(stmt.params["Name"].kind, Some("a".to_kind()));
+
+        stmt.infer_types(true);
+        let types = stmt.request_param_types();
+        assert_eq!(types.len(), 2);
+        assert_eq!(types["DeletedAt"], time::OffsetDateTime::get_type());
+        assert_eq!(types["Nullable"], i64::get_type());
+        assert_eq!(stmt.params.len(), 4);
+
+        // untyped replaces the type of the parameter already bound
+        stmt.infer_types(false);
+        stmt.add_param_untyped("Id", &2_i64);
+        assert!(!stmt.request_param_types().contains_key("Id"));
+    }
+
+    #[test]
+    fn test_inspect_params() {
+        let mut stmt = Statement::new("SELECT @Id, @tenant_id")
+            .with_param("tenant_id", &"t1")
+            .with_param("Id", &1_i64);
+        assert!(stmt.has_param("tenant_id"));
+        assert!(stmt.has_param("@tenant_id"));
+        assert!(!stmt.has_param("TenantId"));
+        assert_eq!(stmt.param_names().collect::<Vec<_>>(), vec!["Id", "tenant_id"]);
+
+        assert!(stmt.remove_param("@tenant_id"));
+        assert!(!stmt.remove_param("tenant_id"));
+        assert!(!stmt.has_param("tenant_id"));
+        assert!(!stmt.param_types.contains_key("tenant_id"));
+        stmt.add_param("tenant_id", &"t2");
+        assert_eq!(stmt.params["tenant_id"].kind, Some("t2".to_kind()));
+
+        stmt.clear_params();
+        assert_eq!(stmt.param_names().count(), 0);
+        assert!(stmt.param_types.is_empty());
+        assert_eq!(stmt, Statement::new("SELECT @Id, @tenant_id"));
+    }
+
+    #[test]
+    fn test_shared_param() {
+        use crate::statement::{into_request_params, SharedValue};
+        use std::sync::Arc;
+
+        let blob = SharedValue::new(&vec![1_u8; 1024]);
+        let mut insert = Statement::new("INSERT INTO Blob (Id, Data) VALUES (1, @Data)");
+        insert.add_shared_param("Data", &blob);
+        let mut update = Statement::new("UPDATE Blob SET Data = @Data WHERE Id = 2");
+        update.add_shared_param("@Data", &blob);
+        let cloned = update.clone();
+
+        let buffer = |stmt: &Statement| match &stmt.params["Data"].kind {
+            Some(Kind::StringValue(v)) => v.as_ptr(),
+            _ => panic!("must be bytes"),
+        };
+        assert!(Arc::ptr_eq(&insert.params["Data"], &update.params["Data"]));
+        assert_eq!(buffer(&insert), buffer(&update));
+        assert_eq!(buffer(&update), buffer(&cloned));
+        assert_eq!(insert.param_types["Data"], Vec::<u8>::get_type());
+
+        let mut expected = Statement::new("");
+        expected.add_param("Data", &vec![1_u8; 1024]);
+        assert_eq!(insert.params, expected.params);
+        assert_eq!(insert.request_params(), into_request_params(expected.params.clone()));
+
+        // the value not shared is moved into the request
+        let unique = buffer(&expected);
+        match &into_request_params(expected.params)["Data"].kind {
+            Some(Kind::StringValue(v)) => assert_eq!(v.as_ptr(), unique),
+            _ => panic!("must be bytes"),
+        }
+    }
+
+    #[test]
+    fn test_add_params() {
+        use crate::value::SpannerValue;
+
+        let mut params: Vec<(String, SpannerValue)> = vec![];
+        for (i, filter) in ["name", "level", "tags", "deleted", "empty", "struct"]
+            .iter()
+            .enumerate()
+        {
+            let value = match *filter {
+                "name" => "user1".into(),
+                "level" => (i as i64).into(),
+                "tags" => SpannerValue::array(vec!["a", "b"]),
+                "deleted" => Option::<time::OffsetDateTime>::None.into(),
+                "empty" => SpannerValue::array(Vec::<i64>::new()),
+                _ => SpannerValue::Struct(vec![
+                    ("Id".to_string(), 1_i64.into()),
+                    ("Name".to_string(), SpannerValue::null::<String>()),
+                ]),
+            };
+            params.push((filter.to_string(), value));
+        }
+        let mut stmt = Statement::new("SELECT 1");
+        stmt.add_params(params);
+
+        let mut expected = Statement::new("SELECT 1");
+        expected.add_param("name", &"user1");
+        expected.add_param("level", &1_i64);
+        expected.add_param("tags", &vec!["a", "b"]);
+        expected.add_null_param::<time::OffsetDateTime>("deleted");
+        expected.add_param("empty", &Vec::<i64>::new());
+        expected.add_param("struct", &Item2 { id: 1, name: None });
+        assert_eq!(stmt.params, expected.params);
+        assert_eq!(stmt.param_types, expected.param_types);
+    }
+
+    struct Item2 {
+        id: i64,
+        name: Option<String>,
+    }
+
+    impl ToStruct for Item2 {
+        fn to_kinds(&self) -> Kinds {
+            vec![("Id", self.id.to_kind()), ("Name", self.name.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![("Id", i64::get_type()), ("Name", String::get_type())]
+        }
+    }
+
+    #[test]
+    fn test_add_null_param() {
+        let mut stmt = Statement::new("SELECT 1");
+        stmt.add_null_param::<String>("String");
+        stmt.add_null_param::<Vec<u8>>("Bytes");
+        stmt.add_null_param::<time::OffsetDateTime>("Timestamp");
+        stmt.add_null_param::<Vec<i64>>("Array");
+        for name in ["String", "Bytes", "Timestamp", "Array"] {
+            match stmt.params[name].kind {
+                Some(Kind::NullValue(_)) => {}
+                _ => panic!("{} must be null", name),
+            }
+        }
+        assert_eq!(stmt.param_types["String"].code, TypeCode::String as i32);
+        assert_eq!(stmt.param_types["Bytes"].code, TypeCode::Bytes as i32);
+        assert_eq!(stmt.param_types["Timestamp"].code, TypeCode::Timestamp as i32);
+        let array_type = &stmt.param_types["Array"];
+        assert_eq!(array_type.code, TypeCode::Array as i32);
+        assert_eq!(array_type.array_element_type.as_ref().unwrap().code, TypeCode::Int64 as i32);
+    }
+
+    #[test]
+    fn test_interval_to_kind() {
+        let nanos = 4 * 3_600_000_000_000 + 5 * 60_000_000_000 + 6_500_000_000;
+        assert_eq!(Interval::get_type().code, TypeCode::Interval as i32);
+        assert_eq!(
+            Interval::new(14, 3, nanos).to_kind(),
+            Kind::StringValue("P1Y2M3DT4H5M6.5S".to_string())
+        );
+        assert_eq!(
+            Interval::new(-14, 3, -nanos).to_kind(),
+            Kind::StringValue("P-1Y-2M3DT-4H-5M-6.5S".to_string())
+        );
+        assert_eq!(
+            Interval::new(0, 0, -1).to_kind(),
+            Kind::StringValue("PT-0.000000001S".to_string())
+        );
+        assert_eq!(Interval::default().to_kind(), Kind::StringValue("P0Y".to_string()));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_interval_chrono_duration() {
+        let duration = chrono::Duration::hours(-25) + chrono::Duration::milliseconds(1);
+        let interval = Interval::try_from(duration).unwrap();
+        assert_eq!(interval, Interval::new(0, 0, duration.num_nanoseconds().unwrap()));
+        assert_eq!(
+            Interval::new(3, 1, -1).sub_month_duration(),
+            chrono::Duration::nanoseconds(86_399_999_999_999)
+        );
+        assert!(Interval::try_from(chrono::Duration::days(365 * 300)).is_err());
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn test_add_numeric_param() {
+        use crate::statement::Error;
+        use crate::value::{Numeric, NumericError};
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        let mut stmt = Statement::new("SELECT @Max, @Scale, @Rounded");
+        stmt.add_numeric_param("Max", &Decimal::MAX).unwrap();
+        stmt.add_numeric_param("Scale", &Decimal::from_str("-0.123456789").unwrap())
+            .unwrap();
+        assert_eq!(
+            stmt.params["Max"].kind,
+            Some(Kind::StringValue("79228162514264337593543950335".to_string()))
+        );
+        assert_eq!(stmt.param_types["Scale"].code, TypeCode::Numeric as i32);
+
+        let too_precise = Decimal::from_str("1.0123456789").unwrap();
+        match stmt.add_numeric_param("TooPrecise", &too_precise) {
+            Err(Error::InvalidNumeric(name, NumericError::FractionalDigits(10))) => assert_eq!(name, "TooPrecise"),
+            r => panic!("must be an error {:?}", r),
+        }
+        assert!(!stmt.params.contains_key("TooPrecise"));
+        // trailing zeros are not significant
+        assert!(Numeric::new(Decimal::from_str("1.5000000000000").unwrap()).is_ok());
+
+        // banker's rounding
+        stmt.add_param("Rounded", &Numeric::rounded(Decimal::from_str("0.0000000025").unwrap()));
+        assert_eq!(stmt.params["Rounded"].kind, Some(Kind::StringValue("0.000000002".to_string())));
+        let rounded = Numeric::rounded(Decimal::from_str("-0.0000000035").unwrap());
+        assert_eq!(rounded.value(), Decimal::from_str("-0.000000004").unwrap());
+    }
+
+    #[test]
+    fn test_pg_numeric_to_kind() {
+        let pg_type = PgNumeric::get_type();
+        assert_eq!(pg_type.code, TypeCode::Numeric as i32);
+        assert_eq!(pg_type.type_annotation, TypeAnnotationCode::PgNumeric as i32);
+        assert_eq!(PgNumeric::new("NaN").to_kind(), Kind::StringValue("NaN".to_string()));
+        assert_eq!(
+            crate::value::SpannerNumeric::get_type().type_annotation,
+            TypeAnnotationCode::Unspecified as i32
+        );
+    }
+
+    #[test]
+    fn test_commit_timestamp_to_kind() {
+        use crate::value::{CommitTimestamp, TimestampValue};
+        use time::macros::datetime;
+
+        let mut stmt = Statement::new("UPDATE User SET UpdatedAt = @UpdatedAt, DeletedAt = @DeletedAt");
+        stmt.add_param("UpdatedAt", &Some(CommitTimestamp::new()));
+        stmt.add_param("DeletedAt", &Option::<CommitTimestamp>::None);
+        assert_eq!(
+            stmt.params["UpdatedAt"].kind,
+            Some(Kind::StringValue("spanner.commit_timestamp()".to_string()))
+        );
+        assert!(matches!(stmt.params["DeletedAt"].kind, Some(Kind::NullValue(_))));
+        assert_eq!(stmt.param_types["DeletedAt"].code, TypeCode::Timestamp as i32);
+
+        assert_eq!(TimestampValue::get_type().code, TypeCode::Timestamp as i32);
+        assert_eq!(
+            TimestampValue::Commit.to_kind(),
+            Kind::StringValue("spanner.commit_timestamp()".to_string())
+        );
+        let at = TimestampValue::from(datetime!(2022-01-02 03:04:05 +09:00));
+        assert_eq!(at.to_kind(), Kind::StringValue("2022-01-01T18:04:05Z".to_string()));
+    }
+
+    #[test]
+    fn test_float_to_kind() {
+        assert_eq!(f32::get_type().code, TypeCode::Float64 as i32);
+        assert_eq!(f64::get_type().code, TypeCode::Float64 as i32);
+        let cases: Vec<(Kind, Kind, &str)> = vec![
+            (f32::NAN.to_kind(), f64::NAN.to_kind(), "NaN"),
+            (f32::INFINITY.to_kind(), f64::INFINITY.to_kind(), "Infinity"),
+            (f32::NEG_INFINITY.to_kind(), f64::NEG_INFINITY.to_kind(), "-Infinity"),
+        ];
+        for (v32, v64, expected) in cases {
+            match (v32, v64) {
+                (Kind::StringValue(v32), Kind::StringValue(v64)) => {
+                    assert_eq!(v32, expected);
+                    assert_eq!(v64, expected);
+                }
+                _ => panic!("invalid kind"),
+            }
+        }
+        match (1.5_f32.to_kind(), (-0.25_f64).to_kind()) {
+            (Kind::NumberValue(v32), Kind::NumberValue(v64)) => {
+                assert_eq!(v32, 1.5);
+                assert_eq!(v64, -0.25);
+            }
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[test]
+    fn test_float32_to_kind() {
+        assert_eq!(Float32::get_type().code, TypeCode::Float32 as i32);
+        let array_type = Vec::<Float32>::get_type();
+        assert_eq!(array_type.code, TypeCode::Array as i32);
+        assert_eq!(array_type.array_element_type.unwrap().code, TypeCode::Float32 as i32);
+        match Float32(0.5).to_kind() {
+            Kind::NumberValue(v) => assert_eq!(v, 0.5),
+            _ => panic!("invalid kind"),
+        }
+        match Float32(f32::NAN).to_kind() {
+            Kind::StringValue(v) => assert_eq!(v, "NaN"),
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn test_big_decimal_to_kind() {
+        use crate::value::{NumericError, SpannerNumeric};
+        use bigdecimal::BigDecimal;
+        use std::str::FromStr;
+
+        let value = BigDecimal::from_str("-1.23e3").unwrap();
+        let mut stmt = Statement::new("SELECT @value");
+        stmt.add_big_decimal_param("value", &value).unwrap();
+        assert_eq!(stmt.param_types["value"].code, TypeCode::Numeric as i32);
+        match stmt.params["value"].kind.as_ref().unwrap() {
+            Kind::StringValue(v) => assert_eq!(v, "-1230"),
+            _ => panic!("invalid kind"),
+        }
+        let out_of_range = BigDecimal::from_str(&"9".repeat(30)).unwrap();
+        assert!(matches!(
+            stmt.add_big_decimal_param("out_of_range", &out_of_range),
+            Err(crate::statement::Error::InvalidNumeric(name, NumericError::IntegerDigits(30))) if name == "out_of_range"
+        ));
+        assert!(!stmt.params.contains_key("out_of_range"));
+
+        let max = BigDecimal::from_str(&format!("-{}.{}", "9".repeat(29), "9".repeat(9))).unwrap();
+        assert_eq!(SpannerNumeric::try_from(&max).unwrap().as_str(), max.to_string());
+        let integer = BigDecimal::from_str(&"9".repeat(30)).unwrap();
+        assert_eq!(SpannerNumeric::try_from(&integer), Err(NumericError::IntegerDigits(30)));
+        let fractional = BigDecimal::from_str("0.0000000001").unwrap();
+        assert_eq!(SpannerNumeric::try_from(&fractional), Err(NumericError::FractionalDigits(10)));
+        let trailing_zeros = BigDecimal::from_str("1.5000000000000").unwrap();
+        assert!(SpannerNumeric::try_from(&trailing_zeros).is_ok());
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_json_to_kind() {
+        let value = serde_json::json!({"name": "spanner", "tags": [1, 2]});
+        assert_eq!(serde_json::Value::get_type().code, TypeCode::Json as i32);
+        assert_eq!(
+            Vec::<serde_json::Value>::get_type().array_element_type.unwrap().code,
+            TypeCode::Json as i32
+        );
+        match value.to_kind() {
+            Kind::StringValue(v) => assert_eq!(v, r#"{"name":"spanner","tags":[1,2]}"#),
+            _ => panic!("invalid kind"),
+        }
+
+        // JSON null literal is not the same as SQL NULL
+        match serde_json::Value::Null.to_kind() {
+            Kind::StringValue(v) => assert_eq!(v, "null"),
+            _ => panic!("invalid kind"),
+        }
+        match Option::<serde_json::Value>::None.to_kind() {
+            Kind::NullValue(_) => {}
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[test]
+    fn test_json_wrapper_to_kind() {
+        use crate::statement::Error;
+        use crate::value::Json;
+        use std::collections::{BTreeMap, HashMap};
+
+        #[derive(serde::Serialize)]
+        struct Item {
+            name: String,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Payload {
+            item: Item,
+            tags: Vec<&'static str>,
+        }
+
+        let payload = Payload {
+            item: Item {
+                name: "sword".to_string(),
+            },
+            tags: vec!["a", "b"],
+        };
+        let mut stmt = Statement::new("SELECT @Payload, @Nullable");
+        stmt.try_add_param("Payload", &Json(&payload)).unwrap();
+        stmt.try_add_param("Nullable", &Option::<Json<Payload>>::None).unwrap();
+        assert_eq!(
+            stmt.params["Payload"].kind,
+            Some(Kind::StringValue(r#"{"item":{"name":"sword"},"tags":["a","b"]}"#.to_string()))
+        );
+        assert_eq!(stmt.param_types["Payload"].code, TypeCode::Json as i32);
+        assert!(matches!(stmt.params["Nullable"].kind, Some(Kind::NullValue(_))));
+        assert_eq!(stmt.param_types["Nullable"].code, TypeCode::Json as i32);
+
+        // non-string map keys can not be serialized as JSON
+        let invalid = HashMap::from([((1, 2), 3)]);
+        match stmt.try_add_param("Invalid", &vec![Json(&invalid)]) {
+            Err(Error::JsonSerializeError(_)) => {}
+            r => panic!("must be a serialization error {:?}", r),
+        }
+        assert!(!stmt.params.contains_key("Invalid"));
+        let nested = HashMap::from([("nested".to_string(), &invalid)]);
+        assert!(matches!(
+            stmt.try_add_param("Invalid", &nested),
+            Err(Error::JsonSerializeError(_))
+        ));
+        let nested = BTreeMap::from([("nested".to_string(), &invalid)]);
+        assert!(matches!(
+            stmt.try_add_param("Invalid", &Some(nested.clone())),
+            Err(Error::JsonSerializeError(_))
+        ));
+        // the value is bound as the invalid JSON, which Spanner rejects, instead of panicking
+        stmt.add_param("Invalid", &nested);
+        match &stmt.params["Invalid"].kind {
+            Some(Kind::StringValue(v)) => assert!(serde_json::from_str::<serde_json::Value>(v).is_err()),
+            kind => panic!("must be the string {kind:?}"),
+        }
+    }
+
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[test]
+    fn test_add_json_param() {
+        use crate::statement::Error;
+        use std::collections::HashMap;
+
+        let mut stmt = Statement::new("SELECT @Payload, @Str, @Some, @None");
+        stmt.add_json_param("Payload", &HashMap::from([("a", vec![1, 2])]))
+            .unwrap();
+        stmt.add_json_param("Str", "text").unwrap();
+        stmt.add_json_param_nullable("Some", Some(&true)).unwrap();
+        stmt.add_json_param_nullable::<bool>("None", None).unwrap();
+        assert_eq!(
+            stmt.params["Payload"].kind,
+            Some(Kind::StringValue(r#"{"a":[1,2]}"#.to_string()))
+        );
+        assert_eq!(stmt.params["Str"].kind, Some(Kind::StringValue(r#""text""#.to_string())));
+        assert_eq!(stmt.params["Some"].kind, Some(Kind::StringValue("true".to_string())));
+        assert!(matches!(stmt.params["None"].kind, Some(Kind::NullValue(_))));
+        for name in ["Payload", "Str", "Some", "None"] {
+            assert_eq!(stmt.param_types[name].code, TypeCode::Json as i32);
+        }
+
+        let invalid = HashMap::from([((1, 2), 3)]);
+        assert!(matches!(
+            stmt.add_json_param("Invalid", &invalid),
+            Err(Error::JsonSerializeError(_))
+        ));
+        assert!(matches!(
+            stmt.add_json_param_nullable("Invalid", Some(&invalid)),
+            Err(Error::JsonSerializeError(_))
+        ));
+        assert!(!stmt.params.contains_key("Invalid"));
+    }
+
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[test]
+    fn test_map_to_kind() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let mut hash_map = HashMap::new();
+        for (k, v) in [("z", 1), ("a", 2), ("m", 3)] {
+            hash_map.insert(k.to_string(), serde_json::json!(v));
+        }
+        assert_eq!(HashMap::<String, serde_json::Value>::get_type().code, TypeCode::Json as i32);
+        assert_eq!(hash_map.to_kind(), Kind::StringValue(r#"{"a":2,"m":3,"z":1}"#.to_string()));
+
+        let mut btree_map = BTreeMap::new();
+        btree_map.insert("flag".to_string(), "on".to_string());
+        assert_eq!(BTreeMap::<String, String>::get_type().code, TypeCode::Json as i32);
+        assert_eq!(btree_map.to_kind(), Kind::StringValue(r#"{"flag":"on"}"#.to_string()));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_pg_jsonb_to_kind() {
+        let pg_type = crate::value::PgJsonb::get_type();
+        assert_eq!(pg_type.code, TypeCode::Json as i32);
+        assert_eq!(pg_type.type_annotation, TypeAnnotationCode::PgJsonb as i32);
+        let value = crate::value::PgJsonb(serde_json::json!({"a": 1}));
+        assert_eq!(value.to_kind(), Kind::StringValue(r#"{"a":1}"#.to_string()));
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_uuid_to_kind() {
+        use crate::value::UuidBytes;
+
+        let id = uuid::Uuid::parse_str("67E55044-10B1-426F-9247-BB680E5FE0C8").unwrap();
+        assert_eq!(uuid::Uuid::get_type().code, TypeCode::String as i32);
+        match id.to_kind() {
+            Kind::StringValue(v) => assert_eq!(v, "67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            _ => panic!("invalid kind"),
+        }
+        assert_eq!(UuidBytes::get_type().code, TypeCode::Bytes as i32);
+        match UuidBytes(id).to_kind() {
+            Kind::StringValue(v) => assert_eq!(base64::decode(v).unwrap(), id.as_bytes()),
+            _ => panic!("invalid kind"),
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_date_time_to_kind() {
+        use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+        let offset = FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+        let local: DateTime<FixedOffset> = offset.with_ymd_and_hms(2022, 12, 1, 10, 30, 15).unwrap();
+        let utc: DateTime<Utc> = Utc.with_ymd_and_hms(2022, 12, 1, 5, 0, 15).unwrap();
+        match (local.to_kind(), utc.to_kind()) {
+            (Kind::StringValue(l), Kind::StringValue(u)) => {
+                assert_eq!(l, "2022-12-01T05:00:15.000000000Z");
+                assert_eq!(l, u);
+            }
+            _ => panic!("invalid kind"),
+        }
+        assert_eq!(DateTime::<FixedOffset>::get_type().code, TypeCode::Timestamp as i32);
+        assert_eq!(DateTime::<Utc>::get_type().code, TypeCode::Timestamp as i32);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_naive_to_kind() {
+        use chrono::{NaiveDate, NaiveDateTime};
+
+        let date = NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        let date_time: NaiveDateTime = date.and_hms_nano_opt(0, 0, 0, 1).unwrap();
+        match (date.to_kind(), date_time.to_kind()) {
+            (Kind::StringValue(d), Kind::StringValue(t)) => {
+                assert_eq!(d, "0001-01-01");
+                assert_eq!(t, "0001-01-01T00:00:00.000000001Z");
+            }
+            _ => panic!("invalid kind"),
         }
+        assert_eq!(NaiveDate::get_type().code, TypeCode::Date as i32);
+        assert_eq!(NaiveDateTime::get_type().code, TypeCode::Timestamp as i32);
     }
 }
@@ -0,0 +1,444 @@
+//! The query plan and the execution statistics returned by the query in the PLAN or PROFILE mode.
+//!
+//! ```
+//! use google_cloud_spanner::client::Client;
+//! use google_cloud_spanner::reader::AsyncIterator;
+//! use google_cloud_spanner::statement::Statement;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), anyhow::Error> {
+//!     const DATABASE: &str = "projects/local-project/instances/test-instance/databases/local-database";
+//!     let client = Client::new(DATABASE).await?;
+//!
+//!     // the plan only, the query is not executed.
+//!     let plan = client.analyze_query(Statement::new("SELECT * FROM Guild WHERE OwnerUserID = 'u1'")).await?;
+//!     println!("{plan}");
+//!
+//!     // the plan and the execution statistics, they are available after all the rows are read.
+//!     let mut tx = client.single().await?;
+//!     let mut iter = tx.query_with_stats(Statement::new("SELECT * FROM Guild WHERE OwnerUserID = 'u1'")).await?;
+//!     while let Some(row) = iter.next().await? {
+//!         //do something
+//!     }
+//!     if let Some(stats) = iter.stats() {
+//!         println!("rows_scanned={:?}, cpu_time={:?}", stats.stat("rows_scanned"), stats.stat("cpu_time"));
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use google_cloud_googleapis::spanner::v1::plan_node::Kind;
+use google_cloud_googleapis::spanner::v1::result_set_stats::RowCount as ProtoRowCount;
+use google_cloud_googleapis::spanner::v1::{PlanNode as ProtoPlanNode, QueryPlan as ProtoQueryPlan, ResultSetStats};
+use prost_types::value::Kind as ValueKind;
+
+/// StatsValue is the value in the metadata and the statistics of the query.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StatsValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    List(Vec<StatsValue>),
+    Struct(BTreeMap<String, StatsValue>),
+}
+
+impl StatsValue {
+    /// as_str returns the string value, or None if the value is not a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            StatsValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl From<prost_types::Value> for StatsValue {
+    fn from(value: prost_types::Value) -> Self {
+        match value.kind {
+            None | Some(ValueKind::NullValue(_)) => StatsValue::Null,
+            Some(ValueKind::BoolValue(v)) => StatsValue::Bool(v),
+            Some(ValueKind::NumberValue(v)) => StatsValue::Number(v),
+            Some(ValueKind::StringValue(v)) => StatsValue::String(v),
+            Some(ValueKind::ListValue(v)) => StatsValue::List(v.values.into_iter().map(StatsValue::from).collect()),
+            Some(ValueKind::StructValue(v)) => StatsValue::Struct(into_map(Some(v))),
+        }
+    }
+}
+
+impl fmt::Display for StatsValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsValue::Null => write!(f, "null"),
+            StatsValue::Bool(v) => write!(f, "{v}"),
+            StatsValue::Number(v) => write!(f, "{v}"),
+            StatsValue::String(v) => write!(f, "{v}"),
+            StatsValue::List(v) => {
+                write!(f, "[")?;
+                for (i, value) in v.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+            StatsValue::Struct(v) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in v.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key}: {value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn into_map(value: Option<prost_types::Struct>) -> BTreeMap<String, StatsValue> {
+    value
+        .map(|v| v.fields.into_iter().map(|(k, v)| (k, v.into())).collect())
+        .unwrap_or_default()
+}
+
+/// PlanNodeKind is the kind of the plan node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanNodeKind {
+    Unspecified,
+    /// Relational node returns the rows, such as a table scan or a join.
+    Relational,
+    /// Scalar node returns a single value, such as a function call or a constant.
+    Scalar,
+}
+
+impl From<i32> for PlanNodeKind {
+    fn from(value: i32) -> Self {
+        match Kind::from_i32(value) {
+            Some(Kind::Relational) => PlanNodeKind::Relational,
+            Some(Kind::Scalar) => PlanNodeKind::Scalar,
+            _ => PlanNodeKind::Unspecified,
+        }
+    }
+}
+
+/// ChildLink is the link from the parent node to the child node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChildLink {
+    /// The index of the child node in QueryPlan::nodes.
+    pub child_index: usize,
+    /// The role of the child node, such as "Input" or "Split Range". It may be empty.
+    pub type_: String,
+    /// The name of the variable the child scalar node is referenced by. It may be empty.
+    pub variable: String,
+}
+
+/// PlanNode is the node of the query plan tree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlanNode {
+    pub index: usize,
+    pub kind: PlanNodeKind,
+    /// The operator of the node, such as "Distributed Union" or "Table Scan".
+    pub display_name: String,
+    pub child_links: Vec<ChildLink>,
+    /// The condensed representation of the scalar node, such as "($Name = 'u1')".
+    pub description: Option<String>,
+    /// The names and the node indices of the subqueries of the scalar node.
+    pub subqueries: BTreeMap<String, usize>,
+    /// The attributes of the node, such as "scan_target" of the table scan.
+    pub metadata: BTreeMap<String, StatsValue>,
+    /// The execution statistics of the node. It is empty unless the query is executed in the PROFILE mode.
+    pub execution_stats: BTreeMap<String, StatsValue>,
+}
+
+impl From<ProtoPlanNode> for PlanNode {
+    fn from(node: ProtoPlanNode) -> Self {
+        let (description, subqueries) = match node.short_representation {
+            Some(v) => (
+                Some(v.description),
+                v.subqueries.into_iter().map(|(k, v)| (k, v as usize)).collect(),
+            ),
+            None => (None, BTreeMap::new()),
+        };
+        PlanNode {
+            index: node.index as usize,
+            kind: node.kind.into(),
+            display_name: node.display_name,
+            child_links: node
+                .child_links
+                .into_iter()
+                .map(|v| ChildLink {
+                    child_index: v.child_index as usize,
+                    type_: v.r#type,
+                    variable: v.variable,
+                })
+                .collect(),
+            description,
+            subqueries,
+            metadata: into_map(node.metadata),
+            execution_stats: into_map(node.execution_stats),
+        }
+    }
+}
+
+/// QueryPlan is the tree of the plan nodes. The first node is the root.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryPlan {
+    pub nodes: Vec<PlanNode>,
+}
+
+impl QueryPlan {
+    /// root returns the root node, or None if the plan is empty.
+    pub fn root(&self) -> Option<&PlanNode> {
+        self.nodes.first()
+    }
+
+    /// children returns the child nodes of the node with the links to them.
+    pub fn children<'a>(&'a self, node: &'a PlanNode) -> impl Iterator<Item = (&'a ChildLink, &'a PlanNode)> {
+        node.child_links
+            .iter()
+            .filter_map(|link| self.nodes.get(link.child_index).map(|child| (link, child)))
+    }
+
+    fn fmt_node(&self, f: &mut fmt::Formatter<'_>, node: &PlanNode, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", node.display_name, indent = depth * 2)?;
+        if let Some(description) = &node.description {
+            write!(f, ": {description}")?;
+        }
+        if !node.metadata.is_empty() {
+            write!(f, " {}", StatsValue::Struct(node.metadata.clone()))?;
+        }
+        writeln!(f)?;
+        // the depth is bounded by the number of the nodes, so the broken links do not loop forever.
+        if depth < self.nodes.len() {
+            for (_, child) in self.children(node) {
+                self.fmt_node(f, child, depth + 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<ProtoQueryPlan> for QueryPlan {
+    fn from(plan: ProtoQueryPlan) -> Self {
+        QueryPlan {
+            nodes: plan.plan_nodes.into_iter().map(PlanNode::from).collect(),
+        }
+    }
+}
+
+/// Display renders the tree with the relational nodes indented by the depth.
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.root() {
+            Some(root) => self.fmt_node(f, root, 0),
+            None => Ok(()),
+        }
+    }
+}
+
+/// RowCount is the number of the rows modified by the DML statement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RowCount {
+    /// The exact number of the rows modified.
+    Exact(i64),
+    /// The lower bound of the number of the rows modified by the partitioned DML.
+    LowerBound(i64),
+}
+
+/// QueryStats is the query plan and the execution statistics of the query.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryStats {
+    /// The query plan. It is None in the NORMAL mode.
+    pub query_plan: Option<QueryPlan>,
+    /// The execution statistics such as "rows_scanned", "cpu_time" and "elapsed_time".
+    /// It is empty unless the query is executed in the PROFILE mode.
+    pub query_stats: BTreeMap<String, StatsValue>,
+    pub row_count: Option<RowCount>,
+}
+
+impl QueryStats {
+    /// stat returns the execution statistic of the name, such as "rows_scanned".
+    pub fn stat(&self, name: &str) -> Option<&StatsValue> {
+        self.query_stats.get(name)
+    }
+}
+
+impl From<ResultSetStats> for QueryStats {
+    fn from(stats: ResultSetStats) -> Self {
+        QueryStats {
+            query_plan: stats.query_plan.map(QueryPlan::from),
+            query_stats: into_map(stats.query_stats),
+            row_count: stats.row_count.map(|v| match v {
+                ProtoRowCount::RowCountExact(v) => RowCount::Exact(v),
+                ProtoRowCount::RowCountLowerBound(v) => RowCount::LowerBound(v),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use google_cloud_googleapis::spanner::v1::plan_node::{ChildLink, Kind, ShortRepresentation};
+    use google_cloud_googleapis::spanner::v1::result_set_stats::RowCount as ProtoRowCount;
+    use google_cloud_googleapis::spanner::v1::{
+        PlanNode as ProtoPlanNode, QueryPlan as ProtoQueryPlan, ResultSetStats,
+    };
+    use prost_types::value::Kind as ValueKind;
+    use prost_types::{ListValue, Struct, Value};
+
+    use crate::stats::*;
+
+    fn string(v: &str) -> Value {
+        Value {
+            kind: Some(ValueKind::StringValue(v.to_string())),
+        }
+    }
+
+    fn proto_struct(fields: Vec<(&str, Value)>) -> Struct {
+        Struct {
+            fields: fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+        }
+    }
+
+    fn link(child_index: i32, type_: &str) -> ChildLink {
+        ChildLink {
+            child_index,
+            r#type: type_.to_string(),
+            variable: "".to_string(),
+        }
+    }
+
+    fn proto_plan() -> ProtoQueryPlan {
+        ProtoQueryPlan {
+            plan_nodes: vec![
+                ProtoPlanNode {
+                    index: 0,
+                    kind: Kind::Relational as i32,
+                    display_name: "Distributed Union".to_string(),
+                    child_links: vec![link(1, "")],
+                    short_representation: None,
+                    metadata: None,
+                    execution_stats: Some(proto_struct(vec![(
+                        "rows",
+                        Value {
+                            kind: Some(ValueKind::StructValue(proto_struct(vec![
+                                ("total", string("10")),
+                                ("unit", string("rows")),
+                            ]))),
+                        },
+                    )])),
+                },
+                ProtoPlanNode {
+                    index: 1,
+                    kind: Kind::Relational as i32,
+                    display_name: "Filter Scan".to_string(),
+                    child_links: vec![link(2, ""), link(3, "Residual Condition")],
+                    short_representation: None,
+                    metadata: None,
+                    execution_stats: None,
+                },
+                ProtoPlanNode {
+                    index: 2,
+                    kind: Kind::Relational as i32,
+                    display_name: "Table Scan".to_string(),
+                    child_links: vec![],
+                    short_representation: None,
+                    metadata: Some(proto_struct(vec![
+                        ("scan_target", string("Guild")),
+                        ("scan_type", string("TableScan")),
+                    ])),
+                    execution_stats: None,
+                },
+                ProtoPlanNode {
+                    index: 3,
+                    kind: Kind::Scalar as i32,
+                    display_name: "Function".to_string(),
+                    child_links: vec![],
+                    short_representation: Some(ShortRepresentation {
+                        description: "($OwnerUserID = 'u1')".to_string(),
+                        subqueries: HashMap::new(),
+                    }),
+                    metadata: None,
+                    execution_stats: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_query_plan() {
+        let plan = QueryPlan::from(proto_plan());
+        let root = plan.root().unwrap();
+        assert_eq!(root.kind, PlanNodeKind::Relational);
+        assert_eq!(root.display_name, "Distributed Union");
+        let rows = match &root.execution_stats["rows"] {
+            StatsValue::Struct(v) => v,
+            v => panic!("unexpected {v:?}"),
+        };
+        assert_eq!(rows["total"].as_str(), Some("10"));
+
+        let (_, filter) = plan.children(root).next().unwrap();
+        let children: Vec<(&str, &str)> = plan
+            .children(filter)
+            .map(|(link, child)| (link.type_.as_str(), child.display_name.as_str()))
+            .collect();
+        assert_eq!(children, vec![("", "Table Scan"), ("Residual Condition", "Function")]);
+        assert_eq!(plan.nodes[2].metadata["scan_target"].as_str(), Some("Guild"));
+        assert_eq!(plan.nodes[3].kind, PlanNodeKind::Scalar);
+        assert_eq!(plan.nodes[3].description.as_deref(), Some("($OwnerUserID = 'u1')"));
+
+        assert_eq!(
+            plan.to_string(),
+            "Distributed Union\n  Filter Scan\n    Table Scan {scan_target: Guild, scan_type: TableScan}\n    Function: ($OwnerUserID = 'u1')\n"
+        );
+    }
+
+    #[test]
+    fn test_query_plan_broken_link() {
+        let mut proto = proto_plan();
+        proto.plan_nodes[2].child_links = vec![link(0, ""), link(100, "")];
+        let plan = QueryPlan::from(proto);
+        assert_eq!(plan.children(&plan.nodes[2]).count(), 1);
+        // rendering stops at the depth of the number of the nodes.
+        assert_eq!(plan.to_string().lines().count(), 6);
+        assert_eq!(QueryPlan::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_query_stats() {
+        let stats = QueryStats::from(ResultSetStats {
+            query_plan: Some(proto_plan()),
+            query_stats: Some(proto_struct(vec![
+                ("rows_scanned", string("100")),
+                ("cpu_time", string("1.23 msecs")),
+                (
+                    "tags",
+                    Value {
+                        kind: Some(ValueKind::ListValue(ListValue {
+                            values: vec![string("a"), Value { kind: None }],
+                        })),
+                    },
+                ),
+            ])),
+            row_count: Some(ProtoRowCount::RowCountExact(3)),
+        });
+        assert_eq!(stats.query_plan.as_ref().unwrap().nodes.len(), 4);
+        assert_eq!(stats.stat("rows_scanned").and_then(|v| v.as_str()), Some("100"));
+        assert_eq!(stats.stat("cpu_time").and_then(|v| v.as_str()), Some("1.23 msecs"));
+        assert_eq!(stats.stat("tags").unwrap().to_string(), "[a, null]");
+        assert!(stats.stat("unknown").is_none());
+        assert_eq!(stats.row_count, Some(RowCount::Exact(3)));
+
+        let empty = QueryStats::from(ResultSetStats::default());
+        assert_eq!(empty.query_plan, None);
+        assert_eq!(empty.query_stats, BTreeMap::new());
+        assert_eq!(empty.row_count, None);
+    }
+}
@@ -5,18 +5,21 @@ use google_cloud_gax::cancel::CancellationToken;
 use google_cloud_gax::retry::RetrySetting;
 use prost_types::Struct;
 
-use google_cloud_gax::grpc::Status;
-use google_cloud_googleapis::spanner::v1::request_options::Priority;
+use google_cloud_gax::grpc::{Code, Status, Streaming};
+pub use google_cloud_googleapis::spanner::v1::directed_read_options::replica_selection::Type as ReplicaType;
+pub use google_cloud_googleapis::spanner::v1::request_options::Priority;
 use google_cloud_googleapis::spanner::v1::{
-    execute_sql_request::QueryMode, execute_sql_request::QueryOptions as ExecuteQueryOptions, ExecuteSqlRequest,
-    ReadRequest, RequestOptions, TransactionSelector,
+    directed_read_options, execute_sql_request::QueryMode, execute_sql_request::QueryOptions as ExecuteQueryOptions,
+    transaction_selector, BeginTransactionRequest, DirectedReadOptions as InternalDirectedReadOptions,
+    ExecuteSqlRequest, PartialResultSet, ReadRequest, RequestOptions, ResultSetMetadata, TransactionSelector,
 };
 
-use crate::key::{Key, KeySet};
-use crate::reader::{AsyncIterator, RowIterator, StatementReader, TableReader};
-use crate::row::Row;
-use crate::session::ManagedSession;
-use crate::statement::Statement;
+use crate::key::{KeySet, ToKey};
+use crate::reader::{AsyncIterator, Reader, RowIterator, StatementReader, TableReader};
+use crate::row::{Error as RowError, Row};
+use crate::session::{ManagedSession, SessionError, SessionHandle};
+use crate::statement::{into_request_params, Statement};
+use crate::stats::QueryPlan;
 
 #[derive(Clone, Default)]
 pub struct CallOptions {
@@ -26,6 +29,80 @@ pub struct CallOptions {
     pub cancel: Option<CancellationToken>,
 }
 
+/// ReplicaSelection selects the replicas by the location and the type.
+/// The empty location and ReplicaType::Unspecified match any location and type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplicaSelection {
+    pub location: String,
+    pub replica_type: ReplicaType,
+}
+
+impl ReplicaSelection {
+    pub fn new<T: Into<String>>(location: T, replica_type: ReplicaType) -> Self {
+        ReplicaSelection {
+            location: location.into(),
+            replica_type,
+        }
+    }
+}
+
+impl From<ReplicaSelection> for directed_read_options::ReplicaSelection {
+    fn from(selection: ReplicaSelection) -> Self {
+        directed_read_options::ReplicaSelection {
+            location: selection.location,
+            r#type: selection.replica_type.into(),
+        }
+    }
+}
+
+/// DirectedReadOptions selects the replicas that serve the reads and the queries of the read-only transactions.
+/// It is rejected by the read-write transactions.
+/// ```
+/// use google_cloud_spanner::transaction::{DirectedReadOptions, ReplicaSelection, ReplicaType};
+///
+/// let options = DirectedReadOptions::include_replicas(vec![ReplicaSelection::new("us-east1", ReplicaType::ReadOnly)], false);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirectedReadOptions {
+    inner: InternalDirectedReadOptions,
+}
+
+impl DirectedReadOptions {
+    /// include_replicas returns the DirectedReadOptions that uses the replicas in the order of the selections.
+    /// The other replicas are used when none of the selected replicas is available, unless auto_failover_disabled is true.
+    pub fn include_replicas(selections: Vec<ReplicaSelection>, auto_failover_disabled: bool) -> Self {
+        DirectedReadOptions {
+            inner: InternalDirectedReadOptions {
+                replicas: Some(directed_read_options::Replicas::IncludeReplicas(
+                    directed_read_options::IncludeReplicas {
+                        replica_selections: selections.into_iter().map(|s| s.into()).collect(),
+                        auto_failover_disabled,
+                    },
+                )),
+            },
+        }
+    }
+
+    /// exclude_replicas returns the DirectedReadOptions that does not use the selected replicas.
+    pub fn exclude_replicas(selections: Vec<ReplicaSelection>) -> Self {
+        DirectedReadOptions {
+            inner: InternalDirectedReadOptions {
+                replicas: Some(directed_read_options::Replicas::ExcludeReplicas(
+                    directed_read_options::ExcludeReplicas {
+                        replica_selections: selections.into_iter().map(|s| s.into()).collect(),
+                    },
+                )),
+            },
+        }
+    }
+}
+
+impl From<DirectedReadOptions> for InternalDirectedReadOptions {
+    fn from(options: DirectedReadOptions) -> Self {
+        options.inner
+    }
+}
+
 #[derive(Clone)]
 pub struct ReadOptions {
     /// The index to use for reading. If non-empty, you can only read columns
@@ -36,6 +113,22 @@ pub struct ReadOptions {
     /// The maximum number of rows to read. A limit value less than 1 means no limit.
     pub limit: i64,
 
+    /// The number of the partial result sets received ahead of reading the rows. 0 disables the prefetch.
+    pub prefetch_messages: usize,
+
+    /// The maximum bytes of the prefetched values. 0 means no limit.
+    pub max_buffered_bytes: usize,
+
+    /// The tag of the read, which is shown in the statistics tables. It is truncated to 50 characters.
+    pub request_tag: Option<String>,
+
+    /// The replicas that serve the read. None uses the default of the client.
+    pub directed_read_options: Option<DirectedReadOptions>,
+
+    /// Whether the partitions of partition_read are executed with Data Boost, the independent compute resources
+    /// that do not affect the instance. It is ignored by the reads other than the partitioned read.
+    pub data_boost_enabled: bool,
+
     pub call_options: CallOptions,
 }
 
@@ -44,15 +137,63 @@ impl Default for ReadOptions {
         ReadOptions {
             index: "".to_string(),
             limit: 0,
+            prefetch_messages: 0,
+            max_buffered_bytes: 0,
+            request_tag: None,
+            directed_read_options: None,
+            data_boost_enabled: false,
             call_options: CallOptions::default(),
         }
     }
 }
 
+impl ReadOptions {
+    /// with_index returns the ReadOptions that reads the rows by the index.
+    pub fn with_index<T: Into<String>>(mut self, index: T) -> Self {
+        self.index = index.into();
+        self
+    }
+
+    /// with_limit returns the ReadOptions that reads at most limit rows.
+    pub fn with_limit(mut self, limit: i64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// with_request_tag returns the ReadOptions with the tag of the read.
+    pub fn with_request_tag<T: Into<String>>(mut self, tag: T) -> Self {
+        self.request_tag = Some(tag.into());
+        self
+    }
+
+    /// with_directed_read_options returns the ReadOptions that reads from the replicas selected by the options.
+    pub fn with_directed_read_options(mut self, options: DirectedReadOptions) -> Self {
+        self.directed_read_options = Some(options);
+        self
+    }
+
+    /// with_data_boost_enabled returns the ReadOptions whose partitions are executed with Data Boost.
+    pub fn with_data_boost_enabled(mut self, enabled: bool) -> Self {
+        self.data_boost_enabled = enabled;
+        self
+    }
+}
+
 #[derive(Clone)]
 pub struct QueryOptions {
     pub mode: QueryMode,
     pub optimizer_options: Option<ExecuteQueryOptions>,
+    /// The number of the partial result sets received ahead of reading the rows. 0 disables the prefetch.
+    pub prefetch_messages: usize,
+    /// The maximum bytes of the prefetched values. 0 means no limit.
+    pub max_buffered_bytes: usize,
+    /// The tag of the statement, which is shown in the statistics tables. It is truncated to 50 characters.
+    pub request_tag: Option<String>,
+    /// The replicas that serve the query. None uses the default of the client.
+    pub directed_read_options: Option<DirectedReadOptions>,
+    /// Whether the partitions of partition_query are executed with Data Boost, the independent compute resources
+    /// that do not affect the instance. It is ignored by the queries other than the partitioned query.
+    pub data_boost_enabled: bool,
     pub call_options: CallOptions,
 }
 
@@ -61,24 +202,92 @@ impl Default for QueryOptions {
         QueryOptions {
             mode: QueryMode::Normal,
             optimizer_options: None,
+            prefetch_messages: 0,
+            max_buffered_bytes: 0,
+            request_tag: None,
+            directed_read_options: None,
+            data_boost_enabled: false,
             call_options: CallOptions::default(),
         }
     }
 }
 
+/// read_error returns the status with the table, the index and the columns of the read, so that the error such as
+/// the unknown column or the column not stored in the index can be told from the other reads.
+/// The code, the details and the metadata are kept.
+fn read_error(status: Status, table: &str, index: &str, columns: &[&str]) -> Status {
+    let target = if index.is_empty() {
+        table.to_string()
+    } else {
+        format!("{table} by index {index}")
+    };
+    let message = format!("read {}({}): {}", target, columns.join(", "), status.message());
+    Status::with_details_and_metadata(
+        status.code(),
+        message,
+        status.details().to_vec().into(),
+        status.metadata().clone(),
+    )
+}
+
+/// The maximum length of the request tag and the transaction tag.
+const MAX_TAG_LENGTH: usize = 50;
+
+/// truncate_tag returns the tag truncated to the maximum length, which is what Spanner does for the longer tag.
+fn truncate_tag(tag: &str) -> String {
+    match tag.char_indices().nth(MAX_TAG_LENGTH) {
+        Some((end, _)) => {
+            tracing::warn!("the tag is truncated to {} characters: {}", MAX_TAG_LENGTH, tag);
+            tag[..end].to_string()
+        }
+        None => tag.to_string(),
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum QueryError {
+    #[error(transparent)]
+    GRPC(#[from] Status),
+
+    #[error(transparent)]
+    InvalidSession(#[from] SessionError),
+
+    #[error("Too many rows: max_rows={0}")]
+    TooManyRows(usize),
+
+    #[error("Failed to convert the row: row={0}")]
+    RowConversion(usize, #[source] RowError),
+}
+
 pub struct Transaction {
     pub(crate) session: Option<ManagedSession>,
     // for returning ownership of session on before destroy
     pub(crate) sequence_number: AtomicI64,
     pub(crate) transaction_selector: TransactionSelector,
+    pub(crate) transaction_tag: Option<String>,
+    pub(crate) default_priority: Option<Priority>,
+    /// default_directed_read_options is used by the reads and the queries whose options have no directed_read_options.
+    pub(crate) default_directed_read_options: Option<DirectedReadOptions>,
+    /// read_write is true for the read-write transactions, which reject the directed_read_options.
+    pub(crate) read_write: bool,
+    /// pending_begin is the options of the BeginTransaction while the transaction selector is `begin`,
+    /// which means the transaction is begun by the first statement.
+    pub(crate) pending_begin: Option<CallOptions>,
 }
 
 impl Transaction {
-    pub(crate) fn create_request_options(priority: Option<Priority>) -> Option<RequestOptions> {
-        priority.map(|s| RequestOptions {
-            priority: s.into(),
-            request_tag: "".to_string(),
-            transaction_tag: "".to_string(),
+    pub(crate) fn create_request_options(
+        priority: Option<Priority>,
+        request_tag: Option<&str>,
+        transaction_tag: Option<&str>,
+    ) -> Option<RequestOptions> {
+        if priority.is_none() && request_tag.is_none() && transaction_tag.is_none() {
+            return None;
+        }
+        Some(RequestOptions {
+            priority: priority.map(|s| s.into()).unwrap_or_default(),
+            request_tag: request_tag.map(truncate_tag).unwrap_or_default(),
+            transaction_tag: transaction_tag.map(truncate_tag).unwrap_or_default(),
         })
     }
 
@@ -99,24 +308,91 @@ impl Transaction {
         statement: Statement,
         options: QueryOptions,
     ) -> Result<RowIterator<'_>, Status> {
+        self.execute_sql(statement, options, 0).await
+    }
+
+    /// execute_sql executes the statement with the sequence number, which is required for the DML statements.
+    pub(crate) async fn execute_sql(
+        &mut self,
+        statement: Statement,
+        options: QueryOptions,
+        seqno: i64,
+    ) -> Result<RowIterator<'_>, Status> {
+        let param_types = statement.request_param_types();
+        let directed_read_options = self.directed_read_options(options.directed_read_options)?;
         let request = ExecuteSqlRequest {
             session: self.session.as_ref().unwrap().session.name.to_string(),
             transaction: Some(self.transaction_selector.clone()),
             sql: statement.sql,
             params: Some(Struct {
-                fields: statement.params,
+                fields: into_request_params(statement.params),
             }),
-            param_types: statement.param_types,
+            param_types,
             resume_token: vec![],
             query_mode: options.mode.into(),
             partition_token: vec![],
-            seqno: 0,
+            seqno,
             query_options: options.optimizer_options,
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: self.request_options(
+                options.call_options.priority,
+                statement.request_tag.as_deref().or(options.request_tag.as_deref()),
+            ),
+            directed_read_options,
+            data_boost_enabled: false,
         };
-        let session = self.session.as_mut().unwrap().deref_mut();
         let reader = Box::new(StatementReader { request });
-        RowIterator::new(session, reader, Some(options.call_options)).await
+        self.stream(
+            reader,
+            options.call_options,
+            options.prefetch_messages,
+            options.max_buffered_bytes,
+        )
+        .await
+    }
+
+    /// query_with_stats executes a query in the PROFILE mode. It returns a RowIterator for
+    /// retrieving the resulting rows, and the query plan and the execution statistics are
+    /// available by RowIterator::stats after all the rows are read.
+    pub async fn query_with_stats(&mut self, statement: Statement) -> Result<RowIterator<'_>, Status> {
+        let options = QueryOptions {
+            mode: QueryMode::Profile,
+            ..Default::default()
+        };
+        self.query_with_option(statement, options).await
+    }
+
+    /// analyze_query returns the query plan of the statement without executing it.
+    pub async fn analyze_query(&mut self, statement: Statement) -> Result<QueryPlan, Status> {
+        let options = QueryOptions {
+            mode: QueryMode::Plan,
+            ..Default::default()
+        };
+        let mut iter = self.query_with_option(statement, options).await?;
+        while iter.next().await?.is_some() {}
+        Ok(iter
+            .stats()
+            .and_then(|stats| stats.query_plan.clone())
+            .unwrap_or_default())
+    }
+
+    /// query_as executes a query and converts all the rows into T.
+    /// It returns TooManyRows without reading the rest of the rows if the rows are more than max_rows.
+    pub async fn query_as<T>(&mut self, statement: Statement, max_rows: Option<usize>) -> Result<Vec<T>, QueryError>
+    where
+        T: TryFrom<Row, Error = RowError>,
+    {
+        let mut iter = self.query(statement).await?;
+        collect_rows(&mut iter, max_rows).await
+    }
+
+    /// query_one_as executes a query and converts the row into T.
+    /// It returns None if no row is found, and TooManyRows if more than one row is found.
+    pub async fn query_one_as<T>(&mut self, statement: Statement) -> Result<Option<T>, QueryError>
+    where
+        T: TryFrom<Row, Error = RowError>,
+    {
+        let mut iter = self.query(statement).await?;
+        Ok(collect_rows(&mut iter, Some(1)).await?.pop())
     }
 
     /// read returns a RowIterator for reading multiple rows from the database.
@@ -161,6 +437,8 @@ impl Transaction {
         key_set: impl Into<KeySet>,
         options: ReadOptions,
     ) -> Result<RowIterator<'_>, Status> {
+        let index = options.index.clone();
+        let directed_read_options = self.directed_read_options(options.directed_read_options.clone())?;
         let request = ReadRequest {
             session: self.get_session_name(),
             transaction: Some(self.transaction_selector.clone()),
@@ -171,15 +449,134 @@ impl Transaction {
             limit: options.limit,
             resume_token: vec![],
             partition_token: vec![],
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: self.request_options(options.call_options.priority, options.request_tag.as_deref()),
+            directed_read_options,
+            data_boost_enabled: false,
         };
 
-        let session = self.as_mut_session();
         let reader = Box::new(TableReader { request });
-        RowIterator::new(session, reader, Some(options.call_options)).await
+        self.stream(
+            reader,
+            options.call_options,
+            options.prefetch_messages,
+            options.max_buffered_bytes,
+        )
+        .await
+        .map_err(|status| read_error(status, table, &index, columns))
     }
 
-    /// read returns a RowIterator for reading multiple rows from the database.
+    /// read_with_index returns a RowIterator for reading multiple rows from the database by the secondary index.
+    /// The key_set is expressed in the order of the index key columns, and the KeyRange can be used over them.
+    /// Only the columns that are part of the index key, part of the primary key, or stored in the index due to
+    /// a STORING clause can be read. The other columns are rejected by the server.
+    /// ```
+    /// use google_cloud_spanner::key::{Key, KeyRange, RangeKind};
+    /// use google_cloud_spanner::client::Client;
+    /// use google_cloud_spanner::transaction::ReadOptions;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), anyhow::Error> {
+    ///     const DATABASE: &str = "projects/local-project/instances/test-instance/databases/local-database";
+    ///     let client = Client::new(DATABASE).await?;
+    ///
+    ///     let mut tx = client.single().await?;
+    ///     let range = KeyRange::new(Key::new(&"a"), Key::new(&"c"), RangeKind::ClosedOpen);
+    ///     let mut iter = tx.read_with_index("User", "UsersByEmail", &["UserID", "Email"], range, ReadOptions::default()).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn read_with_index(
+        &mut self,
+        table: &str,
+        index: &str,
+        columns: &[&str],
+        key_set: impl Into<KeySet>,
+        options: ReadOptions,
+    ) -> Result<RowIterator<'_>, Status> {
+        self.read_with_option(table, columns, key_set, options.with_index(index))
+            .await
+    }
+
+    /// stream starts the streaming read or query. If the transaction is pending the begin, the transaction
+    /// is begun inline by the request and its ID is used by the following requests. The transaction is begun
+    /// by the BeginTransaction and the request is sent again if no transaction is returned, such as when the
+    /// request fails, so that the following requests are not sent without the transaction.
+    async fn stream(
+        &mut self,
+        mut reader: Box<dyn Reader + Sync + Send>,
+        call_options: CallOptions,
+        prefetch_messages: usize,
+        max_buffered_bytes: usize,
+    ) -> Result<RowIterator<'_>, Status> {
+        if self.pending_begin.is_some() {
+            let session = self.session.as_mut().unwrap().deref_mut();
+            match receive_first_message(session, reader.as_ref(), call_options.clone()).await {
+                Ok((streaming, first, id)) => {
+                    self.set_transaction_id(id);
+                    reader.set_transaction(self.transaction_selector.clone());
+                    let session = self.session.as_mut().unwrap().deref_mut();
+                    let iter = RowIterator::with_first_message(session, reader, streaming, first)?;
+                    return Ok(iter.with_prefetch(prefetch_messages, max_buffered_bytes));
+                }
+                Err(e) => {
+                    tracing::debug!("inline begin failed, begin the transaction explicitly: {}", e);
+                    self.begin_explicitly().await?;
+                    reader.set_transaction(self.transaction_selector.clone());
+                }
+            }
+        }
+        let session = self.session.as_mut().unwrap().deref_mut();
+        let iter = RowIterator::new(session, reader, Some(call_options)).await?;
+        Ok(iter.with_prefetch(prefetch_messages, max_buffered_bytes))
+    }
+
+    /// transaction_id returns the ID of the transaction, which is None if the transaction is not begun yet.
+    pub(crate) fn transaction_id(&self) -> Option<&[u8]> {
+        match &self.transaction_selector.selector {
+            Some(transaction_selector::Selector::Id(id)) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// set_transaction_id sets the ID of the transaction begun inline.
+    pub(crate) fn set_transaction_id(&mut self, id: Vec<u8>) {
+        if let (true, Some(session)) = (self.read_write, &self.session) {
+            session.begun(&id);
+        }
+        self.transaction_selector = TransactionSelector {
+            selector: Some(transaction_selector::Selector::Id(id)),
+        };
+        self.pending_begin = None;
+    }
+
+    /// begin_explicitly begins the transaction pending the begin by the BeginTransaction.
+    pub(crate) async fn begin_explicitly(&mut self) -> Result<(), Status> {
+        let (call_options, tx_options) = match (&self.pending_begin, &self.transaction_selector.selector) {
+            (Some(call_options), Some(transaction_selector::Selector::Begin(options))) => {
+                (call_options.clone(), options.clone())
+            }
+            _ => return Ok(()),
+        };
+        let request = BeginTransactionRequest {
+            session: self.get_session_name(),
+            options: Some(tx_options),
+            request_options: Transaction::create_request_options(
+                call_options.priority,
+                None,
+                self.transaction_tag.as_deref(),
+            ),
+        };
+        let session = self.as_mut_session();
+        let result = session
+            .spanner_client
+            .begin_transaction(request, call_options.cancel, call_options.retry)
+            .await;
+        let tx = session.invalidate_if_needed(result).await?.into_inner();
+        self.set_transaction_id(tx.id);
+        Ok(())
+    }
+
+    /// read_row reads the row of the key. It returns None if the row does not exist.
     /// ```
     /// use google_cloud_spanner::key::Key;
     /// use google_cloud_spanner::client::Client;
@@ -190,30 +587,107 @@ impl Transaction {
     ///     let client = Client::new(DATABASE).await?;
     ///     let mut tx = client.single().await?;
     ///     let row = tx.read_row("Guild", &["GuildID", "OwnerUserID"], Key::new(&"guild1")).await?;
+    ///     let row = tx.read_row("UserItem", &["UserId", "ItemId", "Quantity"], ("user1", 1)).await?;
     ///     Ok(())
     /// }
     /// ```
-    pub async fn read_row(&mut self, table: &str, columns: &[&str], key: Key) -> Result<Option<Row>, Status> {
+    pub async fn read_row(&mut self, table: &str, columns: &[&str], key: impl ToKey) -> Result<Option<Row>, Status> {
         self.read_row_with_option(table, columns, key, ReadOptions::default())
             .await
     }
 
-    /// read returns a RowIterator for reading multiple rows from the database.
+    /// read_row_with_option reads the row of the key. It returns None if the row does not exist.
+    /// The limit of the options is ignored because at most one row is read.
     pub async fn read_row_with_option(
         &mut self,
         table: &str,
         columns: &[&str],
-        key: Key,
+        key: impl ToKey,
         options: ReadOptions,
     ) -> Result<Option<Row>, Status> {
         let call_options = options.call_options.clone();
         let mut reader = self
-            .read_with_option(table, columns, KeySet::from(key), options)
+            .read_with_option(table, columns, KeySet::from(key.to_key()), options.with_limit(1))
             .await?;
         reader.set_call_options(call_options);
         reader.next().await
     }
 
+    /// read_row_as reads the row of the key and converts it into T. It returns None if the row does not exist.
+    pub async fn read_row_as<T>(
+        &mut self,
+        table: &str,
+        columns: &[&str],
+        key: impl ToKey,
+    ) -> Result<Option<T>, QueryError>
+    where
+        T: TryFrom<Row, Error = RowError>,
+    {
+        match self.read_row(table, columns, key).await? {
+            Some(row) => Ok(Some(T::try_from(row).map_err(|e| QueryError::RowConversion(0, e))?)),
+            None => Ok(None),
+        }
+    }
+
+    /// read_rows reads the rows of the keys. The rows that do not exist are not returned,
+    /// and the rows are not returned in any particular order.
+    pub async fn read_rows(
+        &mut self,
+        table: &str,
+        columns: &[&str],
+        keys: Vec<impl ToKey>,
+    ) -> Result<Vec<Row>, Status> {
+        let keys: Vec<_> = keys.iter().map(|key| key.to_key()).collect();
+        let mut reader = self.read(table, columns, keys).await?;
+        let mut rows = vec![];
+        while let Some(row) = reader.next().await? {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// set_default_priority sets the priority of the requests in the transaction whose options have no priority.
+    /// None leaves the priority to the server default.
+    pub fn set_default_priority(&mut self, priority: Option<Priority>) {
+        self.default_priority = priority;
+    }
+
+    /// set_default_directed_read_options sets the DirectedReadOptions of the reads and the queries in the transaction
+    /// whose options have no directed_read_options.
+    pub fn set_default_directed_read_options(&mut self, options: Option<DirectedReadOptions>) {
+        self.default_directed_read_options = options;
+    }
+
+    /// directed_read_options returns the DirectedReadOptions of the read or the query in the transaction.
+    /// It returns InvalidArgument for the read-write transaction, which the API does not allow to direct the reads.
+    pub(crate) fn directed_read_options(
+        &self,
+        options: Option<DirectedReadOptions>,
+    ) -> Result<Option<InternalDirectedReadOptions>, Status> {
+        match options {
+            Some(_) if self.read_write => Err(Status::new(
+                Code::InvalidArgument,
+                "directed_read_options can not be used in the read-write transaction",
+            )),
+            Some(options) => Ok(Some(options.into())),
+            None if self.read_write => Ok(None),
+            None => Ok(self.default_directed_read_options.clone().map(|o| o.into())),
+        }
+    }
+
+    /// request_options returns the RequestOptions of the request in the transaction.
+    pub(crate) fn request_options(
+        &self,
+        priority: Option<Priority>,
+        request_tag: Option<&str>,
+    ) -> Option<RequestOptions> {
+        Transaction::create_request_options(
+            priority.or(self.default_priority),
+            request_tag,
+            self.transaction_tag.as_deref(),
+        )
+    }
+
     pub(crate) fn get_session_name(&self) -> String {
         return self.session.as_ref().unwrap().session.name.to_string();
     }
@@ -228,3 +702,379 @@ impl Transaction {
         self.session.take()
     }
 }
+
+/// receive_first_message sends the request with the inline begin and receives the first response,
+/// which has the ID of the transaction begun by the request.
+async fn receive_first_message(
+    session: &mut SessionHandle,
+    reader: &(dyn Reader + Sync + Send),
+    call_options: CallOptions,
+) -> Result<(Streaming<PartialResultSet>, PartialResultSet, Vec<u8>), Status> {
+    let mut streaming = reader.read(session, Some(call_options)).await?.into_inner();
+    let first = streaming.message().await?.unwrap_or_default();
+    match begun_transaction_id(first.metadata.as_ref()) {
+        Some(id) => Ok((streaming, first, id)),
+        None => Err(Status::new(Code::Internal, NO_TRANSACTION_BEGUN)),
+    }
+}
+
+pub(crate) const NO_TRANSACTION_BEGUN: &str = "no transaction is returned by the inline begin";
+
+/// begun_transaction_id returns the ID of the transaction begun inline, which is in the metadata of the first result set.
+pub(crate) fn begun_transaction_id(metadata: Option<&ResultSetMetadata>) -> Option<Vec<u8>> {
+    metadata
+        .and_then(|metadata| metadata.transaction.as_ref())
+        .map(|tx| tx.id.clone())
+        .filter(|id| !id.is_empty())
+}
+
+/// collect_rows converts the rows into T, returning TooManyRows as soon as the row after max_rows is received.
+pub(crate) async fn collect_rows<T>(
+    iter: &mut (impl AsyncIterator + Send),
+    max_rows: Option<usize>,
+) -> Result<Vec<T>, QueryError>
+where
+    T: TryFrom<Row, Error = RowError>,
+{
+    let mut result = vec![];
+    while let Some(row) = iter.next().await? {
+        if let Some(max_rows) = max_rows {
+            if result.len() >= max_rows {
+                return Err(QueryError::TooManyRows(max_rows));
+            }
+        }
+        let index = result.len();
+        result.push(T::try_from(row).map_err(|e| QueryError::RowConversion(index, e))?);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::atomic::AtomicI64;
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use google_cloud_gax::grpc::Status;
+    use google_cloud_googleapis::spanner::v1::request_options::Priority;
+    use google_cloud_googleapis::spanner::v1::struct_type::Field;
+    use google_cloud_googleapis::spanner::v1::transaction_selector::Selector;
+    use google_cloud_googleapis::spanner::v1::{
+        ResultSetMetadata, Transaction as TransactionProto, TransactionOptions, TransactionSelector, Type, TypeCode,
+    };
+    use prost_types::value::Kind;
+    use prost_types::Value;
+
+    use crate::reader::AsyncIterator;
+    use crate::row::{Error as RowError, Row};
+    use crate::transaction::{
+        begun_transaction_id, collect_rows, read_error, CallOptions, DirectedReadOptions, QueryError, ReadOptions,
+        ReplicaSelection, ReplicaType, Transaction,
+    };
+
+    struct FakeIterator {
+        rows: VecDeque<Row>,
+        read: usize,
+    }
+
+    impl FakeIterator {
+        fn new(values: Vec<&str>) -> Self {
+            let fields = Arc::new(vec![Field {
+                name: "Name".to_string(),
+                r#type: Some(Type {
+                    code: TypeCode::String.into(),
+                    array_element_type: None,
+                    struct_type: None,
+                    type_annotation: 0,
+                    proto_type_fqn: "".to_string(),
+                }),
+            }]);
+            let index = Arc::new(HashMap::from([("Name".to_string(), 0)]));
+            let rows = values
+                .into_iter()
+                .map(|v| {
+                    let kind = match v {
+                        "NULL" => Kind::NullValue(0),
+                        v => Kind::StringValue(v.to_string()),
+                    };
+                    Row::new(Arc::clone(&index), Arc::clone(&fields), vec![Value { kind: Some(kind) }])
+                })
+                .collect();
+            Self { rows, read: 0 }
+        }
+    }
+
+    #[async_trait]
+    impl AsyncIterator for FakeIterator {
+        fn column_metadata(&self, _column_name: &str) -> Option<(usize, Field)> {
+            None
+        }
+
+        async fn next(&mut self) -> Result<Option<Row>, Status> {
+            let row = self.rows.pop_front();
+            self.read += row.is_some() as usize;
+            Ok(row)
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    impl TryFrom<Row> for User {
+        type Error = RowError;
+        fn try_from(row: Row) -> Result<Self, Self::Error> {
+            Ok(User {
+                name: row.column_by_name("Name")?,
+            })
+        }
+    }
+
+    fn names(users: Vec<User>) -> Vec<String> {
+        users.into_iter().map(|u| u.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_collect_rows() {
+        let mut iter = FakeIterator::new(vec!["a", "b", "c"]);
+        assert_eq!(names(collect_rows(&mut iter, None).await.unwrap()), vec!["a", "b", "c"]);
+
+        let mut iter = FakeIterator::new(vec!["a", "b", "c"]);
+        assert_eq!(names(collect_rows(&mut iter, Some(3)).await.unwrap()), vec!["a", "b", "c"]);
+
+        let mut iter = FakeIterator::new(vec![]);
+        assert!(collect_rows::<User>(&mut iter, Some(0)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_collect_rows_too_many_rows() {
+        let mut iter = FakeIterator::new(vec!["a", "b", "c", "d"]);
+        match collect_rows::<User>(&mut iter, Some(2)).await {
+            Err(QueryError::TooManyRows(2)) => {}
+            r => panic!("unexpected {:?}", r.err()),
+        }
+        // the rest of the rows are not read
+        assert_eq!(iter.read, 3);
+
+        let mut iter = FakeIterator::new(vec!["a", "b"]);
+        assert!(matches!(
+            collect_rows::<User>(&mut iter, Some(1)).await,
+            Err(QueryError::TooManyRows(1))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_collect_rows_conversion_error() {
+        let mut iter = FakeIterator::new(vec!["a", "b", "NULL", "d"]);
+        match collect_rows::<User>(&mut iter, None).await {
+            Err(QueryError::RowConversion(2, RowError::UnexpectedNull(..))) => {}
+            r => panic!("unexpected {:?}", r.err()),
+        }
+    }
+
+    #[test]
+    fn test_create_request_options() {
+        assert_eq!(Transaction::create_request_options(None, None, None), None);
+
+        let options = Transaction::create_request_options(None, Some("checkout-query"), Some("checkout")).unwrap();
+        assert_eq!(options.priority, 0);
+        assert_eq!(options.request_tag, "checkout-query");
+        assert_eq!(options.transaction_tag, "checkout");
+
+        let options = Transaction::create_request_options(Some(Priority::Low), None, None).unwrap();
+        assert_eq!(options.priority, Priority::Low as i32);
+        assert_eq!(options.request_tag, "");
+
+        let tag = "日".repeat(60);
+        let options = Transaction::create_request_options(None, Some(&tag), Some(&"a".repeat(50))).unwrap();
+        assert_eq!(options.request_tag, "日".repeat(50));
+        assert_eq!(options.transaction_tag, "a".repeat(50));
+    }
+
+    #[test]
+    fn test_request_options() {
+        let mut tx = Transaction {
+            session: None,
+            sequence_number: AtomicI64::new(0),
+            transaction_selector: TransactionSelector::default(),
+            transaction_tag: None,
+            default_priority: None,
+            default_directed_read_options: None,
+            read_write: false,
+            pending_begin: None,
+        };
+        assert_eq!(tx.request_options(None, None), None);
+        assert_eq!(
+            tx.request_options(Some(Priority::High), None).unwrap().priority,
+            Priority::High as i32
+        );
+
+        tx.set_default_priority(Some(Priority::Low));
+        assert_eq!(tx.request_options(None, None).unwrap().priority, Priority::Low as i32);
+        assert_eq!(
+            tx.request_options(Some(Priority::High), None).unwrap().priority,
+            Priority::High as i32
+        );
+
+        tx.transaction_tag = Some("checkout".to_string());
+        tx.set_default_priority(None);
+        let options = tx.request_options(None, Some("query")).unwrap();
+        assert_eq!(options.priority, Priority::Unspecified as i32);
+        assert_eq!(options.request_tag, "query");
+        assert_eq!(options.transaction_tag, "checkout");
+    }
+
+    #[test]
+    fn test_set_transaction_id() {
+        let mut tx = Transaction {
+            session: None,
+            sequence_number: AtomicI64::new(0),
+            transaction_selector: TransactionSelector {
+                selector: Some(Selector::Begin(TransactionOptions::default())),
+            },
+            transaction_tag: None,
+            default_priority: None,
+            default_directed_read_options: None,
+            read_write: false,
+            pending_begin: Some(CallOptions::default()),
+        };
+        assert_eq!(tx.transaction_id(), None);
+
+        tx.set_transaction_id(vec![1, 2]);
+        assert_eq!(tx.transaction_id(), Some(&[1u8, 2][..]));
+        assert!(tx.pending_begin.is_none());
+        assert_eq!(tx.transaction_selector.selector, Some(Selector::Id(vec![1, 2])));
+    }
+
+    #[test]
+    fn test_begun_transaction_id() {
+        let metadata = |id: Option<Vec<u8>>| ResultSetMetadata {
+            transaction: id.map(|id| TransactionProto {
+                id,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(begun_transaction_id(None), None);
+        assert_eq!(begun_transaction_id(Some(&metadata(None))), None);
+        assert_eq!(begun_transaction_id(Some(&metadata(Some(vec![])))), None);
+        assert_eq!(begun_transaction_id(Some(&metadata(Some(vec![3])))), Some(vec![3]));
+    }
+
+    #[test]
+    fn test_read_options() {
+        let options = ReadOptions::default()
+            .with_index("UserByName")
+            .with_limit(10)
+            .with_request_tag("tag");
+        assert_eq!(options.index, "UserByName");
+        assert_eq!(options.limit, 10);
+        assert_eq!(options.request_tag.as_deref(), Some("tag"));
+    }
+
+    #[test]
+    fn test_read_error() {
+        let status = read_error(
+            Status::not_found("Column not found in table User: Nmae"),
+            "User",
+            "",
+            &["UserID", "Nmae"],
+        );
+        assert_eq!(status.code(), google_cloud_gax::grpc::Code::NotFound);
+        assert_eq!(
+            status.message(),
+            "read User(UserID, Nmae): Column not found in table User: Nmae"
+        );
+
+        let status = read_error(
+            Status::invalid_argument("Column Name is not stored in index UsersByEmail"),
+            "User",
+            "UsersByEmail",
+            &["Email", "Name"],
+        );
+        assert_eq!(status.code(), google_cloud_gax::grpc::Code::InvalidArgument);
+        assert_eq!(
+            status.message(),
+            "read User by index UsersByEmail(Email, Name): Column Name is not stored in index UsersByEmail"
+        );
+    }
+
+    #[test]
+    fn test_directed_read_options() {
+        use google_cloud_googleapis::spanner::v1::directed_read_options::{
+            ExcludeReplicas, IncludeReplicas, ReplicaSelection as InternalReplicaSelection, Replicas,
+        };
+        use google_cloud_googleapis::spanner::v1::DirectedReadOptions as InternalDirectedReadOptions;
+
+        let include: InternalDirectedReadOptions = DirectedReadOptions::include_replicas(
+            vec![
+                ReplicaSelection::new("us-east1", ReplicaType::ReadOnly),
+                ReplicaSelection::new("us-west1", ReplicaType::Unspecified),
+            ],
+            true,
+        )
+        .into();
+        assert_eq!(
+            include.replicas,
+            Some(Replicas::IncludeReplicas(IncludeReplicas {
+                replica_selections: vec![
+                    InternalReplicaSelection {
+                        location: "us-east1".to_string(),
+                        r#type: 2,
+                    },
+                    InternalReplicaSelection {
+                        location: "us-west1".to_string(),
+                        r#type: 0,
+                    },
+                ],
+                auto_failover_disabled: true,
+            }))
+        );
+
+        let exclude: InternalDirectedReadOptions =
+            DirectedReadOptions::exclude_replicas(vec![ReplicaSelection::new("", ReplicaType::ReadWrite)]).into();
+        assert_eq!(
+            exclude.replicas,
+            Some(Replicas::ExcludeReplicas(ExcludeReplicas {
+                replica_selections: vec![InternalReplicaSelection {
+                    location: "".to_string(),
+                    r#type: 1,
+                }],
+            }))
+        );
+    }
+
+    #[test]
+    fn test_transaction_directed_read_options() {
+        let default = DirectedReadOptions::include_replicas(
+            vec![ReplicaSelection::new("us-east1", ReplicaType::ReadOnly)],
+            false,
+        );
+        let request =
+            DirectedReadOptions::exclude_replicas(vec![ReplicaSelection::new("us-west1", ReplicaType::ReadOnly)]);
+        let mut tx = Transaction {
+            session: None,
+            sequence_number: AtomicI64::new(0),
+            transaction_selector: TransactionSelector::default(),
+            transaction_tag: None,
+            default_priority: None,
+            default_directed_read_options: None,
+            read_write: false,
+            pending_begin: None,
+        };
+        assert_eq!(tx.directed_read_options(None).unwrap(), None);
+        tx.set_default_directed_read_options(Some(default.clone()));
+        assert_eq!(tx.directed_read_options(None).unwrap(), Some(default.clone().into()));
+        assert_eq!(
+            tx.directed_read_options(Some(request.clone())).unwrap(),
+            Some(request.clone().into())
+        );
+
+        tx.read_write = true;
+        assert_eq!(tx.directed_read_options(None).unwrap(), None);
+        let err = tx.directed_read_options(Some(request)).unwrap_err();
+        assert_eq!(err.code(), google_cloud_gax::grpc::Code::InvalidArgument);
+    }
+}
@@ -1,6 +1,5 @@
 use std::ops::{Deref, DerefMut};
 use std::sync::atomic::AtomicI64;
-use std::time::SystemTime;
 
 use google_cloud_googleapis::spanner::v1::{
     transaction_options, transaction_selector, BeginTransactionRequest, ExecuteSqlRequest, PartitionOptions,
@@ -13,8 +12,8 @@ use crate::reader::{Reader, RowIterator, StatementReader, TableReader};
 use crate::session::ManagedSession;
 use crate::statement::Statement;
 use crate::transaction::{CallOptions, QueryOptions, ReadOptions, Transaction};
-use crate::value::TimestampBound;
-use google_cloud_gax::grpc::Status;
+use crate::value::{Timestamp, TimestampBound};
+use google_cloud_gax::grpc::{Code, Status};
 
 /// ReadOnlyTransaction provides a snapshot transaction with guaranteed
 /// consistency across reads, but does not allow writes.  Read-only transactions
@@ -32,6 +31,8 @@ use google_cloud_gax::grpc::Status;
 /// TimestampBound for more details.
 pub struct ReadOnlyTransaction {
     base_tx: Transaction,
+    /// The timestamp chosen by the server for the multi-use transaction.
+    /// The single-use transaction returns it in the metadata of the RowIterator.
     pub rts: Option<time::OffsetDateTime>,
 }
 
@@ -55,28 +56,37 @@ impl ReadOnlyTransaction {
             base_tx: Transaction {
                 session: Some(session),
                 sequence_number: AtomicI64::new(0),
-                transaction_selector: TransactionSelector {
-                    selector: Some(transaction_selector::Selector::SingleUse(TransactionOptions {
-                        mode: Some(transaction_options::Mode::ReadOnly(tb.into())),
-                    })),
-                },
+                transaction_selector: single_use_selector(tb),
+                transaction_tag: None,
+                default_priority: None,
+                default_directed_read_options: None,
+                read_write: false,
+                pending_begin: None,
             },
             rts: None,
         })
     }
 
     /// begin starts a snapshot read-only Transaction on Cloud Spanner.
+    /// max_staleness and min_read_timestamp are rejected with InvalidArgument, because they are only for the single-use transaction.
     pub async fn begin(
         mut session: ManagedSession,
         tb: TimestampBound,
         options: CallOptions,
     ) -> Result<ReadOnlyTransaction, Status> {
+        if tb.is_single_use_only() {
+            return Err(Status::new(
+                Code::InvalidArgument,
+                "max_staleness and min_read_timestamp can be used only in the single-use transaction",
+            ));
+        }
         let request = BeginTransactionRequest {
             session: session.session.name.to_string(),
             options: Some(TransactionOptions {
+                exclude_txn_from_change_streams: false,
                 mode: Some(transaction_options::Mode::ReadOnly(tb.into())),
             }),
-            request_options: Transaction::create_request_options(options.priority),
+            request_options: Transaction::create_request_options(options.priority, None, None),
         };
 
         let result = session
@@ -86,8 +96,11 @@ impl ReadOnlyTransaction {
         match session.invalidate_if_needed(result).await {
             Ok(response) => {
                 let tx = response.into_inner();
-                let rts = tx.read_timestamp.unwrap();
-                let st: SystemTime = rts.try_into().unwrap();
+                let rts = match tx.read_timestamp.map(|t| OffsetDateTime::try_from(Timestamp::from(t))) {
+                    Some(Ok(rts)) => rts,
+                    Some(Err(e)) => return Err(Status::new(Code::OutOfRange, format!("invalid read timestamp: {e}"))),
+                    None => return Err(Status::new(Code::Internal, "the read timestamp is not returned")),
+                };
                 Ok(ReadOnlyTransaction {
                     base_tx: Transaction {
                         session: Some(session),
@@ -95,8 +108,13 @@ impl ReadOnlyTransaction {
                         transaction_selector: TransactionSelector {
                             selector: Some(transaction_selector::Selector::Id(tx.id)),
                         },
+                        transaction_tag: None,
+                        default_priority: None,
+                        default_directed_read_options: None,
+                        read_write: false,
+                        pending_begin: None,
                     },
-                    rts: Some(OffsetDateTime::from(st)),
+                    rts: Some(rts),
                 })
             }
             Err(e) => Err(e),
@@ -104,10 +122,113 @@ impl ReadOnlyTransaction {
     }
 }
 
+/// Partition is a partition of the read or the query of the BatchReadOnlyTransaction.
+/// The partition can be serialized with the `serde` feature, and executed in another process
+/// by the BatchReadOnlyTransaction returned by Client::batch_read_only_transaction_from_id.
 pub struct Partition<T: Reader> {
     pub reader: T,
 }
 
+/// BatchReadOnlyTransactionId identifies the BatchReadOnlyTransaction and its session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchReadOnlyTransactionId {
+    pub session: String,
+    pub transaction_id: Vec<u8>,
+}
+
+impl BatchReadOnlyTransactionId {
+    fn new(session: &str, selector: Option<&TransactionSelector>) -> Option<Self> {
+        match selector.and_then(|s| s.selector.as_ref()) {
+            Some(transaction_selector::Selector::Id(id)) => Some(BatchReadOnlyTransactionId {
+                session: session.to_string(),
+                transaction_id: id.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Partition<StatementReader> {
+    /// transaction_id returns the ID of the transaction that the partition is executed in.
+    pub fn transaction_id(&self) -> Option<BatchReadOnlyTransactionId> {
+        let request = &self.reader.request;
+        BatchReadOnlyTransactionId::new(&request.session, request.transaction.as_ref())
+    }
+}
+
+impl Partition<TableReader> {
+    /// transaction_id returns the ID of the transaction that the partition is executed in.
+    pub fn transaction_id(&self) -> Option<BatchReadOnlyTransactionId> {
+        let request = &self.reader.request;
+        BatchReadOnlyTransactionId::new(&request.session, request.transaction.as_ref())
+    }
+}
+
+/// The partitions are serialized as the bytes of the protobuf encoded request.
+#[cfg(feature = "serde")]
+mod partition_serde {
+    use std::marker::PhantomData;
+
+    use google_cloud_googleapis::spanner::v1::{ExecuteSqlRequest, ReadRequest};
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::reader::{StatementReader, TableReader};
+    use crate::transaction_ro::Partition;
+
+    struct RequestVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: prost::Message + Default> Visitor<'de> for RequestVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("the protobuf encoded partition")
+        }
+
+        fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<T, E> {
+            T::decode(v).map_err(E::custom)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(b) = seq.next_element::<u8>()? {
+                bytes.push(b);
+            }
+            self.visit_bytes(&bytes)
+        }
+    }
+
+    impl Serialize for Partition<StatementReader> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&prost::Message::encode_to_vec(&self.reader.request))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Partition<StatementReader> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let request = deserializer.deserialize_bytes(RequestVisitor::<ExecuteSqlRequest>(PhantomData))?;
+            Ok(Partition {
+                reader: StatementReader { request },
+            })
+        }
+    }
+
+    impl Serialize for Partition<TableReader> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&prost::Message::encode_to_vec(&self.reader.request))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Partition<TableReader> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let request = deserializer.deserialize_bytes(RequestVisitor::<ReadRequest>(PhantomData))?;
+            Ok(Partition {
+                reader: TableReader { request },
+            })
+        }
+    }
+}
+
 /// BatchReadOnlyTransaction is a ReadOnlyTransaction that allows for exporting
 /// arbitrarily large amounts of data from Cloud Spanner databases.
 /// BatchReadOnlyTransaction partitions a read/query request. Read/query request
@@ -141,6 +262,38 @@ impl BatchReadOnlyTransaction {
         Ok(BatchReadOnlyTransaction { base_tx: tx })
     }
 
+    /// from_id returns the BatchReadOnlyTransaction of the transaction already begun with the session.
+    pub(crate) fn from_id(session: ManagedSession, id: BatchReadOnlyTransactionId) -> BatchReadOnlyTransaction {
+        BatchReadOnlyTransaction {
+            base_tx: ReadOnlyTransaction {
+                base_tx: Transaction {
+                    session: Some(session),
+                    sequence_number: AtomicI64::new(0),
+                    transaction_selector: TransactionSelector {
+                        selector: Some(transaction_selector::Selector::Id(id.transaction_id)),
+                    },
+                    transaction_tag: None,
+                    default_priority: None,
+                    default_directed_read_options: None,
+                    read_write: false,
+                    pending_begin: None,
+                },
+                rts: None,
+            },
+        }
+    }
+
+    /// id returns the BatchReadOnlyTransactionId that can be sent to another process
+    /// to execute the partitions with Client::batch_read_only_transaction_from_id.
+    pub fn id(&self) -> Option<BatchReadOnlyTransactionId> {
+        BatchReadOnlyTransactionId::new(&self.get_session_name(), Some(&self.transaction_selector))
+    }
+
+    /// close releases the session of the transaction. The partitions of the transaction can not be executed after
+    /// it is closed, so close it after all the partitions are executed, including those executed in other processes.
+    /// The transaction is also closed when it is dropped.
+    pub fn close(self) {}
+
     /// partition_read returns a list of Partitions that can be used to read rows from
     /// the database. These partitions can be executed across multiple processes,
     /// even across different machines. The partition size and count hints can be
@@ -169,6 +322,7 @@ impl BatchReadOnlyTransaction {
     ) -> Result<Vec<Partition<TableReader>>, Status> {
         let columns: Vec<String> = columns.iter().map(|x| x.to_string()).collect();
         let inner_keyset = keys.into().inner;
+        let directed_read_options = self.directed_read_options(ro.directed_read_options.clone())?;
         let request = PartitionReadRequest {
             session: self.get_session_name(),
             transaction: Some(self.transaction_selector.clone()),
@@ -200,7 +354,9 @@ impl BatchReadOnlyTransaction {
                             limit: ro.limit,
                             resume_token: vec![],
                             partition_token: x.partition_token,
-                            request_options: Transaction::create_request_options(ro.call_options.priority),
+                            request_options: self.request_options(ro.call_options.priority, ro.request_tag.as_deref()),
+                            directed_read_options: directed_read_options.clone(),
+                            data_boost_enabled: ro.data_boost_enabled,
                         },
                     },
                 })
@@ -223,14 +379,15 @@ impl BatchReadOnlyTransaction {
         po: Option<PartitionOptions>,
         qo: QueryOptions,
     ) -> Result<Vec<Partition<StatementReader>>, Status> {
+        let directed_read_options = self.directed_read_options(qo.directed_read_options.clone())?;
         let request = PartitionQueryRequest {
             session: self.get_session_name(),
             transaction: Some(self.transaction_selector.clone()),
             sql: stmt.sql.clone(),
             params: Some(prost_types::Struct {
-                fields: stmt.params.clone(),
+                fields: stmt.request_params(),
             }),
-            param_types: stmt.param_types.clone(),
+            param_types: stmt.request_param_types(),
             partition_options: po,
         };
         let result = match self
@@ -250,15 +407,20 @@ impl BatchReadOnlyTransaction {
                             transaction: Some(self.transaction_selector.clone()),
                             sql: stmt.sql.clone(),
                             params: Some(prost_types::Struct {
-                                fields: stmt.params.clone(),
+                                fields: stmt.request_params(),
                             }),
-                            param_types: stmt.param_types.clone(),
+                            param_types: stmt.request_param_types(),
                             resume_token: vec![],
                             query_mode: 0,
                             partition_token: x.partition_token,
                             seqno: 0,
                             query_options: qo.optimizer_options.clone(),
-                            request_options: Transaction::create_request_options(qo.call_options.priority),
+                            request_options: self.request_options(
+                                qo.call_options.priority,
+                                stmt.request_tag.as_deref().or(qo.request_tag.as_deref()),
+                            ),
+                            directed_read_options: directed_read_options.clone(),
+                            data_boost_enabled: qo.data_boost_enabled,
                         },
                     },
                 })
@@ -274,7 +436,176 @@ impl BatchReadOnlyTransaction {
         partition: Partition<T>,
         option: Option<CallOptions>,
     ) -> Result<RowIterator<'_>, Status> {
+        let data_boost_enabled = partition.reader.data_boost_enabled();
         let session = self.as_mut_session();
-        RowIterator::new(session, Box::new(partition.reader), option).await
+        RowIterator::new(session, Box::new(partition.reader), option)
+            .await
+            .map_err(|status| match status.code() {
+                Code::PermissionDenied if data_boost_enabled => data_boost_error(status),
+                _ => status,
+            })
+    }
+}
+
+/// data_boost_error returns the status of the partition executed with Data Boost that is not permitted,
+/// such as when Data Boost is not enabled for the project or the caller has no spanner.databases.useDataBoost permission.
+fn data_boost_error(status: Status) -> Status {
+    let message = format!("data boost is not available: {}", status.message());
+    Status::with_details_and_metadata(
+        status.code(),
+        message,
+        status.details().to_vec().into(),
+        status.metadata().clone(),
+    )
+}
+
+/// single_use_selector returns the selector that runs the read or query in the single-use read-only transaction.
+/// No BeginTransaction is required, and the read timestamp is returned in the metadata of the first result.
+fn single_use_selector(tb: TimestampBound) -> TransactionSelector {
+    TransactionSelector {
+        selector: Some(transaction_selector::Selector::SingleUse(TransactionOptions {
+            exclude_txn_from_change_streams: false,
+            mode: Some(transaction_options::Mode::ReadOnly(tb.into())),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use google_cloud_googleapis::spanner::v1::transaction_options::read_only::TimestampBound as InternalTimestampBound;
+    use google_cloud_googleapis::spanner::v1::transaction_options::{Mode, ReadOnly};
+    use google_cloud_googleapis::spanner::v1::transaction_selector::Selector;
+    use google_cloud_googleapis::spanner::v1::{ExecuteSqlRequest, ReadRequest, TransactionSelector};
+
+    use crate::reader::{StatementReader, TableReader};
+    use crate::transaction_ro::{data_boost_error, single_use_selector, BatchReadOnlyTransactionId, Partition};
+    use crate::value::TimestampBound;
+
+    fn id_selector() -> Option<TransactionSelector> {
+        Some(TransactionSelector {
+            selector: Some(Selector::Id(vec![1, 2, 3])),
+        })
+    }
+
+    #[test]
+    fn test_partition_transaction_id() {
+        let expected = Some(BatchReadOnlyTransactionId {
+            session: "session1".to_string(),
+            transaction_id: vec![1, 2, 3],
+        });
+        let query = Partition {
+            reader: StatementReader {
+                request: ExecuteSqlRequest {
+                    session: "session1".to_string(),
+                    transaction: id_selector(),
+                    ..Default::default()
+                },
+            },
+        };
+        assert_eq!(query.transaction_id(), expected);
+        let read = Partition {
+            reader: TableReader {
+                request: ReadRequest {
+                    session: "session1".to_string(),
+                    transaction: id_selector(),
+                    ..Default::default()
+                },
+            },
+        };
+        assert_eq!(read.transaction_id(), expected);
+        let single = Partition {
+            reader: TableReader {
+                request: ReadRequest {
+                    session: "session1".to_string(),
+                    transaction: Some(single_use_selector(TimestampBound::strong_read())),
+                    ..Default::default()
+                },
+            },
+        };
+        assert_eq!(single.transaction_id(), None);
+    }
+
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[test]
+    fn test_partition_serde() {
+        let query = Partition {
+            reader: StatementReader {
+                request: ExecuteSqlRequest {
+                    session: "session1".to_string(),
+                    transaction: id_selector(),
+                    sql: "SELECT * FROM User".to_string(),
+                    partition_token: vec![4, 5, 6],
+                    data_boost_enabled: true,
+                    ..Default::default()
+                },
+            },
+        };
+        let json = serde_json::to_string(&query).unwrap();
+        let decoded: Partition<StatementReader> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.reader.request, query.reader.request);
+        assert!(decoded.reader.request.data_boost_enabled);
+
+        let read = Partition {
+            reader: TableReader {
+                request: ReadRequest {
+                    session: "session1".to_string(),
+                    transaction: id_selector(),
+                    table: "User".to_string(),
+                    columns: vec!["UserId".to_string()],
+                    partition_token: vec![7, 8, 9],
+                    ..Default::default()
+                },
+            },
+        };
+        let json = serde_json::to_string(&read).unwrap();
+        let decoded: Partition<TableReader> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.reader.request, read.reader.request);
+    }
+
+    #[test]
+    fn test_single_use_selector() {
+        let cases = vec![
+            (TimestampBound::strong_read(), InternalTimestampBound::Strong(true)),
+            (
+                TimestampBound::exact_staleness(Duration::from_secs(15)),
+                InternalTimestampBound::ExactStaleness(prost_types::Duration { seconds: 15, nanos: 0 }),
+            ),
+            (
+                TimestampBound::max_staleness(Duration::from_millis(10_500)),
+                InternalTimestampBound::MaxStaleness(prost_types::Duration {
+                    seconds: 10,
+                    nanos: 500_000_000,
+                }),
+            ),
+        ];
+        for (tb, expected) in cases {
+            match single_use_selector(tb).selector {
+                Some(Selector::SingleUse(options)) => {
+                    assert!(!options.exclude_txn_from_change_streams);
+                    assert_eq!(
+                        options.mode,
+                        Some(Mode::ReadOnly(ReadOnly {
+                            return_read_timestamp: true,
+                            timestamp_bound: Some(expected),
+                        }))
+                    );
+                }
+                other => panic!("unexpected selector {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_data_boost_error() {
+        let status = data_boost_error(google_cloud_gax::grpc::Status::permission_denied(
+            "Caller is missing IAM permission spanner.databases.useDataBoost",
+        ));
+        assert_eq!(status.code(), google_cloud_gax::grpc::Code::PermissionDenied);
+        assert_eq!(
+            status.message(),
+            "data boost is not available: Caller is missing IAM permission spanner.databases.useDataBoost"
+        );
     }
 }
@@ -1,27 +1,91 @@
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
 
 use google_cloud_gax::cancel::CancellationToken;
 use prost_types::Struct;
 
+use crate::mutation::check_delete_all_keys;
+use crate::reader::RowIterator;
+use crate::row::{Error as RowError, Row};
 use crate::session::ManagedSession;
-use crate::statement::Statement;
-use crate::transaction::{CallOptions, QueryOptions, Transaction};
+use crate::statement::{has_then_return, into_request_params, Statement};
+use crate::stats::RowCount;
+use crate::transaction::{
+    begun_transaction_id, collect_rows, CallOptions, QueryError, QueryOptions, Transaction, NO_TRANSACTION_BEGUN,
+};
 use crate::value::Timestamp;
 use google_cloud_gax::grpc::{Code, Status};
 use google_cloud_gax::retry::{RetrySetting, TryAs};
 use google_cloud_googleapis::spanner::v1::commit_request::Transaction::TransactionId;
+pub use google_cloud_googleapis::spanner::v1::transaction_options::read_write::ReadLockMode;
 use google_cloud_googleapis::spanner::v1::{
     commit_request, execute_batch_dml_request, result_set_stats, transaction_options, transaction_selector,
-    BeginTransactionRequest, CommitRequest, CommitResponse, ExecuteBatchDmlRequest, ExecuteSqlRequest, Mutation,
-    ResultSetStats, RollbackRequest, TransactionOptions, TransactionSelector,
+    BeginTransactionRequest, CommitRequest, CommitResponse, ExecuteBatchDmlRequest, ExecuteBatchDmlResponse,
+    ExecuteSqlRequest, Mutation, ResultSet, ResultSetStats, RollbackRequest, TransactionOptions, TransactionSelector,
 };
 
+/// The maximum of CommitOptions::max_commit_delay allowed by the server.
+pub const MAX_COMMIT_DELAY: Duration = Duration::from_millis(500);
+
 #[derive(Clone, Default)]
 pub struct CommitOptions {
     pub return_commit_stats: bool,
     pub call_options: CallOptions,
+    /// The amount of latency the commit is willing to incur, so that the server can batch the commits
+    /// for the better throughput. It must be at most MAX_COMMIT_DELAY.
+    /// None leaves the delay to the server, which assumes the commit is latency sensitive.
+    pub max_commit_delay: Option<Duration>,
+    /// Whether to commit the deletes of all the keys of a table, which are rejected with
+    /// ClientConfig::reject_delete_all_keys.
+    pub allow_delete_all_keys: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BatchUpdateError {
+    #[error(transparent)]
+    GRPC(#[from] Status),
+
+    /// StatementFailed contains the index of the failed statement, its status,
+    /// and the row counts of the statements executed successfully before it.
+    #[error("statement {0} failed: {1}")]
+    StatementFailed(usize, Status, Vec<i64>),
+}
+
+impl TryAs<Status> for BatchUpdateError {
+    fn try_as(&self) -> Option<&Status> {
+        match self {
+            BatchUpdateError::GRPC(s) => Some(s),
+            BatchUpdateError::StatementFailed(_, s, _) => Some(s),
+        }
+    }
+}
+
+/// CommitStats is the statistics of the committed transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitStats {
+    /// mutation_count is the number of the mutations in the transaction, including the index entries.
+    pub mutation_count: i64,
+}
+
+/// CommitResult is the result of the commit.
+/// The commit_stats is returned only when CommitOptions::return_commit_stats is true.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitResult {
+    pub timestamp: Option<Timestamp>,
+    pub commit_stats: Option<CommitStats>,
+}
+
+impl From<CommitResponse> for CommitResult {
+    fn from(response: CommitResponse) -> Self {
+        Self {
+            timestamp: response.commit_timestamp.map(|ts| ts.into()),
+            commit_stats: response.commit_stats.map(|stats| CommitStats {
+                mutation_count: stats.mutation_count,
+            }),
+        }
+    }
 }
 
 /// ReadWriteTransaction provides a locking read-write transaction.
@@ -78,8 +142,9 @@ pub struct CommitOptions {
 /// amount of wall time spent retrying.
 pub struct ReadWriteTransaction {
     base_tx: Transaction,
-    tx_id: Vec<u8>,
     wb: Vec<Mutation>,
+    default_max_commit_delay: Option<Duration>,
+    reject_delete_all_keys: bool,
 }
 
 impl Deref for ReadWriteTransaction {
@@ -103,35 +168,37 @@ pub struct BeginError {
 
 impl ReadWriteTransaction {
     pub async fn begin(session: ManagedSession, options: CallOptions) -> Result<ReadWriteTransaction, BeginError> {
-        ReadWriteTransaction::begin_internal(
-            session,
-            transaction_options::Mode::ReadWrite(transaction_options::ReadWrite::default()),
-            options,
-        )
-        .await
+        ReadWriteTransaction::begin_with_transaction_tag(session, options, None).await
+    }
+
+    /// begin_with_transaction_tag begins the transaction with the tag, which is shown in the
+    /// transaction statistics and lock statistics tables and is sent with all the requests in the transaction.
+    /// The tag is truncated to 50 characters.
+    pub async fn begin_with_transaction_tag(
+        session: ManagedSession,
+        options: CallOptions,
+        transaction_tag: Option<String>,
+    ) -> Result<ReadWriteTransaction, BeginError> {
+        ReadWriteTransaction::begin_internal(session, read_write_options(false, None), options, transaction_tag).await
     }
 
     pub async fn begin_partitioned_dml(
         session: ManagedSession,
         options: CallOptions,
     ) -> Result<ReadWriteTransaction, BeginError> {
-        ReadWriteTransaction::begin_internal(
-            session,
-            transaction_options::Mode::PartitionedDml(transaction_options::PartitionedDml {}),
-            options,
-        )
-        .await
+        ReadWriteTransaction::begin_internal(session, partitioned_dml_options(false), options, None).await
     }
 
-    async fn begin_internal(
+    pub(crate) async fn begin_internal(
         mut session: ManagedSession,
-        mode: transaction_options::Mode,
+        tx_options: TransactionOptions,
         options: CallOptions,
+        transaction_tag: Option<String>,
     ) -> Result<ReadWriteTransaction, BeginError> {
         let request = BeginTransactionRequest {
             session: session.session.name.to_string(),
-            options: Some(TransactionOptions { mode: Some(mode) }),
-            request_options: Transaction::create_request_options(options.priority),
+            options: Some(tx_options),
+            request_options: Transaction::create_request_options(options.priority, None, transaction_tag.as_deref()),
         };
         let result = session
             .spanner_client
@@ -144,6 +211,7 @@ impl ReadWriteTransaction {
             }
         };
         let tx = response.into_inner();
+        session.begun(&tx.id);
         Ok(ReadWriteTransaction {
             base_tx: Transaction {
                 session: Some(session),
@@ -151,80 +219,255 @@ impl ReadWriteTransaction {
                 transaction_selector: TransactionSelector {
                     selector: Some(transaction_selector::Selector::Id(tx.id.clone())),
                 },
+                transaction_tag,
+                default_priority: None,
+                default_directed_read_options: None,
+                read_write: true,
+                pending_begin: None,
             },
-            tx_id: tx.id,
             wb: vec![],
+            default_max_commit_delay: None,
+            reject_delete_all_keys: false,
         })
     }
 
+    /// begin_inline returns the transaction begun by its first statement, which saves the round trip of the
+    /// BeginTransaction. The transaction is begun by the BeginTransaction before the commit if no statement
+    /// is executed. The statements take `&mut self`, so that only the first one begins the transaction.
+    pub(crate) fn begin_inline(
+        session: ManagedSession,
+        tx_options: TransactionOptions,
+        options: CallOptions,
+        transaction_tag: Option<String>,
+    ) -> ReadWriteTransaction {
+        ReadWriteTransaction {
+            base_tx: Transaction {
+                session: Some(session),
+                sequence_number: AtomicI64::new(0),
+                transaction_selector: TransactionSelector {
+                    selector: Some(transaction_selector::Selector::Begin(tx_options)),
+                },
+                transaction_tag,
+                default_priority: None,
+                default_directed_read_options: None,
+                read_write: true,
+                pending_begin: Some(options),
+            },
+            wb: vec![],
+            default_max_commit_delay: None,
+            reject_delete_all_keys: false,
+        }
+    }
+
+    /// set_default_max_commit_delay sets the max_commit_delay of the commit whose options have no max_commit_delay.
+    pub fn set_default_max_commit_delay(&mut self, max_commit_delay: Option<Duration>) {
+        self.default_max_commit_delay = max_commit_delay;
+    }
+
+    /// set_reject_delete_all_keys sets whether to reject the commit that deletes all the keys of a table
+    /// unless CommitOptions::allow_delete_all_keys is true.
+    pub fn set_reject_delete_all_keys(&mut self, reject: bool) {
+        self.reject_delete_all_keys = reject;
+    }
+
     pub fn buffer_write(&mut self, ms: Vec<Mutation>) {
         self.wb.extend_from_slice(&ms)
     }
 
+    /// update executes a DML statement against the database. It returns the number of the rows affected,
+    /// which is the lower bound of it in the partitioned DML.
+    /// The statement which is not a DML, such as SELECT, returns the InvalidArgument error.
     pub async fn update(&mut self, stmt: Statement) -> Result<i64, Status> {
         self.update_with_option(stmt, QueryOptions::default()).await
     }
 
+    /// update_with_option executes a DML statement against the database. It returns the number of the rows affected.
     pub async fn update_with_option(&mut self, stmt: Statement, options: QueryOptions) -> Result<i64, Status> {
+        let param_types = stmt.request_param_types();
+        let directed_read_options = self.directed_read_options(options.directed_read_options)?;
         let request = ExecuteSqlRequest {
             session: self.get_session_name(),
             transaction: Some(self.transaction_selector.clone()),
             sql: stmt.sql.to_string(),
-            params: Some(prost_types::Struct { fields: stmt.params }),
-            param_types: stmt.param_types,
+            params: Some(prost_types::Struct {
+                fields: into_request_params(stmt.params),
+            }),
+            param_types,
             resume_token: vec![],
             query_mode: options.mode.into(),
             partition_token: vec![],
             seqno: self.sequence_number.fetch_add(1, Ordering::Relaxed),
             query_options: options.optimizer_options,
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: self.request_options(
+                options.call_options.priority,
+                stmt.request_tag.as_deref().or(options.request_tag.as_deref()),
+            ),
+            directed_read_options,
+            data_boost_enabled: false,
         };
 
+        let result_set = self.execute_update(request, options.call_options).await?;
+        extract_row_count(result_set.stats)
+    }
+
+    /// execute_update executes the DML statement, which begins the transaction if it is pending the begin.
+    /// The transaction is begun by the BeginTransaction and the statement is executed again if no transaction
+    /// is returned, such as when the statement fails.
+    async fn execute_update(
+        &mut self,
+        mut request: ExecuteSqlRequest,
+        options: CallOptions,
+    ) -> Result<ResultSet, Status> {
+        if self.pending_begin.is_none() {
+            return self.send_execute_sql(request, options).await;
+        }
+        match self.send_execute_sql(request.clone(), options.clone()).await {
+            Ok(result_set) => match begun_transaction_id(result_set.metadata.as_ref()) {
+                Some(id) => {
+                    self.set_transaction_id(id);
+                    return Ok(result_set);
+                }
+                None => tracing::debug!("{}, begin the transaction explicitly", NO_TRANSACTION_BEGUN),
+            },
+            Err(e) => tracing::debug!("inline begin failed, begin the transaction explicitly: {}", e),
+        }
+        self.begin_explicitly().await?;
+        request.transaction = Some(self.transaction_selector.clone());
+        self.send_execute_sql(request, options).await
+    }
+
+    async fn send_execute_sql(
+        &mut self,
+        request: ExecuteSqlRequest,
+        options: CallOptions,
+    ) -> Result<ResultSet, Status> {
         let session = self.as_mut_session();
         let result = session
             .spanner_client
-            .execute_sql(request, options.call_options.cancel, options.call_options.retry)
+            .execute_sql(request, options.cancel, options.retry)
             .await;
-        let response = session.invalidate_if_needed(result).await?;
-        Ok(extract_row_count(response.into_inner().stats))
+        Ok(session.invalidate_if_needed(result).await?.into_inner())
+    }
+
+    /// update_returning executes a DML statement with THEN RETURN, such as
+    /// `INSERT ... THEN RETURN id`. The returned rows are read from the RowIterator,
+    /// and the number of the rows affected is in RowIterator::stats after all the rows are read.
+    pub async fn update_returning(&mut self, stmt: Statement) -> Result<RowIterator<'_>, Status> {
+        self.update_returning_with_option(stmt, QueryOptions::default()).await
     }
 
-    pub async fn batch_update(&mut self, stmt: Vec<Statement>) -> Result<Vec<i64>, Status> {
+    /// update_returning_with_option is the same as update_returning with the options.
+    pub async fn update_returning_with_option(
+        &mut self,
+        stmt: Statement,
+        options: QueryOptions,
+    ) -> Result<RowIterator<'_>, Status> {
+        let seqno = self.sequence_number.fetch_add(1, Ordering::Relaxed);
+        self.base_tx.execute_sql(stmt, options, seqno).await
+    }
+
+    /// update_returning_as executes a DML statement with THEN RETURN and converts all the returned rows into T.
+    /// It returns the rows and the number of the rows affected.
+    pub async fn update_returning_as<T>(&mut self, stmt: Statement) -> Result<(Vec<T>, i64), QueryError>
+    where
+        T: TryFrom<Row, Error = RowError>,
+    {
+        let mut iter = self.update_returning(stmt).await?;
+        let rows = collect_rows(&mut iter, None).await?;
+        let row_count = match iter.stats().and_then(|stats| stats.row_count) {
+            Some(RowCount::Exact(count)) | Some(RowCount::LowerBound(count)) => count,
+            None => return Err(Status::new(Code::InvalidArgument, NOT_DML).into()),
+        };
+        Ok((rows, row_count))
+    }
+
+    /// batch_update groups one or more DML statements and sends them to Spanner in a
+    /// single RPC. This is an efficient way to execute multiple DML statements.
+    ///
+    /// A slice of counts is returned, where each count represents the number of
+    /// affected rows for the given query at the same index. If an error occurs,
+    /// BatchUpdateError::StatementFailed is returned with the index of the failed statement
+    /// and the counts of the statements executed before it.
+    ///
+    /// The statements with THEN RETURN are rejected, because the returned rows can not be read.
+    /// Use update_returning for them.
+    pub async fn batch_update(&mut self, stmt: Vec<Statement>) -> Result<Vec<i64>, BatchUpdateError> {
         self.batch_update_with_option(stmt, QueryOptions::default()).await
     }
 
+    /// batch_update_with_option is the same as batch_update with the options.
     pub async fn batch_update_with_option(
         &mut self,
         stmt: Vec<Statement>,
         options: QueryOptions,
-    ) -> Result<Vec<i64>, Status> {
+    ) -> Result<Vec<i64>, BatchUpdateError> {
+        if stmt.is_empty() {
+            return Err(Status::new(Code::InvalidArgument, "the statements must not be empty").into());
+        }
+        if let Some(index) = stmt.iter().position(|x| has_then_return(&x.sql)) {
+            let message = format!("the statement {index} has THEN RETURN, use update_returning instead");
+            return Err(Status::new(Code::InvalidArgument, message).into());
+        }
         let request = ExecuteBatchDmlRequest {
             session: self.get_session_name(),
             transaction: Some(self.transaction_selector.clone()),
             seqno: self.sequence_number.fetch_add(1, Ordering::Relaxed),
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: self.request_options(options.call_options.priority, options.request_tag.as_deref()),
             statements: stmt
                 .into_iter()
                 .map(|x| execute_batch_dml_request::Statement {
+                    param_types: x.request_param_types(),
                     sql: x.sql,
-                    params: Some(Struct { fields: x.params }),
-                    param_types: x.param_types,
+                    params: Some(Struct {
+                        fields: into_request_params(x.params),
+                    }),
                 })
                 .collect(),
         };
 
+        let response = self.execute_batch_update(request, options.call_options).await?;
+        extract_batch_row_counts(response)
+    }
+
+    /// execute_batch_update executes the DML statements, which begins the transaction if it is pending the begin.
+    /// The transaction is begun by the first statement, so that no transaction is returned if it fails.
+    async fn execute_batch_update(
+        &mut self,
+        mut request: ExecuteBatchDmlRequest,
+        options: CallOptions,
+    ) -> Result<ExecuteBatchDmlResponse, Status> {
+        if self.pending_begin.is_none() {
+            return self.send_execute_batch_dml(request, options).await;
+        }
+        match self.send_execute_batch_dml(request.clone(), options.clone()).await {
+            Ok(response) => {
+                let metadata = response.result_sets.first().and_then(|rs| rs.metadata.as_ref());
+                match begun_transaction_id(metadata) {
+                    Some(id) => {
+                        self.set_transaction_id(id);
+                        return Ok(response);
+                    }
+                    None => tracing::debug!("{}, begin the transaction explicitly", NO_TRANSACTION_BEGUN),
+                }
+            }
+            Err(e) => tracing::debug!("inline begin failed, begin the transaction explicitly: {}", e),
+        }
+        self.begin_explicitly().await?;
+        request.transaction = Some(self.transaction_selector.clone());
+        self.send_execute_batch_dml(request, options).await
+    }
+
+    async fn send_execute_batch_dml(
+        &mut self,
+        request: ExecuteBatchDmlRequest,
+        options: CallOptions,
+    ) -> Result<ExecuteBatchDmlResponse, Status> {
         let session = self.as_mut_session();
         let result = session
             .spanner_client
-            .execute_batch_dml(request, options.call_options.cancel, options.call_options.retry)
+            .execute_batch_dml(request, options.cancel, options.retry)
             .await;
-        let response = session.invalidate_if_needed(result).await?;
-        Ok(response
-            .into_inner()
-            .result_sets
-            .into_iter()
-            .map(|x| extract_row_count(x.stats))
-            .collect())
+        Ok(session.invalidate_if_needed(result).await?.into_inner())
     }
 
     pub async fn end<S, E>(
@@ -232,6 +475,20 @@ impl ReadWriteTransaction {
         result: Result<S, E>,
         options: Option<CommitOptions>,
     ) -> Result<(Option<Timestamp>, S), E>
+    where
+        E: TryAs<Status> + From<Status>,
+    {
+        let (commit_result, success) = self.end_with_commit_result(result, options).await?;
+        Ok((commit_result.timestamp, success))
+    }
+
+    /// end_with_commit_result is the same as end but returns the CommitResult,
+    /// which contains the commit stats if CommitOptions::return_commit_stats is true.
+    pub async fn end_with_commit_result<S, E>(
+        &mut self,
+        result: Result<S, E>,
+        options: Option<CommitOptions>,
+    ) -> Result<(CommitResult, S), E>
     where
         E: TryAs<Status> + From<Status>,
     {
@@ -239,7 +496,7 @@ impl ReadWriteTransaction {
         match result {
             Ok(success) => {
                 let cr = self.commit(opt).await?;
-                Ok((cr.commit_timestamp.map(|e| e.into()), success))
+                Ok((cr.into(), success))
             }
             Err(err) => {
                 if let Some(status) = err.try_as() {
@@ -258,7 +515,7 @@ impl ReadWriteTransaction {
         &mut self,
         result: Result<T, E>,
         options: Option<CommitOptions>,
-    ) -> Result<(Option<Timestamp>, T), (E, Option<ManagedSession>)>
+    ) -> Result<(CommitResult, T), (E, Option<ManagedSession>)>
     where
         E: TryAs<Status> + From<Status>,
     {
@@ -266,7 +523,7 @@ impl ReadWriteTransaction {
 
         return match result {
             Ok(s) => match self.commit(opt).await {
-                Ok(c) => Ok((c.commit_timestamp.map(|ts| ts.into()), s)),
+                Ok(c) => Ok((c.into(), s)),
                 // Retry the transaction using the same session on ABORT error.
                 // Cloud Spanner will create the new transaction with the previous
                 // one's wound-wait priority.
@@ -298,11 +555,19 @@ impl ReadWriteTransaction {
         };
     }
 
-    pub(crate) async fn commit(&mut self, options: CommitOptions) -> Result<CommitResponse, Status> {
-        let tx_id = self.tx_id.clone();
+    pub(crate) async fn commit(&mut self, mut options: CommitOptions) -> Result<CommitResponse, Status> {
+        options.call_options.priority = options.call_options.priority.or(self.default_priority);
+        options.max_commit_delay = options.max_commit_delay.or(self.default_max_commit_delay);
+        if self.reject_delete_all_keys && !options.allow_delete_all_keys {
+            check_delete_all_keys(&self.wb)?;
+        }
+        // the transaction with no statement is not begun yet.
+        self.begin_explicitly().await?;
+        let tx_id = self.transaction_id().unwrap_or_default().to_vec();
         let mutations = self.wb.to_vec();
+        let transaction_tag = self.transaction_tag.clone();
         let session = self.as_mut_session();
-        commit(session, mutations, TransactionId(tx_id), options).await
+        commit(session, mutations, TransactionId(tx_id), options, transaction_tag.as_deref()).await
     }
 
     pub(crate) async fn rollback(
@@ -310,8 +575,13 @@ impl ReadWriteTransaction {
         cancel: Option<CancellationToken>,
         retry: Option<RetrySetting>,
     ) -> Result<(), Status> {
+        // the transaction not begun yet has nothing to roll back.
+        let transaction_id = match self.transaction_id() {
+            Some(id) => id.to_vec(),
+            None => return Ok(()),
+        };
         let request = RollbackRequest {
-            transaction_id: self.tx_id.clone(),
+            transaction_id,
             session: self.get_session_name(),
         };
         let session = self.as_mut_session();
@@ -326,14 +596,9 @@ pub(crate) async fn commit(
     ms: Vec<Mutation>,
     tx: commit_request::Transaction,
     commit_options: CommitOptions,
+    transaction_tag: Option<&str>,
 ) -> Result<CommitResponse, Status> {
-    let request = CommitRequest {
-        session: session.session.name.to_string(),
-        mutations: ms,
-        transaction: Some(tx),
-        request_options: Transaction::create_request_options(commit_options.call_options.priority),
-        return_commit_stats: commit_options.return_commit_stats,
-    };
+    let request = create_commit_request(session.session.name.to_string(), ms, tx, &commit_options, transaction_tag)?;
     let result = session
         .spanner_client
         .commit(request, commit_options.call_options.cancel, commit_options.call_options.retry)
@@ -341,19 +606,396 @@ pub(crate) async fn commit(
     let response = session.invalidate_if_needed(result).await;
     match response {
         Ok(r) => Ok(r.into_inner()),
-        Err(s) => Err(s),
+        Err(s) => Err(commit_timestamp_error(s)),
+    }
+}
+
+/// commit_timestamp_error returns the status of the commit which writes CommitTimestamp
+/// to the column without the allow_commit_timestamp option, with the hint to set the option.
+/// The other statuses are returned as they are.
+fn commit_timestamp_error(status: Status) -> Status {
+    if status.code() != Code::FailedPrecondition || !status.message().contains("allow_commit_timestamp") {
+        return status;
+    }
+    let message = format!(
+        "CommitTimestamp requires the column option allow_commit_timestamp=true: {}",
+        status.message()
+    );
+    Status::with_details_and_metadata(
+        status.code(),
+        message,
+        status.details().to_vec().into(),
+        status.metadata().clone(),
+    )
+}
+
+fn create_commit_request(
+    session: String,
+    ms: Vec<Mutation>,
+    tx: commit_request::Transaction,
+    commit_options: &CommitOptions,
+    transaction_tag: Option<&str>,
+) -> Result<CommitRequest, Status> {
+    let max_commit_delay = match commit_options.max_commit_delay {
+        Some(delay) if delay > MAX_COMMIT_DELAY => {
+            return Err(Status::new(
+                Code::InvalidArgument,
+                format!("max_commit_delay must be at most {MAX_COMMIT_DELAY:?}: {delay:?}"),
+            ))
+        }
+        Some(delay) => Some(prost_types::Duration {
+            seconds: delay.as_secs() as i64,
+            nanos: delay.subsec_nanos() as i32,
+        }),
+        None => None,
+    };
+    Ok(CommitRequest {
+        session,
+        mutations: ms,
+        transaction: Some(tx),
+        request_options: Transaction::create_request_options(
+            commit_options.call_options.priority,
+            None,
+            transaction_tag,
+        ),
+        return_commit_stats: commit_options.return_commit_stats,
+        max_commit_delay,
+    })
+}
+
+/// read_write_options returns the options of the read-write transaction.
+/// The mutations of the transaction excluded from the change streams are not recorded in the change streams
+/// with the allow_txn_exclusion option. None of read_lock_mode leaves it to the server default.
+pub(crate) fn read_write_options(
+    exclude_txn_from_change_streams: bool,
+    read_lock_mode: Option<ReadLockMode>,
+) -> TransactionOptions {
+    TransactionOptions {
+        exclude_txn_from_change_streams,
+        mode: Some(transaction_options::Mode::ReadWrite(transaction_options::ReadWrite {
+            read_lock_mode: read_lock_mode.unwrap_or(ReadLockMode::Unspecified).into(),
+        })),
     }
 }
 
-fn extract_row_count(rs: Option<ResultSetStats>) -> i64 {
-    match rs {
-        Some(o) => match o.row_count {
-            Some(o) => match o {
-                result_set_stats::RowCount::RowCountExact(v) => v,
-                result_set_stats::RowCount::RowCountLowerBound(v) => v,
+/// partitioned_dml_options returns the options of the partitioned DML transaction.
+pub(crate) fn partitioned_dml_options(exclude_txn_from_change_streams: bool) -> TransactionOptions {
+    TransactionOptions {
+        exclude_txn_from_change_streams,
+        mode: Some(transaction_options::Mode::PartitionedDml(
+            transaction_options::PartitionedDml {},
+        )),
+    }
+}
+
+/// The error message of the statement whose stats contain no row count.
+const NOT_DML: &str = "the row count is not returned, the statement is not a DML";
+
+/// extract_batch_row_counts returns the row counts of the statements in the batch.
+/// The response stops at the first failed statement, whose status is in the response status,
+/// and the row counts of the statements before it are still valid.
+fn extract_batch_row_counts(response: ExecuteBatchDmlResponse) -> Result<Vec<i64>, BatchUpdateError> {
+    let row_counts = response
+        .result_sets
+        .into_iter()
+        .map(|x| extract_row_count(x.stats))
+        .collect::<Result<Vec<i64>, Status>>()?;
+    match response.status {
+        Some(status) if status.code != Code::Ok as i32 => {
+            let status = Status::new(Code::from(status.code), status.message);
+            Err(BatchUpdateError::StatementFailed(row_counts.len(), status, row_counts))
+        }
+        _ => Ok(row_counts),
+    }
+}
+
+/// extract_row_count returns the exact row count, or the lower bound of it for the partitioned DML.
+/// The statement which is not a DML returns InvalidArgument, because its stats contain no row count.
+fn extract_row_count(rs: Option<ResultSetStats>) -> Result<i64, Status> {
+    match rs.and_then(|o| o.row_count) {
+        Some(result_set_stats::RowCount::RowCountExact(v)) => Ok(v),
+        Some(result_set_stats::RowCount::RowCountLowerBound(v)) => Ok(v),
+        None => Err(Status::new(Code::InvalidArgument, NOT_DML)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use google_cloud_gax::grpc::{Code, Status};
+    use google_cloud_googleapis::spanner::v1::result_set_stats::RowCount;
+    use google_cloud_googleapis::spanner::v1::{commit_request, commit_response, transaction_options};
+    use google_cloud_googleapis::spanner::v1::{CommitResponse, ExecuteBatchDmlResponse, ResultSet, ResultSetStats};
+
+    use crate::apiv1::fake_spanner::{FakeSpanner, BEGUN_TRANSACTION_ID};
+    use crate::reader::AsyncIterator;
+    use crate::statement::{single_type, Statement};
+    use crate::stats::RowCount as StatsRowCount;
+    use crate::transaction_rw::{
+        commit_timestamp_error, create_commit_request, extract_batch_row_counts, extract_row_count,
+        partitioned_dml_options, read_write_options, BatchUpdateError, CommitOptions, CommitResult, CommitStats,
+        ReadLockMode, MAX_COMMIT_DELAY,
+    };
+    use google_cloud_googleapis::spanner::v1::struct_type::Field;
+    use google_cloud_googleapis::spanner::v1::transaction_selector::Selector;
+    use google_cloud_googleapis::spanner::v1::{
+        PartialResultSet, ResultSetMetadata, StructType, Transaction, TypeCode,
+    };
+    use prost_types::value::Kind;
+
+    fn stats(row_count: Option<RowCount>) -> Option<ResultSetStats> {
+        Some(ResultSetStats {
+            query_plan: None,
+            query_stats: None,
+            row_count,
+        })
+    }
+
+    #[test]
+    fn test_extract_row_count() {
+        assert_eq!(extract_row_count(stats(Some(RowCount::RowCountExact(3)))).unwrap(), 3);
+        assert_eq!(extract_row_count(stats(Some(RowCount::RowCountExact(0)))).unwrap(), 0);
+        assert_eq!(extract_row_count(stats(Some(RowCount::RowCountLowerBound(100)))).unwrap(), 100);
+        assert_eq!(extract_row_count(stats(None)).unwrap_err().code(), Code::InvalidArgument);
+        assert_eq!(extract_row_count(None).unwrap_err().code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_commit_result() {
+        let commit_timestamp = prost_types::Timestamp {
+            seconds: 1_662_000_000,
+            nanos: 123,
+        };
+        let result: CommitResult = CommitResponse {
+            commit_timestamp: Some(commit_timestamp.clone()),
+            commit_stats: Some(commit_response::CommitStats { mutation_count: 6 }),
+        }
+        .into();
+        assert_eq!(result.timestamp.unwrap().seconds, 1_662_000_000);
+        assert_eq!(result.commit_stats, Some(CommitStats { mutation_count: 6 }));
+
+        let result: CommitResult = CommitResponse {
+            commit_timestamp: Some(commit_timestamp),
+            commit_stats: None,
+        }
+        .into();
+        assert!(result.timestamp.is_some());
+        assert_eq!(result.commit_stats, None);
+    }
+
+    fn batch_response(row_counts: &[i64], status: Option<(Code, &str)>) -> ExecuteBatchDmlResponse {
+        ExecuteBatchDmlResponse {
+            result_sets: row_counts
+                .iter()
+                .map(|count| ResultSet {
+                    metadata: None,
+                    rows: vec![],
+                    stats: stats(Some(RowCount::RowCountExact(*count))),
+                })
+                .collect(),
+            status: status.map(|(code, message)| google_cloud_googleapis::rpc::Status {
+                code: code as i32,
+                message: message.to_string(),
+                details: vec![],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_extract_batch_row_counts() {
+        let counts = extract_batch_row_counts(batch_response(&[1, 0, 3], Some((Code::Ok, "")))).unwrap();
+        assert_eq!(counts, vec![1, 0, 3]);
+        let counts = extract_batch_row_counts(batch_response(&[2], None)).unwrap();
+        assert_eq!(counts, vec![2]);
+
+        let response = batch_response(&[1, 2], Some((Code::InvalidArgument, "Syntax error")));
+        match extract_batch_row_counts(response).unwrap_err() {
+            BatchUpdateError::StatementFailed(index, status, counts) => {
+                assert_eq!(index, 2);
+                assert_eq!(status.code(), Code::InvalidArgument);
+                assert_eq!(status.message(), "Syntax error");
+                assert_eq!(counts, vec![1, 2]);
+            }
+            err => panic!("unexpected error {err:?}"),
+        }
+
+        let response = batch_response(&[], Some((Code::InvalidArgument, "Syntax error")));
+        assert!(matches!(
+            extract_batch_row_counts(response).unwrap_err(),
+            BatchUpdateError::StatementFailed(0, _, counts) if counts.is_empty()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_batch_update_statement_failed() {
+        let fake = FakeSpanner::default();
+        let client = fake.client().await;
+        fake.set_batch_dml(vec![Ok(1), Ok(2), Err(Status::invalid_argument("Syntax error")), Ok(3)]);
+
+        let mut tx = client.begin_read_write_transaction().await.unwrap();
+        let stmts = (0..4)
+            .map(|i| Statement::new(format!("UPDATE Guild SET OwnerUserId = 'u{i}' WHERE GuildId = 'g{i}'")))
+            .collect();
+        let result = tx.batch_update(stmts).await;
+        match result {
+            Err(BatchUpdateError::StatementFailed(index, ref status, ref counts)) => {
+                assert_eq!(index, 2);
+                assert_eq!(status.code(), Code::InvalidArgument);
+                assert_eq!(status.message(), "Syntax error");
+                assert_eq!(counts, &vec![1, 2]);
+            }
+            ref err => panic!("unexpected result {err:?}"),
+        }
+        // the transaction is begun by the first statement and rolled back.
+        let _ = tx.end(result, None).await;
+        assert_eq!(fake.requests("ExecuteBatchDml"), 1);
+        assert_eq!(fake.requests("BeginTransaction"), 0);
+        let rollbacks = fake.rollbacks();
+        assert_eq!(rollbacks.len(), 1);
+        assert_eq!(rollbacks[0].transaction_id, BEGUN_TRANSACTION_ID);
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_update_returning() {
+        let fake = FakeSpanner::default();
+        let client = fake.client().await;
+        let value = |v: &str| prost_types::Value {
+            kind: Some(Kind::StringValue(v.to_string())),
+        };
+        fake.set_partial_result_sets(vec![
+            PartialResultSet {
+                metadata: Some(ResultSetMetadata {
+                    row_type: Some(StructType {
+                        fields: vec![Field {
+                            name: "GuildId".to_string(),
+                            r#type: Some(single_type(TypeCode::String)),
+                        }],
+                    }),
+                    transaction: Some(Transaction {
+                        id: BEGUN_TRANSACTION_ID.to_vec(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                values: vec![value("g1")],
+                ..Default::default()
+            },
+            PartialResultSet {
+                values: vec![value("g2")],
+                stats: stats(Some(RowCount::RowCountExact(2))),
+                ..Default::default()
             },
-            None => 0,
-        },
-        None => 0,
+        ]);
+
+        let mut tx = client.begin_read_write_transaction().await.unwrap();
+        let stmt = Statement::new("UPDATE Guild SET OwnerUserId = 'u1' WHERE TRUE THEN RETURN GuildId");
+        let mut iter = tx.update_returning(stmt).await.unwrap();
+        let mut guild_ids = vec![];
+        while let Some(row) = iter.next().await.unwrap() {
+            guild_ids.push(row.column_by_name::<String>("GuildId").unwrap());
+        }
+        assert_eq!(guild_ids, vec!["g1", "g2"]);
+        assert_eq!(iter.stats().and_then(|stats| stats.row_count), Some(StatsRowCount::Exact(2)));
+        drop(iter);
+
+        // the transaction begun by the statement is committed.
+        tx.end(Ok::<(), Status>(()), None).await.unwrap();
+        assert_eq!(fake.requests("BeginTransaction"), 0);
+        assert!(matches!(
+            fake.selectors()[0].as_ref().and_then(|tx| tx.selector.as_ref()),
+            Some(Selector::Begin(_))
+        ));
+        let commits = fake.commits();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(
+            commits[0].transaction,
+            Some(commit_request::Transaction::TransactionId(BEGUN_TRANSACTION_ID.to_vec()))
+        );
+        client.close().await;
+    }
+
+    #[test]
+    fn test_transaction_options() {
+        let options = read_write_options(true, None);
+        assert!(options.exclude_txn_from_change_streams);
+        assert!(matches!(options.mode, Some(transaction_options::Mode::ReadWrite(_))));
+        assert!(!read_write_options(false, None).exclude_txn_from_change_streams);
+
+        let read_lock_mode = |mode| match read_write_options(false, mode).mode {
+            Some(transaction_options::Mode::ReadWrite(rw)) => rw.read_lock_mode,
+            _ => panic!("must be the read-write transaction"),
+        };
+        assert_eq!(read_lock_mode(None), ReadLockMode::Unspecified as i32);
+        assert_eq!(
+            read_lock_mode(Some(ReadLockMode::Pessimistic)),
+            ReadLockMode::Pessimistic as i32
+        );
+        assert_eq!(read_lock_mode(Some(ReadLockMode::Optimistic)), ReadLockMode::Optimistic as i32);
+
+        let options = partitioned_dml_options(true);
+        assert!(options.exclude_txn_from_change_streams);
+        assert!(matches!(options.mode, Some(transaction_options::Mode::PartitionedDml(_))));
+        assert!(!partitioned_dml_options(false).exclude_txn_from_change_streams);
+    }
+
+    #[test]
+    fn test_commit_timestamp_error() {
+        let message = "Cannot write commit timestamp because the allow_commit_timestamp column option is not set to true for column User.UpdatedAt";
+        let status = commit_timestamp_error(Status::new(Code::FailedPrecondition, message));
+        assert_eq!(Code::FailedPrecondition, status.code());
+        assert_eq!(
+            format!("CommitTimestamp requires the column option allow_commit_timestamp=true: {message}"),
+            status.message()
+        );
+
+        let status = commit_timestamp_error(Status::new(Code::FailedPrecondition, "other"));
+        assert_eq!("other", status.message());
+        let status = commit_timestamp_error(Status::new(Code::InvalidArgument, message));
+        assert_eq!(message, status.message());
+    }
+
+    #[test]
+    fn test_commit_request_max_commit_delay() {
+        let request = |max_commit_delay| {
+            let options = CommitOptions {
+                max_commit_delay,
+                ..Default::default()
+            };
+            let tx = commit_request::Transaction::TransactionId(vec![1]);
+            create_commit_request("session".to_string(), vec![], tx, &options, None)
+        };
+        assert_eq!(request(None).unwrap().max_commit_delay, None);
+        let delay = request(Some(Duration::from_millis(100)))
+            .unwrap()
+            .max_commit_delay
+            .unwrap();
+        assert_eq!((delay.seconds, delay.nanos), (0, 100_000_000));
+        let delay = request(Some(Duration::ZERO)).unwrap().max_commit_delay.unwrap();
+        assert_eq!((delay.seconds, delay.nanos), (0, 0));
+        let delay = request(Some(MAX_COMMIT_DELAY)).unwrap().max_commit_delay.unwrap();
+        assert_eq!((delay.seconds, delay.nanos), (0, 500_000_000));
+        let err = request(Some(Duration::from_millis(501))).unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_create_commit_request_single_use() {
+        let options = CommitOptions {
+            return_commit_stats: true,
+            ..Default::default()
+        };
+        let tx = commit_request::Transaction::SingleUseTransaction(read_write_options(false, None));
+        let request = create_commit_request("session".to_string(), vec![], tx, &options, Some("blind-write")).unwrap();
+        assert_eq!(request.session, "session");
+        assert!(request.return_commit_stats);
+        assert_eq!(request.request_options.unwrap().transaction_tag, "blind-write");
+        match request.transaction.unwrap() {
+            commit_request::Transaction::SingleUseTransaction(tx) => {
+                assert!(matches!(tx.mode, Some(transaction_options::Mode::ReadWrite(_))))
+            }
+            _ => panic!("must be the single use transaction"),
+        }
     }
 }
@@ -1,8 +1,14 @@
 use std::ops::Deref;
 use std::time::Duration;
 
+use google_cloud_googleapis::spanner::v1::struct_type::Field;
 use google_cloud_googleapis::spanner::v1::transaction_options::read_only::TimestampBound as InternalTimestampBound;
 use google_cloud_googleapis::spanner::v1::transaction_options::ReadOnly;
+use google_cloud_googleapis::spanner::v1::{StructType, Type, TypeCode};
+use prost_types::value::Kind;
+use prost_types::ListValue;
+
+use crate::statement::{single_type, ToKind};
 
 /// https://cloud.google.com/spanner/docs/storing-numeric-data#precision_of_numeric_types
 /// -99999999999999999999999999999.999999999～99999999999999999999999999999.999999999
@@ -27,7 +33,375 @@ impl SpannerNumeric {
     }
 }
 
-#[derive(Clone, PartialEq, Eq)]
+impl From<u64> for SpannerNumeric {
+    fn from(value: u64) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+/// PgNumeric is bound as the PostgreSQL dialect's NUMERIC type (PG.NUMERIC).
+/// Unlike SpannerNumeric, it can hold `NaN`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct PgNumeric(String);
+
+impl Default for PgNumeric {
+    fn default() -> Self {
+        Self::new("0")
+    }
+}
+
+impl PgNumeric {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_nan(&self) -> bool {
+        self.0 == "NaN"
+    }
+}
+
+/// PgJsonb is bound as the PostgreSQL dialect's JSONB type (PG.JSONB).
+#[cfg(feature = "serde_json")]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PgJsonb(pub serde_json::Value);
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::Value> for PgJsonb {
+    fn from(value: serde_json::Value) -> Self {
+        Self(value)
+    }
+}
+
+/// Json binds any serializable value as Cloud Spanner's JSON type, and reads a JSON column into any deserializable value.
+///
+/// ```
+/// use google_cloud_spanner::statement::Statement;
+/// use google_cloud_spanner::value::Json;
+///
+/// #[derive(serde::Serialize)]
+/// struct Payload {
+///     tags: Vec<String>,
+/// }
+///
+/// let payload = Payload { tags: vec!["a".to_string()] };
+/// let mut stmt = Statement::new("INSERT INTO Event (EventId, Payload) VALUES (1, @Payload)");
+/// stmt.try_add_param("Payload", &Json(&payload)).unwrap();
+/// ```
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Json<T>(pub T);
+
+/// NumericError is returned when a value does not fit in Cloud Spanner's NUMERIC type.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum NumericError {
+    #[error("NUMERIC supports at most 29 integer digits: actual={0}")]
+    IntegerDigits(u64),
+    #[error("NUMERIC supports at most 9 fractional digits: actual={0}")]
+    FractionalDigits(u64),
+}
+
+/// Checks the number of digits of a value with `digits` significant digits and `scale` against the NUMERIC range.
+#[cfg(any(feature = "bigdecimal", feature = "rust_decimal"))]
+fn check_numeric_digits(digits: i64, scale: i64) -> Result<(), NumericError> {
+    let integer = (digits - scale).max(0) as u64;
+    if integer > 29 {
+        return Err(NumericError::IntegerDigits(integer));
+    }
+    let fractional = scale.max(0) as u64;
+    if fractional > 9 {
+        return Err(NumericError::FractionalDigits(fractional));
+    }
+    Ok(())
+}
+
+/// Checks the value against the NUMERIC range and keeps its canonical string form.
+#[cfg(feature = "bigdecimal")]
+impl TryFrom<&bigdecimal::BigDecimal> for SpannerNumeric {
+    type Error = NumericError;
+
+    fn try_from(value: &bigdecimal::BigDecimal) -> Result<Self, Self::Error> {
+        let normalized = value.normalized();
+        let (_, scale) = normalized.as_bigint_and_exponent();
+        check_numeric_digits(normalized.digits() as i64, scale)?;
+        Ok(Self::new(value.to_string()))
+    }
+}
+
+/// Numeric is a rust_decimal::Decimal checked against the range of Cloud Spanner's NUMERIC type.
+#[cfg(feature = "rust_decimal")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Numeric(rust_decimal::Decimal);
+
+#[cfg(feature = "rust_decimal")]
+impl Numeric {
+    /// Returns an error if the value has more than 9 fractional digits.
+    pub fn new(value: rust_decimal::Decimal) -> Result<Self, NumericError> {
+        let normalized = value.normalize();
+        let digits = normalized.mantissa().unsigned_abs().to_string().len() as i64;
+        check_numeric_digits(digits, normalized.scale() as i64)?;
+        Ok(Self(value))
+    }
+
+    /// Rounds the fractional digits beyond the 9th with banker's rounding, as Cloud Spanner does.
+    pub fn rounded(value: rust_decimal::Decimal) -> Self {
+        // rust_decimal can not hold more than 29 integer digits.
+        Self(value.round_dp(9))
+    }
+
+    pub fn value(&self) -> rust_decimal::Decimal {
+        self.0
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl TryFrom<rust_decimal::Decimal> for Numeric {
+    type Error = NumericError;
+
+    fn try_from(value: rust_decimal::Decimal) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl From<Numeric> for rust_decimal::Decimal {
+    fn from(value: Numeric) -> Self {
+        value.0
+    }
+}
+
+/// Float32 is bound as Cloud Spanner's FLOAT32 type.
+/// A bare f32 is widened to FLOAT64.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Float32(pub f32);
+
+impl From<f32> for Float32 {
+    fn from(value: f32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Float32> for f32 {
+    fn from(value: Float32) -> Self {
+        value.0
+    }
+}
+
+/// ProtoName provides the fully qualified name of a protocol buffer message or enum, e.g. `my.package.MyMessage`.
+pub trait ProtoName {
+    const FULL_NAME: &'static str;
+}
+
+/// Proto is bound as Cloud Spanner's PROTO type.
+/// The message is sent in the serialized form with the name of T.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Proto<T>(pub T);
+
+/// EnumValue is bound as Cloud Spanner's ENUM type.
+/// prost generates `From<T> for i32`. `TryFrom<i32>` is required to read the value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct EnumValue<T>(pub T);
+
+/// UuidBytes is bound as Cloud Spanner's BYTES type using the 16-byte big-endian
+/// representation of the UUID. A bare uuid::Uuid is bound as a hyphenated STRING.
+#[cfg(feature = "uuid")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UuidBytes(pub uuid::Uuid);
+
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for UuidBytes {
+    fn from(value: uuid::Uuid) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<UuidBytes> for uuid::Uuid {
+    fn from(value: UuidBytes) -> Self {
+        value.0
+    }
+}
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_MINUTE: i64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: i64 = 60 * NANOS_PER_MINUTE;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum IntervalError {
+    #[error("invalid interval: {0}")]
+    Invalid(String),
+    #[error("interval out of range: {0}")]
+    OutOfRange(String),
+}
+
+/// Interval is bound as Cloud Spanner's INTERVAL type.
+/// Each part may be negative independently of the others, e.g. `P1M-1D` is one month minus one day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub nanos: i64,
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, nanos: i64) -> Self {
+        Self { months, days, nanos }
+    }
+
+    /// Returns the days and nanoseconds as a chrono::Duration. The months are ignored.
+    #[cfg(feature = "chrono")]
+    pub fn sub_month_duration(&self) -> chrono::Duration {
+        chrono::Duration::days(self.days as i64) + chrono::Duration::nanoseconds(self.nanos)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::Duration> for Interval {
+    type Error = IntervalError;
+
+    fn try_from(value: chrono::Duration) -> Result<Self, Self::Error> {
+        let nanos = value
+            .num_nanoseconds()
+            .ok_or_else(|| IntervalError::OutOfRange(value.to_string()))?;
+        Ok(Self::new(0, 0, nanos))
+    }
+}
+
+/// Formats the interval in the ISO 8601 duration format used by Cloud Spanner, e.g. `P1Y2M3DT4H5M6.5S`.
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if *self == Self::default() {
+            return f.write_str("P0Y");
+        }
+        f.write_str("P")?;
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years != 0 {
+            write!(f, "{}Y", years)?;
+        }
+        if months != 0 {
+            write!(f, "{}M", months)?;
+        }
+        if self.days != 0 {
+            write!(f, "{}D", self.days)?;
+        }
+        if self.nanos == 0 {
+            return Ok(());
+        }
+        f.write_str("T")?;
+        let sign = if self.nanos < 0 { "-" } else { "" };
+        let nanos = self.nanos.unsigned_abs();
+        let hours = nanos / NANOS_PER_HOUR as u64;
+        let minutes = nanos % NANOS_PER_HOUR as u64 / NANOS_PER_MINUTE as u64;
+        let seconds = nanos % NANOS_PER_MINUTE as u64 / NANOS_PER_SECOND as u64;
+        let fraction = nanos % NANOS_PER_SECOND as u64;
+        if hours != 0 {
+            write!(f, "{}{}H", sign, hours)?;
+        }
+        if minutes != 0 {
+            write!(f, "{}{}M", sign, minutes)?;
+        }
+        if seconds != 0 || fraction != 0 {
+            write!(f, "{}{}", sign, seconds)?;
+            if fraction != 0 {
+                write!(f, ".{}", format!("{:09}", fraction).trim_end_matches('0'))?;
+            }
+            f.write_str("S")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Interval {
+    type Err = IntervalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || IntervalError::Invalid(s.to_string());
+        let out_of_range = || IntervalError::OutOfRange(s.to_string());
+        let mut rest = s.strip_prefix('P').ok_or_else(invalid)?;
+        let mut interval = Interval::default();
+        let mut in_time = false;
+        let mut found = false;
+        while !rest.is_empty() {
+            if let Some(time) = rest.strip_prefix('T') {
+                if in_time {
+                    return Err(invalid());
+                }
+                in_time = true;
+                rest = time;
+                continue;
+            }
+            let end = rest.find(|c: char| c.is_ascii_alphabetic()).ok_or_else(invalid)?;
+            let (number, unit) = (&rest[..end], &rest[end..end + 1]);
+            rest = &rest[end + 1..];
+            let (negative, number) = match number.strip_prefix('-') {
+                Some(n) => (true, n),
+                None => (false, number.strip_prefix('+').unwrap_or(number)),
+            };
+            let (integer, fraction) = match number.split_once(['.', ',']) {
+                Some((i, f)) if in_time && unit == "S" && !f.is_empty() && f.len() <= 9 => (i, f),
+                Some(_) => return Err(invalid()),
+                None => (number, ""),
+            };
+            if integer.is_empty() || !integer.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let integer: i64 = integer.parse().map_err(|_| out_of_range())?;
+            let integer = if negative { -integer } else { integer };
+            found = true;
+            match (in_time, unit) {
+                (false, "Y") => {
+                    let months = i32::try_from(integer).ok().and_then(|v| v.checked_mul(12));
+                    let months = months.and_then(|v| interval.months.checked_add(v));
+                    interval.months = months.ok_or_else(out_of_range)?;
+                }
+                (false, "M") => {
+                    let months = i32::try_from(integer).ok().and_then(|v| interval.months.checked_add(v));
+                    interval.months = months.ok_or_else(out_of_range)?;
+                }
+                (false, "W") => {
+                    let days = i32::try_from(integer).ok().and_then(|v| v.checked_mul(7));
+                    let days = days.and_then(|v| interval.days.checked_add(v));
+                    interval.days = days.ok_or_else(out_of_range)?;
+                }
+                (false, "D") => {
+                    let days = i32::try_from(integer).ok().and_then(|v| interval.days.checked_add(v));
+                    interval.days = days.ok_or_else(out_of_range)?;
+                }
+                (true, "H" | "M" | "S") => {
+                    let unit_nanos = match unit {
+                        "H" => NANOS_PER_HOUR,
+                        "M" => NANOS_PER_MINUTE,
+                        _ => NANOS_PER_SECOND,
+                    };
+                    let mut nanos = integer.checked_mul(unit_nanos).ok_or_else(out_of_range)?;
+                    if !fraction.is_empty() {
+                        let fraction: i64 = format!("{:0<9}", fraction).parse().map_err(|_| invalid())?;
+                        nanos = nanos
+                            .checked_add(if negative { -fraction } else { fraction })
+                            .ok_or_else(out_of_range)?;
+                    }
+                    interval.nanos = interval.nanos.checked_add(nanos).ok_or_else(out_of_range)?;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        if !found {
+            return Err(invalid());
+        }
+        Ok(interval)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Timestamp {
     /// Represents seconds of UTC time since Unix epoch
     /// 1970-01-01T00:00:00Z. Must be from 0001-01-01T00:00:00Z to
@@ -58,6 +432,35 @@ impl From<prost_types::Timestamp> for Timestamp {
     }
 }
 
+impl From<time::OffsetDateTime> for Timestamp {
+    fn from(t: time::OffsetDateTime) -> Self {
+        Timestamp {
+            seconds: t.unix_timestamp(),
+            nanos: t.nanosecond() as i32,
+        }
+    }
+}
+
+/// utc_timestamp returns the time in UTC if it is in the range of TIMESTAMP,
+/// from 0001-01-01T00:00:00Z to 9999-12-31T23:59:59.999999999Z.
+pub(crate) fn utc_timestamp(t: &time::OffsetDateTime) -> Option<time::OffsetDateTime> {
+    t.checked_to_offset(time::UtcOffset::UTC)
+        .filter(|utc| (1..=9999).contains(&utc.year()))
+}
+
+/// The conversion fails if the timestamp is out of the range of OffsetDateTime.
+impl TryFrom<Timestamp> for time::OffsetDateTime {
+    type Error = time::error::ComponentRange;
+
+    fn try_from(t: Timestamp) -> Result<Self, Self::Error> {
+        let nanos = t.seconds as i128 * 1_000_000_000 + t.nanos as i128;
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos)
+    }
+}
+
+/// CommitTimestamp writes the commit timestamp of the transaction as the spanner.commit_timestamp() sentinel,
+/// in the mutations and in the statement parameters. None of Option<CommitTimestamp> writes NULL.
+/// The column must have the allow_commit_timestamp=true option, otherwise the commit fails with FailedPrecondition.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 pub struct CommitTimestamp {
     pub(crate) timestamp: time::OffsetDateTime,
@@ -91,7 +494,290 @@ impl From<CommitTimestamp> for time::OffsetDateTime {
     }
 }
 
-#[derive(Clone)]
+/// TimestampValue is either the commit timestamp placeholder or an explicit timestamp.
+/// It allows the same struct to be used both when the commit timestamp is written and when an explicit value is written.
+/// A column written with the commit timestamp is read as `TimestampValue::At`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TimestampValue {
+    /// The placeholder replaced with the commit timestamp of the transaction by Cloud Spanner.
+    Commit,
+    At(time::OffsetDateTime),
+}
+
+impl From<time::OffsetDateTime> for TimestampValue {
+    fn from(value: time::OffsetDateTime) -> Self {
+        TimestampValue::At(value)
+    }
+}
+
+impl From<CommitTimestamp> for TimestampValue {
+    fn from(_: CommitTimestamp) -> Self {
+        TimestampValue::Commit
+    }
+}
+
+/// SpannerValue is a value whose type is determined at runtime.
+/// It is used to bind the parameters of different types at once with Statement::add_params,
+/// and to read the columns without knowing their types with Row::value and Row::to_map.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpannerValue {
+    /// NULL of the type.
+    Null(Type),
+    Bool(bool),
+    Int64(i64),
+    Float64(f64),
+    Float32(f32),
+    String(String),
+    Bytes(Vec<u8>),
+    Timestamp(time::OffsetDateTime),
+    Date(time::Date),
+    Numeric(SpannerNumeric),
+    /// JSON text.
+    Json(String),
+    /// Elements with the element type.
+    Array(Type, Vec<SpannerValue>),
+    /// Fields in order.
+    Struct(Vec<(String, SpannerValue)>),
+}
+
+impl SpannerValue {
+    /// null returns NULL of the type T.
+    pub fn null<T: ToKind>() -> Self {
+        SpannerValue::Null(T::get_type())
+    }
+
+    /// array returns an array whose element type is T. The element type is kept even if the array is empty.
+    pub fn array<T>(values: impl IntoIterator<Item = T>) -> Self
+    where
+        T: ToKind + Into<SpannerValue>,
+    {
+        SpannerValue::Array(T::get_type(), values.into_iter().map(Into::into).collect())
+    }
+
+    pub fn to_kind(&self) -> Kind {
+        match self {
+            SpannerValue::Null(_) => Kind::NullValue(prost_types::NullValue::NullValue.into()),
+            SpannerValue::Bool(v) => v.to_kind(),
+            SpannerValue::Int64(v) => v.to_kind(),
+            SpannerValue::Float64(v) => v.to_kind(),
+            SpannerValue::Float32(v) => Float32(*v).to_kind(),
+            SpannerValue::String(v) | SpannerValue::Json(v) => v.to_kind(),
+            SpannerValue::Bytes(v) => v.to_kind(),
+            SpannerValue::Timestamp(v) => v.to_kind(),
+            SpannerValue::Date(v) => v.to_kind(),
+            SpannerValue::Numeric(v) => v.to_kind(),
+            SpannerValue::Array(_, values) => Kind::ListValue(ListValue {
+                values: values
+                    .iter()
+                    .map(|v| prost_types::Value {
+                        kind: Some(v.to_kind()),
+                    })
+                    .collect(),
+            }),
+            SpannerValue::Struct(fields) => Kind::ListValue(ListValue {
+                values: fields
+                    .iter()
+                    .map(|(_, v)| prost_types::Value {
+                        kind: Some(v.to_kind()),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+
+    pub fn get_type(&self) -> Type {
+        match self {
+            SpannerValue::Null(tp) => tp.clone(),
+            SpannerValue::Bool(_) => bool::get_type(),
+            SpannerValue::Int64(_) => i64::get_type(),
+            SpannerValue::Float64(_) => f64::get_type(),
+            SpannerValue::Float32(_) => Float32::get_type(),
+            SpannerValue::String(_) => String::get_type(),
+            SpannerValue::Bytes(_) => Vec::<u8>::get_type(),
+            SpannerValue::Timestamp(_) => time::OffsetDateTime::get_type(),
+            SpannerValue::Date(_) => time::Date::get_type(),
+            SpannerValue::Numeric(_) => SpannerNumeric::get_type(),
+            SpannerValue::Json(_) => single_type(TypeCode::Json),
+            SpannerValue::Array(element_type, _) => Type {
+                array_element_type: Some(Box::new(element_type.clone())),
+                ..single_type(TypeCode::Array)
+            },
+            SpannerValue::Struct(fields) => Type {
+                struct_type: Some(StructType {
+                    fields: fields
+                        .iter()
+                        .map(|(name, v)| Field {
+                            name: name.to_string(),
+                            r#type: Some(v.get_type()),
+                        })
+                        .collect(),
+                }),
+                ..single_type(TypeCode::Struct)
+            },
+        }
+    }
+}
+
+impl From<bool> for SpannerValue {
+    fn from(value: bool) -> Self {
+        SpannerValue::Bool(value)
+    }
+}
+
+impl From<i64> for SpannerValue {
+    fn from(value: i64) -> Self {
+        SpannerValue::Int64(value)
+    }
+}
+
+impl From<i32> for SpannerValue {
+    fn from(value: i32) -> Self {
+        SpannerValue::Int64(value.into())
+    }
+}
+
+impl From<i16> for SpannerValue {
+    fn from(value: i16) -> Self {
+        SpannerValue::Int64(value.into())
+    }
+}
+
+impl From<i8> for SpannerValue {
+    fn from(value: i8) -> Self {
+        SpannerValue::Int64(value.into())
+    }
+}
+
+impl From<u32> for SpannerValue {
+    fn from(value: u32) -> Self {
+        SpannerValue::Int64(value.into())
+    }
+}
+
+impl From<f64> for SpannerValue {
+    fn from(value: f64) -> Self {
+        SpannerValue::Float64(value)
+    }
+}
+
+impl From<f32> for SpannerValue {
+    fn from(value: f32) -> Self {
+        SpannerValue::Float64(value.into())
+    }
+}
+
+impl From<Float32> for SpannerValue {
+    fn from(value: Float32) -> Self {
+        SpannerValue::Float32(value.0)
+    }
+}
+
+impl From<String> for SpannerValue {
+    fn from(value: String) -> Self {
+        SpannerValue::String(value)
+    }
+}
+
+impl From<&str> for SpannerValue {
+    fn from(value: &str) -> Self {
+        SpannerValue::String(value.to_string())
+    }
+}
+
+impl From<char> for SpannerValue {
+    fn from(value: char) -> Self {
+        SpannerValue::String(value.to_string())
+    }
+}
+
+impl From<Vec<u8>> for SpannerValue {
+    fn from(value: Vec<u8>) -> Self {
+        SpannerValue::Bytes(value)
+    }
+}
+
+impl From<&[u8]> for SpannerValue {
+    fn from(value: &[u8]) -> Self {
+        SpannerValue::Bytes(value.to_vec())
+    }
+}
+
+impl From<time::OffsetDateTime> for SpannerValue {
+    fn from(value: time::OffsetDateTime) -> Self {
+        SpannerValue::Timestamp(value)
+    }
+}
+
+impl From<time::Date> for SpannerValue {
+    fn from(value: time::Date) -> Self {
+        SpannerValue::Date(value)
+    }
+}
+
+impl From<SpannerNumeric> for SpannerValue {
+    fn from(value: SpannerNumeric) -> Self {
+        SpannerValue::Numeric(value)
+    }
+}
+
+impl<T> From<Option<T>> for SpannerValue
+where
+    T: ToKind + Into<SpannerValue>,
+{
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(v) => v.into(),
+            None => SpannerValue::null::<T>(),
+        }
+    }
+}
+
+/// SpannerValue is serialized as the JSON-like value for dumping the rows.
+/// INT64 is a number, BYTES is base64, TIMESTAMP and DATE are RFC3339, NUMERIC and JSON are strings,
+/// and STRUCT is a map from the field names.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SpannerValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeMap, SerializeSeq};
+        match self {
+            SpannerValue::Null(_) => serializer.serialize_none(),
+            SpannerValue::Bool(v) => serializer.serialize_bool(*v),
+            SpannerValue::Int64(v) => serializer.serialize_i64(*v),
+            SpannerValue::Float64(v) => serializer.serialize_f64(*v),
+            SpannerValue::Float32(v) => serializer.serialize_f32(*v),
+            SpannerValue::String(v) | SpannerValue::Json(v) => serializer.serialize_str(v),
+            SpannerValue::Bytes(v) => serializer.serialize_str(&base64::encode(v)),
+            SpannerValue::Timestamp(v) => serializer.serialize_str(
+                &v.to_offset(time::UtcOffset::UTC)
+                    .format(&time::format_description::well_known::Rfc3339)
+                    .map_err(S::Error::custom)?,
+            ),
+            SpannerValue::Date(v) => serializer.serialize_str(
+                &v.format(time::macros::format_description!("[year]-[month]-[day]"))
+                    .map_err(S::Error::custom)?,
+            ),
+            SpannerValue::Numeric(v) => serializer.serialize_str(v.as_str()),
+            SpannerValue::Array(_, values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for v in values {
+                    seq.serialize_element(v)?;
+                }
+                seq.end()
+            }
+            SpannerValue::Struct(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, v) in fields {
+                    map.serialize_entry(name, v)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// TimestampBound is the bound of the timestamp at which the read-only transaction reads.
+/// max_staleness and min_read_timestamp can be used only in the single-use transaction.
+#[derive(Clone, Debug, PartialEq)]
 pub struct TimestampBound {
     inner: InternalTimestampBound,
 }
@@ -122,6 +808,14 @@ impl TimestampBound {
             inner: InternalTimestampBound::ReadTimestamp(t.into()),
         }
     }
+
+    /// is_single_use_only returns true if the bound can be used only in the single-use transaction.
+    pub(crate) fn is_single_use_only(&self) -> bool {
+        matches!(
+            self.inner,
+            InternalTimestampBound::MaxStaleness(_) | InternalTimestampBound::MinReadTimestamp(_)
+        )
+    }
 }
 
 impl From<TimestampBound> for ReadOnly {
@@ -132,3 +826,80 @@ impl From<TimestampBound> for ReadOnly {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use google_cloud_googleapis::spanner::v1::transaction_options::read_only::TimestampBound as InternalTimestampBound;
+    use google_cloud_googleapis::spanner::v1::transaction_options::ReadOnly;
+    use time::macros::datetime;
+
+    use crate::value::{utc_timestamp, Timestamp, TimestampBound};
+
+    #[test]
+    fn test_timestamp_bound() {
+        let ts = Timestamp {
+            seconds: 1_600_000_000,
+            nanos: 123,
+        };
+        let cases = vec![
+            (TimestampBound::strong_read(), InternalTimestampBound::Strong(true), false),
+            (
+                TimestampBound::exact_staleness(Duration::from_millis(15_500)),
+                InternalTimestampBound::ExactStaleness(prost_types::Duration {
+                    seconds: 15,
+                    nanos: 500_000_000,
+                }),
+                false,
+            ),
+            (
+                TimestampBound::max_staleness(Duration::from_secs(10)),
+                InternalTimestampBound::MaxStaleness(prost_types::Duration { seconds: 10, nanos: 0 }),
+                true,
+            ),
+            (
+                TimestampBound::read_timestamp(ts.clone()),
+                InternalTimestampBound::ReadTimestamp(ts.clone().into()),
+                false,
+            ),
+            (
+                TimestampBound::min_read_timestamp(ts.clone()),
+                InternalTimestampBound::MinReadTimestamp(ts.into()),
+                true,
+            ),
+        ];
+        for (tb, expected, single_use_only) in cases {
+            assert_eq!(tb.is_single_use_only(), single_use_only, "{tb:?}");
+            let read_only: ReadOnly = tb.into();
+            assert!(read_only.return_read_timestamp);
+            assert_eq!(read_only.timestamp_bound, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_utc_timestamp() {
+        let t = datetime!(2022-09-01 12:34:56 +09:00);
+        assert_eq!(utc_timestamp(&t), Some(datetime!(2022-09-01 03:34:56 UTC)));
+        assert!(utc_timestamp(&datetime!(0001-01-01 00:00:00 UTC)).is_some());
+        assert!(utc_timestamp(&datetime!(9999-12-31 23:59:59.999999999 UTC)).is_some());
+        assert!(utc_timestamp(&datetime!(0000-12-31 23:59:59 UTC)).is_none());
+        assert!(utc_timestamp(&datetime!(0001-01-01 08:00:00 +09:00)).is_none());
+        assert!(utc_timestamp(&datetime!(9999-12-31 23:00:00 -05:00)).is_none());
+    }
+
+    #[test]
+    fn test_timestamp_offset_date_time() {
+        let t = datetime!(2022-09-01 12:34:56.123456789 +09:00);
+        let ts = Timestamp::from(t);
+        assert_eq!(ts.seconds, t.unix_timestamp());
+        assert_eq!(ts.nanos, 123456789);
+        assert_eq!(time::OffsetDateTime::try_from(ts).unwrap(), t);
+
+        let ts = Timestamp {
+            seconds: i64::MAX,
+            nanos: 0,
+        };
+        assert!(time::OffsetDateTime::try_from(ts).is_err());
+    }
+}
@@ -6,10 +6,12 @@ use google_cloud_spanner::statement::Statement;
 mod common;
 use common::*;
 use google_cloud_spanner::key::Key;
+use google_cloud_spanner::reader::AsyncIterator;
 
 use google_cloud_gax::grpc::{Code, Status};
+use google_cloud_spanner::mutation::WriteBuilder;
 use google_cloud_spanner::retry::TransactionRetry;
-use google_cloud_spanner::value::Timestamp;
+use google_cloud_spanner::value::{CommitTimestamp, Timestamp};
 use serial_test::serial;
 use time::OffsetDateTime;
 
@@ -178,6 +180,45 @@ async fn test_batch_read_only_transaction() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+#[serial]
+async fn test_batch_read_only_transaction_from_id() -> Result<(), anyhow::Error> {
+    std::env::set_var("SPANNER_EMULATOR_HOST", "localhost:9010");
+    let now = OffsetDateTime::now_utc();
+
+    let mut session = create_session().await;
+    let many = (0..100)
+        .map(|x| create_user_mutation(&format!("user_partition_id_{}_{}", now.unix_timestamp(), x), &now))
+        .collect();
+    replace_test_data(&mut session, many).await.unwrap();
+
+    let client = Client::new(DATABASE).await.context("error")?;
+    let mut tx = client.batch_read_only_transaction().await.unwrap();
+    let stmt = Statement::new(format!(
+        "SELECT UserId FROM User p WHERE p.UserId LIKE 'user_partition_id_{}_%' ",
+        now.unix_timestamp()
+    ));
+    let partitions = tx.partition_query(stmt).await.unwrap();
+
+    // The other client executes the partitions of the transaction.
+    let worker = Client::new(DATABASE).await.context("error")?;
+    let mut worker_tx = worker
+        .batch_read_only_transaction_from_id(tx.id().unwrap())
+        .await
+        .unwrap();
+    let mut rows = vec![];
+    for partition in partitions {
+        assert_eq!(partition.transaction_id(), tx.id());
+        let mut iter = worker_tx.execute(partition, None).await.unwrap();
+        while let Some(row) = iter.next().await.unwrap() {
+            rows.push(row.column_by_name::<String>("UserId").unwrap());
+        }
+    }
+    assert_eq!(100, rows.len());
+    tx.close();
+    Ok(())
+}
+
 #[tokio::test]
 #[serial]
 async fn test_begin_read_write_transaction_retry() -> Result<(), anyhow::Error> {
@@ -205,3 +246,89 @@ async fn test_begin_read_write_transaction_retry() -> Result<(), anyhow::Error>
     assert_eq!(retry_count, 5);
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn test_inline_begin_transaction() -> Result<(), anyhow::Error> {
+    std::env::set_var("SPANNER_EMULATOR_HOST", "localhost:9010");
+    let now = OffsetDateTime::now_utc();
+    let mut session = create_session().await;
+    let user_id = format!("user_inline_{}", now.unix_timestamp());
+    replace_test_data(&mut session, vec![create_user_mutation(&user_id, &now)])
+        .await
+        .unwrap();
+
+    let client = Client::new(DATABASE).await.context("error")?;
+
+    // the first statement begins the transaction
+    let tx = &mut client.begin_read_write_transaction().await?;
+    let result = async {
+        let mut stmt = Statement::new("SELECT UserId FROM User WHERE UserId = @UserId");
+        stmt.add_param("UserId", &user_id);
+        let mut iter = tx.query(stmt).await?;
+        let row = iter.next().await?.unwrap();
+        assert_eq!(row.column::<String>(0)?, user_id);
+        assert!(iter.next().await?.is_none());
+        drop(iter);
+        let mut stmt = Statement::new("UPDATE User SET NullableString = 'inline' WHERE UserId = @UserId");
+        stmt.add_param("UserId", &user_id);
+        Ok::<i64, RunInTxError>(tx.update(stmt).await?)
+    }
+    .await;
+    let (ts, count) = tx.end(result, None).await?;
+    assert!(ts.is_some());
+    assert_eq!(count, 1);
+
+    // the transaction is begun explicitly when the first statement fails
+    let tx = &mut client.begin_read_write_transaction().await?;
+    let result = async {
+        let err = tx
+            .update(Statement::new("UPDATE UserNotFound SET Quantity = 1 WHERE true"))
+            .await
+            .unwrap_err();
+        assert_ne!(err.code(), Code::Ok);
+        let mut stmt = Statement::new("UPDATE User SET NullableString = 'explicit' WHERE UserId = @UserId");
+        stmt.add_param("UserId", &user_id);
+        Ok::<i64, RunInTxError>(tx.update(stmt).await?)
+    }
+    .await;
+    let (ts, count) = tx.end(result, None).await?;
+    assert!(ts.is_some());
+    assert_eq!(count, 1);
+
+    // the transaction without the statement is begun before the commit
+    let tx = &mut client.begin_read_write_transaction().await?;
+    tx.buffer_write(vec![create_user_mutation(&user_id, &now)]);
+    let (ts, _) = tx.end(Ok::<(), RunInTxError>(()), None).await?;
+    assert!(ts.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_commit_timestamp_round_trip() -> Result<(), anyhow::Error> {
+    let now = OffsetDateTime::now_utc();
+    let guild_id = format!("guild_commit_ts_{}", now.unix_timestamp_nanos());
+    let client = Client::new(DATABASE).await.context("error")?;
+    let mutation = WriteBuilder::insert("Guild")
+        .set("GuildId", &guild_id)
+        .set("OwnerUserId", &"owner")
+        .set("UpdatedAt", &CommitTimestamp::new())
+        .build();
+    let commit_timestamp: OffsetDateTime = client.apply(vec![mutation]).await?.unwrap().try_into()?;
+
+    let row = client
+        .read_row("Guild", &["UpdatedAt"], (guild_id.as_str(),))
+        .await?
+        .unwrap();
+    assert_eq!(commit_timestamp, row.column_by_name::<OffsetDateTime>("UpdatedAt")?);
+    #[cfg(feature = "chrono")]
+    {
+        let updated_at = row.column_by_name::<chrono::DateTime<chrono::Utc>>("UpdatedAt")?;
+        assert_eq!(
+            commit_timestamp.unix_timestamp_nanos() as i64,
+            updated_at.timestamp_nanos_opt().unwrap()
+        );
+    }
+    Ok(())
+}
@@ -55,8 +55,8 @@ impl TryFromStruct for UserItem {
 }
 
 #[allow(dead_code)]
-pub fn user_columns() -> Vec<&'static str> {
-    vec![
+pub fn user_columns() -> [&'static str; 19] {
+    [
         "UserId",
         "NotNullINT64",
         "NullableINT64",
@@ -107,8 +107,10 @@ pub async fn replace_test_data(
                 session: session.session.name.to_string(),
                 mutations,
                 return_commit_stats: false,
+                max_commit_delay: None,
                 request_options: None,
                 transaction: Some(SingleUseTransaction(TransactionOptions {
+                    exclude_txn_from_change_streams: false,
                     mode: Some(Mode::ReadWrite(ReadWrite::default())),
                 })),
             },
@@ -1,7 +1,7 @@
-use google_cloud_spanner::key::Key;
+use google_cloud_spanner::key::{Key, KeyRange};
 
 use google_cloud_spanner::row::Row;
-use google_cloud_spanner::statement::Statement;
+use google_cloud_spanner::statement::{Kinds, Statement, ToKind, ToStruct, Types};
 use google_cloud_spanner::transaction::CallOptions;
 use google_cloud_spanner::transaction_ro::{BatchReadOnlyTransaction, ReadOnlyTransaction};
 use google_cloud_spanner::value::TimestampBound;
@@ -298,7 +298,29 @@ async fn test_read_row() {
 
     let mut tx = read_only_transaction(session).await;
     let row = tx.read_row("User", &["UserId"], Key::new(&user_id)).await.unwrap();
-    assert!(row.is_some())
+    assert!(row.is_some());
+    let row = tx.read_row("User", &["UserId"], ("user_x_not_found",)).await.unwrap();
+    assert!(row.is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_read_rows() {
+    let now = OffsetDateTime::now_utc();
+    let mut session = create_session().await;
+    let user_id = format!("user_rows_{}", now.unix_timestamp());
+    let mutations = vec![create_user_mutation(&user_id, &now)];
+    let _ = replace_test_data(&mut session, mutations).await.unwrap();
+
+    let mut tx = ReadOnlyTransaction::single(session, TimestampBound::strong_read())
+        .await
+        .unwrap();
+    let rows = tx
+        .read_rows("User", &["UserId"], vec![(user_id.as_str(),), ("user_rows_not_found",)])
+        .await
+        .unwrap();
+    assert_eq!(1, rows.len());
+    assert_eq!(user_id, rows[0].column_by_name::<String>("UserId").unwrap());
 }
 
 #[tokio::test]
@@ -321,3 +343,91 @@ async fn test_read_multi_row() {
         .unwrap();
     assert_eq!(2, all_rows(row).await.len());
 }
+
+#[tokio::test]
+#[serial]
+async fn test_read_prefix_range() {
+    let now = OffsetDateTime::now_utc();
+    let mut session = create_session().await;
+    let user_id = format!("user_prefix_{}", now.unix_timestamp());
+    // The other user whose ID starts with the ID of the user is not in the prefix range.
+    let other_user_id = format!("{user_id}0");
+    let mutations = vec![
+        create_user_mutation(&user_id, &now),
+        create_user_mutation(&other_user_id, &now),
+        create_user_item_mutation(&user_id, 1),
+        create_user_item_mutation(&user_id, 2),
+        create_user_item_mutation(&other_user_id, 1),
+    ];
+    let _ = replace_test_data(&mut session, mutations).await.unwrap();
+
+    let mut tx = read_only_transaction(session).await;
+    let iter = tx
+        .read("UserItem", &["UserId", "ItemId"], KeyRange::prefix((user_id.as_str(),)))
+        .await
+        .unwrap();
+    let rows = all_rows(iter).await;
+    assert_eq!(2, rows.len());
+    for row in rows {
+        assert_eq!(user_id, row.column_by_name::<String>("UserId").unwrap());
+    }
+}
+
+struct UnnestItem {
+    item_id: i64,
+}
+
+impl ToStruct for UnnestItem {
+    fn to_kinds(&self) -> Kinds {
+        vec![("ItemId", self.item_id.to_kind())]
+    }
+
+    fn get_types() -> Types {
+        vec![("ItemId", i64::get_type())]
+    }
+}
+
+struct UnnestUser {
+    user_id: String,
+    items: Vec<UnnestItem>,
+}
+
+impl ToStruct for UnnestUser {
+    fn to_kinds(&self) -> Kinds {
+        vec![("UserId", self.user_id.to_kind()), ("Items", self.items.to_kind())]
+    }
+
+    fn get_types() -> Types {
+        vec![("UserId", String::get_type()), ("Items", Vec::<UnnestItem>::get_type())]
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_query_unnest_struct_array() {
+    let session = create_session().await;
+    let mut tx = read_only_transaction(session).await;
+    let rows = vec![
+        UnnestUser {
+            user_id: "user1".to_string(),
+            items: vec![UnnestItem { item_id: 1 }, UnnestItem { item_id: 2 }],
+        },
+        UnnestUser {
+            user_id: "user2".to_string(),
+            items: vec![],
+        },
+    ];
+    let mut stmt =
+        Statement::new("SELECT r.UserId, ARRAY_LENGTH(r.Items) AS ItemCount FROM UNNEST(@rows) AS r ORDER BY r.UserId");
+    stmt.add_param("rows", &rows);
+    let result = execute_query(&mut tx, stmt).await;
+    assert_eq!(2, result.len());
+    assert_eq!("user1", result[0].column_by_name::<String>("UserId").unwrap());
+    assert_eq!(2, result[0].column_by_name::<i64>("ItemCount").unwrap());
+    assert_eq!(0, result[1].column_by_name::<i64>("ItemCount").unwrap());
+
+    let mut stmt = Statement::new("SELECT COUNT(*) AS Count FROM UNNEST(@rows)");
+    stmt.add_param("rows", &Vec::<UnnestUser>::new());
+    let result = execute_query(&mut tx, stmt).await;
+    assert_eq!(0, result[0].column_by_name::<i64>("Count").unwrap());
+}
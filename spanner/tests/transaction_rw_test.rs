@@ -122,6 +122,45 @@ async fn test_partitioned_dml() {
     assert_eq!(value, "aaa");
 }
 
+#[tokio::test]
+#[serial]
+async fn test_update_returning() {
+    let now = OffsetDateTime::now_utc();
+    let mut session = create_session().await;
+
+    let user_id = format!("user_{}", now.unix_timestamp());
+    let _cr = replace_test_data(&mut session, vec![create_user_mutation(&user_id, &now)])
+        .await
+        .unwrap();
+
+    let mut tx = match ReadWriteTransaction::begin(session, CallOptions::default()).await {
+        Ok(tx) => tx,
+        Err(e) => panic!("begin first error {:?}", e.status),
+    };
+    let result = async {
+        let mut stmt =
+            Statement::new("UPDATE User SET NullableString = 'bbb' WHERE UserId = @UserId THEN RETURN NullableString");
+        stmt.add_param("UserId", &user_id);
+        let mut iter = tx.update_returning(stmt).await?;
+        let mut values = vec![];
+        while let Some(row) = iter.next().await? {
+            values.push(row.column::<String>(0).unwrap());
+        }
+        assert_eq!(values, vec!["bbb".to_string()]);
+        assert_eq!(
+            iter.stats().unwrap().row_count,
+            Some(google_cloud_spanner::stats::RowCount::Exact(1))
+        );
+
+        let stmt = Statement::new("UPDATE User SET NullableString = 'ccc' WHERE true THEN RETURN UserId");
+        let err = tx.batch_update(vec![stmt]).await.unwrap_err();
+        assert!(err.to_string().contains("THEN RETURN"), "{err}");
+        Ok::<(), Status>(())
+    }
+    .await;
+    assert!(tx.end(result, None).await.is_ok());
+}
+
 #[tokio::test]
 #[serial]
 async fn test_rollback() {